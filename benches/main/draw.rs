@@ -24,10 +24,10 @@ fn draw(c: &mut Criterion) {
                 let backend = TestBackend::new(width, height);
                 let terminal = Terminal::new(backend).unwrap();
                 let (tx, _) = tokio::sync::mpsc::unbounded_channel();
-                let mut channel =
-                    TelevisionChannel::Files(Channel::new(vec![
-                        PathBuf::from("."),
-                    ]));
+                let mut channel = TelevisionChannel::Files(Channel::new(
+                    vec![PathBuf::from(".")],
+                    &config.channel_config(),
+                ));
                 channel.find("television");
                 // Wait for the channel to finish loading
                 let mut tv = Television::new(tx, channel, config, None);
@@ -5,8 +5,9 @@ use ratatui::prelude::{Line, Style};
 use ratatui::style::Color;
 use ratatui::widgets::{Block, BorderType, Borders, ListDirection, Padding};
 use television::channels::entry::merge_ranges;
-use television::channels::entry::{Entry, PreviewType};
+use television::channels::entry::{Entry, EntryKind, PreviewType};
 use television::screen::colors::ResultsColorscheme;
+use television::screen::layout::Truncation;
 use television::screen::results::build_results_list;
 
 pub fn draw_results_list(c: &mut Criterion) {
@@ -30,6 +31,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/README.md".to_string(),
@@ -47,6 +53,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/re.pyi".to_string(),
@@ -64,6 +75,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/io.pyi".to_string(),
@@ -81,6 +97,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/gc.pyi".to_string(),
@@ -98,6 +119,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/uu.pyi".to_string(),
@@ -115,6 +141,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/nt.pyi".to_string(),
@@ -132,6 +163,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/dis.pyi".to_string(),
@@ -149,6 +185,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/imp.pyi".to_string(),
@@ -166,6 +207,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/bdb.pyi".to_string(),
@@ -183,6 +229,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/abc.pyi".to_string(),
@@ -200,6 +251,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/cgi.pyi".to_string(),
@@ -217,6 +273,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/bz2.pyi".to_string(),
@@ -234,6 +295,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/grp.pyi".to_string(),
@@ -251,6 +317,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/ast.pyi".to_string(),
@@ -268,6 +339,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/csv.pyi".to_string(),
@@ -285,6 +361,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/pdb.pyi".to_string(),
@@ -302,6 +383,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/pwd.pyi".to_string(),
@@ -319,6 +405,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/ssl.pyi".to_string(),
@@ -336,6 +427,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/tty.pyi".to_string(),
@@ -353,6 +449,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/nis.pyi".to_string(),
@@ -370,6 +471,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/pty.pyi".to_string(),
@@ -387,6 +493,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/cmd.pyi".to_string(),
@@ -404,6 +515,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/tests/utils.py".to_string(),
@@ -421,6 +537,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/pyproject.toml".to_string(),
@@ -438,6 +559,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/MAINTAINERS.md".to_string(),
@@ -455,6 +581,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/enum.pyi".to_string(),
@@ -472,6 +603,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/hmac.pyi".to_string(),
@@ -489,6 +625,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/uuid.pyi".to_string(),
@@ -506,6 +647,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/glob.pyi".to_string(),
@@ -523,6 +669,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/_ast.pyi".to_string(),
@@ -540,6 +691,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/_csv.pyi".to_string(),
@@ -557,6 +713,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/code.pyi".to_string(),
@@ -574,6 +735,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/spwd.pyi".to_string(),
@@ -591,6 +757,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/_msi.pyi".to_string(),
@@ -608,6 +779,11 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
         Entry {
             name: "typeshed/stdlib/time.pyi".to_string(),
@@ -619,6 +795,8 @@ pub fn draw_results_list(c: &mut Criterion) {
             }),
             line_number: None,
             preview_type: PreviewType::Files,
+            search_text: None,
+            kind: EntryKind::Item,
             name_match_ranges: Some(merge_ranges(&[
                 (0, 1),
                 (1, 2),
@@ -626,10 +804,14 @@ pub fn draw_results_list(c: &mut Criterion) {
                 (3, 4),
             ])),
             value_match_ranges: None,
+            previewable: true,
+            group: None,
+            snippet: None,
         },
     ];
 
     let colorscheme = ResultsColorscheme {
+        border_fg: Color::Indexed(222),
         result_name_fg: Color::Indexed(222),
         result_preview_fg: Color::Indexed(222),
         result_line_number_fg: Color::Indexed(222),
@@ -654,7 +836,13 @@ pub fn draw_results_list(c: &mut Criterion) {
                 None,
                 ListDirection::BottomToTop,
                 false,
+                false,
+                Truncation::Right,
                 &colorscheme,
+                80,
+                0,
+                false,
+                None,
             );
         });
     });
@@ -16,7 +16,8 @@ use quote::quote;
 ///     // ...
 /// }
 ///
-/// let television_channel: TelevisionChannel = CliTvChannel::Files.to_channel();
+/// let television_channel: TelevisionChannel =
+///     CliTvChannel::Files.to_channel(&ChannelConfig::default());
 ///
 /// assert!(matches!(television_channel, TelevisionChannel::Files(_)));
 /// ```
@@ -94,7 +95,7 @@ fn impl_cli_channel(ast: &syn::DeriveInput) -> TokenStream {
                 let inner_type = &fields.unnamed[0].ty;
 
                 quote! {
-                    CliTvChannel::#variant_name => TelevisionChannel::#variant_name(#inner_type::default())
+                    CliTvChannel::#variant_name => TelevisionChannel::#variant_name(#inner_type::from(config))
                 }
             } else {
                 panic!("Enum variants should have exactly one unnamed field.");
@@ -108,7 +109,7 @@ fn impl_cli_channel(ast: &syn::DeriveInput) -> TokenStream {
         #cli_enum
 
         impl CliTvChannel {
-            pub fn to_channel(self) -> TelevisionChannel {
+            pub fn to_channel(self, config: &ChannelConfig) -> TelevisionChannel {
                 match self {
                     #(#arms),*
                 }
@@ -193,6 +194,16 @@ fn impl_tv_channel(ast: &syn::DeriveInput) -> TokenStream {
                 }
             }
 
+            fn current_query(&self) -> &str {
+                match self {
+                    #(
+                        #enum_name::#variant_names(ref channel) => {
+                            channel.current_query()
+                        }
+                    )*
+                }
+            }
+
             fn results(&mut self, num_entries: u32, offset: u32) -> Vec<Entry> {
                 match self {
                     #(
@@ -233,6 +244,16 @@ fn impl_tv_channel(ast: &syn::DeriveInput) -> TokenStream {
                 }
             }
 
+            fn reset(&mut self) {
+                match self {
+                    #(
+                        #enum_name::#variant_names(ref mut channel) => {
+                            channel.reset()
+                        }
+                    )*
+                }
+            }
+
             fn result_count(&self) -> u32 {
                 match self {
                     #(
@@ -273,6 +294,46 @@ fn impl_tv_channel(ast: &syn::DeriveInput) -> TokenStream {
                 }
             }
 
+            fn pause(&mut self) -> bool {
+                match self {
+                    #(
+                        #enum_name::#variant_names(ref mut channel) => {
+                            channel.pause()
+                        }
+                    )*
+                }
+            }
+
+            fn resume(&mut self) {
+                match self {
+                    #(
+                        #enum_name::#variant_names(ref mut channel) => {
+                            channel.resume()
+                        }
+                    )*
+                }
+            }
+
+            fn set_match_target(&mut self, target: MatchTarget) -> bool {
+                match self {
+                    #(
+                        #enum_name::#variant_names(ref mut channel) => {
+                            channel.set_match_target(target)
+                        }
+                    )*
+                }
+            }
+
+            fn set_typed_query(&mut self, query: &str) {
+                match self {
+                    #(
+                        #enum_name::#variant_names(ref mut channel) => {
+                            channel.set_typed_query(query)
+                        }
+                    )*
+                }
+            }
+
             fn supports_preview(&self) -> bool {
                 match self {
                     #(
@@ -342,13 +403,13 @@ fn impl_unit_channel(ast: &syn::DeriveInput) -> TokenStream {
         }
     };
 
-    // Generate Into<TelevisionChannel> implementation
+    // Generate the method creating the corresponding channel
     let into_impl = quote! {
-        impl Into<TelevisionChannel> for UnitChannel {
-            fn into(self) -> TelevisionChannel {
+        impl UnitChannel {
+            pub fn to_channel(self, config: &ChannelConfig) -> TelevisionChannel {
                 match self {
                     #(
-                        UnitChannel::#variant_names => TelevisionChannel::#variant_names(Default::default()),
+                        UnitChannel::#variant_names => TelevisionChannel::#variant_names(From::from(config)),
                     )*
                 }
             }
@@ -1,9 +1,24 @@
 use serde::{Deserialize, Serialize};
+use strum::Display;
+
+use crate::event::Key;
 
 /// The different actions that can be performed by the application.
+///
+/// Actions display as their `snake_case` configuration name (e.g. `toggle_help`).
 #[derive(
-    Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Hash, PartialOrd, Ord,
+    Debug,
+    Display,
+    Clone,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Hash,
+    PartialOrd,
+    Ord,
 )]
+#[strum(serialize_all = "snake_case")]
 pub enum Action {
     // input actions
     /// Add a character to the input buffer.
@@ -47,6 +62,10 @@ pub enum Action {
     /// Add entry under cursor to the list of selected entries and move the cursor up.
     #[serde(alias = "toggle_selection_up")]
     ToggleSelectionUp,
+    /// Select every matching entry that isn't selected and deselect every
+    /// one that is.
+    #[serde(alias = "invert_selection")]
+    InvertSelection,
     /// Confirm current selection (multi select or entry under cursor).
     #[serde(alias = "select_entry")]
     #[serde(alias = "confirm_selection")]
@@ -70,9 +89,85 @@ pub enum Action {
     /// Select the previous page of entries in the currently focused list.
     #[serde(alias = "select_prev_page")]
     SelectPrevPage,
+    /// Select the entry at the given position (starting at 0) among the
+    /// currently visible results.
+    #[serde(skip)]
+    SelectVisibleEntry(usize),
+    /// Label the visible results with letters, typing a label selecting the
+    /// corresponding result.
+    #[serde(alias = "toggle_quicknav")]
+    ToggleQuicknav,
+    /// Type a key of the label of the result to jump to.
+    #[serde(skip)]
+    QuicknavKey(Key),
     /// Copy the currently selected entry to the clipboard.
     #[serde(alias = "copy_entry_to_clipboard")]
     CopyEntryToClipboard,
+    /// Copy every selected entry to the clipboard, one per line (see
+    /// `AppConfig::copy_all_separator`).
+    #[serde(alias = "copy_all_selected")]
+    CopyAllSelected,
+    /// Copy the paths of the selected entries relative to the configured
+    /// base directory to the clipboard.
+    #[serde(alias = "copy_relative_path")]
+    CopyRelativePath,
+    /// Use the directory of the currently selected entry as the base
+    /// directory of `CopyRelativePath`.
+    #[serde(alias = "set_relative_path_base")]
+    SetRelativePathBase,
+    /// Open or print the directory containing the selected entry (see
+    /// `AppConfig::reveal_in_parent`).
+    #[serde(alias = "reveal_in_parent")]
+    RevealInParent,
+    /// Use the currently selected entry as the new query and re-run the search.
+    #[serde(alias = "use_selection_as_query")]
+    UseSelectionAsQuery,
+    /// Extend the query with the text following the match that all the
+    /// results have in common, like shell completion.
+    #[serde(alias = "complete_common_prefix")]
+    CompleteCommonPrefix,
+    /// Replace the current query and re-run the search.
+    #[serde(skip)]
+    SetQuery(String),
+    /// Pin the current query as a filter that keeps applying to the results
+    /// and clear the input.
+    #[serde(alias = "push_filter")]
+    PushFilter,
+    /// Remove the last pinned filter.
+    #[serde(alias = "pop_filter")]
+    PopFilter,
+    /// Collapse or expand the group of the selected result when results are
+    /// grouped.
+    #[serde(alias = "toggle_group")]
+    ToggleGroup,
+    /// Reverse the order of the results, from the worst match to the best
+    /// one or the other way around.
+    #[serde(alias = "toggle_sort_direction")]
+    ToggleSortDirection,
+    /// Clear the query, the pinned filters and the selected entries.
+    #[serde(alias = "reset_channel")]
+    ResetChannel,
+    /// Run the source command of the channel again, keeping the query and
+    /// the selected entries.
+    #[serde(alias = "reload_source")]
+    ReloadSource,
+    /// Pause loading the channel's entries in the background, or resume it.
+    #[serde(alias = "toggle_pause_scanning")]
+    TogglePauseScanning,
+    /// Switch between matching the entries' full text (e.g. with ANSI escape
+    /// sequences and hidden fields) and only their visible text.
+    #[serde(alias = "toggle_match_target")]
+    ToggleMatchTarget,
+    /// Toggle the split search mode, where a second pane searches the
+    /// contents of the entry selected in the results.
+    #[serde(alias = "toggle_split_search")]
+    ToggleSplitSearch,
+    /// Move the focus between the panes of the split search mode.
+    #[serde(alias = "switch_split_focus")]
+    SwitchSplitFocus,
+    /// Run the configured `execute_command` on the selected entries.
+    #[serde(alias = "execute_command")]
+    ExecuteCommand,
     // preview actions
     /// Scroll the preview up by one line.
     #[serde(alias = "scroll_preview_up")]
@@ -86,6 +181,35 @@ pub enum Action {
     /// Scroll the preview down by half a page.
     #[serde(alias = "scroll_preview_half_page_down")]
     ScrollPreviewHalfPageDown,
+    /// Scroll the preview to the very beginning of its content.
+    #[serde(alias = "scroll_preview_top")]
+    ScrollPreviewTop,
+    /// Scroll the preview to the very end of its content.
+    #[serde(alias = "scroll_preview_bottom")]
+    ScrollPreviewBottom,
+    /// Keep the preview's scroll offset when the selected entry changes.
+    #[serde(alias = "lock_preview_scroll")]
+    LockPreviewScroll,
+    /// Generate the preview of the selected entry when previews are shown on
+    /// demand.
+    #[serde(alias = "show_preview_for_current")]
+    ShowPreviewForCurrent,
+    /// Move the preview's current line down by one line.
+    #[serde(alias = "preview_cursor_down")]
+    PreviewCursorDown,
+    /// Move the preview's current line up by one line.
+    #[serde(alias = "preview_cursor_up")]
+    PreviewCursorUp,
+    /// Start typing a search within the preview's content, or close the
+    /// search if it is open.
+    #[serde(alias = "toggle_preview_search")]
+    TogglePreviewSearch,
+    /// Scroll the preview to the next match of the preview search.
+    #[serde(alias = "next_preview_match")]
+    NextPreviewMatch,
+    /// Scroll the preview to the previous match of the preview search.
+    #[serde(alias = "prev_preview_match")]
+    PrevPreviewMatch,
     /// Open the currently selected entry in the default application.
     #[serde(skip)]
     OpenEntry,
@@ -102,12 +226,33 @@ pub enum Action {
     /// Quit the application.
     #[serde(alias = "quit")]
     Quit,
-    /// Toggle the help bar.
+    /// Show or hide the help overlay listing the keybindings.
     #[serde(alias = "toggle_help")]
     ToggleHelp,
+    /// Give more room to the action names in the help tables.
+    #[serde(alias = "widen_help_actions")]
+    WidenHelpActions,
+    /// Give more room to the keys in the help tables.
+    #[serde(alias = "widen_help_keys")]
+    WidenHelpKeys,
     /// Toggle the preview panel.
     #[serde(alias = "toggle_preview")]
     TogglePreview,
+    /// Wrap long queries over several lines or scroll them horizontally.
+    #[serde(alias = "toggle_input_wrap")]
+    ToggleInputWrap,
+    /// Show or hide the overlay listing the latest keys and actions.
+    #[serde(alias = "toggle_debug_overlay")]
+    ToggleDebugOverlay,
+    /// Expand the preview to the whole terminal, hiding the results and
+    /// the input, or go back to the normal view.
+    #[serde(alias = "toggle_preview_fullscreen")]
+    TogglePreviewFullscreen,
+    /// Preview the selected entry with the next of the channel's
+    /// alternative previewers, going back to its own previewer after the
+    /// last one.
+    #[serde(alias = "cycle_previewer")]
+    CyclePreviewer,
     /// Signal an error with the given message.
     #[serde(skip)]
     Error(String),
@@ -121,4 +266,21 @@ pub enum Action {
     /// Toggle the remote control in `send to channel` mode.
     #[serde(alias = "toggle_send_to_channel")]
     ToggleSendToChannel,
+    /// Switch between replacing and appending to the channel entries are
+    /// sent to.
+    #[serde(alias = "toggle_send_to_channel_mode")]
+    ToggleSendToChannelMode,
+    // keybinding editor actions
+    /// Toggle the interactive keybinding editor.
+    #[serde(alias = "toggle_keybinding_editor")]
+    ToggleKeybindingEditor,
+    /// Bind the given key to the action currently selected in the keybinding editor.
+    #[serde(skip)]
+    CaptureKey(Key),
+    /// Answer the confirmation prompt shown before running a command.
+    #[serde(skip)]
+    AnswerConfirmation(Key),
+    /// Dismiss the error currently shown.
+    #[serde(skip)]
+    DismissError,
 }
@@ -1,17 +1,26 @@
 use rustc_hash::FxHashSet;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use tokio::sync::mpsc;
-use tracing::{debug, trace};
+use tracing::{debug, trace, warn};
 
-use crate::channels::entry::Entry;
+use crate::channels::entry::{Entry, PreviewType};
+use crate::channels::pending::PendingChannel;
 use crate::channels::TelevisionChannel;
 use crate::config::{
-    merge_keybindings, parse_key, Binding, Config, KeyBindings,
+    merge_keybindings, parse_key, Binding, Config, KeyBindings, RevealMode,
 };
-use crate::keymap::Keymap;
+use crate::confirmation::Confirmation;
+use crate::debug_log::DebugLog;
+use crate::keymap::{ChordBuffer, Chorded, Keymap};
 use crate::render::UiState;
 use crate::television::{Mode, Television};
+use crate::utils::command::open_with_default_app;
+use crate::utils::idle::IdleTimer;
+use crate::utils::last_channel::save_last_channel;
+use crate::utils::query_fifo::read_query_fifo;
 use crate::{
     action::Action,
     event::{Event, EventLoop, Key},
@@ -21,9 +30,17 @@ use crate::{
 /// The main application struct that holds the state of the application.
 pub struct App {
     keymap: Keymap,
+    /// Holds back the first key of a chord until the chord is complete.
+    chords: ChordBuffer,
+    /// Keybindings passed through to the parent process, kept around to
+    /// rebuild the keymap when keybindings are edited at runtime.
+    passthrough_keybindings: KeyBindings,
     // maybe move these two into config instead of passing them
     // via the cli?
     tick_rate: f64,
+    /// Accepts the current selection after a period without input, if
+    /// configured.
+    idle_timer: Option<IdleTimer>,
     /// The television instance that handles channels and entries.
     television: Television,
     /// A flag that indicates whether the application should quit during the next frame.
@@ -55,8 +72,14 @@ pub struct App {
     ui_state_tx: mpsc::UnboundedSender<UiState>,
     /// Render task handle
     render_task: Option<tokio::task::JoinHandle<Result<()>>>,
+    /// Receives the accepted selections when the application keeps running
+    /// after a selection is accepted.
+    loop_handler: Option<LoopHandler>,
 }
 
+/// Handles a selection accepted in loop mode, see [`App::loop_on_accept`].
+pub type LoopHandler = Box<dyn FnMut(AppOutput) -> Result<()> + Send>;
+
 /// The outcome of an action.
 #[derive(Debug)]
 pub enum ActionOutcome {
@@ -71,6 +94,13 @@ pub enum ActionOutcome {
 pub struct AppOutput {
     pub selected_entries: Option<FxHashSet<Entry>>,
     pub passthrough: Option<String>,
+    /// The 1-based rank among the results of the entry under the cursor
+    /// when entries were accepted.
+    pub selected_index: Option<usize>,
+    /// The query when entries were accepted.
+    pub query: Option<String>,
+    /// Warnings to print once the terminal is restored.
+    pub warnings: Vec<String>,
 }
 
 impl From<ActionOutcome> for AppOutput {
@@ -79,18 +109,30 @@ impl From<ActionOutcome> for AppOutput {
             ActionOutcome::Entries(entries) => Self {
                 selected_entries: Some(entries),
                 passthrough: None,
+                selected_index: None,
+                query: None,
+                warnings: Vec::new(),
             },
             ActionOutcome::Input(input) => Self {
                 selected_entries: None,
                 passthrough: Some(input),
+                selected_index: None,
+                query: None,
+                warnings: Vec::new(),
             },
             ActionOutcome::Passthrough(entries, key) => Self {
                 selected_entries: Some(entries),
                 passthrough: Some(key),
+                selected_index: None,
+                query: None,
+                warnings: Vec::new(),
             },
             ActionOutcome::None => Self {
                 selected_entries: None,
                 passthrough: None,
+                selected_index: None,
+                query: None,
+                warnings: Vec::new(),
             },
         }
     }
@@ -111,8 +153,15 @@ impl App {
         let (_, event_rx) = mpsc::unbounded_channel();
         let (event_abort_tx, _) = mpsc::unbounded_channel();
         let tick_rate = config.application.tick_rate;
-        let keybindings = merge_keybindings(config.keybindings.clone(), {
-            &KeyBindings::from(passthrough_keybindings.iter().filter_map(
+        let idle_timer = config.application.idle_accept_ms.map(|ms| {
+            IdleTimer::new(Duration::from_millis(ms), Instant::now())
+        });
+        let chords = ChordBuffer::new(
+            &config.chords,
+            Duration::from_millis(config.application.chord_timeout_ms),
+        );
+        let passthrough_keybindings =
+            KeyBindings::from(passthrough_keybindings.iter().filter_map(
                 |s| match parse_key(s) {
                     Ok(key) => Some((
                         Action::SelectPassthrough(s.to_string()),
@@ -123,9 +172,11 @@ impl App {
                         None
                     }
                 },
-            ))
-        });
-        let keymap = Keymap::from(&keybindings);
+            ));
+        let keymap = Keymap::from(&merge_keybindings(
+            config.keybindings.clone(),
+            &passthrough_keybindings,
+        ));
 
         debug!("{:?}", keymap);
         let (ui_state_tx, ui_state_rx) = mpsc::unbounded_channel();
@@ -134,7 +185,10 @@ impl App {
 
         Self {
             keymap,
+            chords,
+            passthrough_keybindings,
             tick_rate,
+            idle_timer,
             television,
             should_quit: false,
             should_suspend: false,
@@ -147,9 +201,60 @@ impl App {
             ui_state_rx,
             ui_state_tx,
             render_task: None,
+            loop_handler: None,
         }
     }
 
+    /// Keep running after a selection is accepted, handing it to `handler`
+    /// and starting a new search instead of exiting.
+    pub fn loop_on_accept(&mut self, handler: LoopHandler) {
+        self.loop_handler = Some(handler);
+    }
+
+    /// Show the overlay listing the latest keys and actions.
+    pub fn show_debug_overlay(&mut self) {
+        self.television.debug_overlay_visible = true;
+    }
+
+    /// The latest keys received and actions dispatched.
+    pub fn debug_log(&self) -> &DebugLog {
+        &self.television.debug_log
+    }
+
+    /// Replace the query with each line read from the named pipe at `path`.
+    pub fn read_queries_from(&mut self, path: PathBuf) {
+        tokio::spawn(read_query_fifo(path, self.action_tx.clone()));
+    }
+
+    /// Open the preview scrolled to the given line (starting at 1) for the
+    /// initially selected entry.
+    pub fn open_preview_at_line(&mut self, line: u16) {
+        self.television.initial_preview_line = Some(line);
+    }
+
+    /// Start in send to channel mode, where the channel's entries can be
+    /// sent to another channel right away.
+    pub fn start_in_send_to_channel(&mut self) {
+        self.television.handle_toggle_send_to_channel();
+    }
+
+    /// Run a command with the interface suspended.
+    fn execute_command(&mut self, command: String) -> Result<()> {
+        if !self.render_tx.is_closed() {
+            // stop reading terminal events so that the command gets all of
+            // the user's input
+            let _ = self.event_abort_tx.send(());
+            self.render_tx.send(RenderingTask::Execute(command))?;
+        }
+        Ok(())
+    }
+
+    /// Construct the channel in the background, replacing the one the
+    /// application was created with once it is ready.
+    pub fn load_channel(&mut self, pending: PendingChannel) {
+        self.television.load_channel(pending);
+    }
+
     /// Run the application main loop.
     ///
     /// This function will start the event loop and the rendering loop and handle
@@ -171,9 +276,7 @@ impl App {
     ) -> Result<AppOutput> {
         if !headless {
             debug!("Starting backend event loop");
-            let event_loop = EventLoop::new(self.tick_rate, true);
-            self.event_rx = event_loop.rx;
-            self.event_abort_tx = event_loop.abort_tx;
+            self.restart_event_loop();
         }
 
         // Rendering loop
@@ -206,13 +309,20 @@ impl App {
                 > 0
             {
                 for event in event_buf.drain(..) {
-                    if let Some(action) = self.convert_event_to_action(event) {
+                    for action in self.convert_event_to_actions(event) {
                         if action != Action::Tick {
                             debug!("Queuing new action: {action:?}");
                         }
                         action_tx.send(action)?;
                     }
                 }
+            } else if !headless && self.event_rx.is_closed() {
+                // the event loop is stopped while a command is executed,
+                // wait for it to finish without busy-looping
+                tokio::time::sleep(Duration::from_secs_f64(
+                    1.0 / self.tick_rate,
+                ))
+                .await;
             }
             // It's important that this shouldn't block if no actions are available
             let action_outcome = self.handle_actions(&mut action_buf).await?;
@@ -228,11 +338,62 @@ impl App {
                     rendering_task.await??;
                 }
 
-                return Ok(AppOutput::from(action_outcome));
+                if self.television.config.application.remember_last_channel {
+                    self.remember_channel();
+                }
+
+                let mut output = self.output(action_outcome);
+                output.warnings =
+                    std::mem::take(&mut self.television.warnings);
+                return Ok(output);
             }
         }
     }
 
+    /// The output of the application for `outcome`, along with the rank of
+    /// the entry under the cursor and the query if entries were accepted.
+    fn output(&self, outcome: ActionOutcome) -> AppOutput {
+        let mut output = AppOutput::from(outcome);
+        if output.selected_entries.is_some() {
+            output.selected_index = self.television.selected_rank();
+            output.query = Some(self.television.current_pattern.clone());
+        }
+        output
+    }
+
+    /// Hand the accepted entries to the loop handler and start a new search
+    /// (see [`App::loop_on_accept`]).
+    fn handle_in_loop(&mut self, entries: FxHashSet<Entry>) -> Result<()> {
+        let output = self.output(ActionOutcome::Entries(entries));
+        if let Some(handler) = self.loop_handler.as_mut() {
+            handler(output)?;
+        }
+        self.television.handle_reset_channel();
+        self.action_tx.send(Action::Render)?;
+        Ok(())
+    }
+
+    /// Record the current channel so that it can be reopened by default.
+    ///
+    /// Channels fed from stdin can't be reopened and aren't recorded.
+    fn remember_channel(&self) {
+        if matches!(self.television.channel, TelevisionChannel::Stdin(_)) {
+            return;
+        }
+        if let Err(e) = save_last_channel(
+            &self.television.config.application.data_dir,
+            &self.television.channel.name().to_lowercase(),
+        ) {
+            warn!("Unable to record the last channel: {:?}", e);
+        }
+    }
+
+    fn restart_event_loop(&mut self) {
+        let event_loop = EventLoop::new(self.tick_rate, true);
+        self.event_rx = event_loop.rx;
+        self.event_abort_tx = event_loop.abort_tx;
+    }
+
     /// Run the application in headless mode.
     ///
     /// This function will start the event loop and handle all actions that are sent to the
@@ -242,6 +403,57 @@ impl App {
         self.run(false, true).await
     }
 
+    /// Convert an event to actions, resolving the configured chords.
+    ///
+    /// Keys starting a chord are held back until the chord is complete, or
+    /// handled alone if the chord's second key doesn't arrive in time.
+    fn convert_event_to_actions(&mut self, event: Event<Key>) -> Vec<Action> {
+        let now = Instant::now();
+        let mut actions = Vec::new();
+        if let Event::Input(key) = event {
+            self.television.debug_log.record_key(key);
+        }
+        if matches!(event, Event::Tick) {
+            if let Some(key) = self.chords.expire(now) {
+                actions
+                    .extend(self.convert_event_to_action(Event::Input(key)));
+            }
+        }
+        match event {
+            Event::Input(key) if self.accepts_chords() => {
+                for chorded in self.chords.feed(key, now) {
+                    match chorded {
+                        Chorded::Key(key) => actions.extend(
+                            self.convert_event_to_action(Event::Input(key)),
+                        ),
+                        Chorded::Action(action) => {
+                            if let Some(timer) = self.idle_timer.as_mut() {
+                                timer.reset(now);
+                            }
+                            actions.push(action);
+                        }
+                    }
+                }
+            }
+            event => actions.extend(self.convert_event_to_action(event)),
+        }
+        actions
+    }
+
+    /// Whether keys are matched against the keybindings, as opposed to
+    /// being captured by a prompt (errors, confirmations, the keybinding
+    /// editor waiting for a key, the quicknav labels).
+    fn accepts_chords(&self) -> bool {
+        self.television.error.is_none()
+            && self.television.confirmation.is_none()
+            && !self
+                .television
+                .keybinding_editor
+                .as_ref()
+                .is_some_and(|e| e.capturing)
+            && self.television.quicknav.is_none()
+    }
+
     /// Convert an event to an action.
     ///
     /// This function will convert an event to an action based on the current
@@ -252,12 +464,64 @@ impl App {
     ///
     /// # Returns
     /// The action that corresponds to the given event.
-    fn convert_event_to_action(&self, event: Event<Key>) -> Option<Action> {
+    fn convert_event_to_action(
+        &mut self,
+        event: Event<Key>,
+    ) -> Option<Action> {
+        if let Some(timer) = self.idle_timer.as_mut() {
+            match event {
+                Event::Input(_) => timer.reset(Instant::now()),
+                Event::Tick if timer.elapsed(Instant::now()) => {
+                    debug!("Idle timeout elapsed, accepting selection");
+                    self.idle_timer = None;
+                    return Some(Action::SelectAndExit);
+                }
+                _ => {}
+            }
+        }
+        // any key dismisses the notice shown, and is handled as usual
+        if matches!(event, Event::Input(_)) {
+            self.television.notice = None;
+        }
+        let editor = self.television.keybinding_editor.as_ref();
         let action = match event {
+            // any key dismisses the error shown
+            Event::Input(_) if self.television.error.is_some() => {
+                Action::DismissError
+            }
+            // a command is waiting for confirmation
+            Event::Input(keycode)
+                if self.television.confirmation.is_some() =>
+            {
+                Action::AnswerConfirmation(keycode)
+            }
+            // the keybinding editor is waiting for a key to bind
+            Event::Input(keycode) if editor.is_some_and(|e| e.capturing) => {
+                Action::CaptureKey(keycode)
+            }
+            // the labels of the visible results are shown
+            Event::Input(keycode) if self.television.quicknav.is_some() => {
+                Action::QuicknavKey(keycode)
+            }
             Event::Input(keycode) => {
                 // get action based on keybindings
                 if let Some(action) = self.keymap.get(&keycode) {
                     debug!("Keybinding found: {action:?}");
+                    // quitting while the keybinding editor is open closes it
+                    if *action == Action::Quit && editor.is_some() {
+                        return Some(Action::ToggleKeybindingEditor);
+                    }
+                    // and so does the help overlay
+                    if *action == Action::Quit && self.television.help_visible
+                    {
+                        return Some(Action::ToggleHelp);
+                    }
+                    // and the preview search
+                    if *action == Action::Quit
+                        && self.television.preview_state.search.is_some()
+                    {
+                        return Some(Action::TogglePreviewSearch);
+                    }
                     action.clone()
                 } else {
                     // text input events
@@ -270,6 +534,12 @@ impl App {
                         Key::Home | Key::Ctrl('a') => Action::GoToInputStart,
                         Key::End | Key::Ctrl('e') => Action::GoToInputEnd,
                         Key::Char(c) => Action::AddInputChar(c),
+                        // alt-1 through alt-9 then alt-0 jump to the
+                        // corresponding visible result
+                        Key::Alt(c) if c.is_ascii_digit() => {
+                            let n = c.to_digit(10).unwrap_or_default();
+                            Action::SelectVisibleEntry((n as usize + 9) % 10)
+                        }
                         _ => Action::NoOp,
                     }
                 }
@@ -317,6 +587,7 @@ impl App {
                 if action != Action::Tick {
                     trace!("{action:?}");
                 }
+                self.television.debug_log.record_action(&action);
                 match action {
                     Action::Quit => {
                         self.should_quit = true;
@@ -329,16 +600,50 @@ impl App {
                     Action::Resume => {
                         self.should_suspend = false;
                         self.render_tx.send(RenderingTask::Resume)?;
+                        // the event loop is stopped while a command runs
+                        if self.event_rx.is_closed() {
+                            self.restart_event_loop();
+                        }
+                    }
+                    Action::ExecuteCommand => {
+                        if let Some(command) =
+                            self.television.execute_command_line()
+                        {
+                            if self.television.execute_needs_confirmation() {
+                                self.television.confirmation =
+                                    Some(Confirmation::new(command));
+                            } else {
+                                self.execute_command(command)?;
+                            }
+                        }
+                    }
+                    Action::AnswerConfirmation(key) => {
+                        if let Some(command) =
+                            self.television.answer_confirmation(key)
+                        {
+                            self.execute_command(command)?;
+                        }
                     }
                     Action::SelectAndExit => {
+                        let entries = self
+                            .television
+                            .get_accepted_entries(Some(Mode::Channel));
+                        if entries.as_ref().is_some_and(|entries| {
+                            !self.television.check_accepted_entries(entries)
+                        }) {
+                            continue;
+                        }
+                        if self.loop_handler.is_some() {
+                            if let Some(entries) = entries {
+                                self.handle_in_loop(entries)?;
+                            }
+                            continue;
+                        }
                         self.should_quit = true;
                         if !self.render_tx.is_closed() {
                             self.render_tx.send(RenderingTask::Quit)?;
                         }
-                        if let Some(entries) = self
-                            .television
-                            .get_selected_entries(Some(Mode::Channel))
-                        {
+                        if let Some(entries) = entries {
                             return Ok(ActionOutcome::Entries(entries));
                         }
 
@@ -347,12 +652,17 @@ impl App {
                         ));
                     }
                     Action::SelectPassthrough(passthrough) => {
+                        let entries = self
+                            .television
+                            .get_accepted_entries(Some(Mode::Channel));
+                        if entries.as_ref().is_some_and(|entries| {
+                            !self.television.check_accepted_entries(entries)
+                        }) {
+                            continue;
+                        }
                         self.should_quit = true;
                         self.render_tx.send(RenderingTask::Quit)?;
-                        if let Some(entries) = self
-                            .television
-                            .get_selected_entries(Some(Mode::Channel))
-                        {
+                        if let Some(entries) = entries {
                             return Ok(ActionOutcome::Passthrough(
                                 entries,
                                 passthrough,
@@ -360,6 +670,45 @@ impl App {
                         }
                         return Ok(ActionOutcome::None);
                     }
+                    Action::RevealInParent => {
+                        let Some(parent) =
+                            self.television.selected_parent_directory()
+                        else {
+                            continue;
+                        };
+                        match self
+                            .television
+                            .config
+                            .application
+                            .reveal_in_parent
+                        {
+                            RevealMode::Open => {
+                                if let Err(e) = open_with_default_app(&parent)
+                                {
+                                    self.television.error = Some(format!(
+                                        "Failed to open {}: {e}",
+                                        parent.display()
+                                    ));
+                                }
+                            }
+                            RevealMode::Print => {
+                                // the directory is output as the accepted
+                                // entry, along with the query and rank
+                                let entries =
+                                    FxHashSet::from_iter([Entry::new(
+                                        parent.to_string_lossy().to_string(),
+                                        PreviewType::Files,
+                                    )]);
+                                if self.loop_handler.is_some() {
+                                    self.handle_in_loop(entries)?;
+                                    continue;
+                                }
+                                self.should_quit = true;
+                                self.render_tx.send(RenderingTask::Quit)?;
+                                return Ok(ActionOutcome::Entries(entries));
+                            }
+                        }
+                    }
                     Action::ClearScreen => {
                         self.render_tx.send(RenderingTask::ClearScreen)?;
                     }
@@ -382,6 +731,13 @@ impl App {
                 if let Some(action) = self.television.update(&action)? {
                     self.action_tx.send(action)?;
                 };
+                // keybindings may have been edited
+                if matches!(action, Action::CaptureKey(_)) {
+                    self.keymap = Keymap::from(&merge_keybindings(
+                        self.television.config.keybindings.clone(),
+                        &self.passthrough_keybindings,
+                    ));
+                }
             }
         }
         Ok(ActionOutcome::None)
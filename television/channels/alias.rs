@@ -2,8 +2,8 @@ use std::collections::HashSet;
 
 use crate::channels::entry::Entry;
 use crate::channels::entry::PreviewType;
-use crate::channels::OnAir;
-use crate::matcher::{config::Config, injector::Injector, Matcher};
+use crate::channels::{ChannelConfig, OnAir};
+use crate::matcher::{injector::Injector, Matcher};
 use crate::utils::indices::sep_name_and_value_indices;
 use devicons::FileIcon;
 use rustc_hash::FxBuildHasher;
@@ -49,8 +49,10 @@ fn get_raw_aliases(shell: &str) -> Vec<String> {
 }
 
 impl Channel {
-    pub fn new() -> Self {
-        let matcher = Matcher::new(Config::default().n_threads(NUM_THREADS));
+    pub fn new(config: &ChannelConfig) -> Self {
+        let matcher = Matcher::new(
+            config.matcher_config("alias").n_threads(NUM_THREADS),
+        );
         let injector = matcher.injector();
         tokio::spawn(load_aliases(injector));
 
@@ -62,9 +64,9 @@ impl Channel {
     }
 }
 
-impl Default for Channel {
-    fn default() -> Self {
-        Self::new()
+impl From<&ChannelConfig> for Channel {
+    fn from(config: &ChannelConfig) -> Self {
+        Self::new(config)
     }
 }
 
@@ -73,6 +75,10 @@ impl OnAir for Channel {
         self.matcher.find(pattern);
     }
 
+    fn current_query(&self) -> &str {
+        &self.matcher.last_pattern
+    }
+
     fn results(&mut self, num_entries: u32, offset: u32) -> Vec<Entry> {
         self.matcher.tick();
         self.matcher
@@ -133,6 +139,11 @@ impl OnAir for Channel {
         }
     }
 
+    fn reset(&mut self) {
+        self.selected_entries.clear();
+        self.find("");
+    }
+
     fn result_count(&self) -> u32 {
         self.matcher.matched_item_count
     }
@@ -14,9 +14,12 @@ use rustc_hash::{FxBuildHasher, FxHashSet};
 use tracing::debug;
 
 use crate::channels::entry::{Entry, PreviewCommand, PreviewType};
-use crate::channels::OnAir;
+use crate::channels::{ChannelConfig, OnAir};
 use crate::matcher::Matcher;
-use crate::matcher::{config::Config, injector::Injector};
+use crate::matcher::{
+    config::{Config, MatchTarget},
+    injector::Injector,
+};
 use crate::utils::command::shell_command;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -30,35 +33,20 @@ pub enum PreviewKind {
 pub struct Channel {
     pub name: String,
     matcher: Matcher<String>,
+    /// The configuration the matcher is created with again on `reload`.
+    matcher_config: Config,
     entries_command: String,
     preview_kind: PreviewKind,
     selected_entries: FxHashSet<Entry>,
 }
 
-impl Default for Channel {
-    fn default() -> Self {
+impl From<&ChannelConfig> for Channel {
+    fn from(config: &ChannelConfig) -> Self {
         Self::new(
             "Files",
             "find . -type f",
             Some(PreviewCommand::new("bat -n --color=always {}", ":")),
-        )
-    }
-}
-
-impl From<CableChannelPrototype> for Channel {
-    fn from(prototype: CableChannelPrototype) -> Self {
-        Self::new(
-            &prototype.name,
-            &prototype.source_command,
-            match prototype.preview_command {
-                Some(command) => Some(PreviewCommand::new(
-                    &command,
-                    &prototype
-                        .preview_delimiter
-                        .unwrap_or(DEFAULT_DELIMITER.to_string()),
-                )),
-                None => None,
-            },
+            config,
         )
     }
 }
@@ -79,8 +67,10 @@ impl Channel {
         name: &str,
         entries_command: &str,
         preview_command: Option<PreviewCommand>,
+        config: &ChannelConfig,
     ) -> Self {
-        let matcher = Matcher::new(Config::default());
+        let matcher_config = config.matcher_config(name);
+        let matcher = Matcher::new(matcher_config.clone());
         let injector = matcher.injector();
         tokio::spawn(load_candidates(entries_command.to_string(), injector));
         let preview_kind = match preview_command {
@@ -94,12 +84,46 @@ impl Channel {
         debug!("Preview kind: {:?}", preview_kind);
         Self {
             matcher,
+            matcher_config,
             entries_command: entries_command.to_string(),
             preview_kind,
             name: name.to_string(),
             selected_entries: HashSet::with_hasher(FxBuildHasher),
         }
     }
+
+    pub fn from_prototype(
+        prototype: CableChannelPrototype,
+        config: &ChannelConfig,
+    ) -> Self {
+        Self::new(
+            &prototype.name,
+            &prototype.source_command,
+            match prototype.preview_command {
+                Some(command) => Some(PreviewCommand::new(
+                    &command,
+                    &prototype
+                        .preview_delimiter
+                        .unwrap_or(DEFAULT_DELIMITER.to_string()),
+                )),
+                None => None,
+            },
+            config,
+        )
+    }
+
+    /// Run the source command again, its output replacing the current
+    /// entries.
+    ///
+    /// The selected entries are kept, the query has to be matched again with
+    /// `find`.
+    pub fn reload(&mut self) {
+        self.matcher = Matcher::new(self.matcher_config.clone());
+        tokio::spawn(load_candidates(
+            self.entries_command.clone(),
+            self.matcher.injector(),
+        ));
+    }
 }
 
 #[allow(clippy::unused_async)]
@@ -148,6 +172,10 @@ impl OnAir for Channel {
         self.matcher.find(pattern);
     }
 
+    fn current_query(&self) -> &str {
+        &self.matcher.last_pattern
+    }
+
     fn results(&mut self, num_entries: u32, offset: u32) -> Vec<Entry> {
         self.matcher.tick();
         self.matcher
@@ -200,6 +228,11 @@ impl OnAir for Channel {
         }
     }
 
+    fn reset(&mut self) {
+        self.selected_entries.clear();
+        self.find("");
+    }
+
     fn result_count(&self) -> u32 {
         self.matcher.matched_item_count
     }
@@ -217,6 +250,13 @@ impl OnAir for Channel {
     fn supports_preview(&self) -> bool {
         self.preview_kind != PreviewKind::None
     }
+
+    fn set_match_target(&mut self, target: MatchTarget) -> bool {
+        self.matcher.set_target(target, |line, cols| {
+            cols[0] = line.as_str().into();
+        });
+        true
+    }
 }
 
 #[derive(Clone, Debug, serde::Deserialize, PartialEq)]
@@ -226,6 +266,9 @@ pub struct CableChannelPrototype {
     pub preview_command: Option<String>,
     #[serde(default = "default_delimiter")]
     pub preview_delimiter: Option<String>,
+    /// The number of entries listed when previewing the channel from the
+    /// remote control, overriding `ui.guide_preview_entries`.
+    pub guide_preview_entries: Option<u32>,
 }
 
 pub const DEFAULT_DELIMITER: &str = " ";
@@ -1,72 +1,98 @@
-use crate::channels::entry::{Entry, PreviewCommand, PreviewType};
-use crate::channels::{OnAir, TelevisionChannel};
-use crate::matcher::{config::Config, injector::Injector, Matcher};
+use crate::channels::entry::{Entry, EntryKind, PreviewCommand, PreviewType};
+use crate::channels::{ChannelConfig, OnAir, TelevisionChannel};
+use crate::matcher::{injector::Injector, Matcher};
 use crate::utils::files::{get_default_num_threads, walk_builder};
 use devicons::FileIcon;
 use rustc_hash::{FxBuildHasher, FxHashSet};
 use std::collections::HashSet;
 use std::path::PathBuf;
+use tokio::task::JoinHandle;
 
 pub struct Channel {
     matcher: Matcher<String>,
-    crawl_handle: tokio::task::JoinHandle<()>,
+    crawl_handles: Vec<JoinHandle<()>>,
     // PERF: cache results (to make deleting characters smoother) with
     // a shallow stack of sub-patterns as keys (e.g. "a", "ab", "abc")
     selected_entries: FxHashSet<Entry>,
 }
 
 impl Channel {
-    pub fn new(paths: Vec<PathBuf>) -> Self {
-        let matcher = Matcher::new(Config::default().match_paths(true));
-        // start loading files in the background
-        let crawl_handle = tokio::spawn(load_dirs(paths, matcher.injector()));
+    pub fn new(paths: Vec<PathBuf>, config: &ChannelConfig) -> Self {
+        Self::crawl(
+            paths,
+            Matcher::new(config.matcher_config("dirs").match_paths(true)),
+        )
+    }
+
+    /// Start crawling the given paths in the background.
+    fn crawl(paths: Vec<PathBuf>, matcher: Matcher<String>) -> Self {
+        let injector = matcher.injector();
+        let crawl_handle =
+            tokio::task::spawn_blocking(move || load_dirs(&paths, &injector));
         Channel {
             matcher,
-            crawl_handle,
+            crawl_handles: vec![crawl_handle],
             selected_entries: HashSet::with_hasher(FxBuildHasher),
         }
     }
+
+    /// A channel crawling the paths sent from the given channel.
+    pub fn from_channel(
+        source: &mut TelevisionChannel,
+        config: &ChannelConfig,
+    ) -> Self {
+        Self::crawl(
+            source_paths(source),
+            Matcher::new(config.sent_matcher_config("dirs").match_paths(true)),
+        )
+    }
+
+    /// Crawl the paths sent from the given channel on top of the ones
+    /// already loaded, skipping the directories already listed.
+    pub fn ingest(&mut self, source: &mut TelevisionChannel) {
+        let paths = source_paths(source);
+        let injector = self.matcher.injector();
+        self.crawl_handles
+            .push(tokio::task::spawn_blocking(move || {
+                load_dirs(&paths, &injector);
+            }));
+    }
 }
 
-impl Default for Channel {
-    fn default() -> Self {
-        Self::new(vec![std::env::current_dir().unwrap()])
+impl From<&ChannelConfig> for Channel {
+    fn from(config: &ChannelConfig) -> Self {
+        Self::new(vec![std::env::current_dir().unwrap()], config)
     }
 }
 
-impl From<&mut TelevisionChannel> for Channel {
-    fn from(value: &mut TelevisionChannel) -> Self {
-        match value {
-            c @ TelevisionChannel::GitRepos(_) => {
-                let entries = if c.selected_entries().is_empty() {
-                    c.results(c.result_count(), 0)
-                } else {
-                    c.selected_entries().iter().cloned().collect()
-                };
-                Self::new(
-                    entries
-                        .iter()
-                        .map(|entry| PathBuf::from(entry.name.clone()))
-                        .collect(),
-                )
-            }
-            c @ TelevisionChannel::Dirs(_) => {
-                let entries = if c.selected_entries().is_empty() {
-                    c.results(c.result_count(), 0)
-                } else {
-                    c.selected_entries().iter().cloned().collect()
-                };
-                Self::new(
-                    entries
-                        .iter()
-                        .map(|entry| PathBuf::from(&entry.name))
-                        .collect::<HashSet<_>>()
-                        .into_iter()
-                        .collect(),
-                )
-            }
-            _ => unreachable!(),
+/// The paths to crawl for the entries sent from the given channel.
+fn source_paths(value: &mut TelevisionChannel) -> Vec<PathBuf> {
+    match value {
+        c @ TelevisionChannel::GitRepos(_) => {
+            let entries = if c.selected_entries().is_empty() {
+                c.results(c.result_count(), 0)
+            } else {
+                c.selected_entries().iter().cloned().collect()
+            };
+            entries
+                .iter()
+                .map(|entry| PathBuf::from(entry.name.clone()))
+                .collect()
         }
+        c @ (TelevisionChannel::Dirs(_) | TelevisionChannel::Stdin(_)) => {
+            let entries = if c.selected_entries().is_empty() {
+                c.results(c.result_count(), 0)
+            } else {
+                c.selected_entries().iter().cloned().collect()
+            };
+            entries
+                .iter()
+                .map(|entry| PathBuf::from(&entry.name))
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect()
+        }
+        _ => unreachable!(),
     }
 }
 
@@ -81,6 +107,10 @@ impl OnAir for Channel {
         self.matcher.find(pattern);
     }
 
+    fn current_query(&self) -> &str {
+        &self.matcher.last_pattern
+    }
+
     fn results(&mut self, num_entries: u32, offset: u32) -> Vec<Entry> {
         self.matcher.tick();
         self.matcher
@@ -96,6 +126,7 @@ impl OnAir for Channel {
                     )),
                 )
                 .with_name_match_ranges(&item.match_indices)
+                .with_kind(EntryKind::Directory)
                 .with_icon(FileIcon::from(&path))
             })
             .collect()
@@ -112,6 +143,7 @@ impl OnAir for Channel {
                 )),
             )
             .with_icon(FileIcon::from(&path))
+            .with_kind(EntryKind::Directory)
         })
     }
 
@@ -127,6 +159,11 @@ impl OnAir for Channel {
         }
     }
 
+    fn reset(&mut self) {
+        self.selected_entries.clear();
+        self.find("");
+    }
+
     fn result_count(&self) -> u32 {
         self.matcher.matched_item_count
     }
@@ -136,20 +173,29 @@ impl OnAir for Channel {
     }
 
     fn running(&self) -> bool {
-        self.matcher.status.running
+        self.matcher.status.running && !self.matcher.paused()
     }
 
     fn shutdown(&self) {
-        self.crawl_handle.abort();
+        super::stop_crawls(&self.matcher, &self.crawl_handles);
     }
 
     fn supports_preview(&self) -> bool {
         true
     }
+
+    fn pause(&mut self) -> bool {
+        self.matcher.pause();
+        true
+    }
+
+    fn resume(&mut self) {
+        self.matcher.resume();
+    }
 }
 
-#[allow(clippy::unused_async)]
-async fn load_dirs(paths: Vec<PathBuf>, injector: Injector<String>) {
+/// Crawl the given paths, blocking until done (or resumed if paused).
+fn load_dirs(paths: &[PathBuf], injector: &Injector<String>) {
     if paths.is_empty() {
         return;
     }
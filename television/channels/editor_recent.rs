@@ -0,0 +1,414 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use devicons::FileIcon;
+use directories::BaseDirs;
+use rustc_hash::{FxBuildHasher, FxHashMap, FxHashSet};
+use tracing::debug;
+
+use crate::channels::entry::{Entry, PreviewType};
+use crate::channels::{ChannelConfig, OnAir};
+use crate::matcher::{injector::Injector, Matcher};
+
+/// Environment variable pointing to the editor history file to read from.
+///
+/// When unset, the channel falls back to the default locations of vim's
+/// `viminfo` and VS Code's `storage.json`.
+const EDITOR_HISTORY_ENV_VAR: &str = "TV_EDITOR_HISTORY";
+
+/// The format of an editor history file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryFormat {
+    /// Vim's `viminfo` file, where file marks look like `> /path/to/file`.
+    Viminfo,
+    /// VS Code's `storage.json`, which lists recently opened paths as
+    /// `file://` URIs.
+    VsCode,
+    /// One path per line, most recent first.
+    Plain,
+}
+
+impl HistoryFormat {
+    /// Guess the format of a history file from its name.
+    pub fn from_path(path: &Path) -> Self {
+        let file_name = path
+            .file_name()
+            .map(|f| f.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        if file_name.contains("viminfo") {
+            HistoryFormat::Viminfo
+        } else if is_json {
+            HistoryFormat::VsCode
+        } else {
+            HistoryFormat::Plain
+        }
+    }
+}
+
+/// Parse the contents of a history file into the paths it lists, most
+/// recent first.
+///
+/// Paths may appear several times, which is used for frecency ranking.
+pub fn parse_history(content: &str, format: HistoryFormat) -> Vec<String> {
+    match format {
+        HistoryFormat::Viminfo => content
+            .lines()
+            .filter_map(|line| line.strip_prefix('>'))
+            .map(|path| path.trim().to_string())
+            .filter(|path| !path.is_empty())
+            .collect(),
+        HistoryFormat::VsCode => parse_vscode_storage(content),
+        HistoryFormat::Plain => content
+            .lines()
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+            .map(String::from)
+            .collect(),
+    }
+}
+
+fn parse_vscode_storage(content: &str) -> Vec<String> {
+    let Ok(storage) = serde_json::from_str::<serde_json::Value>(content)
+    else {
+        debug!("Failed to parse VS Code storage");
+        return Vec::new();
+    };
+    storage
+        .pointer("/openedPathsList/entries")
+        .and_then(serde_json::Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.get("fileUri")?.as_str())
+        .filter_map(|uri| uri.strip_prefix("file://"))
+        .map(percent_decode)
+        .collect()
+}
+
+/// Decode the percent-encoded bytes of a URI path (e.g. `%20`).
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let Some(byte) = std::str::from_utf8(&bytes[i + 1..i + 3])
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Rank paths listed most recent first by frecency.
+///
+/// Each occurrence of a path is weighted by its recency, the most recent
+/// occurrence weighing the most, and a path's score is the sum of the
+/// weights of its occurrences so that frequently visited paths rank higher.
+/// Ties keep the order of the most recent occurrence.
+pub fn rank_by_frecency(paths: Vec<String>) -> Vec<String> {
+    let total = paths.len();
+    let mut scores: FxHashMap<String, (usize, usize)> = FxHashMap::default();
+    for (i, path) in paths.into_iter().enumerate() {
+        let score = scores.entry(path).or_insert((0, i));
+        score.0 += total - i;
+    }
+    let mut ranked: Vec<(String, (usize, usize))> =
+        scores.into_iter().collect();
+    ranked.sort_by(|(_, (score_a, first_a)), (_, (score_b, first_b))| {
+        score_b.cmp(score_a).then(first_a.cmp(first_b))
+    });
+    ranked.into_iter().map(|(path, _)| path).collect()
+}
+
+/// Expand a leading `~` to the user's home directory.
+fn expand_home(path: &str, home: Option<&Path>) -> PathBuf {
+    match (path.strip_prefix("~/"), home) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// The history files to read from, along with their format.
+fn history_sources() -> Vec<(PathBuf, HistoryFormat)> {
+    if let Some(path) = std::env::var_os(EDITOR_HISTORY_ENV_VAR) {
+        let path = PathBuf::from(path);
+        let format = HistoryFormat::from_path(&path);
+        return vec![(path, format)];
+    }
+    let Some(base_dirs) = BaseDirs::new() else {
+        return Vec::new();
+    };
+    vec![
+        (
+            base_dirs.home_dir().join(".viminfo"),
+            HistoryFormat::Viminfo,
+        ),
+        (
+            base_dirs
+                .config_dir()
+                .join("Code/User/globalStorage/storage.json"),
+            HistoryFormat::VsCode,
+        ),
+    ]
+}
+
+pub struct Channel {
+    matcher: Matcher<String>,
+    selected_entries: FxHashSet<Entry>,
+    crawl_handle: tokio::task::JoinHandle<()>,
+}
+
+impl Channel {
+    pub fn new(config: &ChannelConfig) -> Self {
+        let matcher = Matcher::new(
+            config.matcher_config("editor-recent").match_paths(true),
+        );
+        let crawl_handle = tokio::spawn(load_recent_files(
+            history_sources(),
+            matcher.injector(),
+        ));
+        Self {
+            matcher,
+            selected_entries: HashSet::with_hasher(FxBuildHasher),
+            crawl_handle,
+        }
+    }
+}
+
+impl From<&ChannelConfig> for Channel {
+    fn from(config: &ChannelConfig) -> Self {
+        Self::new(config)
+    }
+}
+
+fn build_entry(path: String) -> Entry {
+    let icon = FileIcon::from(&path);
+    Entry::new(path, PreviewType::Files).with_icon(icon)
+}
+
+impl OnAir for Channel {
+    fn find(&mut self, pattern: &str) {
+        self.matcher.find(pattern);
+    }
+
+    fn current_query(&self) -> &str {
+        &self.matcher.last_pattern
+    }
+
+    fn results(&mut self, num_entries: u32, offset: u32) -> Vec<Entry> {
+        self.matcher.tick();
+        self.matcher
+            .results(num_entries, offset)
+            .into_iter()
+            .map(|item| {
+                build_entry(item.matched_string)
+                    .with_name_match_ranges(&item.match_indices)
+            })
+            .collect()
+    }
+
+    fn get_result(&self, index: u32) -> Option<Entry> {
+        self.matcher
+            .get_result(index)
+            .map(|item| build_entry(item.matched_string))
+    }
+
+    fn selected_entries(&self) -> &FxHashSet<Entry> {
+        &self.selected_entries
+    }
+
+    fn toggle_selection(&mut self, entry: &Entry) {
+        if self.selected_entries.contains(entry) {
+            self.selected_entries.remove(entry);
+        } else {
+            self.selected_entries.insert(entry.clone());
+        }
+    }
+
+    fn reset(&mut self) {
+        self.selected_entries.clear();
+        self.find("");
+    }
+
+    fn result_count(&self) -> u32 {
+        self.matcher.matched_item_count
+    }
+
+    fn total_count(&self) -> u32 {
+        self.matcher.total_item_count
+    }
+
+    fn running(&self) -> bool {
+        self.matcher.status.running
+    }
+
+    fn shutdown(&self) {
+        self.crawl_handle.abort();
+    }
+
+    fn supports_preview(&self) -> bool {
+        true
+    }
+}
+
+#[allow(clippy::unused_async)]
+async fn load_recent_files(
+    sources: Vec<(PathBuf, HistoryFormat)>,
+    injector: Injector<String>,
+) {
+    let home = BaseDirs::new().map(|dirs| dirs.home_dir().to_path_buf());
+    let mut seen = HashSet::new();
+    for (source, format) in sources {
+        let content = match std::fs::read_to_string(&source) {
+            Ok(content) => content,
+            Err(e) => {
+                debug!("Unable to read editor history {:?}: {:?}", source, e);
+                continue;
+            }
+        };
+        rank_by_frecency(parse_history(&content, format))
+            .into_iter()
+            .map(|path| expand_home(&path, home.as_deref()))
+            .filter(|path| path.is_file())
+            .map(|path| path.to_string_lossy().into_owned())
+            .filter(|path| seen.insert(path.clone()))
+            .for_each(|path| {
+                let () = injector.push(path, |e, cols| {
+                    cols[0] = e.clone().into();
+                });
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matcher::config::Config;
+
+    const SAMPLE_VIMINFO: &str = "\
+# This viminfo file was generated by Vim 9.0.
+# File marks:
+'0  12  4  ~/projects/tv/main.rs
+|4,48,12,4,1700000000,\"~/projects/tv/main.rs\"
+
+# History of marks within files (newest to oldest):
+
+> ~/projects/tv/main.rs
+\t\"\t12\t4
+
+> /etc/hosts
+\t\"\t1\t0
+
+> ~/projects/tv/Cargo.toml
+\t\"\t3\t0
+
+> /etc/hosts
+\t\"\t2\t0
+";
+
+    #[test]
+    fn test_parse_viminfo() {
+        assert_eq!(
+            parse_history(SAMPLE_VIMINFO, HistoryFormat::Viminfo),
+            vec![
+                "~/projects/tv/main.rs",
+                "/etc/hosts",
+                "~/projects/tv/Cargo.toml",
+                "/etc/hosts",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_vscode_storage() {
+        let storage = r#"{
+            "openedPathsList": {
+                "entries": [
+                    {"fileUri": "file:///home/user/My%20Notes.md"},
+                    {"folderUri": "file:///home/user/projects"},
+                    {"fileUri": "file:///etc/hosts"}
+                ]
+            }
+        }"#;
+        assert_eq!(
+            parse_history(storage, HistoryFormat::VsCode),
+            vec!["/home/user/My Notes.md", "/etc/hosts"]
+        );
+        assert!(parse_history("not json", HistoryFormat::VsCode).is_empty());
+    }
+
+    #[test]
+    fn test_rank_by_frecency() {
+        let ranked = rank_by_frecency(parse_history(
+            SAMPLE_VIMINFO,
+            HistoryFormat::Viminfo,
+        ));
+        // /etc/hosts: 3 + 1, main.rs: 4, Cargo.toml: 2
+        assert_eq!(
+            ranked,
+            vec![
+                "~/projects/tv/main.rs",
+                "/etc/hosts",
+                "~/projects/tv/Cargo.toml"
+            ]
+        );
+        let ranked = rank_by_frecency(
+            ["a", "b", "c", "c", "c", "c"].map(String::from).to_vec(),
+        );
+        // a: 6, b: 5, c: 4 + 3 + 2 + 1
+        assert_eq!(ranked, vec!["c", "a", "b"]);
+    }
+
+    #[test]
+    fn test_history_format_from_path() {
+        assert_eq!(
+            HistoryFormat::from_path(Path::new("/home/user/.viminfo")),
+            HistoryFormat::Viminfo
+        );
+        assert_eq!(
+            HistoryFormat::from_path(Path::new("storage.json")),
+            HistoryFormat::VsCode
+        );
+        assert_eq!(
+            HistoryFormat::from_path(Path::new("recent.txt")),
+            HistoryFormat::Plain
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missing_history_file() {
+        let mut matcher = Matcher::new(Config::default());
+        load_recent_files(
+            vec![(
+                PathBuf::from("/nonexistent/.viminfo"),
+                HistoryFormat::Viminfo,
+            )],
+            matcher.injector(),
+        )
+        .await;
+        matcher.tick();
+        assert_eq!(matcher.total_item_count, 0);
+    }
+
+    #[test]
+    fn test_expand_home() {
+        assert_eq!(
+            expand_home("~/notes.md", Some(Path::new("/home/user"))),
+            PathBuf::from("/home/user/notes.md")
+        );
+        assert_eq!(
+            expand_home("/etc/hosts", Some(Path::new("/home/user"))),
+            PathBuf::from("/etc/hosts")
+        );
+    }
+}
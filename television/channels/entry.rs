@@ -4,8 +4,12 @@ use std::{
 };
 
 use devicons::FileIcon;
+use serde::{Deserialize, Serialize};
 use strum::EnumString;
 
+use crate::matcher::config::MatchTarget;
+use crate::utils::json::JsonFieldPath;
+
 // NOTE: having an enum for entry types would be nice since it would allow
 // having a nicer implementation for transitions between channels. This would
 // permit implementing `From<EntryType>` for channels which would make the
@@ -28,6 +32,31 @@ pub struct Entry {
     pub line_number: Option<usize>,
     /// The type of preview associated with the entry.
     pub preview_type: PreviewType,
+    /// An optional hidden text the matcher uses instead of the entry's name.
+    pub search_text: Option<String>,
+    /// What the entry represents, which may change how it is displayed.
+    pub kind: EntryKind,
+    /// Whether a preview can be generated for the entry, a placeholder being
+    /// shown instead when it can't.
+    pub previewable: bool,
+    /// The group the entry is listed under when results are grouped (see
+    /// `UiConfig::group_results`).
+    pub group: Option<String>,
+    /// A line of the entry's content matching the query, only displayed
+    /// next to the entry (see `AppConfig::content_snippets`).
+    pub snippet: Option<String>,
+}
+
+/// What an entry represents.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum EntryKind {
+    #[default]
+    Item,
+    /// A directory, displayed with a trailing slash.
+    Directory,
+    /// The header of a group of results, which can be collapsed to hide
+    /// them.
+    GroupHeader { collapsed: bool },
 }
 
 impl Hash for Entry {
@@ -106,6 +135,11 @@ impl Entry {
             icon: None,
             line_number: None,
             preview_type,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         }
     }
 
@@ -140,6 +174,72 @@ impl Entry {
         self
     }
 
+    pub fn with_kind(mut self, kind: EntryKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Mark the entry as (not) previewable, e.g. for directories or special
+    /// markers in channels otherwise previewing files.
+    pub fn with_previewable(mut self, previewable: bool) -> Self {
+        self.previewable = previewable;
+        self
+    }
+
+    /// Set the group the entry is listed under when results are grouped.
+    pub fn with_group(mut self, group: String) -> Self {
+        self.group = Some(group);
+        self
+    }
+
+    /// Set the line of the entry's content displayed next to it.
+    pub fn with_snippet(mut self, snippet: String) -> Self {
+        self.snippet = Some(snippet);
+        self
+    }
+
+    /// Set a hidden text to match against instead of the entry's name.
+    ///
+    /// This lets channels expose richer matchable metadata (e.g. tags) while
+    /// still displaying the entry's name.
+    pub fn with_search_text(mut self, search_text: String) -> Self {
+        self.search_text = Some(search_text);
+        self
+    }
+
+    /// The text the matcher should use for this entry: its search text if
+    /// present, its name otherwise.
+    pub fn matchable_text(&self) -> &str {
+        self.search_text.as_deref().unwrap_or(&self.name)
+    }
+
+    /// The text the matcher should use for this entry given what is matched,
+    /// its name when only the visible text is (its escape sequences being
+    /// dropped when pushed).
+    pub fn target_text(&self, target: MatchTarget) -> &str {
+        match target {
+            MatchTarget::Full => self.matchable_text(),
+            MatchTarget::Visible => &self.name,
+        }
+    }
+
+    /// The lines the entry's name spans in the results list.
+    ///
+    /// Most entries fit on a single line but some sources (e.g. commit
+    /// messages) yield names containing line feeds, each of which starts a
+    /// new line. A trailing line feed doesn't.
+    pub fn display_lines(&self) -> std::str::Split<'_, char> {
+        self.name
+            .strip_suffix('\n')
+            .unwrap_or(&self.name)
+            .split('\n')
+    }
+
+    /// The number of rows the entry's name takes in the results list.
+    pub fn display_height(&self) -> usize {
+        self.display_lines().count()
+    }
+
     pub fn stdout_repr(&self) -> String {
         let mut repr = self.name.clone();
         if let Some(line_number) = self.line_number {
@@ -149,6 +249,77 @@ impl Entry {
     }
 }
 
+/// Which part of the accepted entries is output.
+///
+/// The name of an entry is its underlying value (e.g. the path of a file or
+/// the name of an environment variable), which is what accepting it outputs
+/// by default. Its value is the extra data some entries carry on top of it
+/// (e.g. the value of an environment variable or a matched line of text).
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize,
+)]
+#[serde(try_from = "String", into = "String")]
+pub enum OutputField {
+    /// The name of the entry, e.g. a path, followed by its line number if
+    /// any (see `Entry::stdout_repr`).
+    #[default]
+    Name,
+    /// What the results list shows: the name followed by the value if any.
+    Display,
+    /// The value carried by the entry (e.g. an environment variable's
+    /// value), the name if it has none.
+    Value,
+    /// A field of the entry's value parsed as JSON, given as a path such as
+    /// `.user.name`, the name if there is no such field.
+    JsonField(String),
+}
+
+impl TryFrom<String> for OutputField {
+    type Error = String;
+
+    fn try_from(field: String) -> Result<Self, Self::Error> {
+        match field.as_str() {
+            "name" => Ok(OutputField::Name),
+            "display" => Ok(OutputField::Display),
+            "value" => Ok(OutputField::Value),
+            _ if field.starts_with('.') => Ok(OutputField::JsonField(field)),
+            _ => Err(format!(
+                "invalid output field {field:?}, expected \"name\", \
+                \"display\", \"value\" or a JSON field path like \".name\""
+            )),
+        }
+    }
+}
+
+impl From<OutputField> for String {
+    fn from(field: OutputField) -> Self {
+        match field {
+            OutputField::Name => String::from("name"),
+            OutputField::Display => String::from("display"),
+            OutputField::Value => String::from("value"),
+            OutputField::JsonField(path) => path,
+        }
+    }
+}
+
+impl Entry {
+    /// The given part of the entry, as output when it is accepted.
+    pub fn output(&self, field: &OutputField) -> String {
+        match (field, &self.value) {
+            (OutputField::Display, Some(value)) => {
+                format!("{} {value}", self.name)
+            }
+            (OutputField::Value, Some(value)) => value.clone(),
+            (OutputField::JsonField(path), Some(value)) => {
+                JsonFieldPath::parse(path)
+                    .extract(value)
+                    .unwrap_or_else(|| self.stdout_repr())
+            }
+            _ => self.stdout_repr(),
+        }
+    }
+}
+
 pub const ENTRY_PLACEHOLDER: Entry = Entry {
     name: String::new(),
     value: None,
@@ -157,6 +328,11 @@ pub const ENTRY_PLACEHOLDER: Entry = Entry {
     icon: None,
     line_number: None,
     preview_type: PreviewType::EnvVar,
+    search_text: None,
+    kind: EntryKind::Item,
+    previewable: true,
+    group: None,
+    snippet: None,
 };
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
@@ -196,6 +372,56 @@ pub enum PreviewType {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_output_field() {
+        let entry = Entry::new(String::from("alice"), PreviewType::Basic)
+            .with_value(String::from(
+                r#"{"name": "alice", "team": {"name": "core"}}"#,
+            ))
+            .with_line_number(3);
+
+        assert_eq!(entry.output(&OutputField::Name), "alice:3");
+        assert_eq!(
+            entry.output(&OutputField::Display),
+            r#"alice {"name": "alice", "team": {"name": "core"}}"#
+        );
+        assert_eq!(
+            entry.output(&OutputField::Value),
+            r#"{"name": "alice", "team": {"name": "core"}}"#
+        );
+        assert_eq!(
+            entry.output(&OutputField::JsonField(String::from(".team.name"))),
+            "core"
+        );
+        // entries lacking the field fall back to their name
+        assert_eq!(
+            entry.output(&OutputField::JsonField(String::from(".missing"))),
+            "alice:3"
+        );
+        let entry =
+            Entry::new(String::from("src/main.rs"), PreviewType::Files);
+        assert_eq!(entry.output(&OutputField::Value), "src/main.rs");
+        assert_eq!(entry.output(&OutputField::Display), "src/main.rs");
+    }
+
+    #[test]
+    fn test_deserialize_output_field() {
+        #[derive(Deserialize)]
+        struct Config {
+            field: OutputField,
+        }
+        let field = |raw: &str| {
+            toml::from_str::<Config>(&format!("field = {raw:?}"))
+                .map(|config| config.field)
+        };
+        assert_eq!(field("value").unwrap(), OutputField::Value);
+        assert_eq!(
+            field(".user.name").unwrap(),
+            OutputField::JsonField(String::from(".user.name"))
+        );
+        assert!(field("path").is_err());
+    }
+
     #[test]
     fn test_empty_input() {
         let ranges: Vec<(u32, u32)> = vec![];
@@ -230,6 +456,11 @@ mod tests {
             icon: None,
             line_number: None,
             preview_type: PreviewType::Basic,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         };
         assert_eq!(entry.stdout_repr(), "test name with spaces");
     }
@@ -244,7 +475,22 @@ mod tests {
             icon: None,
             line_number: Some(a),
             preview_type: PreviewType::Basic,
+            search_text: None,
+            kind: EntryKind::Item,
+            previewable: true,
+            group: None,
+            snippet: None,
         };
         assert_eq!(entry.stdout_repr(), "test_file_name.rs:10");
     }
+
+    #[test]
+    fn test_matchable_text() {
+        let entry = Entry::new("name".to_string(), PreviewType::Basic);
+        assert_eq!(entry.matchable_text(), "name");
+
+        let entry = entry.with_search_text("hidden tags".to_string());
+        assert_eq!(entry.matchable_text(), "hidden tags");
+        assert_eq!(entry.name, "name");
+    }
 }
@@ -1,11 +1,17 @@
 use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use devicons::FileIcon;
-use rustc_hash::{FxBuildHasher, FxHashSet};
+use regex::Regex;
+use rustc_hash::{FxBuildHasher, FxHashMap, FxHashSet, FxHasher};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
 
-use super::OnAir;
+use super::{ChannelConfig, OnAir};
 use crate::channels::entry::{Entry, PreviewType};
-use crate::matcher::{config::Config, Matcher};
+use crate::matcher::Matcher;
 use crate::utils::indices::sep_name_and_value_indices;
 
 #[derive(Debug, Clone)]
@@ -14,6 +20,159 @@ struct EnvVar {
     value: String,
 }
 
+/// How the variables are ordered while the query is empty.
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum EnvSort {
+    /// Alphabetically by name.
+    #[default]
+    Name,
+    /// From the shortest value to the longest one, then by name.
+    ValueLength,
+    /// From the most recently changed value to the least recently changed
+    /// one, then by name.
+    ///
+    /// Changes are tracked across runs in the data directory, so the first
+    /// run lists the variables by name.
+    RecentlyChanged,
+}
+
+/// Which variables the env channel lists and in which order.
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize,
+)]
+#[serde(default)]
+pub struct EnvOptions {
+    pub sort: EnvSort,
+    /// A regular expression matching the names of the variables to leave
+    /// out (e.g. `^(LS_COLORS|_)$`).
+    pub exclude: Option<String>,
+    /// The maximum number of variables listed, the first ones in `sort`
+    /// order being kept.
+    pub limit: Option<usize>,
+}
+
+impl EnvOptions {
+    /// The variables to list, in order.
+    fn apply(
+        &self,
+        vars: impl IntoIterator<Item = (String, String)>,
+        data_dir: Option<&Path>,
+    ) -> Vec<(String, String)> {
+        let exclude = self.exclude.as_deref().and_then(|pattern| {
+            Regex::new(pattern)
+                .inspect_err(|e| {
+                    warn!("Invalid env exclude pattern {:?}: {}", pattern, e);
+                })
+                .ok()
+        });
+        let mut vars = vars
+            .into_iter()
+            .filter(|(name, _)| {
+                !exclude.as_ref().is_some_and(|re| re.is_match(name))
+            })
+            .collect::<Vec<_>>();
+        match self.sort {
+            EnvSort::Name => vars.sort(),
+            EnvSort::ValueLength => {
+                vars.sort_by(|(a, a_value), (b, b_value)| {
+                    a_value.len().cmp(&b_value.len()).then_with(|| a.cmp(b))
+                });
+            }
+            EnvSort::RecentlyChanged => {
+                let changed_at = data_dir
+                    .map(|data_dir| record_changes(data_dir, &vars, now()))
+                    .unwrap_or_default();
+                sort_by_recent_change(&mut vars, &changed_at);
+            }
+        }
+        vars.truncate(self.limit.unwrap_or(usize::MAX));
+        vars
+    }
+}
+
+/// The name of the file, in the data directory, recording when the value of
+/// each variable last changed.
+const CHANGES_FILE_NAME: &str = "env_changes.json";
+
+/// When the value of a variable last changed.
+///
+/// Only a hash of the value is kept so that no secret ends up on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+struct Change {
+    value_hash: u64,
+    changed_at: u64,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+fn value_hash(value: &str) -> u64 {
+    let mut hasher = FxHasher::default();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Compare `vars` with the values recorded in `data_dir`, record the new and
+/// changed ones as changed at `now` and return when each variable last
+/// changed.
+fn record_changes(
+    data_dir: &Path,
+    vars: &[(String, String)],
+    now: u64,
+) -> FxHashMap<String, u64> {
+    let path = data_dir.join(CHANGES_FILE_NAME);
+    let previous: FxHashMap<String, Change> = std::fs::read(&path)
+        .map_err(anyhow::Error::from)
+        .and_then(|content| Ok(serde_json::from_slice(&content)?))
+        .inspect_err(|e| debug!("Unable to read {:?}: {:?}", path, e))
+        .unwrap_or_default();
+    // variables that are no longer set are forgotten
+    let changes = vars
+        .iter()
+        .map(|(name, value)| {
+            let value_hash = value_hash(value);
+            let change = match previous.get(name) {
+                Some(change) if change.value_hash == value_hash => *change,
+                _ => Change {
+                    value_hash,
+                    changed_at: now,
+                },
+            };
+            (name.clone(), change)
+        })
+        .collect::<FxHashMap<_, _>>();
+    if let Err(e) = std::fs::create_dir_all(data_dir)
+        .map_err(anyhow::Error::from)
+        .and_then(|()| {
+            Ok(std::fs::write(&path, serde_json::to_vec(&changes)?)?)
+        })
+    {
+        debug!("Unable to write {:?}: {:?}", path, e);
+    }
+    changes
+        .into_iter()
+        .map(|(name, change)| (name, change.changed_at))
+        .collect()
+}
+
+fn sort_by_recent_change(
+    vars: &mut [(String, String)],
+    changed_at: &FxHashMap<String, u64>,
+) {
+    vars.sort_by(|(a, _), (b, _)| {
+        changed_at
+            .get(b)
+            .cmp(&changed_at.get(a))
+            .then_with(|| a.cmp(b))
+    });
+}
+
 #[allow(clippy::module_name_repetitions)]
 pub struct Channel {
     matcher: Matcher<EnvVar>,
@@ -25,19 +184,25 @@ const NUM_THREADS: usize = 1;
 const FILE_ICON_STR: &str = "config";
 
 impl Channel {
-    pub fn new() -> Self {
-        let matcher = Matcher::new(Config::default().n_threads(NUM_THREADS));
+    pub fn new(config: &ChannelConfig) -> Self {
+        Self::with_vars(config, &config.env, std::env::vars())
+    }
+
+    /// A channel listing the given variables according to `options`.
+    fn with_vars(
+        config: &ChannelConfig,
+        options: &EnvOptions,
+        vars: impl IntoIterator<Item = (String, String)>,
+    ) -> Self {
+        let matcher =
+            Matcher::new(config.matcher_config("env").n_threads(NUM_THREADS));
         let injector = matcher.injector();
-        for (name, value) in std::env::vars() {
-            let () = injector.push(
-                EnvVar {
-                    name: name.clone(),
-                    value: value.clone(),
-                },
-                |e, cols| {
-                    cols[0] = (e.name.clone() + &e.value).into();
-                },
-            );
+        // the variables are listed in the order they are pushed while the
+        // query is empty
+        for (name, value) in options.apply(vars, config.data_dir.as_deref()) {
+            let () = injector.push(EnvVar { name, value }, |e, cols| {
+                cols[0] = (e.name.clone() + &e.value).into();
+            });
         }
         Channel {
             matcher,
@@ -47,9 +212,9 @@ impl Channel {
     }
 }
 
-impl Default for Channel {
-    fn default() -> Self {
-        Self::new()
+impl From<&ChannelConfig> for Channel {
+    fn from(config: &ChannelConfig) -> Self {
+        Self::new(config)
     }
 }
 
@@ -58,6 +223,10 @@ impl OnAir for Channel {
         self.matcher.find(pattern);
     }
 
+    fn current_query(&self) -> &str {
+        &self.matcher.last_pattern
+    }
+
     fn results(&mut self, num_entries: u32, offset: u32) -> Vec<Entry> {
         self.matcher.tick();
         self.matcher
@@ -118,6 +287,11 @@ impl OnAir for Channel {
         }
     }
 
+    fn reset(&mut self) {
+        self.selected_entries.clear();
+        self.find("");
+    }
+
     fn result_count(&self) -> u32 {
         self.matcher.matched_item_count
     }
@@ -136,3 +310,90 @@ impl OnAir for Channel {
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listed_names(options: &EnvOptions) -> Vec<String> {
+        let vars = [("PATH", "/usr/bin:/bin"), ("EDITOR", "vim"), ("_", "tv")]
+            .map(|(name, value)| (name.to_string(), value.to_string()));
+        let mut channel =
+            Channel::with_vars(&ChannelConfig::default(), options, vars);
+        channel.matcher.tick();
+        while channel.matcher.status.running {
+            channel.matcher.tick();
+        }
+        channel
+            .results(10, 0)
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect()
+    }
+
+    #[test]
+    fn test_configured_sort_orders_variables() {
+        assert_eq!(
+            listed_names(&EnvOptions::default()),
+            ["EDITOR", "PATH", "_"]
+        );
+        assert_eq!(
+            listed_names(&EnvOptions {
+                sort: EnvSort::ValueLength,
+                ..Default::default()
+            }),
+            ["_", "EDITOR", "PATH"]
+        );
+    }
+
+    #[test]
+    fn test_excluded_and_limited_variables() {
+        assert_eq!(
+            listed_names(&EnvOptions {
+                exclude: Some(String::from("^_$")),
+                limit: Some(1),
+                ..Default::default()
+            }),
+            ["EDITOR"]
+        );
+        // invalid patterns are ignored
+        assert_eq!(
+            listed_names(&EnvOptions {
+                exclude: Some(String::from("(")),
+                ..Default::default()
+            }),
+            ["EDITOR", "PATH", "_"]
+        );
+    }
+
+    #[test]
+    fn test_recently_changed_variables_are_listed_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let vars = |editor: &str| {
+            [("PATH", "/usr/bin:/bin"), ("EDITOR", editor), ("_", "tv")]
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+        };
+        let mut listed = vars("vim");
+        let changed_at = record_changes(dir.path(), &listed, 10);
+        sort_by_recent_change(&mut listed, &changed_at);
+        assert_eq!(listed.map(|(name, _)| name), ["EDITOR", "PATH", "_"]);
+
+        let mut listed = vars("nano");
+        let changed_at = record_changes(dir.path(), &listed, 20);
+        assert_eq!(changed_at["EDITOR"], 20);
+        assert_eq!(changed_at["PATH"], 10);
+        sort_by_recent_change(&mut listed, &changed_at);
+        assert_eq!(listed.map(|(name, _)| name), ["EDITOR", "PATH", "_"]);
+
+        let mut listed = vars("nano");
+        listed[2].1 = String::from("ls");
+        let changed_at = record_changes(dir.path(), &listed, 30);
+        sort_by_recent_change(&mut listed, &changed_at);
+        assert_eq!(listed.map(|(name, _)| name), ["_", "EDITOR", "PATH"]);
+        // values are not written to disk
+        let recorded =
+            std::fs::read_to_string(dir.path().join(CHANGES_FILE_NAME))
+                .unwrap();
+        assert!(!recorded.contains("nano"));
+    }
+}
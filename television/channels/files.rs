@@ -1,96 +1,279 @@
 use crate::channels::entry::{Entry, PreviewType};
-use crate::channels::{OnAir, TelevisionChannel};
-use crate::matcher::{config::Config, injector::Injector, Matcher};
+use crate::channels::{ChannelConfig, OnAir, TelevisionChannel};
+use crate::matcher::{config::Dedup, injector::Injector, Matcher};
 use crate::utils::files::{get_default_num_threads, walk_builder};
 use devicons::FileIcon;
-use rustc_hash::{FxBuildHasher, FxHashSet};
+use ignore::overrides::{Override, OverrideBuilder};
+use parking_lot::Mutex;
+use rustc_hash::{FxBuildHasher, FxHashMap, FxHashSet};
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+/// The globs of the paths skipped by the channel on top of the gitignore
+/// rules when not configured otherwise.
+pub const DEFAULT_IGNORE_PATTERNS: &[&str] =
+    &["node_modules", "__pycache__", ".git"];
+
+/// The number of bytes of each file searched for content snippets.
+pub const MAX_SNIPPET_FILE_SIZE: u64 = 1024 * 1024;
+
+/// The first line of `content` containing every word of the query, ignoring
+/// case, trimmed.
+///
+/// The matcher's syntax is ignored: negated terms are skipped and the
+/// exact, prefix and suffix markers are stripped from the others.
+///
+/// # Example
+/// ```
+/// use television::channels::files::content_snippet;
+///
+/// let content = "fn main() {\n    println!(\"Hello, World!\");\n}\n";
+/// assert_eq!(
+///     content_snippet(content, "hello world"),
+///     Some("println!(\"Hello, World!\");")
+/// );
+/// assert_eq!(content_snippet(content, "goodbye"), None);
+/// ```
+pub fn content_snippet<'a>(content: &'a str, query: &str) -> Option<&'a str> {
+    let terms = query
+        .split_whitespace()
+        .filter(|term| !term.starts_with('!'))
+        .map(|term| {
+            term.trim_start_matches(['\'', '^'])
+                .trim_end_matches('$')
+                .to_lowercase()
+        })
+        .filter(|term| !term.is_empty())
+        .collect::<Vec<_>>();
+    if terms.is_empty() {
+        return None;
+    }
+    content
+        .lines()
+        .find(|line| {
+            let line = line.to_lowercase();
+            terms.iter().all(|term| line.contains(term.as_str()))
+        })
+        .map(str::trim)
+}
+
+/// The content snippet of the file at `path` for the query, only the first
+/// `MAX_SNIPPET_FILE_SIZE` bytes of text files being searched.
+fn file_content_snippet(path: &str, query: &str) -> Option<String> {
+    let mut buffer = Vec::new();
+    std::fs::File::open(path)
+        .and_then(|file| {
+            file.take(MAX_SNIPPET_FILE_SIZE).read_to_end(&mut buffer)
+        })
+        .ok()?;
+    // skip binary files
+    if buffer.contains(&0) {
+        return None;
+    }
+    content_snippet(&String::from_utf8_lossy(&buffer), query)
+        .map(ToString::to_string)
+}
+
+/// The directory a file is listed under when results are grouped.
+fn parent_group(path: &str) -> String {
+    match Path::new(path).parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => {
+            parent.to_string_lossy().to_string()
+        }
+        _ => String::from("."),
+    }
+}
+
+/// The content snippets of the files searched for a query, filled in the
+/// background.
+#[derive(Default)]
+struct Snippets {
+    query: String,
+    by_path: FxHashMap<String, Option<String>>,
+}
 
 pub struct Channel {
     matcher: Matcher<String>,
-    crawl_handle: tokio::task::JoinHandle<()>,
+    crawl_handles: Vec<JoinHandle<()>>,
+    /// The globs of the paths skipped when crawling, on top of the
+    /// gitignore rules.
+    ignore_patterns: Vec<String>,
     // PERF: cache results (to make deleting characters smoother) with
     // a shallow stack of sub-patterns as keys (e.g. "a", "ab", "abc")
     selected_entries: FxHashSet<Entry>,
+    content_snippets: bool,
+    /// The content snippets of the files searched for the typed query.
+    snippets: Arc<Mutex<Snippets>>,
+    /// The files the content snippets were looked for in for the typed
+    /// query.
+    requested_snippets: FxHashSet<String>,
+    /// Whether the entries are annotated with the directory they're grouped
+    /// under.
+    group_results: bool,
 }
 
 impl Channel {
-    pub fn new(paths: Vec<PathBuf>) -> Self {
-        let matcher = Matcher::new(Config::default().match_paths(true));
-        // start loading files in the background
-        let crawl_handle = tokio::spawn(load_files(paths, matcher.injector()));
+    pub fn new(paths: Vec<PathBuf>, config: &ChannelConfig) -> Self {
+        let matcher =
+            Matcher::new(config.matcher_config("files").match_paths(true));
+        Self::crawl(paths, matcher, config)
+    }
+
+    /// Start crawling the given paths in the background.
+    fn crawl(
+        paths: Vec<PathBuf>,
+        matcher: Matcher<String>,
+        config: &ChannelConfig,
+    ) -> Self {
+        let ignore_patterns = config.ignore_patterns.clone();
+        let injector = matcher.injector();
+        let crawl_handle = tokio::task::spawn_blocking(move || {
+            load_files(&paths, &ignore_patterns, &injector);
+        });
         Channel {
             matcher,
-            crawl_handle,
+            crawl_handles: vec![crawl_handle],
+            ignore_patterns: config.ignore_patterns.clone(),
             selected_entries: HashSet::with_hasher(FxBuildHasher),
+            content_snippets: config.content_snippets,
+            snippets: Arc::default(),
+            requested_snippets: FxHashSet::default(),
+            group_results: config.group_results,
         }
     }
-}
 
-impl Default for Channel {
-    fn default() -> Self {
-        Self::new(vec![std::env::current_dir().unwrap()])
+    /// The entry listing the file at `path`.
+    fn entry(&self, path: String) -> Entry {
+        let icon = FileIcon::from(&path);
+        let group = self.group_results.then(|| parent_group(&path));
+        let entry = Entry::new(path, PreviewType::Files).with_icon(icon);
+        match group {
+            Some(group) => entry.with_group(group),
+            None => entry,
+        }
     }
-}
 
-impl From<&mut TelevisionChannel> for Channel {
-    fn from(value: &mut TelevisionChannel) -> Self {
-        match value {
-            c @ TelevisionChannel::GitRepos(_) => {
-                let entries = if c.selected_entries().is_empty() {
-                    c.results(c.result_count(), 0)
-                } else {
-                    c.selected_entries().iter().cloned().collect()
-                };
-                Self::new(
-                    entries
-                        .iter()
-                        .map(|entry| PathBuf::from(entry.name.clone()))
-                        .collect(),
-                )
-            }
-            c @ TelevisionChannel::Files(_) => {
-                let entries = if c.selected_entries().is_empty() {
-                    c.results(c.result_count(), 0)
-                } else {
-                    c.selected_entries().iter().cloned().collect()
-                };
-                Self::new(
-                    entries
-                        .iter()
-                        .map(|entry| PathBuf::from(entry.name.clone()))
-                        .collect(),
-                )
-            }
-            c @ TelevisionChannel::Text(_) => {
-                let entries = if c.selected_entries().is_empty() {
-                    c.results(c.result_count(), 0)
-                } else {
-                    c.selected_entries().iter().cloned().collect()
-                };
-                Self::new(
-                    entries
-                        .iter()
-                        .map(|entry| PathBuf::from(&entry.name))
-                        .collect::<FxHashSet<_>>()
-                        .into_iter()
-                        .collect(),
-                )
+    /// Annotate the entries with the content snippets of their files found
+    /// so far, the files not searched yet being searched in the background.
+    ///
+    /// Each file is read at most once per query.
+    fn attach_snippets(&mut self, entries: &mut [Entry]) {
+        let snippets = self.snippets.lock();
+        if snippets.query.trim().is_empty() {
+            return;
+        }
+        let mut paths = Vec::new();
+        for entry in entries {
+            match snippets.by_path.get(&entry.name) {
+                Some(snippet) => entry.snippet.clone_from(snippet),
+                None => {
+                    if self.requested_snippets.insert(entry.name.clone()) {
+                        paths.push(entry.name.clone());
+                    }
+                }
             }
-            c @ TelevisionChannel::Dirs(_) => {
-                let entries = c.results(c.result_count(), 0);
-                Self::new(
-                    entries
-                        .iter()
-                        .map(|entry| PathBuf::from(&entry.name))
-                        .collect::<FxHashSet<_>>()
-                        .into_iter()
-                        .collect(),
-                )
+        }
+        if paths.is_empty() {
+            return;
+        }
+        let query = snippets.query.clone();
+        drop(snippets);
+        let snippets = self.snippets.clone();
+        tokio::task::spawn_blocking(move || {
+            for path in paths {
+                let snippet = file_content_snippet(&path, &query);
+                let mut snippets = snippets.lock();
+                // the query changed meanwhile
+                if snippets.query != query {
+                    return;
+                }
+                snippets.by_path.insert(path, snippet);
             }
-            _ => unreachable!(),
+        });
+    }
+
+    /// A channel crawling the paths sent from the given channel.
+    pub fn from_channel(
+        source: &mut TelevisionChannel,
+        config: &ChannelConfig,
+    ) -> Self {
+        let matcher = Matcher::new(
+            config.sent_matcher_config("files").match_paths(true),
+        );
+        Self::crawl(source_paths(source), matcher, config)
+    }
+
+    /// Crawl the paths sent from the given channel on top of the ones
+    /// already loaded, skipping the files already listed.
+    pub fn ingest(&mut self, source: &mut TelevisionChannel) {
+        let paths = source_paths(source);
+        let ignore_patterns = self.ignore_patterns.clone();
+        let injector = self.matcher.injector();
+        self.crawl_handles
+            .push(tokio::task::spawn_blocking(move || {
+                load_files(&paths, &ignore_patterns, &injector);
+            }));
+    }
+}
+
+impl From<&ChannelConfig> for Channel {
+    fn from(config: &ChannelConfig) -> Self {
+        Self::new(vec![std::env::current_dir().unwrap()], config)
+    }
+}
+
+/// The paths to crawl for the entries sent from the given channel.
+fn source_paths(value: &mut TelevisionChannel) -> Vec<PathBuf> {
+    match value {
+        c @ TelevisionChannel::GitRepos(_) => {
+            let entries = if c.selected_entries().is_empty() {
+                c.results(c.result_count(), 0)
+            } else {
+                c.selected_entries().iter().cloned().collect()
+            };
+            entries
+                .iter()
+                .map(|entry| PathBuf::from(entry.name.clone()))
+                .collect()
+        }
+        c @ (TelevisionChannel::Files(_) | TelevisionChannel::Stdin(_)) => {
+            let entries = if c.selected_entries().is_empty() {
+                c.results(c.result_count(), 0)
+            } else {
+                c.selected_entries().iter().cloned().collect()
+            };
+            entries
+                .iter()
+                .map(|entry| PathBuf::from(entry.name.clone()))
+                .collect()
+        }
+        c @ TelevisionChannel::Text(_) => {
+            let entries = if c.selected_entries().is_empty() {
+                c.results(c.result_count(), 0)
+            } else {
+                c.selected_entries().iter().cloned().collect()
+            };
+            entries
+                .iter()
+                .map(|entry| PathBuf::from(&entry.name))
+                .collect::<FxHashSet<_>>()
+                .into_iter()
+                .collect()
+        }
+        c @ TelevisionChannel::Dirs(_) => {
+            let entries = c.results(c.result_count(), 0);
+            entries
+                .iter()
+                .map(|entry| PathBuf::from(&entry.name))
+                .collect::<FxHashSet<_>>()
+                .into_iter()
+                .collect()
         }
+        _ => unreachable!(),
     }
 }
 
@@ -99,26 +282,31 @@ impl OnAir for Channel {
         self.matcher.find(pattern);
     }
 
+    fn current_query(&self) -> &str {
+        &self.matcher.last_pattern
+    }
+
     fn results(&mut self, num_entries: u32, offset: u32) -> Vec<Entry> {
         self.matcher.tick();
-        self.matcher
+        let mut entries = self
+            .matcher
             .results(num_entries, offset)
             .into_iter()
             .map(|item| {
-                let path = item.matched_string;
-                Entry::new(path.clone(), PreviewType::Files)
+                self.entry(item.matched_string)
                     .with_name_match_ranges(&item.match_indices)
-                    .with_icon(FileIcon::from(&path))
             })
-            .collect()
+            .collect::<Vec<_>>();
+        if self.content_snippets {
+            self.attach_snippets(&mut entries);
+        }
+        entries
     }
 
     fn get_result(&self, index: u32) -> Option<Entry> {
-        self.matcher.get_result(index).map(|item| {
-            let path = item.matched_string;
-            Entry::new(path.clone(), PreviewType::Files)
-                .with_icon(FileIcon::from(&path))
-        })
+        self.matcher
+            .get_result(index)
+            .map(|item| self.entry(item.matched_string))
     }
 
     fn selected_entries(&self) -> &FxHashSet<Entry> {
@@ -133,6 +321,11 @@ impl OnAir for Channel {
         }
     }
 
+    fn reset(&mut self) {
+        self.selected_entries.clear();
+        self.find("");
+    }
+
     fn result_count(&self) -> u32 {
         self.matcher.matched_item_count
     }
@@ -142,26 +335,98 @@ impl OnAir for Channel {
     }
 
     fn running(&self) -> bool {
-        self.matcher.status.running
+        self.matcher.status.running && !self.matcher.paused()
     }
 
     fn shutdown(&self) {
-        self.crawl_handle.abort();
+        super::stop_crawls(&self.matcher, &self.crawl_handles);
     }
 
     fn supports_preview(&self) -> bool {
         true
     }
+
+    fn pause(&mut self) -> bool {
+        self.matcher.pause();
+        true
+    }
+
+    fn resume(&mut self) {
+        self.matcher.resume();
+    }
+
+    fn set_typed_query(&mut self, query: &str) {
+        let mut snippets = self.snippets.lock();
+        if snippets.query != query {
+            query.clone_into(&mut snippets.query);
+            snippets.by_path.clear();
+            self.requested_snippets.clear();
+        }
+    }
+}
+
+/// Compile the ignore globs into walker overrides, skipping invalid globs.
+fn build_ignore_overrides(
+    root: &Path,
+    patterns: &[String],
+) -> Option<Override> {
+    if patterns.is_empty() {
+        return None;
+    }
+    let mut builder = OverrideBuilder::new(root);
+    for pattern in patterns {
+        if let Err(e) = builder.add(&format!("!{pattern}")) {
+            warn!("Invalid ignore pattern {:?}: {:?}", pattern, e);
+        }
+    }
+    builder.build().ok()
+}
+
+/// The path a walked file is deduplicated by: its path under the canonical
+/// form of the crawled path it was found under.
+fn dedup_key(path: &Path, canonical_roots: &[(PathBuf, PathBuf)]) -> PathBuf {
+    canonical_roots
+        .iter()
+        .filter(|(root, _)| path.starts_with(root))
+        .max_by_key(|(root, _)| root.components().count())
+        .and_then(|(root, canonical)| {
+            let relative = path.strip_prefix(root).ok()?;
+            // joining an empty path would add a trailing separator to
+            // crawled files
+            if relative.as_os_str().is_empty() {
+                return Some(canonical.clone());
+            }
+            Some(canonical.join(relative))
+        })
+        .unwrap_or_else(|| path.to_path_buf())
 }
 
-#[allow(clippy::unused_async)]
-async fn load_files(paths: Vec<PathBuf>, injector: Injector<String>) {
+/// Crawl the given paths, blocking until done (or resumed if paused).
+fn load_files(
+    paths: &[PathBuf],
+    ignore_patterns: &[String],
+    injector: &Injector<String>,
+) {
     if paths.is_empty() {
         return;
     }
     let current_dir = std::env::current_dir().unwrap();
-    let mut builder =
-        walk_builder(&paths[0], get_default_num_threads(), None, None);
+    // symbolic links aren't followed, so paths to the same file through
+    // symlinks can only come from crawled paths going through symlinks
+    let canonical_roots = if injector.dedup() == Dedup::None {
+        Vec::new()
+    } else {
+        paths
+            .iter()
+            .filter_map(|path| Some((path.clone(), path.canonicalize().ok()?)))
+            .collect::<Vec<_>>()
+    };
+    let mut builder = walk_builder(
+        &paths[0],
+        get_default_num_threads(),
+        build_ignore_overrides(&paths[0], ignore_patterns),
+        None,
+    );
     paths[1..].iter().for_each(|path| {
         builder.add(path);
     });
@@ -170,6 +435,7 @@ async fn load_files(paths: Vec<PathBuf>, injector: Injector<String>) {
     walker.run(|| {
         let injector = injector.clone();
         let current_dir = current_dir.clone();
+        let canonical_roots = &canonical_roots;
         Box::new(move |result| {
             if let Ok(entry) = result {
                 if entry.file_type().unwrap().is_file() {
@@ -178,13 +444,183 @@ async fn load_files(paths: Vec<PathBuf>, injector: Injector<String>) {
                         .strip_prefix(&current_dir)
                         .unwrap_or(entry.path())
                         .to_string_lossy();
-                    let () =
-                        injector.push(file_path.to_string(), |e, cols| {
+                    let push =
+                        |e: &String, cols: &mut [nucleo::Utf32String]| {
                             cols[0] = e.to_string().into();
-                        });
+                        };
+                    if injector.dedup() == Dedup::None {
+                        injector.push(file_path.to_string(), push);
+                    } else {
+                        // paths to the same file through symlinks are
+                        // duplicates
+                        let key = dedup_key(entry.path(), canonical_roots);
+                        injector.push_with_key(
+                            file_path.to_string(),
+                            &key.to_string_lossy(),
+                            push,
+                        );
+                    }
                 }
             }
             ignore::WalkState::Continue
         })
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matcher::config::Config;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_content_snippet() {
+        let content = "\
+[package]
+name = \"television\"
+description = \"A blazingly fast fuzzy finder\"
+";
+        assert_eq!(
+            content_snippet(content, "NAME"),
+            Some("name = \"television\"")
+        );
+        assert_eq!(
+            content_snippet(content, "fuzzy fast"),
+            Some("description = \"A blazingly fast fuzzy finder\"")
+        );
+        assert_eq!(content_snippet(content, "fuzzy slow"), None);
+        assert_eq!(content_snippet(content, "  "), None);
+        // the matcher's syntax is ignored
+        assert_eq!(
+            content_snippet(content, "^'fuzzy finder$ !name"),
+            Some("description = \"A blazingly fast fuzzy finder\"")
+        );
+        assert_eq!(content_snippet(content, "!name"), None);
+    }
+
+    #[test]
+    fn test_file_content_snippet_skips_binary_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let text = dir.path().join("notes.txt");
+        let binary = dir.path().join("image.bin");
+        std::fs::write(&text, "  todo: ship it\n").unwrap();
+        std::fs::write(&binary, b"todo\0\x89PNG").unwrap();
+
+        assert_eq!(
+            file_content_snippet(&text.to_string_lossy(), "todo"),
+            Some(String::from("todo: ship it"))
+        );
+        assert_eq!(
+            file_content_snippet(&binary.to_string_lossy(), "todo"),
+            None
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_content_snippets_are_found_in_the_background() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "foo\nNote: bar\n")
+            .unwrap();
+        let config = ChannelConfig {
+            content_snippets: true,
+            ..ChannelConfig::default()
+        };
+        let mut channel =
+            Channel::new(vec![dir.path().to_path_buf()], &config);
+        // the pinned filters are left out of the typed query
+        channel.set_typed_query("'note !baz");
+        channel.find("txt 'note !baz");
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let entry = loop {
+            let entry = channel.results(10, 0).pop();
+            if entry.as_ref().is_some_and(|e| e.snippet.is_some())
+                || Instant::now() > deadline
+            {
+                break entry.unwrap();
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        };
+        assert_eq!(entry.snippet.as_deref(), Some("Note: bar"));
+        // the snippet is only displayed
+        assert_eq!(entry.value, None);
+    }
+
+    #[tokio::test]
+    async fn test_ignore_patterns_exclude_matching_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src/build")).unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), "").unwrap();
+        std::fs::write(dir.path().join("src/build/out.rs"), "").unwrap();
+        std::fs::write(dir.path().join("debug.log"), "").unwrap();
+
+        let mut matcher = Matcher::new(Config::default());
+        load_files(
+            &[dir.path().to_path_buf()],
+            &[String::from("build"), String::from("*.log")],
+            &matcher.injector(),
+        );
+        matcher.find("");
+        matcher.tick();
+        while matcher.status.running {
+            matcher.tick();
+        }
+
+        let entries = matcher
+            .results(10, 0)
+            .into_iter()
+            .map(|item| item.matched_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            vec![dir.path().join("src/main.rs").to_string_lossy()]
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_dedup_drops_files_reached_through_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), "").unwrap();
+        std::os::unix::fs::symlink(
+            dir.path().join("src"),
+            dir.path().join("link"),
+        )
+        .unwrap();
+
+        let mut matcher = Matcher::new(Config::default().dedup(Dedup::Exact));
+        load_files(
+            &[dir.path().join("src"), dir.path().join("link")],
+            &[],
+            &matcher.injector(),
+        );
+        matcher.find("");
+        matcher.tick();
+        while matcher.status.running {
+            matcher.tick();
+        }
+
+        assert_eq!(matcher.results(10, 0).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_files_sent_again_are_listed_once() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "").unwrap();
+
+        let mut matcher = Matcher::new(
+            ChannelConfig::default().sent_matcher_config("files"),
+        );
+        load_files(&[dir.path().join("a.txt")], &[], &matcher.injector());
+        load_files(&[dir.path().to_path_buf()], &[], &matcher.injector());
+        matcher.find("");
+        matcher.tick();
+        while matcher.status.running {
+            matcher.tick();
+        }
+
+        assert_eq!(matcher.results(10, 0).len(), 2);
+    }
+}
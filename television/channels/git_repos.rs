@@ -3,13 +3,13 @@ use directories::BaseDirs;
 use ignore::overrides::OverrideBuilder;
 use rustc_hash::{FxBuildHasher, FxHashSet};
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tokio::task::JoinHandle;
 use tracing::debug;
 
 use crate::channels::entry::{Entry, PreviewCommand, PreviewType};
-use crate::channels::OnAir;
-use crate::matcher::{config::Config, injector::Injector, Matcher};
+use crate::channels::{ChannelConfig, OnAir};
+use crate::matcher::{injector::Injector, Matcher};
 use crate::utils::files::{get_default_num_threads, walk_builder};
 
 pub struct Channel {
@@ -21,13 +21,15 @@ pub struct Channel {
 }
 
 impl Channel {
-    pub fn new() -> Self {
-        let matcher = Matcher::new(Config::default().match_paths(true));
+    pub fn new(config: &ChannelConfig) -> Self {
+        let matcher =
+            Matcher::new(config.matcher_config("git-repos").match_paths(true));
         let base_dirs = BaseDirs::new().unwrap();
-        let crawl_handle = tokio::spawn(crawl_for_repos(
-            base_dirs.home_dir().to_path_buf(),
-            matcher.injector(),
-        ));
+        let home = base_dirs.home_dir().to_path_buf();
+        let injector = matcher.injector();
+        let crawl_handle = tokio::task::spawn_blocking(move || {
+            crawl_for_repos(&home, &injector);
+        });
 
         let preview_command = PreviewCommand {
             command: String::from(
@@ -46,9 +48,9 @@ impl Channel {
     }
 }
 
-impl Default for Channel {
-    fn default() -> Self {
-        Self::new()
+impl From<&ChannelConfig> for Channel {
+    fn from(config: &ChannelConfig) -> Self {
+        Self::new(config)
     }
 }
 
@@ -57,6 +59,10 @@ impl OnAir for Channel {
         self.matcher.find(pattern);
     }
 
+    fn current_query(&self) -> &str {
+        &self.matcher.last_pattern
+    }
+
     fn results(&mut self, num_entries: u32, offset: u32) -> Vec<Entry> {
         self.matcher.tick();
         self.matcher
@@ -97,6 +103,11 @@ impl OnAir for Channel {
         }
     }
 
+    fn reset(&mut self) {
+        self.selected_entries.clear();
+        self.find("");
+    }
+
     fn result_count(&self) -> u32 {
         self.matcher.matched_item_count
     }
@@ -106,17 +117,29 @@ impl OnAir for Channel {
     }
 
     fn running(&self) -> bool {
-        self.matcher.status.running
+        self.matcher.status.running && !self.matcher.paused()
     }
 
     fn shutdown(&self) {
         debug!("Shutting down git repos channel");
-        self.crawl_handle.abort();
+        super::stop_crawls(
+            &self.matcher,
+            std::slice::from_ref(&self.crawl_handle),
+        );
     }
 
     fn supports_preview(&self) -> bool {
         true
     }
+
+    fn pause(&mut self) -> bool {
+        self.matcher.pause();
+        true
+    }
+
+    fn resume(&mut self) {
+        self.matcher.resume();
+    }
 }
 
 fn get_ignored_paths() -> Vec<PathBuf> {
@@ -166,12 +189,12 @@ fn get_ignored_paths() -> Vec<PathBuf> {
 
     ignored_paths
 }
-#[allow(clippy::unused_async)]
-async fn crawl_for_repos(starting_point: PathBuf, injector: Injector<String>) {
-    let mut walker_overrides_builder = OverrideBuilder::new(&starting_point);
+/// Crawl the given directory, blocking until done (or resumed if paused).
+fn crawl_for_repos(starting_point: &Path, injector: &Injector<String>) {
+    let mut walker_overrides_builder = OverrideBuilder::new(starting_point);
     walker_overrides_builder.add(".git").unwrap();
     let walker = walk_builder(
-        &starting_point,
+        starting_point,
         get_default_num_threads(),
         Some(walker_overrides_builder.build().unwrap()),
         Some(get_ignored_paths()),
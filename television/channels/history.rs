@@ -0,0 +1,371 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use devicons::FileIcon;
+use directories::BaseDirs;
+use rustc_hash::{FxBuildHasher, FxHashSet};
+use tracing::debug;
+
+use crate::channels::entry::{Entry, PreviewType};
+use crate::channels::{ChannelConfig, OnAir};
+use crate::matcher::{injector::Injector, Matcher};
+
+const SHELL_ENV_VAR: &str = "SHELL";
+/// Environment variable overriding the location of the history file.
+const HISTFILE_ENV_VAR: &str = "HISTFILE";
+const FILE_ICON_STR: &str = "sh";
+
+/// The format of a shell history file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryFormat {
+    /// One command per line, optionally preceded by `#<timestamp>` lines.
+    Bash,
+    /// One command per line, optionally in the extended format
+    /// `: <timestamp>:<duration>;<command>`. Multi-line commands continue
+    /// on the next line after a trailing backslash.
+    Zsh,
+    /// A YAML-like list of `- cmd: <command>` items.
+    Fish,
+}
+
+impl HistoryFormat {
+    /// Guess the history format from the path of the user's shell.
+    pub fn from_shell(shell: &str) -> Option<Self> {
+        match Path::new(shell).file_name()?.to_str()? {
+            "bash" => Some(HistoryFormat::Bash),
+            "zsh" => Some(HistoryFormat::Zsh),
+            "fish" => Some(HistoryFormat::Fish),
+            _ => None,
+        }
+    }
+
+    /// The default location of the history file.
+    fn default_path(self, base_dirs: &BaseDirs) -> PathBuf {
+        match self {
+            HistoryFormat::Bash => base_dirs.home_dir().join(".bash_history"),
+            HistoryFormat::Zsh => base_dirs.home_dir().join(".zsh_history"),
+            HistoryFormat::Fish => {
+                base_dirs.data_local_dir().join("fish/fish_history")
+            }
+        }
+    }
+}
+
+/// Parse the contents of a history file into the commands it lists, oldest
+/// first.
+pub fn parse_history(content: &str, format: HistoryFormat) -> Vec<String> {
+    match format {
+        HistoryFormat::Bash => content
+            .lines()
+            .filter(|line| !is_bash_timestamp(line))
+            .filter(|line| !line.trim().is_empty())
+            .map(String::from)
+            .collect(),
+        HistoryFormat::Zsh => parse_zsh_history(content),
+        HistoryFormat::Fish => content
+            .lines()
+            .filter_map(|line| line.strip_prefix("- cmd: "))
+            .filter(|command| !command.trim().is_empty())
+            .map(String::from)
+            .collect(),
+    }
+}
+
+fn is_bash_timestamp(line: &str) -> bool {
+    line.strip_prefix('#').is_some_and(|timestamp| {
+        !timestamp.is_empty() && timestamp.chars().all(|c| c.is_ascii_digit())
+    })
+}
+
+fn parse_zsh_history(content: &str) -> Vec<String> {
+    let mut commands = Vec::new();
+    let mut current: Option<String> = None;
+    for line in content.lines() {
+        let line = match current.take() {
+            Some(mut command) => {
+                command.push('\n');
+                command.push_str(line);
+                command
+            }
+            None => strip_zsh_extended_prefix(line).to_string(),
+        };
+        if let Some(continued) = line.strip_suffix('\\') {
+            current = Some(continued.to_string());
+        } else if !line.trim().is_empty() {
+            commands.push(line);
+        }
+    }
+    commands.extend(current.filter(|command| !command.trim().is_empty()));
+    commands
+}
+
+/// Strip the `: <timestamp>:<duration>;` prefix of zsh's extended history
+/// format.
+fn strip_zsh_extended_prefix(line: &str) -> &str {
+    line.strip_prefix(": ")
+        .and_then(|rest| rest.split_once(';'))
+        .filter(|(meta, _)| {
+            meta.split(':').all(|n| {
+                !n.is_empty() && n.chars().all(|c| c.is_ascii_digit())
+            })
+        })
+        .map_or(line, |(_, command)| command)
+}
+
+/// Undo zsh's metafication of non-ASCII bytes, where some bytes are stored
+/// as `0x83` followed by the original byte xor-ed with 32.
+fn unmetafy(bytes: &[u8]) -> Vec<u8> {
+    const META: u8 = 0x83;
+    let mut unmetafied = Vec::with_capacity(bytes.len());
+    let mut bytes = bytes.iter();
+    while let Some(&byte) = bytes.next() {
+        if byte == META {
+            if let Some(&next) = bytes.next() {
+                unmetafied.push(next ^ 32);
+            }
+        } else {
+            unmetafied.push(byte);
+        }
+    }
+    unmetafied
+}
+
+/// Deduplicate commands listed oldest first, keeping the most recent
+/// occurrence of each and ordering them most recent first.
+pub fn dedup_by_recency(commands: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    commands
+        .into_iter()
+        .rev()
+        .filter(|command| seen.insert(command.clone()))
+        .collect()
+}
+
+/// The history file of the user's shell along with its format.
+fn history_source() -> Option<(PathBuf, HistoryFormat)> {
+    let format =
+        HistoryFormat::from_shell(&std::env::var(SHELL_ENV_VAR).ok()?)?;
+    let path = match std::env::var_os(HISTFILE_ENV_VAR) {
+        Some(path) => PathBuf::from(path),
+        None => format.default_path(&BaseDirs::new()?),
+    };
+    Some((path, format))
+}
+
+pub struct Channel {
+    matcher: Matcher<String>,
+    file_icon: FileIcon,
+    selected_entries: FxHashSet<Entry>,
+    crawl_handle: tokio::task::JoinHandle<()>,
+}
+
+impl Channel {
+    pub fn new(config: &ChannelConfig) -> Self {
+        let matcher = Matcher::new(config.matcher_config("history"));
+        let crawl_handle =
+            tokio::spawn(load_history(history_source(), matcher.injector()));
+        Self {
+            matcher,
+            file_icon: FileIcon::from(FILE_ICON_STR),
+            selected_entries: HashSet::with_hasher(FxBuildHasher),
+            crawl_handle,
+        }
+    }
+
+    fn build_entry(&self, command: String) -> Entry {
+        Entry::new(command, PreviewType::Basic).with_icon(self.file_icon)
+    }
+}
+
+impl From<&ChannelConfig> for Channel {
+    fn from(config: &ChannelConfig) -> Self {
+        Self::new(config)
+    }
+}
+
+impl OnAir for Channel {
+    fn find(&mut self, pattern: &str) {
+        self.matcher.find(pattern);
+    }
+
+    fn current_query(&self) -> &str {
+        &self.matcher.last_pattern
+    }
+
+    fn results(&mut self, num_entries: u32, offset: u32) -> Vec<Entry> {
+        self.matcher.tick();
+        self.matcher
+            .results(num_entries, offset)
+            .into_iter()
+            .map(|item| {
+                self.build_entry(item.matched_string)
+                    .with_name_match_ranges(&item.match_indices)
+            })
+            .collect()
+    }
+
+    fn get_result(&self, index: u32) -> Option<Entry> {
+        self.matcher
+            .get_result(index)
+            .map(|item| self.build_entry(item.matched_string))
+    }
+
+    fn selected_entries(&self) -> &FxHashSet<Entry> {
+        &self.selected_entries
+    }
+
+    fn toggle_selection(&mut self, entry: &Entry) {
+        if self.selected_entries.contains(entry) {
+            self.selected_entries.remove(entry);
+        } else {
+            self.selected_entries.insert(entry.clone());
+        }
+    }
+
+    fn reset(&mut self) {
+        self.selected_entries.clear();
+        self.find("");
+    }
+
+    fn result_count(&self) -> u32 {
+        self.matcher.matched_item_count
+    }
+
+    fn total_count(&self) -> u32 {
+        self.matcher.total_item_count
+    }
+
+    fn running(&self) -> bool {
+        self.matcher.status.running
+    }
+
+    fn shutdown(&self) {
+        self.crawl_handle.abort();
+    }
+
+    fn supports_preview(&self) -> bool {
+        true
+    }
+}
+
+#[allow(clippy::unused_async)]
+async fn load_history(
+    source: Option<(PathBuf, HistoryFormat)>,
+    injector: Injector<String>,
+) {
+    let Some((path, format)) = source else {
+        debug!("Unable to detect the shell's history file");
+        return;
+    };
+    let bytes = match std::fs::read(&path) {
+        Ok(bytes) if format == HistoryFormat::Zsh => unmetafy(&bytes),
+        Ok(bytes) => bytes,
+        Err(e) => {
+            debug!("Unable to read shell history {:?}: {:?}", path, e);
+            return;
+        }
+    };
+    let content = String::from_utf8_lossy(&bytes);
+    for command in dedup_by_recency(parse_history(&content, format)) {
+        let () = injector.push(command, |e, cols| {
+            cols[0] = e.clone().into();
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_zsh_extended_history() {
+        let content = "\
+: 1700000000:0;git status
+: 1700000005:2;cargo test \\
+--workspace
+ls -la
+";
+        assert_eq!(
+            parse_history(content, HistoryFormat::Zsh),
+            vec!["git status", "cargo test \n--workspace", "ls -la"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_zsh_history_entry() {
+        let channel = Channel::new(&ChannelConfig::default());
+        channel.shutdown();
+        let command = parse_history(
+            ": 1700000000:0;git log --oneline",
+            HistoryFormat::Zsh,
+        )
+        .remove(0);
+        let entry = channel.build_entry(command);
+
+        assert_eq!(entry.name, "git log --oneline");
+        assert_eq!(entry.stdout_repr(), "git log --oneline");
+    }
+
+    #[test]
+    fn test_parse_bash_history() {
+        let content = "#1700000000\ngit status\n\nls\n";
+        assert_eq!(
+            parse_history(content, HistoryFormat::Bash),
+            vec!["git status", "ls"]
+        );
+    }
+
+    #[test]
+    fn test_parse_fish_history() {
+        let content = "\
+- cmd: git status
+  when: 1700000000
+- cmd: ls
+  when: 1700000005
+  paths:
+    - src
+";
+        assert_eq!(
+            parse_history(content, HistoryFormat::Fish),
+            vec!["git status", "ls"]
+        );
+    }
+
+    #[test]
+    fn test_dedup_by_recency() {
+        let commands = ["ls", "git status", "ls", "cargo build"]
+            .map(String::from)
+            .to_vec();
+        assert_eq!(
+            dedup_by_recency(commands),
+            vec!["cargo build", "ls", "git status"]
+        );
+    }
+
+    #[test]
+    fn test_history_format_from_shell() {
+        assert_eq!(
+            HistoryFormat::from_shell("/usr/bin/zsh"),
+            Some(HistoryFormat::Zsh)
+        );
+        assert_eq!(
+            HistoryFormat::from_shell("/bin/bash"),
+            Some(HistoryFormat::Bash)
+        );
+        assert_eq!(
+            HistoryFormat::from_shell("fish"),
+            Some(HistoryFormat::Fish)
+        );
+        assert_eq!(HistoryFormat::from_shell("/bin/nu"), None);
+    }
+
+    #[test]
+    fn test_unmetafy() {
+        // "é" is 0xc3 0xa9, zsh stores 0xa9 as 0x83 0x89
+        assert_eq!(
+            String::from_utf8(unmetafy(&[b'c', b'a', b'f', 0xc3, 0x83, 0x89]))
+                .unwrap(),
+            "café"
+        );
+    }
+}
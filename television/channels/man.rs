@@ -0,0 +1,238 @@
+use std::collections::HashSet;
+use std::process::Command;
+
+use devicons::FileIcon;
+use rustc_hash::{FxBuildHasher, FxHashSet};
+use tracing::debug;
+
+use crate::channels::entry::{Entry, PreviewCommand, PreviewType};
+use crate::channels::{ChannelConfig, OnAir};
+use crate::matcher::{injector::Injector, Matcher};
+
+/// A man page reference, e.g. `git(1)`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ManPage {
+    pub name: String,
+    pub section: String,
+}
+
+impl ManPage {
+    pub fn new(name: &str, section: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            section: section.to_string(),
+        }
+    }
+
+    /// Parse a page reference of the form `name(section)` or
+    /// `name (section)`.
+    pub fn parse(raw: &str) -> Option<Self> {
+        let (name, section) = raw.trim().strip_suffix(')')?.split_once('(')?;
+        let name = name.trim();
+        if name.is_empty() || section.is_empty() {
+            return None;
+        }
+        Some(Self::new(name, section))
+    }
+}
+
+impl std::fmt::Display for ManPage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}({})", self.name, self.section)
+    }
+}
+
+/// Parse a line of `man -k` output into the man pages it documents.
+///
+/// Depending on the platform, lines look like
+/// `git (1)              - the stupid content tracker` or
+/// `git(1), git-help(1) - the stupid content tracker`, and several pages
+/// may share the same description.
+fn parse_apropos_line(line: &str) -> Vec<ManPage> {
+    let refs = line.split_once(" - ").map_or(line, |(refs, _)| refs);
+    refs.split(',').filter_map(ManPage::parse).collect()
+}
+
+pub struct Channel {
+    matcher: Matcher<String>,
+    file_icon: FileIcon,
+    selected_entries: FxHashSet<Entry>,
+    crawl_handle: tokio::task::JoinHandle<()>,
+}
+
+const NUM_THREADS: usize = 1;
+const FILE_ICON_STR: &str = "txt";
+
+// `{1}` is the section followed by the closing parenthesis (e.g. `1)`)
+const PREVIEW_COMMAND: &str =
+    "section={1}; MANWIDTH=80 man \"${section%)}\" {0} | col -bx";
+const PREVIEW_DELIMITER: &str = "(";
+
+impl Channel {
+    pub fn new(config: &ChannelConfig) -> Self {
+        let matcher =
+            Matcher::new(config.matcher_config("man").n_threads(NUM_THREADS));
+        let crawl_handle = tokio::spawn(load_man_pages(matcher.injector()));
+        Self {
+            matcher,
+            file_icon: FileIcon::from(FILE_ICON_STR),
+            selected_entries: HashSet::with_hasher(FxBuildHasher),
+            crawl_handle,
+        }
+    }
+
+    fn build_entry(&self, page: String) -> Entry {
+        Entry::new(
+            page,
+            PreviewType::Command(PreviewCommand::new(
+                PREVIEW_COMMAND,
+                PREVIEW_DELIMITER,
+            )),
+        )
+        .with_icon(self.file_icon)
+    }
+}
+
+impl From<&ChannelConfig> for Channel {
+    fn from(config: &ChannelConfig) -> Self {
+        Self::new(config)
+    }
+}
+
+impl OnAir for Channel {
+    fn find(&mut self, pattern: &str) {
+        self.matcher.find(pattern);
+    }
+
+    fn current_query(&self) -> &str {
+        &self.matcher.last_pattern
+    }
+
+    fn results(&mut self, num_entries: u32, offset: u32) -> Vec<Entry> {
+        self.matcher.tick();
+        self.matcher
+            .results(num_entries, offset)
+            .into_iter()
+            .map(|item| {
+                self.build_entry(item.matched_string)
+                    .with_name_match_ranges(&item.match_indices)
+            })
+            .collect()
+    }
+
+    fn get_result(&self, index: u32) -> Option<Entry> {
+        self.matcher
+            .get_result(index)
+            .map(|item| self.build_entry(item.matched_string))
+    }
+
+    fn selected_entries(&self) -> &FxHashSet<Entry> {
+        &self.selected_entries
+    }
+
+    fn toggle_selection(&mut self, entry: &Entry) {
+        if self.selected_entries.contains(entry) {
+            self.selected_entries.remove(entry);
+        } else {
+            self.selected_entries.insert(entry.clone());
+        }
+    }
+
+    fn reset(&mut self) {
+        self.selected_entries.clear();
+        self.find("");
+    }
+
+    fn result_count(&self) -> u32 {
+        self.matcher.matched_item_count
+    }
+
+    fn total_count(&self) -> u32 {
+        self.matcher.total_item_count
+    }
+
+    fn running(&self) -> bool {
+        self.matcher.status.running
+    }
+
+    fn shutdown(&self) {
+        self.crawl_handle.abort();
+    }
+
+    fn supports_preview(&self) -> bool {
+        true
+    }
+}
+
+#[allow(clippy::unused_async)]
+async fn load_man_pages(injector: Injector<String>) {
+    let output = match Command::new("man").arg("-k").arg(".").output() {
+        Ok(output) => output,
+        Err(e) => {
+            debug!("Failed to list man pages: {:?}", e);
+            return;
+        }
+    };
+    let mut seen = HashSet::new();
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .flat_map(parse_apropos_line)
+        .filter(|page| seen.insert(page.clone()))
+        .for_each(|page| {
+            let () = injector.push(page.to_string(), |e, cols| {
+                cols[0] = e.clone().into();
+            });
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_man_page() {
+        assert_eq!(ManPage::parse("git(1)"), Some(ManPage::new("git", "1")));
+        assert_eq!(
+            ManPage::parse(" printf (3p) "),
+            Some(ManPage::new("printf", "3p"))
+        );
+        assert_eq!(ManPage::parse("git"), None);
+        assert_eq!(ManPage::parse("(1)"), None);
+        assert_eq!(ManPage::parse("git()"), None);
+    }
+
+    #[test]
+    fn test_parse_apropos_line() {
+        assert_eq!(
+            parse_apropos_line(
+                "git (1)              - the stupid content tracker"
+            ),
+            vec![ManPage::new("git", "1")]
+        );
+        assert_eq!(
+            parse_apropos_line(
+                "git(1), git-help(1) - the stupid content tracker"
+            ),
+            vec![ManPage::new("git", "1"), ManPage::new("git-help", "1")]
+        );
+        assert!(parse_apropos_line(".: nothing appropriate.").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_man_page_entry() {
+        let channel = Channel::new(&ChannelConfig::default());
+        channel.shutdown();
+        let page = ManPage::parse("git (1)").unwrap();
+        let entry = channel.build_entry(page.to_string());
+
+        assert_eq!(entry.name, "git(1)");
+        assert_eq!(entry.stdout_repr(), "git(1)");
+        assert_eq!(
+            entry.preview_type,
+            PreviewType::Command(PreviewCommand::new(
+                PREVIEW_COMMAND,
+                PREVIEW_DELIMITER
+            ))
+        );
+    }
+}
@@ -1,16 +1,27 @@
 use crate::channels::entry::Entry;
+use crate::matcher::config::{self, Dedup, MatchTarget};
+use crate::matcher::Matcher;
 use anyhow::Result;
 use rustc_hash::FxHashSet;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 use television_derive::{Broadcast, ToCliChannel, ToUnitChannel};
+use tokio::task::JoinHandle;
 
 pub mod alias;
 pub mod cable;
 pub mod dirs;
+pub mod editor_recent;
 pub mod entry;
 pub mod env;
 pub mod files;
 pub mod git_repos;
+pub mod history;
+pub mod man;
+pub mod pending;
+pub mod ports;
 pub mod remote_control;
+pub mod services;
 pub mod stdin;
 pub mod text;
 
@@ -30,6 +41,11 @@ pub mod text;
 ///   ```ignore
 ///   fn find(&mut self, pattern: &str);
 ///   ```
+/// - `current_query`: Get the last pattern passed to `find`, e.g. for
+///   channels matching entries on their own.
+///   ```ignore
+///   fn current_query(&self) -> &str;
+///   ```
 /// - `results`: Get the results of the search (at a given point in time, see
 ///   above). This method returns a specific portion of entries that match the
 ///   search pattern. The `num_entries` parameter specifies the number of
@@ -42,6 +58,11 @@ pub mod text;
 ///   ```ignore
 ///   fn get_result(&self, index: u32) -> Option<Entry>;
 ///   ```
+/// - `reset`: Clear the selected entries and go back to the unfiltered
+///   results.
+///   ```ignore
+///   fn reset(&mut self);
+///   ```
 /// - `result_count`: Get the number of results currently available.
 ///   ```ignore
 ///   fn result_count(&self) -> u32;
@@ -61,6 +82,9 @@ pub trait OnAir: Send {
     /// `results`.
     fn find(&mut self, pattern: &str);
 
+    /// Get the last pattern passed to `find`.
+    fn current_query(&self) -> &str;
+
     /// Get the results of the search (that are currently available).
     fn results(&mut self, num_entries: u32, offset: u32) -> Vec<Entry>;
 
@@ -73,6 +97,11 @@ pub trait OnAir: Send {
     /// Toggles selection for the entry under the cursor.
     fn toggle_selection(&mut self, entry: &Entry);
 
+    /// Clear the selected entries and match every entry again.
+    ///
+    /// Channels holding state tied to the query should reset it here too.
+    fn reset(&mut self);
+
     /// Get the number of results currently available.
     fn result_count(&self) -> u32;
 
@@ -87,6 +116,109 @@ pub trait OnAir: Send {
 
     /// Whether this channel supports previewing entries.
     fn supports_preview(&self) -> bool;
+
+    /// Pause loading entries in the background until `resume` is called,
+    /// the channel not being `running` meanwhile, returning whether the
+    /// channel supports it.
+    ///
+    /// Channels loading their entries at once don't need to be paused.
+    fn pause(&mut self) -> bool {
+        false
+    }
+
+    /// Resume loading entries in the background after `pause`.
+    fn resume(&mut self) {}
+
+    /// Match the entries against what the given target designates,
+    /// returning whether the channel supports it.
+    ///
+    /// Channels whose entries have no hidden text nor escape sequences don't
+    /// need to support this.
+    fn set_match_target(&mut self, _target: MatchTarget) -> bool {
+        false
+    }
+
+    /// Set the query typed by the user, i.e. the pattern passed to `find`
+    /// without the pinned filters.
+    ///
+    /// Only channels annotating their results with what the user looks for
+    /// need it (e.g. with content snippets).
+    fn set_typed_query(&mut self, _query: &str) {}
+}
+
+/// Stop the tasks loading a channel's entries into `matcher`.
+///
+/// The matcher is resumed first so that a paused crawl winds down rather
+/// than blocks forever.
+fn stop_crawls<I>(matcher: &Matcher<I>, crawl_handles: &[JoinHandle<()>])
+where
+    I: Sync + Send + Clone + 'static,
+{
+    matcher.resume();
+    crawl_handles.iter().for_each(JoinHandle::abort);
+}
+
+/// The settings channels are created with, taken from the user's
+/// configuration (see `AppConfig::channel_config`).
+#[derive(Clone, Debug)]
+pub struct ChannelConfig {
+    /// The configuration of the channel's matcher, which the channel may
+    /// adjust to its entries (e.g. to match paths).
+    pub matcher: config::Config,
+    /// The globs of the paths skipped when crawling the file system on top
+    /// of the gitignore rules.
+    pub ignore_patterns: Vec<String>,
+    /// Whether file results are annotated with the first line of the file
+    /// matching the query.
+    pub content_snippets: bool,
+    /// How the entries of each channel are deduplicated, by channel name.
+    pub dedup: BTreeMap<String, Dedup>,
+    /// Which variables the env channel lists and in which order.
+    pub env: env::EnvOptions,
+    /// The directory where channels keep their state across runs.
+    pub data_dir: Option<PathBuf>,
+    /// Whether results are annotated with the group they're listed under
+    /// (see `UiConfig::group_results`).
+    pub group_results: bool,
+}
+
+impl ChannelConfig {
+    /// The configuration of the matcher of the channel with the given name,
+    /// deduplicating its entries as configured for it.
+    pub fn matcher_config(&self, channel_name: &str) -> config::Config {
+        self.matcher
+            .clone()
+            .dedup(self.dedup.get(channel_name).copied().unwrap_or_default())
+    }
+
+    /// The configuration of the matcher of a channel entries are sent to,
+    /// whose entries are deduplicated even if not configured to so that
+    /// entries sent to it again aren't listed twice.
+    pub fn sent_matcher_config(&self, channel_name: &str) -> config::Config {
+        let config = self.matcher_config(channel_name);
+        if config.dedup == Dedup::None {
+            config.dedup(Dedup::Exact)
+        } else {
+            config
+        }
+    }
+}
+
+impl Default for ChannelConfig {
+    fn default() -> Self {
+        Self {
+            matcher: config::Config::default(),
+            ignore_patterns: files::DEFAULT_IGNORE_PATTERNS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            content_snippets: false,
+            dedup: BTreeMap::new(),
+            env: env::EnvOptions::default(),
+            data_dir: None,
+            group_results: false,
+        }
+    }
 }
 
 /// The available television channels.
@@ -149,6 +281,30 @@ pub enum TelevisionChannel {
     ///
     /// This channel allows to search through aliases.
     Alias(alias::Channel),
+    /// The man pages channel.
+    ///
+    /// This channel allows to search through man pages by name and section.
+    Man(man::Channel),
+    /// The recently edited files channel.
+    ///
+    /// This channel allows to search through the files listed in an editor's
+    /// history, most frecent first.
+    EditorRecent(editor_recent::Channel),
+    /// The shell history channel.
+    ///
+    /// This channel allows to search through the commands in the current
+    /// shell's history, most recent first.
+    History(history::Channel),
+    /// The listening ports channel.
+    ///
+    /// This channel allows to search through listening TCP and UDP ports and
+    /// the processes owning them.
+    Ports(ports::Channel),
+    /// The system services channel.
+    ///
+    /// This channel allows to search through the services managed by
+    /// systemd or launchd along with their state.
+    Services(services::Channel),
     /// The remote control channel.
     ///
     /// This channel allows to switch between different channels.
@@ -162,17 +318,46 @@ pub enum TelevisionChannel {
     Cable(cable::Channel),
 }
 
-impl From<&Entry> for TelevisionChannel {
-    fn from(entry: &Entry) -> Self {
-        UnitChannel::try_from(entry.name.as_str()).unwrap().into()
+impl TelevisionChannel {
+    pub fn zap(
+        &self,
+        channel_name: &str,
+        config: ChannelConfig,
+    ) -> Result<TelevisionChannel> {
+        match self {
+            TelevisionChannel::RemoteControl(remote_control) => {
+                remote_control.zap(channel_name, config)
+            }
+            _ => unreachable!(),
+        }
     }
-}
 
-impl TelevisionChannel {
-    pub fn zap(&self, channel_name: &str) -> Result<TelevisionChannel> {
+    pub fn lazy_zap(
+        &self,
+        channel_name: &str,
+        config: ChannelConfig,
+    ) -> impl FnOnce() -> Result<TelevisionChannel> + Send + 'static {
+        match self {
+            TelevisionChannel::RemoteControl(remote_control) => {
+                remote_control.lazy_zap(channel_name, config)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn describe(&self, channel_name: &str) -> Vec<String> {
+        match self {
+            TelevisionChannel::RemoteControl(remote_control) => {
+                remote_control.describe(channel_name)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn guide_preview_entries(&self, channel_name: &str) -> Option<u32> {
         match self {
             TelevisionChannel::RemoteControl(remote_control) => {
-                remote_control.zap(channel_name)
+                remote_control.guide_preview_entries(channel_name)
             }
             _ => unreachable!(),
         }
@@ -181,10 +366,71 @@ impl TelevisionChannel {
     pub fn name(&self) -> String {
         match self {
             TelevisionChannel::Cable(channel) => channel.name.clone(),
-            TelevisionChannel::Stdin(_) => String::from("Stdin"),
+            TelevisionChannel::Stdin(channel) => match channel.skipped_lines()
+            {
+                0 => String::from("Stdin"),
+                1 => String::from("Stdin (1 malformed line skipped)"),
+                n => format!("Stdin ({n} malformed lines skipped)"),
+            },
             _ => UnitChannel::from(self).to_string(),
         }
     }
+
+    /// The name the channel's options are looked up by in the
+    /// configuration, i.e. its name in lowercase, channels fed from stdin
+    /// always going by `stdin` whatever their name says.
+    pub fn config_name(&self) -> String {
+        match self {
+            TelevisionChannel::Stdin(_) => String::from("stdin"),
+            _ => self.name().to_lowercase(),
+        }
+    }
+
+    /// Run the source command of command-backed channels again, replacing
+    /// their entries, and return whether the channel has such a command.
+    pub fn reload_source(&mut self) -> bool {
+        match self {
+            TelevisionChannel::Cable(channel) => {
+                channel.reload();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the channel's entries are paths to files or directories.
+    pub fn is_file_backed(&self) -> bool {
+        matches!(
+            self,
+            TelevisionChannel::Files(_)
+                | TelevisionChannel::Dirs(_)
+                | TelevisionChannel::GitRepos(_)
+                | TelevisionChannel::Text(_)
+                | TelevisionChannel::EditorRecent(_)
+        )
+    }
+
+    /// Add the entries sent from `source` to this channel's own, the same
+    /// way they would be loaded by transitioning `source` to this channel.
+    ///
+    /// # Errors
+    /// If `source` can't be sent to this channel.
+    pub fn ingest(&mut self, source: &mut TelevisionChannel) -> Result<()> {
+        let target = UnitChannel::from(&*self);
+        if !source.available_transitions().contains(&target) {
+            anyhow::bail!(
+                "{} can't be sent to {target}",
+                UnitChannel::from(&*source)
+            );
+        }
+        match self {
+            TelevisionChannel::Files(channel) => channel.ingest(source),
+            TelevisionChannel::Dirs(channel) => channel.ingest(source),
+            TelevisionChannel::Text(channel) => channel.ingest(source),
+            _ => unreachable!(),
+        }
+        Ok(())
+    }
 }
 
 macro_rules! variant_to_module {
@@ -209,6 +455,21 @@ macro_rules! variant_to_module {
     (Alias) => {
         alias::Channel
     };
+    (Man) => {
+        man::Channel
+    };
+    (EditorRecent) => {
+        editor_recent::Channel
+    };
+    (History) => {
+        history::Channel
+    };
+    (Ports) => {
+        ports::Channel
+    };
+    (Services) => {
+        services::Channel
+    };
     (RemoteControl) => {
         remote_control::RemoteControl
     };
@@ -245,16 +506,16 @@ macro_rules! variant_to_module {
 ///         }
 ///     }
 ///
-///     pub fn transition_to(self, target: UnitChannel) -> TelevisionChannel {
+///     pub fn transition_to(self, target: UnitChannel, config: &ChannelConfig) -> TelevisionChannel {
 ///         match (self, target) {
 ///             (tv_channel @ TelevisionChannel::Files(_), UnitChannel::Text) => {
-///                 TelevisionChannel::Text(text::Channel::from(tv_channel))
+///                 TelevisionChannel::Text(text::Channel::from_channel(tv_channel, config))
 ///             },
 ///             (tv_channel @ TelevisionChannel::GitRepos(_), UnitChannel::Files) => {
-///                 TelevisionChannel::Files(files::Channel::from(tv_channel))
+///                 TelevisionChannel::Files(files::Channel::from_channel(tv_channel, config))
 ///             },
 ///             (tv_channel @ TelevisionChannel::GitRepos(_), UnitChannel::Text) => {
-///                 TelevisionChannel::Text(text::Channel::from(tv_channel))
+///                 TelevisionChannel::Text(text::Channel::from_channel(tv_channel, config))
 ///             },
 ///             _ => unreachable!(),
 ///         }
@@ -280,13 +541,17 @@ macro_rules! define_transitions {
                 }
             }
 
-            pub fn transition_to(&mut self, target: UnitChannel) -> TelevisionChannel {
+            pub fn transition_to(
+                &mut self,
+                target: UnitChannel,
+                config: &ChannelConfig,
+            ) -> TelevisionChannel {
                 match (self, target) {
                     $(
                         $(
                             (tv_channel @ TelevisionChannel::$from_variant(_), UnitChannel::$to_variant) => {
                                 TelevisionChannel::$to_variant(
-                                    <variant_to_module!($to_variant)>::from(tv_channel)
+                                    <variant_to_module!($to_variant)>::from_channel(tv_channel, config)
                                 )
                             },
                         )*
@@ -308,4 +573,156 @@ define_transitions! {
     Files => [Files, Text],
     Dirs => [Files, Text, Dirs],
     GitRepos => [Files, Text, Dirs],
+    Stdin => [Files, Text, Dirs],
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::matcher::{config::Config, Matcher};
+
+    /// A channel endlessly loading numbered entries in the background.
+    struct EndlessChannel {
+        matcher: Matcher<String>,
+        selected_entries: FxHashSet<Entry>,
+        stopped: Arc<AtomicBool>,
+    }
+
+    impl EndlessChannel {
+        fn new() -> Self {
+            let matcher = Matcher::new(Config::default());
+            let injector = matcher.injector();
+            let stopped = Arc::new(AtomicBool::new(false));
+            let stop = stopped.clone();
+            std::thread::spawn(move || {
+                for i in 0.. {
+                    if stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    injector.push(i.to_string(), |e, cols| {
+                        cols[0] = e.clone().into();
+                    });
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+            });
+            Self {
+                matcher,
+                selected_entries: FxHashSet::default(),
+                stopped,
+            }
+        }
+    }
+
+    impl OnAir for EndlessChannel {
+        fn find(&mut self, pattern: &str) {
+            self.matcher.find(pattern);
+        }
+
+        fn current_query(&self) -> &str {
+            &self.matcher.last_pattern
+        }
+
+        fn results(&mut self, num_entries: u32, offset: u32) -> Vec<Entry> {
+            self.matcher.tick();
+            self.matcher
+                .results(num_entries, offset)
+                .into_iter()
+                .map(|item| {
+                    Entry::new(item.matched_string, entry::PreviewType::Basic)
+                })
+                .collect()
+        }
+
+        fn get_result(&self, index: u32) -> Option<Entry> {
+            self.matcher.get_result(index).map(|item| {
+                Entry::new(item.matched_string, entry::PreviewType::Basic)
+            })
+        }
+
+        fn selected_entries(&self) -> &FxHashSet<Entry> {
+            &self.selected_entries
+        }
+
+        fn toggle_selection(&mut self, _entry: &Entry) {}
+
+        fn reset(&mut self) {
+            self.find("");
+        }
+
+        fn result_count(&self) -> u32 {
+            self.matcher.matched_item_count
+        }
+
+        fn total_count(&self) -> u32 {
+            self.matcher.total_item_count
+        }
+
+        fn running(&self) -> bool {
+            !self.matcher.paused()
+        }
+
+        fn shutdown(&self) {
+            self.stopped.store(true, Ordering::Relaxed);
+            self.matcher.resume();
+        }
+
+        fn supports_preview(&self) -> bool {
+            false
+        }
+
+        fn pause(&mut self) -> bool {
+            self.matcher.pause();
+            true
+        }
+
+        fn resume(&mut self) {
+            self.matcher.resume();
+        }
+    }
+
+    /// The number of entries loaded so far by the channel.
+    fn loaded_count(channel: &mut EndlessChannel) -> u32 {
+        channel.matcher.tick();
+        while channel.matcher.status.running {
+            channel.matcher.tick();
+        }
+        channel.results(1, 0);
+        channel.total_count()
+    }
+
+    #[test]
+    fn test_paused_channel_stops_loading_entries() {
+        let mut channel = EndlessChannel::new();
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(channel.running());
+
+        channel.pause();
+        assert!(!channel.running());
+        // let the entry being pushed when pausing land
+        std::thread::sleep(Duration::from_millis(20));
+        let paused_count = loaded_count(&mut channel);
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(loaded_count(&mut channel), paused_count);
+
+        channel.resume();
+        assert!(channel.running());
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(loaded_count(&mut channel) > paused_count);
+        channel.shutdown();
+    }
+
+    #[test]
+    fn test_current_query_is_the_last_pattern_found() {
+        let mut channel = TelevisionChannel::Env(env::Channel::new(
+            &ChannelConfig::default(),
+        ));
+        assert_eq!(channel.current_query(), "");
+
+        channel.find("abc");
+        assert_eq!(channel.current_query(), "abc");
+    }
 }
@@ -0,0 +1,42 @@
+use anyhow::{anyhow, Result};
+use tokio::sync::oneshot::{self, error::TryRecvError};
+
+use crate::channels::TelevisionChannel;
+
+/// A channel being constructed in the background.
+///
+/// Some channels are slow to construct (e.g. when they need to run external
+/// commands) and would otherwise block the first frame. The UI shows the
+/// channel as loading until it is ready to replace the current one.
+pub struct PendingChannel {
+    pub name: String,
+    rx: oneshot::Receiver<TelevisionChannel>,
+}
+
+impl PendingChannel {
+    /// Construct a channel named `name` on a blocking thread.
+    pub fn spawn<F>(name: String, init: F) -> Self
+    where
+        F: FnOnce() -> TelevisionChannel + Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        tokio::task::spawn_blocking(move || {
+            let _ = tx.send(init());
+        });
+        Self { name, rx }
+    }
+
+    /// The constructed channel, if it is ready.
+    ///
+    /// # Errors
+    /// If the channel's construction failed (e.g. it panicked).
+    pub fn try_take(&mut self) -> Result<Option<TelevisionChannel>> {
+        match self.rx.try_recv() {
+            Ok(channel) => Ok(Some(channel)),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Closed) => {
+                Err(anyhow!("Failed to initialize channel {}", self.name))
+            }
+        }
+    }
+}
@@ -0,0 +1,431 @@
+use std::collections::HashSet;
+use std::path::Path;
+use std::process::Command;
+
+use devicons::FileIcon;
+use rustc_hash::{FxBuildHasher, FxHashMap, FxHashSet};
+use tracing::debug;
+
+use crate::channels::entry::{Entry, PreviewCommand, PreviewType};
+use crate::channels::{ChannelConfig, OnAir};
+use crate::matcher::{injector::Injector, Matcher};
+use crate::utils::indices::sep_name_and_value_indices;
+
+/// A listening socket and the process owning it, if it could be found.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Socket {
+    pub port: u16,
+    /// The protocol, suffixed with `6` for IPv6 sockets (e.g. `tcp6`).
+    pub proto: String,
+    pub pid: Option<u32>,
+    pub process: Option<String>,
+}
+
+impl Socket {
+    /// What is listed next to the port: the protocol, pid and name of the
+    /// owning process.
+    fn details(&self) -> String {
+        let pid = self.pid.map_or(String::from("-"), |pid| pid.to_string());
+        format!(
+            "{}{FIELD_DELIMITER}{pid}{FIELD_DELIMITER}{}",
+            self.proto,
+            self.process.as_deref().unwrap_or("-")
+        )
+    }
+}
+
+impl std::fmt::Display for Socket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{FIELD_DELIMITER}{}", self.port, self.details())
+    }
+}
+
+/// The state of listening TCP sockets and of unconnected UDP sockets in
+/// `/proc/net`.
+const TCP_LISTEN: &str = "0A";
+const UDP_UNCONNECTED: &str = "07";
+
+/// Parse the content of a `/proc/net/{tcp,tcp6,udp,udp6}` file into the
+/// port and inode of its listening sockets.
+///
+/// Lines look like
+/// `0: 00000000:1F90 00000000:0000 0A 00000000:00000000 00:00000000
+/// 00000000  1000  0 12345 1 ...`, the local port being in hexadecimal.
+fn parse_proc_net(content: &str, proto: &str) -> Vec<(u16, u64)> {
+    let listening = if proto.starts_with("tcp") {
+        TCP_LISTEN
+    } else {
+        UDP_UNCONNECTED
+    };
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            if fields.get(3) != Some(&listening) {
+                return None;
+            }
+            let (_, port) = fields.get(1)?.rsplit_once(':')?;
+            let port = u16::from_str_radix(port, 16).ok()?;
+            let inode = fields.get(9)?.parse().ok()?;
+            Some((port, inode))
+        })
+        .collect()
+}
+
+/// Parse a line of `lsof -nP -iTCP -sTCP:LISTEN -iUDP` output.
+///
+/// Lines look like
+/// `nginx  1234 root  6u  IPv4 0x1234  0t0  TCP *:80 (LISTEN)`.
+fn parse_lsof_line(line: &str) -> Option<Socket> {
+    let fields = line.split_whitespace().collect::<Vec<_>>();
+    let [process, pid, _, _, ip, _, _, proto, address, ..] = fields[..] else {
+        return None;
+    };
+    let (_, port) = address.rsplit_once(':')?;
+    let proto = proto.to_lowercase();
+    Some(Socket {
+        port: port.parse().ok()?,
+        proto: if ip == "IPv6" {
+            format!("{proto}6")
+        } else {
+            proto
+        },
+        pid: pid.parse().ok(),
+        process: Some(process.replace("\\x20", " ")),
+    })
+}
+
+/// Map the inodes of the sockets opened by each process to the process' pid
+/// and name.
+fn socket_owners(proc_dir: &Path) -> FxHashMap<u64, (u32, String)> {
+    let mut owners = FxHashMap::default();
+    let Ok(processes) = std::fs::read_dir(proc_dir) else {
+        return owners;
+    };
+    for process in processes.filter_map(Result::ok) {
+        let Some(pid) = process
+            .file_name()
+            .to_str()
+            .and_then(|pid| pid.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let Ok(fds) = std::fs::read_dir(process.path().join("fd")) else {
+            continue;
+        };
+        let name = std::fs::read_to_string(process.path().join("comm"))
+            .map(|comm| comm.trim_end().to_string())
+            .unwrap_or_default();
+        for fd in fds.filter_map(Result::ok) {
+            let Ok(target) = std::fs::read_link(fd.path()) else {
+                continue;
+            };
+            if let Some(inode) = target
+                .to_str()
+                .and_then(|target| target.strip_prefix("socket:["))
+                .and_then(|target| target.strip_suffix(']'))
+                .and_then(|inode| inode.parse().ok())
+            {
+                owners.insert(inode, (pid, name.clone()));
+            }
+        }
+    }
+    owners
+}
+
+/// The listening sockets found in `/proc/net`, if it can be read.
+fn proc_sockets(proc_dir: &Path) -> Option<Vec<Socket>> {
+    let mut tables = Vec::new();
+    for proto in ["tcp", "tcp6", "udp", "udp6"] {
+        if let Ok(content) =
+            std::fs::read_to_string(proc_dir.join("net").join(proto))
+        {
+            tables.push((proto, parse_proc_net(&content, proto)));
+        }
+    }
+    if tables.is_empty() {
+        return None;
+    }
+    let owners = socket_owners(proc_dir);
+    Some(
+        tables
+            .into_iter()
+            .flat_map(|(proto, sockets)| {
+                sockets.into_iter().map(|(port, inode)| {
+                    let owner = owners.get(&inode);
+                    Socket {
+                        port,
+                        proto: proto.to_string(),
+                        pid: owner.map(|(pid, _)| *pid),
+                        process: owner.map(|(_, name)| name.clone()),
+                    }
+                })
+            })
+            .collect(),
+    )
+}
+
+/// The listening sockets reported by `lsof`, for platforms without
+/// `/proc/net`.
+fn lsof_sockets() -> Vec<Socket> {
+    match Command::new("lsof")
+        .args(["-nP", "-iTCP", "-sTCP:LISTEN", "-iUDP"])
+        .output()
+    {
+        Ok(output) => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .skip(1)
+            .filter_map(parse_lsof_line)
+            .collect(),
+        Err(e) => {
+            debug!("Failed to list listening sockets: {:?}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Entries are named after the port, which is what accepting them outputs,
+/// the rest of the socket's details being their value.
+pub struct Channel {
+    matcher: Matcher<Socket>,
+    file_icon: FileIcon,
+    selected_entries: FxHashSet<Entry>,
+    crawl_handle: tokio::task::JoinHandle<()>,
+}
+
+const NUM_THREADS: usize = 1;
+const FILE_ICON_STR: &str = "config";
+
+const FIELD_DELIMITER: &str = "  ";
+// `{}` is the port, both commands listing the processes using it
+const PREVIEW_COMMAND: &str =
+    "lsof -nP -i :{} 2>/dev/null || ss -tuanp 'sport = :'{}";
+
+impl Channel {
+    pub fn new(config: &ChannelConfig) -> Self {
+        let matcher = Matcher::new(
+            config.matcher_config("ports").n_threads(NUM_THREADS),
+        );
+        let crawl_handle = tokio::spawn(load_sockets(matcher.injector()));
+        Self {
+            matcher,
+            file_icon: FileIcon::from(FILE_ICON_STR),
+            selected_entries: HashSet::with_hasher(FxBuildHasher),
+            crawl_handle,
+        }
+    }
+
+    fn build_entry(&self, socket: &Socket) -> Entry {
+        Entry::new(
+            socket.port.to_string(),
+            PreviewType::Command(PreviewCommand::new(
+                PREVIEW_COMMAND,
+                FIELD_DELIMITER,
+            )),
+        )
+        .with_value(socket.details())
+        .with_icon(self.file_icon)
+    }
+}
+
+impl From<&ChannelConfig> for Channel {
+    fn from(config: &ChannelConfig) -> Self {
+        Self::new(config)
+    }
+}
+
+impl OnAir for Channel {
+    fn find(&mut self, pattern: &str) {
+        self.matcher.find(pattern);
+    }
+
+    fn current_query(&self) -> &str {
+        &self.matcher.last_pattern
+    }
+
+    fn results(&mut self, num_entries: u32, offset: u32) -> Vec<Entry> {
+        self.matcher.tick();
+        self.matcher
+            .results(num_entries, offset)
+            .into_iter()
+            .map(|item| {
+                let (
+                    name_indices,
+                    value_indices,
+                    should_add_name_indices,
+                    should_add_value_indices,
+                ) = sep_name_and_value_indices(
+                    &mut item.match_indices.iter().map(|i| i.0).collect(),
+                    u32::try_from(item.inner.port.to_string().len()).unwrap(),
+                );
+
+                let mut entry = self.build_entry(&item.inner);
+                if should_add_name_indices {
+                    let name_indices: Vec<(u32, u32)> =
+                        name_indices.into_iter().map(|i| (i, i + 1)).collect();
+                    entry = entry.with_name_match_ranges(&name_indices);
+                }
+                if should_add_value_indices {
+                    let value_indices: Vec<(u32, u32)> = value_indices
+                        .into_iter()
+                        .map(|i| (i, i + 1))
+                        .collect();
+                    entry = entry.with_value_match_ranges(&value_indices);
+                }
+                entry
+            })
+            .collect()
+    }
+
+    fn get_result(&self, index: u32) -> Option<Entry> {
+        self.matcher
+            .get_result(index)
+            .map(|item| self.build_entry(&item.inner))
+    }
+
+    fn selected_entries(&self) -> &FxHashSet<Entry> {
+        &self.selected_entries
+    }
+
+    fn toggle_selection(&mut self, entry: &Entry) {
+        if self.selected_entries.contains(entry) {
+            self.selected_entries.remove(entry);
+        } else {
+            self.selected_entries.insert(entry.clone());
+        }
+    }
+
+    fn reset(&mut self) {
+        self.selected_entries.clear();
+        self.find("");
+    }
+
+    fn result_count(&self) -> u32 {
+        self.matcher.matched_item_count
+    }
+
+    fn total_count(&self) -> u32 {
+        self.matcher.total_item_count
+    }
+
+    fn running(&self) -> bool {
+        self.matcher.status.running
+    }
+
+    fn shutdown(&self) {
+        self.crawl_handle.abort();
+    }
+
+    fn supports_preview(&self) -> bool {
+        true
+    }
+}
+
+#[allow(clippy::unused_async)]
+async fn load_sockets(injector: Injector<Socket>) {
+    let mut sockets =
+        proc_sockets(Path::new("/proc")).unwrap_or_else(lsof_sockets);
+    sockets.sort_by(|a, b| {
+        (a.port, &a.proto, a.pid).cmp(&(b.port, &b.proto, b.pid))
+    });
+    let mut seen = HashSet::new();
+    sockets
+        .into_iter()
+        .filter(|socket| seen.insert(socket.clone()))
+        .for_each(|socket| {
+            let () = injector.push(socket, |e, cols| {
+                cols[0] = (e.port.to_string() + &e.details()).into();
+            });
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROC_NET_TCP: &str = "  \
+  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 12345 1 0000000000000000 100 0 0 10 0
+   1: 0100007F:0CEA 0100007F:A2B4 01 00000000:00000000 00:00000000 00000000  1000        0 23456 1 0000000000000000 20 4 30 10 -1
+   2: 0100007F:1538 00000000:0000 0A 00000000:00000000 00:00000000 00000000   999        0 34567 1 0000000000000000 100 0 0 10 0
+";
+
+    #[test]
+    fn test_parse_proc_net() {
+        // the established connection is left out
+        assert_eq!(
+            parse_proc_net(PROC_NET_TCP, "tcp"),
+            vec![(8080, 12345), (5432, 34567)]
+        );
+        // UDP sockets have a different "listening" state
+        assert!(parse_proc_net(PROC_NET_TCP, "udp").is_empty());
+    }
+
+    #[test]
+    fn test_parse_lsof_line() {
+        assert_eq!(
+            parse_lsof_line(
+                "nginx    1234 root    6u  IPv4 0x12345      0t0  TCP *:80 (LISTEN)"
+            ),
+            Some(Socket {
+                port: 80,
+                proto: String::from("tcp"),
+                pid: Some(1234),
+                process: Some(String::from("nginx")),
+            })
+        );
+        assert_eq!(
+            parse_lsof_line(
+                "Google\\x20Chrome 42 me 9u IPv6 0x1 0t0 UDP [::1]:5353"
+            )
+            .map(|socket| socket.to_string())
+            .as_deref(),
+            Some("5353  udp6  42  Google Chrome")
+        );
+        assert_eq!(
+            parse_lsof_line(
+                "COMMAND   PID USER   FD   TYPE DEVICE SIZE/OFF NODE NAME"
+            ),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_entries_output_the_port() {
+        let channel = Channel::new(&ChannelConfig::default());
+        channel.shutdown();
+        let entry = channel.build_entry(&Socket {
+            port: 8080,
+            proto: String::from("tcp"),
+            pid: Some(42),
+            process: Some(String::from("nginx")),
+        });
+
+        assert_eq!(entry.stdout_repr(), "8080");
+        assert_eq!(entry.value.as_deref(), Some("tcp  42  nginx"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_proc_sockets() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("net")).unwrap();
+        std::fs::write(dir.path().join("net/tcp"), PROC_NET_TCP).unwrap();
+        std::fs::create_dir_all(dir.path().join("42/fd")).unwrap();
+        std::fs::write(dir.path().join("42/comm"), "nginx\n").unwrap();
+        std::os::unix::fs::symlink(
+            "socket:[12345]",
+            dir.path().join("42/fd/3"),
+        )
+        .unwrap();
+
+        let sockets = proc_sockets(dir.path())
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(sockets, ["8080  tcp  42  nginx", "5432  tcp  -  -"]);
+        assert!(proc_sockets(&dir.path().join("missing")).is_none());
+    }
+}
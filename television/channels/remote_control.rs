@@ -3,7 +3,9 @@ use std::fmt::Display;
 
 use crate::channels::cable::{CableChannelPrototype, CableChannels};
 use crate::channels::entry::{Entry, PreviewType};
-use crate::channels::{CliTvChannel, OnAir, TelevisionChannel, UnitChannel};
+use crate::channels::{
+    ChannelConfig, CliTvChannel, OnAir, TelevisionChannel, UnitChannel,
+};
 use crate::matcher::{config::Config, Matcher};
 use anyhow::Result;
 use clap::ValueEnum;
@@ -72,17 +74,32 @@ impl RemoteControl {
         Self::new(television_channel.available_transitions(), None)
     }
 
-    pub fn zap(&self, channel_name: &str) -> Result<TelevisionChannel> {
-        match self
+    pub fn zap(
+        &self,
+        channel_name: &str,
+        config: ChannelConfig,
+    ) -> Result<TelevisionChannel> {
+        self.lazy_zap(channel_name, config)()
+    }
+
+    /// Like [`RemoteControl::zap`] but deferring the construction of the
+    /// channel, e.g. to a background task.
+    pub fn lazy_zap(
+        &self,
+        channel_name: &str,
+        config: ChannelConfig,
+    ) -> impl FnOnce() -> Result<TelevisionChannel> + Send + 'static {
+        let prototype = self
             .cable_channels
             .as_ref()
-            .and_then(|channels| channels.get(channel_name).cloned())
-        {
-            Some(prototype) => {
-                Ok(TelevisionChannel::Cable(cable::Channel::from(prototype)))
-            }
-            None => match UnitChannel::try_from(channel_name) {
-                Ok(channel) => Ok(channel.into()),
+            .and_then(|channels| channels.get(channel_name).cloned());
+        let channel_name = channel_name.to_string();
+        move || match prototype {
+            Some(prototype) => Ok(TelevisionChannel::Cable(
+                cable::Channel::from_prototype(prototype, &config),
+            )),
+            None => match UnitChannel::try_from(channel_name.as_str()) {
+                Ok(channel) => Ok(channel.to_channel(&config)),
                 Err(_) => Err(anyhow::anyhow!(
                     "No channel or cable channel prototype found for {}",
                     channel_name
@@ -90,6 +107,32 @@ impl RemoteControl {
             },
         }
     }
+
+    /// A description of the given channel, listing the commands of cable
+    /// channels.
+    pub fn describe(&self, channel_name: &str) -> Vec<String> {
+        let Some(prototype) = self
+            .cable_channels
+            .as_ref()
+            .and_then(|channels| channels.get(channel_name))
+        else {
+            return vec![String::from("Builtin channel")];
+        };
+        let mut lines = vec![format!("Source: {}", prototype.source_command)];
+        if let Some(preview_command) = &prototype.preview_command {
+            lines.push(format!("Preview: {preview_command}"));
+        }
+        lines
+    }
+
+    /// The number of entries the given cable channel asks to list when
+    /// previewed from the remote control, if any.
+    pub fn guide_preview_entries(&self, channel_name: &str) -> Option<u32> {
+        self.cable_channels
+            .as_ref()
+            .and_then(|channels| channels.get(channel_name))
+            .and_then(|prototype| prototype.guide_preview_entries)
+    }
 }
 
 impl Default for RemoteControl {
@@ -137,6 +180,10 @@ impl OnAir for RemoteControl {
         self.matcher.find(pattern);
     }
 
+    fn current_query(&self) -> &str {
+        &self.matcher.last_pattern
+    }
+
     fn results(&mut self, num_entries: u32, offset: u32) -> Vec<Entry> {
         self.matcher.tick();
         self.matcher
@@ -168,6 +215,11 @@ impl OnAir for RemoteControl {
     #[allow(unused_variables)]
     fn toggle_selection(&mut self, entry: &Entry) {}
 
+    fn reset(&mut self) {
+        self.selected_entries.clear();
+        self.find("");
+    }
+
     fn result_count(&self) -> u32 {
         self.matcher.matched_item_count
     }
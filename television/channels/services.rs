@@ -0,0 +1,381 @@
+use std::collections::HashSet;
+use std::process::Command;
+
+use devicons::FileIcon;
+use rustc_hash::{FxBuildHasher, FxHashMap, FxHashSet};
+use tracing::debug;
+
+use crate::channels::entry::{Entry, PreviewCommand, PreviewType};
+use crate::channels::{ChannelConfig, OnAir};
+use crate::matcher::{injector::Injector, Matcher};
+
+/// A system service and its state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Service {
+    pub name: String,
+    /// Whether the service is running (e.g. `active (running)`).
+    pub state: String,
+    /// Whether the service is started at boot (e.g. `enabled`), if known.
+    pub enabled: Option<String>,
+    pub description: String,
+}
+
+impl Service {
+    /// The state and description of the service, shown next to its name.
+    fn summary(&self) -> String {
+        let mut summary = self.state.clone();
+        if let Some(enabled) = &self.enabled {
+            summary.push_str(", ");
+            summary.push_str(enabled);
+        }
+        if !self.description.is_empty() {
+            summary.push_str(FIELD_DELIMITER);
+            summary.push_str(&self.description);
+        }
+        summary
+    }
+}
+
+/// Split the first whitespace separated field off `s`.
+fn next_field(s: &str) -> Option<(&str, &str)> {
+    let s = s.trim_start();
+    if s.is_empty() {
+        return None;
+    }
+    let end = s.find(char::is_whitespace).unwrap_or(s.len());
+    Some((&s[..end], &s[end..]))
+}
+
+/// Parse a line of `systemctl list-units --type=service --all --plain
+/// --no-legend` output.
+///
+/// Lines look like
+/// `ssh.service loaded active running OpenBSD Secure Shell server`, units
+/// that failed possibly being marked with a leading `●`.
+fn parse_systemctl_unit(line: &str) -> Option<Service> {
+    let line = line.trim_start().trim_start_matches('●');
+    let (name, rest) = next_field(line)?;
+    let (load, rest) = next_field(rest)?;
+    let (active, rest) = next_field(rest)?;
+    let (sub, description) = next_field(rest)?;
+    // units referenced by others but not installed
+    if load == "not-found" {
+        return None;
+    }
+    Some(Service {
+        name: name.to_string(),
+        state: format!("{active} ({sub})"),
+        enabled: None,
+        description: description.trim().to_string(),
+    })
+}
+
+/// Parse `systemctl list-unit-files --type=service --plain --no-legend`
+/// output into whether each unit is started at boot.
+///
+/// Lines look like `ssh.service enabled enabled`, the last field being the
+/// vendor preset.
+fn parse_systemctl_unit_files(output: &str) -> FxHashMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (name, rest) = next_field(line)?;
+            let (enabled, _) = next_field(rest)?;
+            Some((name.to_string(), enabled.to_string()))
+        })
+        .collect()
+}
+
+/// Parse a line of `launchctl list` output.
+///
+/// Lines look like `123 0 com.example.agent`, the pid being `-` for jobs
+/// that aren't running and the status being the last exit status.
+fn parse_launchctl_line(line: &str) -> Option<Service> {
+    let fields = line.split_whitespace().collect::<Vec<_>>();
+    let [pid, status, label] = fields[..] else {
+        return None;
+    };
+    let state = match (pid.parse::<u32>(), status) {
+        (Ok(pid), _) => format!("running (pid {pid})"),
+        (Err(_), "0") if pid == "-" => String::from("stopped"),
+        (Err(_), _) if pid == "-" => format!("stopped (exit status {status})"),
+        // the header
+        _ => return None,
+    };
+    Some(Service {
+        name: label.to_string(),
+        state,
+        enabled: None,
+        description: String::new(),
+    })
+}
+
+/// The stdout of the given command, if it could be run successfully.
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    match Command::new(program).args(args).output() {
+        Ok(output) if output.status.success() => {
+            Some(String::from_utf8_lossy(&output.stdout).into_owned())
+        }
+        Ok(output) => {
+            debug!("{program} exited with {}", output.status);
+            None
+        }
+        Err(e) => {
+            debug!("Failed to run {program}: {:?}", e);
+            None
+        }
+    }
+}
+
+/// The services managed by systemd.
+fn systemctl_services() -> Vec<Service> {
+    const ARGS: [&str; 5] = [
+        "--type=service",
+        "--all",
+        "--plain",
+        "--no-legend",
+        "--no-pager",
+    ];
+    let Some(units) =
+        command_output("systemctl", &[&["list-units"], &ARGS[..]].concat())
+    else {
+        return Vec::new();
+    };
+    let unit_files = command_output(
+        "systemctl",
+        &[&["list-unit-files"], &ARGS[..]].concat(),
+    )
+    .map(|output| parse_systemctl_unit_files(&output))
+    .unwrap_or_default();
+    units
+        .lines()
+        .filter_map(parse_systemctl_unit)
+        .map(|service| Service {
+            enabled: unit_files.get(&service.name).cloned(),
+            ..service
+        })
+        .collect()
+}
+
+/// The jobs managed by launchd.
+fn launchctl_services() -> Vec<Service> {
+    command_output("launchctl", &["list"])
+        .map(|output| {
+            output.lines().filter_map(parse_launchctl_line).collect()
+        })
+        .unwrap_or_default()
+}
+
+pub struct Channel {
+    matcher: Matcher<Service>,
+    file_icon: FileIcon,
+    selected_entries: FxHashSet<Entry>,
+    crawl_handle: tokio::task::JoinHandle<()>,
+}
+
+const NUM_THREADS: usize = 1;
+const FILE_ICON_STR: &str = "config";
+
+const FIELD_DELIMITER: &str = "  ";
+#[cfg(target_os = "macos")]
+const PREVIEW_COMMAND: &str = "launchctl list {} 2>&1";
+#[cfg(not(target_os = "macos"))]
+const PREVIEW_COMMAND: &str =
+    "systemctl status --no-pager --full --lines=30 {} 2>&1";
+
+impl Channel {
+    pub fn new(config: &ChannelConfig) -> Self {
+        let matcher = Matcher::new(
+            config.matcher_config("services").n_threads(NUM_THREADS),
+        );
+        let crawl_handle = tokio::spawn(load_services(matcher.injector()));
+        Self {
+            matcher,
+            file_icon: FileIcon::from(FILE_ICON_STR),
+            selected_entries: HashSet::with_hasher(FxBuildHasher),
+            crawl_handle,
+        }
+    }
+
+    fn build_entry(&self, service: &Service) -> Entry {
+        Entry::new(
+            service.name.clone(),
+            PreviewType::Command(PreviewCommand::new(PREVIEW_COMMAND, " ")),
+        )
+        .with_value(service.summary())
+        .with_icon(self.file_icon)
+    }
+}
+
+impl From<&ChannelConfig> for Channel {
+    fn from(config: &ChannelConfig) -> Self {
+        Self::new(config)
+    }
+}
+
+impl OnAir for Channel {
+    fn find(&mut self, pattern: &str) {
+        self.matcher.find(pattern);
+    }
+
+    fn current_query(&self) -> &str {
+        &self.matcher.last_pattern
+    }
+
+    fn results(&mut self, num_entries: u32, offset: u32) -> Vec<Entry> {
+        self.matcher.tick();
+        self.matcher
+            .results(num_entries, offset)
+            .into_iter()
+            .map(|item| {
+                self.build_entry(&item.inner)
+                    .with_name_match_ranges(&item.match_indices)
+            })
+            .collect()
+    }
+
+    fn get_result(&self, index: u32) -> Option<Entry> {
+        self.matcher
+            .get_result(index)
+            .map(|item| self.build_entry(&item.inner))
+    }
+
+    fn selected_entries(&self) -> &FxHashSet<Entry> {
+        &self.selected_entries
+    }
+
+    fn toggle_selection(&mut self, entry: &Entry) {
+        if self.selected_entries.contains(entry) {
+            self.selected_entries.remove(entry);
+        } else {
+            self.selected_entries.insert(entry.clone());
+        }
+    }
+
+    fn reset(&mut self) {
+        self.selected_entries.clear();
+        self.find("");
+    }
+
+    fn result_count(&self) -> u32 {
+        self.matcher.matched_item_count
+    }
+
+    fn total_count(&self) -> u32 {
+        self.matcher.total_item_count
+    }
+
+    fn running(&self) -> bool {
+        self.matcher.status.running
+    }
+
+    fn shutdown(&self) {
+        self.crawl_handle.abort();
+    }
+
+    fn supports_preview(&self) -> bool {
+        true
+    }
+}
+
+/// List the system's services, platforms without a supported service
+/// manager having none.
+#[allow(clippy::unused_async)]
+async fn load_services(injector: Injector<Service>) {
+    let mut services = if cfg!(target_os = "macos") {
+        launchctl_services()
+    } else {
+        systemctl_services()
+    };
+    services.sort_by(|a, b| a.name.cmp(&b.name));
+    for service in services {
+        let () = injector.push(service, |e, cols| {
+            cols[0] = e.name.clone().into();
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SYSTEMCTL_UNITS: &str = "\
+ssh.service                 loaded    active   running OpenBSD Secure Shell server
+● nginx.service             loaded    failed   failed  A high performance web server
+cron.service                loaded    inactive dead    Regular background program processing daemon
+auditd.service              not-found inactive dead    auditd.service
+";
+
+    const SYSTEMCTL_UNIT_FILES: &str = "\
+cron.service                 enabled         enabled
+nginx.service                disabled        enabled
+ssh.service                  enabled         enabled
+";
+
+    #[test]
+    fn test_parse_systemctl_units() {
+        let enabled = parse_systemctl_unit_files(SYSTEMCTL_UNIT_FILES);
+        let services = SYSTEMCTL_UNITS
+            .lines()
+            .filter_map(parse_systemctl_unit)
+            .map(|service| Service {
+                enabled: enabled.get(&service.name).cloned(),
+                ..service
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            services
+                .iter()
+                .map(|service| (service.name.as_str(), service.summary()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "ssh.service",
+                    String::from(
+                        "active (running), enabled  OpenBSD Secure Shell server"
+                    )
+                ),
+                (
+                    "nginx.service",
+                    String::from(
+                        "failed (failed), disabled  A high performance web server"
+                    )
+                ),
+                (
+                    "cron.service",
+                    String::from(
+                        "inactive (dead), enabled  Regular background program \
+                        processing daemon"
+                    )
+                ),
+            ]
+        );
+        assert_eq!(parse_systemctl_unit(""), None);
+    }
+
+    #[test]
+    fn test_parse_launchctl_line() {
+        assert_eq!(parse_launchctl_line("PID\tStatus\tLabel"), None);
+        assert_eq!(
+            parse_launchctl_line("123\t0\tcom.example.agent")
+                .map(|service| service.summary())
+                .as_deref(),
+            Some("running (pid 123)")
+        );
+        assert_eq!(
+            parse_launchctl_line("-\t0\tcom.example.idle")
+                .map(|service| service.summary())
+                .as_deref(),
+            Some("stopped")
+        );
+        assert_eq!(
+            parse_launchctl_line("-\t78\tcom.example.crashed"),
+            Some(Service {
+                name: String::from("com.example.crashed"),
+                state: String::from("stopped (exit status 78)"),
+                enabled: None,
+                description: String::new(),
+            })
+        );
+    }
+}
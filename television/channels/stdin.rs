@@ -1,46 +1,125 @@
 use std::{
     collections::HashSet,
     io::{stdin, BufRead},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
     thread::spawn,
 };
 
 use rustc_hash::{FxBuildHasher, FxHashSet};
 use tracing::debug;
 
-use super::OnAir;
+use super::{ChannelConfig, OnAir};
 use crate::channels::entry::{Entry, PreviewType};
-use crate::matcher::{config::Config, injector::Injector, Matcher};
+use crate::matcher::{config::MatchTarget, injector::Injector, Matcher};
+use crate::utils::json::JsonFieldPath;
 
 pub struct Channel {
     matcher: Matcher<String>,
     preview_type: PreviewType,
     selected_entries: FxHashSet<Entry>,
+    json_field: Option<JsonFieldPath>,
+    skipped_lines: Arc<AtomicUsize>,
 }
 
 impl Channel {
-    pub fn new(preview_type: PreviewType) -> Self {
-        let matcher = Matcher::new(Config::default());
+    pub fn new(preview_type: PreviewType, config: &ChannelConfig) -> Self {
+        Self::with_json_field(preview_type, None, config)
+    }
+
+    /// Create a stdin channel that optionally parses each line as JSON.
+    ///
+    /// When a field is given, entries display and match that field of each
+    /// JSON line while carrying the whole line as their value. Lines that
+    /// can't be parsed or lack the field are skipped.
+    pub fn with_json_field(
+        preview_type: PreviewType,
+        json_field: Option<JsonFieldPath>,
+        config: &ChannelConfig,
+    ) -> Self {
+        let matcher = Matcher::new(config.matcher_config("stdin"));
         let injector = matcher.injector();
+        let skipped_lines = Arc::new(AtomicUsize::new(0));
 
-        spawn(move || stream_from_stdin(&injector));
+        let field = json_field.clone();
+        let skipped = skipped_lines.clone();
+        spawn(move || stream_from_stdin(&injector, field.as_ref(), &skipped));
 
         Self {
             matcher,
             preview_type,
             selected_entries: HashSet::with_hasher(FxBuildHasher),
+            json_field,
+            skipped_lines,
+        }
+    }
+
+    /// The number of lines that were skipped because they couldn't be parsed.
+    pub fn skipped_lines(&self) -> usize {
+        self.skipped_lines.load(Ordering::Relaxed)
+    }
+
+    fn build_entry(
+        &self,
+        name: String,
+        line: String,
+        preview_type: PreviewType,
+    ) -> Entry {
+        let entry = Entry::new(name, preview_type);
+        if self.json_field.is_some() {
+            entry.with_value(line)
+        } else {
+            entry
         }
     }
 }
 
-impl Default for Channel {
-    fn default() -> Self {
-        Self::new(PreviewType::default())
+impl From<&ChannelConfig> for Channel {
+    fn from(config: &ChannelConfig) -> Self {
+        Self::new(PreviewType::default(), config)
     }
 }
 
 const TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
 
-fn stream_from_stdin(injector: &Injector<String>) {
+/// Push a line read from stdin into the matcher.
+///
+/// The line is matched as is unless a JSON field is given, in which case the
+/// field is extracted from the line and the line is counted as skipped if
+/// that fails.
+fn push_line(
+    injector: &Injector<String>,
+    line: &str,
+    json_field: Option<&JsonFieldPath>,
+    skipped: &AtomicUsize,
+) {
+    let line = line.trim();
+    if line.is_empty() {
+        return;
+    }
+    let Some(field) = json_field else {
+        injector.push(line.to_string(), |e, cols| {
+            cols[0] = e.to_string().into();
+        });
+        return;
+    };
+    if let Some(value) = field.extract(line) {
+        injector.push(line.to_string(), move |_, cols| {
+            cols[0] = value.as_str().into();
+        });
+    } else {
+        debug!("Skipping line: {:?}", line);
+        skipped.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+fn stream_from_stdin(
+    injector: &Injector<String>,
+    json_field: Option<&JsonFieldPath>,
+    skipped: &AtomicUsize,
+) {
     let mut stdin = stdin().lock();
     let mut buffer = String::new();
 
@@ -48,12 +127,7 @@ fn stream_from_stdin(injector: &Injector<String>) {
     loop {
         match stdin.read_line(&mut buffer) {
             Ok(c) if c > 0 => {
-                let buf = buffer.trim();
-                if !buf.is_empty() {
-                    injector.push(buf.to_string(), |e, cols| {
-                        cols[0] = e.to_string().into();
-                    });
-                }
+                push_line(injector, &buffer, json_field, skipped);
                 buffer.clear();
             }
             Ok(0) => {
@@ -75,6 +149,10 @@ impl OnAir for Channel {
         self.matcher.find(pattern);
     }
 
+    fn current_query(&self) -> &str {
+        &self.matcher.last_pattern
+    }
+
     fn results(&mut self, num_entries: u32, offset: u32) -> Vec<Entry> {
         self.matcher.tick();
         self.matcher
@@ -83,15 +161,23 @@ impl OnAir for Channel {
             .map(|item| {
                 // NOTE: we're passing `PreviewType::Basic` here just as a placeholder
                 // to avoid storing the preview command multiple times for each item.
-                Entry::new(item.matched_string, PreviewType::Basic)
-                    .with_name_match_ranges(&item.match_indices)
+                self.build_entry(
+                    item.matched_string,
+                    item.inner,
+                    PreviewType::Basic,
+                )
+                .with_name_match_ranges(&item.match_indices)
             })
             .collect()
     }
 
     fn get_result(&self, index: u32) -> Option<Entry> {
         self.matcher.get_result(index).map(|item| {
-            Entry::new(item.matched_string, self.preview_type.clone())
+            self.build_entry(
+                item.matched_string,
+                item.inner,
+                self.preview_type.clone(),
+            )
         })
     }
 
@@ -107,6 +193,11 @@ impl OnAir for Channel {
         }
     }
 
+    fn reset(&mut self) {
+        self.selected_entries.clear();
+        self.find("");
+    }
+
     fn result_count(&self) -> u32 {
         self.matcher.matched_item_count
     }
@@ -124,4 +215,58 @@ impl OnAir for Channel {
     fn supports_preview(&self) -> bool {
         self.preview_type != PreviewType::None
     }
+
+    fn set_match_target(&mut self, target: MatchTarget) -> bool {
+        let json_field = self.json_field.clone();
+        self.matcher.set_target(target, move |line, cols| {
+            cols[0] = match &json_field {
+                Some(field) => field.extract(line).unwrap_or_default().into(),
+                None => line.as_str().into(),
+            };
+        });
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matcher::config::Config;
+
+    const SAMPLE_LINES: &[&str] = &[
+        r#"{"name": "alice", "user": {"name": "Alice", "id": 1}}"#,
+        r#"{"name": "bob", "user": {"name": "Bob", "id": 2}}"#,
+        r#"{"name": "carol", "user": {"id": 3}}"#,
+        "not json at all",
+    ];
+
+    #[test]
+    fn test_push_json_lines_skips_malformed_lines() {
+        let mut matcher = Matcher::new(Config::default());
+        let injector = matcher.injector();
+        let skipped = AtomicUsize::new(0);
+        let field = JsonFieldPath::parse(".user.name");
+
+        for line in SAMPLE_LINES {
+            push_line(&injector, line, Some(&field), &skipped);
+        }
+        matcher.find("");
+        matcher.tick();
+        while matcher.status.running {
+            matcher.tick();
+        }
+
+        assert_eq!(skipped.load(Ordering::Relaxed), 2);
+        let results = matcher.results(10, 0);
+        assert_eq!(
+            results
+                .iter()
+                .map(|item| (
+                    item.matched_string.as_str(),
+                    item.inner.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            vec![("Alice", SAMPLE_LINES[0]), ("Bob", SAMPLE_LINES[1])]
+        );
+    }
 }
@@ -1,6 +1,6 @@
-use super::{OnAir, TelevisionChannel};
+use super::{ChannelConfig, OnAir, TelevisionChannel};
 use crate::channels::entry::{Entry, PreviewType};
-use crate::matcher::{config::Config, injector::Injector, Matcher};
+use crate::matcher::{config::Dedup, injector::Injector, Matcher};
 use crate::utils::files::{get_default_num_threads, walk_builder};
 use crate::utils::strings::{
     proportion_of_printable_ascii_characters, PRINTABLE_ASCII_THRESHOLD,
@@ -15,6 +15,7 @@ use std::{
     path::{Path, PathBuf},
     sync::{atomic::AtomicUsize, Arc},
 };
+use tokio::task::JoinHandle;
 use tracing::{debug, trace, warn};
 
 #[derive(Debug, Clone)]
@@ -37,80 +38,136 @@ impl CandidateLine {
 #[allow(clippy::module_name_repetitions)]
 pub struct Channel {
     matcher: Matcher<CandidateLine>,
-    crawl_handle: tokio::task::JoinHandle<()>,
+    crawl_handles: Vec<JoinHandle<()>>,
     selected_entries: FxHashSet<Entry>,
+    /// Whether lines are deduplicated by their location rather than by
+    /// their content (see `push_line`).
+    dedup_by_location: bool,
 }
 
 impl Channel {
-    pub fn new(directories: Vec<PathBuf>) -> Self {
-        let matcher = Matcher::new(Config::default());
-        // start loading files in the background
-        let crawl_handle = tokio::spawn(crawl_for_candidates(
-            directories,
-            matcher.injector(),
-        ));
+    pub fn new(directories: Vec<PathBuf>, config: &ChannelConfig) -> Self {
+        Self::load(
+            Source::Directories(directories),
+            Matcher::new(config.matcher_config("text")),
+            false,
+        )
+    }
+
+    pub fn from_file_paths(
+        file_paths: Vec<PathBuf>,
+        config: &ChannelConfig,
+    ) -> Self {
+        Self::load(
+            Source::FilePaths(file_paths),
+            Matcher::new(config.matcher_config("text")),
+            false,
+        )
+    }
+
+    /// A channel loading the lines sent from the given channel.
+    ///
+    /// Unless the lines are configured to be deduplicated by their content,
+    /// the lines loaded twice from the same file are deduplicated.
+    pub fn from_channel(
+        source: &mut TelevisionChannel,
+        config: &ChannelConfig,
+    ) -> Self {
+        Self::load(
+            Source::from(source),
+            Matcher::new(config.sent_matcher_config("text")),
+            config.matcher_config("text").dedup == Dedup::None,
+        )
+    }
+
+    /// Start loading the given source's lines in the background.
+    fn load(
+        source: Source,
+        matcher: Matcher<CandidateLine>,
+        dedup_by_location: bool,
+    ) -> Self {
+        let injector = matcher.injector();
+        let crawl_handle = tokio::task::spawn_blocking(move || {
+            load_source(source, &injector, dedup_by_location);
+        });
         Channel {
             matcher,
-            crawl_handle,
+            crawl_handles: vec![crawl_handle],
             selected_entries: HashSet::with_hasher(FxBuildHasher),
+            dedup_by_location,
         }
     }
 
-    fn from_file_paths(file_paths: Vec<PathBuf>) -> Self {
-        let matcher = Matcher::new(Config::default());
-        let injector = matcher.injector();
-        let current_dir = std::env::current_dir().unwrap();
-        let crawl_handle = tokio::spawn(async move {
+    /// Load the lines sent from the given channel on top of the ones already
+    /// loaded, skipping the lines already listed.
+    pub fn ingest(&mut self, source: &mut TelevisionChannel) {
+        let source = Source::from(source);
+        let injector = self.matcher.injector();
+        let dedup_by_location = self.dedup_by_location;
+        self.crawl_handles
+            .push(tokio::task::spawn_blocking(move || {
+                load_source(source, &injector, dedup_by_location);
+            }));
+    }
+}
+
+/// Where the channel's lines are loaded from.
+enum Source {
+    /// Every text file found when crawling the directories.
+    Directories(Vec<PathBuf>),
+    /// The given text files.
+    FilePaths(Vec<PathBuf>),
+    /// Lines already found by another text channel.
+    TextEntries(Vec<Entry>),
+}
+
+/// Load the source's lines, blocking until done (or resumed if paused).
+fn load_source(
+    source: Source,
+    injector: &Injector<CandidateLine>,
+    dedup_by_location: bool,
+) {
+    match source {
+        Source::Directories(directories) => {
+            crawl_for_candidates(&directories, injector, dedup_by_location);
+        }
+        Source::FilePaths(file_paths) => {
+            let current_dir = std::env::current_dir().unwrap();
             let mut lines_in_mem = 0;
             for path in file_paths {
                 if lines_in_mem > MAX_LINES_IN_MEM {
                     break;
                 }
-                if let Some(injected_lines) =
-                    try_inject_lines(&injector, &current_dir, &path)
-                {
+                if let Some(injected_lines) = try_inject_lines(
+                    injector,
+                    &current_dir,
+                    &path,
+                    dedup_by_location,
+                ) {
                     lines_in_mem += injected_lines;
                 }
             }
-        });
-
-        Channel {
-            matcher,
-            crawl_handle,
-            selected_entries: HashSet::with_hasher(FxBuildHasher),
         }
-    }
-
-    fn from_text_entries(entries: Vec<Entry>) -> Self {
-        let matcher = Matcher::new(Config::default());
-        let injector = matcher.injector();
-        let load_handle = tokio::spawn(async move {
+        Source::TextEntries(entries) => {
             for entry in entries.into_iter().take(MAX_LINES_IN_MEM) {
                 let v = entry.value.unwrap();
-                injector.push(
+                push_line(
+                    injector,
                     CandidateLine::new(
                         entry.name.into(),
                         v,
                         entry.line_number.unwrap(),
                     ),
-                    |e, cols| {
-                        cols[0] = e.line.clone().into();
-                    },
+                    dedup_by_location,
                 );
             }
-        });
-
-        Channel {
-            matcher,
-            crawl_handle: load_handle,
-            selected_entries: HashSet::with_hasher(FxBuildHasher),
         }
     }
 }
 
-impl Default for Channel {
-    fn default() -> Self {
-        Self::new(vec![std::env::current_dir().unwrap()])
+impl From<&ChannelConfig> for Channel {
+    fn from(config: &ChannelConfig) -> Self {
+        Self::new(vec![std::env::current_dir().unwrap()], config)
     }
 }
 
@@ -122,10 +179,11 @@ impl Default for Channel {
 /// `MAX_LINES_IN_MEM / 100` (assuming 100 lines per file on average).
 const MAX_PIPED_FILES: usize = MAX_LINES_IN_MEM / 200;
 
-impl From<&mut TelevisionChannel> for Channel {
+impl From<&mut TelevisionChannel> for Source {
     fn from(value: &mut TelevisionChannel) -> Self {
         match value {
-            c @ TelevisionChannel::Files(_) => {
+            c
+            @ (TelevisionChannel::Files(_) | TelevisionChannel::Stdin(_)) => {
                 let entries = if c.selected_entries().is_empty() {
                     c.results(
                         c.result_count().min(
@@ -136,7 +194,7 @@ impl From<&mut TelevisionChannel> for Channel {
                 } else {
                     c.selected_entries().iter().cloned().collect()
                 };
-                Self::from_file_paths(
+                Source::FilePaths(
                     entries
                         .iter()
                         .flat_map(|entry| {
@@ -151,7 +209,7 @@ impl From<&mut TelevisionChannel> for Channel {
                 } else {
                     c.selected_entries().iter().cloned().collect()
                 };
-                Self::new(
+                Source::Directories(
                     entries
                         .iter()
                         .flat_map(|entry| {
@@ -166,7 +224,7 @@ impl From<&mut TelevisionChannel> for Channel {
                 } else {
                     c.selected_entries().iter().cloned().collect()
                 };
-                Self::from_text_entries(entries)
+                Source::TextEntries(entries)
             }
             c @ TelevisionChannel::Dirs(_) => {
                 let entries = if c.selected_entries().is_empty() {
@@ -174,7 +232,7 @@ impl From<&mut TelevisionChannel> for Channel {
                 } else {
                     c.selected_entries().iter().cloned().collect()
                 };
-                Self::new(
+                Source::Directories(
                     entries
                         .iter()
                         .map(|entry| PathBuf::from(&entry.name))
@@ -191,6 +249,10 @@ impl OnAir for Channel {
         self.matcher.find(pattern);
     }
 
+    fn current_query(&self) -> &str {
+        &self.matcher.last_pattern
+    }
+
     fn results(&mut self, num_entries: u32, offset: u32) -> Vec<Entry> {
         self.matcher.tick();
         self.matcher
@@ -230,6 +292,11 @@ impl OnAir for Channel {
         }
     }
 
+    fn reset(&mut self) {
+        self.selected_entries.clear();
+        self.find("");
+    }
+
     fn result_count(&self) -> u32 {
         self.matcher.matched_item_count
     }
@@ -239,16 +306,25 @@ impl OnAir for Channel {
     }
 
     fn running(&self) -> bool {
-        self.matcher.status.running
+        self.matcher.status.running && !self.matcher.paused()
     }
 
     fn shutdown(&self) {
-        self.crawl_handle.abort();
+        super::stop_crawls(&self.matcher, &self.crawl_handles);
     }
 
     fn supports_preview(&self) -> bool {
         true
     }
+
+    fn pause(&mut self) -> bool {
+        self.matcher.pause();
+        true
+    }
+
+    fn resume(&mut self) {
+        self.matcher.resume();
+    }
 }
 
 /// The maximum file size we're willing to search in.
@@ -271,10 +347,10 @@ const MAX_FILE_SIZE: u64 = 4 * 1024 * 1024;
 /// so this should take around 100 x `10_000_000` = 1GB of memory.
 const MAX_LINES_IN_MEM: usize = 10_000_000;
 
-#[allow(clippy::unused_async)]
-async fn crawl_for_candidates(
-    directories: Vec<PathBuf>,
-    injector: Injector<CandidateLine>,
+fn crawl_for_candidates(
+    directories: &[PathBuf],
+    injector: &Injector<CandidateLine>,
+    dedup_by_location: bool,
 ) {
     if directories.is_empty() {
         return;
@@ -306,9 +382,12 @@ async fn crawl_for_candidates(
                         }
                     }
                     // try to inject the lines of the file
-                    if let Some(injected_lines) =
-                        try_inject_lines(&injector, &current_dir, entry.path())
-                    {
+                    if let Some(injected_lines) = try_inject_lines(
+                        &injector,
+                        &current_dir,
+                        entry.path(),
+                        dedup_by_location,
+                    ) {
                         lines_in_mem.fetch_add(
                             injected_lines,
                             std::sync::atomic::Ordering::Relaxed,
@@ -321,10 +400,29 @@ async fn crawl_for_candidates(
     });
 }
 
+/// Push a line into the matcher, deduplicating it by its path and line
+/// number rather than by its content if `by_location` is set.
+fn push_line(
+    injector: &Injector<CandidateLine>,
+    line: CandidateLine,
+    by_location: bool,
+) {
+    let push = |e: &CandidateLine, cols: &mut [nucleo::Utf32String]| {
+        cols[0] = e.line.clone().into();
+    };
+    if by_location {
+        let key = format!("{}:{}", line.path.display(), line.line_number);
+        injector.push_with_key(line, &key, push);
+    } else {
+        injector.push(line, push);
+    }
+}
+
 fn try_inject_lines(
     injector: &Injector<CandidateLine>,
     current_dir: &PathBuf,
     path: &Path,
+    dedup_by_location: bool,
 ) -> Option<usize> {
     match File::open(path) {
         Ok(file) => {
@@ -366,9 +464,7 @@ fn try_inject_lines(
                             l.clone(),
                             line_number,
                         );
-                        let () = injector.push(candidate, |e, cols| {
-                            cols[0] = e.line.clone().into();
-                        });
+                        push_line(injector, candidate, dedup_by_location);
                         injected_lines += 1;
                     }
                     Err(e) => {
@@ -385,3 +481,34 @@ fn try_inject_lines(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_lines_sent_again_are_listed_once() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "same\nother\n").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "same\n").unwrap();
+
+        let mut matcher =
+            Matcher::new(ChannelConfig::default().sent_matcher_config("text"));
+        let paths = vec![dir.path().join("a.txt"), dir.path().join("b.txt")];
+        for _ in 0..2 {
+            load_source(
+                Source::FilePaths(paths.clone()),
+                &matcher.injector(),
+                true,
+            );
+        }
+        matcher.find("");
+        matcher.tick();
+        while matcher.status.running {
+            matcher.tick();
+        }
+
+        // identical lines of different files are kept
+        assert_eq!(matcher.results(10, 0).len(), 3);
+    }
+}
@@ -1,5 +1,6 @@
 use clap::{Parser, Subcommand, ValueEnum};
 
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
@@ -8,13 +9,11 @@ pub struct Cli {
     /// A list of the available channels can be displayed using the
     /// `list-channels` command. The channel can also be changed from within
     /// the application.
-    #[arg(
-        value_enum,
-        default_value = "files",
-        index = 1,
-        verbatim_doc_comment
-    )]
-    pub channel: String,
+    /// Defaults to the channel named by the `TV_DEFAULT_CHANNEL` environment
+    /// variable, else to the last channel used when the
+    /// `remember_last_channel` option is enabled, else to the files channel.
+    #[arg(value_enum, index = 1, verbatim_doc_comment)]
+    pub channel: Option<String>,
 
     /// A preview command to use with the stdin channel.
     ///
@@ -34,6 +33,56 @@ pub struct Cli {
     #[arg(long, default_value = "false", verbatim_doc_comment)]
     pub no_preview: bool,
 
+    /// Start in send to channel mode.
+    ///
+    /// The channel's entries (e.g. the lines piped through stdin) are loaded
+    /// as usual and can immediately be sent to one of the channels listed.
+    /// Example: `git ls-files | tv --send-to-channel`
+    #[arg(long, default_value = "false", verbatim_doc_comment)]
+    pub send_to_channel: bool,
+
+    /// Keep running after a selection is accepted.
+    ///
+    /// Each accepted selection is printed right away, followed by an empty
+    /// line, and a new search starts until television is exited.
+    /// Example: `tv --loop | while read -r entry; do ...; done`
+    #[arg(long = "loop", default_value = "false", verbatim_doc_comment)]
+    pub loop_mode: bool,
+
+    /// Print the 1-based rank of the accepted entry among the results.
+    ///
+    /// The rank of the entry under the cursor is printed on its own line,
+    /// before the selected entries (and after the passthrough key, if any).
+    /// Example: `tv --print-index | head -n 1`
+    #[arg(long, default_value = "false", verbatim_doc_comment)]
+    pub print_index: bool,
+
+    /// Print the query along with the accepted entries.
+    ///
+    /// The query is printed on the first line, followed by the
+    /// `query_separator` line from the configuration file and then by the
+    /// selection.
+    /// Example: `tv --print-query | sed '1,/^---selection---$/d'`
+    #[arg(long, default_value = "false", verbatim_doc_comment)]
+    pub print_query: bool,
+
+    /// Start with the debug overlay shown.
+    ///
+    /// The overlay lists the latest keys received and the actions they
+    /// resolved to, which helps diagnosing keybinding issues. It can also be
+    /// toggled with the `toggle_debug_overlay` action.
+    #[arg(long, default_value = "false", verbatim_doc_comment)]
+    pub debug: bool,
+
+    /// The vertical layout of the application.
+    ///
+    /// `top` places the input bar above the results list while `bottom`
+    /// places it below, with results growing upwards from the prompt.
+    /// This overrides the `input_bar_position` option from the configuration
+    /// file.
+    #[arg(long, value_enum, value_name = "LAYOUT", verbatim_doc_comment)]
+    pub layout: Option<LayoutOrientation>,
+
     /// The delimiter used to extract fields from the entry to provide to the
     /// preview command.
     ///
@@ -41,6 +90,15 @@ pub struct Cli {
     #[arg(long, value_name = "STRING", default_value = " ", value_parser = delimiter_parser, verbatim_doc_comment)]
     pub delimiter: String,
 
+    /// Parse each line of stdin as JSON and display the given field.
+    ///
+    /// The field is specified as a dot-separated path (e.g. `.name` or
+    /// `.user.name`) and is used for display and matching while the full
+    /// JSON object is used for the preview and the output.
+    /// Lines that can't be parsed or lack the field are skipped.
+    #[arg(long, value_name = "PATH", verbatim_doc_comment)]
+    pub json_field: Option<String>,
+
     /// The application's tick rate.
     ///
     /// The tick rate is the number of times the application will update per
@@ -74,6 +132,19 @@ pub struct Cli {
     #[arg(long, value_name = "STRING", verbatim_doc_comment)]
     pub passthrough_keybindings: Option<String>,
 
+    /// A command to run on the selected entries with the `execute_command`
+    /// action.
+    ///
+    /// Every occurrence of {} is replaced with the quoted selected entries,
+    /// followed by their line number if any (e.g. `src/main.rs:12`).
+    /// The interface is suspended while the command runs so interactive
+    /// commands can be used.
+    /// Example: `tv --execute 'less {}' --keybindings='execute_command="ctrl-x"'`
+    /// This overrides the `execute_command` option from the configuration
+    /// file.
+    #[arg(long, value_name = "STRING", verbatim_doc_comment)]
+    pub execute: Option<String>,
+
     /// Input text to pass to the channel to prefill the prompt.
     ///
     /// This can be used to provide a default value for the prompt upon
@@ -81,12 +152,34 @@ pub struct Cli {
     #[arg(short, long, value_name = "STRING", verbatim_doc_comment)]
     pub input: Option<String>,
 
+    /// Read queries from a named pipe, one per line.
+    ///
+    /// Each line written to the pipe replaces the current query and re-runs
+    /// the search, which lets an external program drive the search live
+    /// (e.g. an editor sending the word under the cursor):
+    /// `mkfifo /tmp/tv-query && tv --query-fifo /tmp/tv-query`
+    /// then `echo foo > /tmp/tv-query`.
+    #[arg(long, value_name = "PATH", verbatim_doc_comment)]
+    pub query_fifo: Option<String>,
+
+    /// Open the preview scrolled to the given line for the initially
+    /// selected entry.
+    ///
+    /// This is meant for integrations that know which line of the selected
+    /// entry matters (e.g. a grep match or an editor's cursor). Lines start
+    /// at 1 and the following selections are previewed as usual.
+    #[arg(long, value_name = "LINE", verbatim_doc_comment)]
+    pub preview_line: Option<u16>,
+
     /// The working directory to start the application in.
     ///
     /// This can be used to specify a different working directory for the
     /// application to start in. This is useful when the application is
     /// started from a different directory than the one the user wants to
     /// interact with.
+    ///
+    /// The `files`, `dirs` and `text` channels search this directory, the
+    /// paths they output being relative to it.
     #[arg(value_name = "PATH", index = 2, verbatim_doc_comment)]
     pub working_directory: Option<String>,
 
@@ -106,6 +199,8 @@ pub struct Cli {
 pub enum Command {
     /// Lists the available channels.
     ListChannels,
+    /// Prints the configuration, merged with the defaults, and exits.
+    PrintConfig,
     /// Initializes shell completion ("tv init zsh")
     #[clap(name = "init")]
     InitShell {
@@ -115,6 +210,12 @@ pub enum Command {
     },
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum LayoutOrientation {
+    Top,
+    Bottom,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
 pub enum Shell {
     Bash,
@@ -6,10 +6,13 @@ use tracing::debug;
 
 use crate::channels::cable::{parse_preview_kind, PreviewKind};
 use crate::channels::{
-    cable::CableChannelPrototype, entry::PreviewCommand, CliTvChannel,
+    cable::{CableChannelPrototype, Channel as CableChannel},
+    entry::PreviewCommand,
+    ChannelConfig, CliTvChannel, TelevisionChannel,
 };
-use crate::cli::args::{Cli, Command};
+use crate::cli::args::{Cli, Command, LayoutOrientation};
 use crate::config::KeyBindings;
+use crate::screen::layout::InputPosition;
 use crate::{
     cable,
     config::{get_config_dir, get_data_dir},
@@ -17,15 +20,28 @@ use crate::{
 
 pub mod args;
 
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Clone)]
 pub struct PostProcessedCli {
     pub channel: ParsedCliChannel,
+    /// Whether the channel was left unspecified and defaulted.
+    pub default_channel: bool,
     pub preview_kind: PreviewKind,
+    pub json_field: Option<String>,
     pub no_preview: bool,
+    pub send_to_channel: bool,
+    pub loop_mode: bool,
+    pub print_index: bool,
+    pub print_query: bool,
+    pub debug: bool,
+    pub layout: Option<InputPosition>,
     pub tick_rate: Option<f64>,
     pub frame_rate: Option<f64>,
     pub passthrough_keybindings: Vec<String>,
+    pub execute: Option<String>,
     pub input: Option<String>,
+    pub query_fifo: Option<String>,
+    pub preview_line: Option<u16>,
     pub command: Option<Command>,
     pub working_directory: Option<String>,
     pub autocomplete_prompt: Option<String>,
@@ -36,12 +52,23 @@ impl Default for PostProcessedCli {
     fn default() -> Self {
         Self {
             channel: ParsedCliChannel::Builtin(CliTvChannel::Files),
+            default_channel: true,
             preview_kind: PreviewKind::None,
+            json_field: None,
             no_preview: false,
+            send_to_channel: false,
+            loop_mode: false,
+            print_index: false,
+            print_query: false,
+            debug: false,
+            layout: None,
             tick_rate: None,
             frame_rate: None,
             passthrough_keybindings: Vec::new(),
+            execute: None,
             input: None,
+            query_fifo: None,
+            preview_line: None,
             command: None,
             working_directory: None,
             autocomplete_prompt: None,
@@ -83,8 +110,10 @@ impl From<Cli> for PostProcessedCli {
 
         let channel: ParsedCliChannel;
         let working_directory: Option<String>;
+        let default_channel = cli.channel.is_none();
+        let cli_channel = cli.channel.unwrap_or(String::from("files"));
 
-        match parse_channel(&cli.channel) {
+        match parse_channel(&cli_channel) {
             Ok(p) => {
                 channel = p;
                 working_directory = cli.working_directory;
@@ -93,12 +122,12 @@ impl From<Cli> for PostProcessedCli {
                 // if the path is provided as first argument and it exists, use it as the working
                 // directory and default to the files channel
                 if cli.working_directory.is_none()
-                    && Path::new(&cli.channel).exists()
+                    && Path::new(&cli_channel).exists()
                 {
                     channel = ParsedCliChannel::Builtin(CliTvChannel::Files);
-                    working_directory = Some(cli.channel.clone());
+                    working_directory = Some(cli_channel.clone());
                 } else {
-                    unknown_channel_exit(&cli.channel);
+                    unknown_channel_exit(&cli_channel);
                     unreachable!();
                 }
             }
@@ -106,12 +135,23 @@ impl From<Cli> for PostProcessedCli {
 
         Self {
             channel,
+            default_channel,
             preview_kind,
+            json_field: cli.json_field,
             no_preview: cli.no_preview,
+            send_to_channel: cli.send_to_channel,
+            loop_mode: cli.loop_mode,
+            print_index: cli.print_index,
+            print_query: cli.print_query,
+            debug: cli.debug,
+            layout: cli.layout.map(InputPosition::from),
             tick_rate: cli.tick_rate,
             frame_rate: cli.frame_rate,
             passthrough_keybindings,
+            execute: cli.execute,
             input: cli.input,
+            query_fifo: cli.query_fifo,
+            preview_line: cli.preview_line,
             command: cli.command,
             working_directory,
             autocomplete_prompt: cli.autocomplete_prompt,
@@ -120,6 +160,15 @@ impl From<Cli> for PostProcessedCli {
     }
 }
 
+impl From<LayoutOrientation> for InputPosition {
+    fn from(layout: LayoutOrientation) -> Self {
+        match layout {
+            LayoutOrientation::Top => InputPosition::Top,
+            LayoutOrientation::Bottom => InputPosition::Bottom,
+        }
+    }
+}
+
 fn cli_parsing_error_exit(message: &str) {
     eprintln!("Error parsing CLI arguments: {message}\n");
     std::process::exit(1);
@@ -143,6 +192,15 @@ impl ParsedCliChannel {
             Self::Cable(c) => c.name.clone(),
         }
     }
+
+    pub fn to_channel(self, config: &ChannelConfig) -> TelevisionChannel {
+        match self {
+            Self::Builtin(c) => c.to_channel(config),
+            Self::Cable(c) => TelevisionChannel::Cable(
+                CableChannel::from_prototype(c, config),
+            ),
+        }
+    }
 }
 
 const CLI_KEYBINDINGS_DELIMITER: char = ';';
@@ -316,15 +374,25 @@ mod tests {
     #[allow(clippy::float_cmp)]
     fn test_from_cli() {
         let cli = Cli {
-            channel: "files".to_string(),
+            channel: Some("files".to_string()),
             preview: Some("bat -n --color=always {}".to_string()),
             no_preview: false,
+            send_to_channel: false,
+            loop_mode: false,
+            print_index: false,
+            print_query: false,
+            debug: false,
+            layout: None,
             delimiter: ":".to_string(),
+            json_field: Some(".name".to_string()),
             tick_rate: Some(50.0),
             frame_rate: Some(60.0),
             keybindings: None,
             passthrough_keybindings: Some("q,ctrl-w,ctrl-t".to_string()),
+            execute: None,
             input: None,
+            query_fifo: None,
+            preview_line: None,
             command: None,
             working_directory: Some("/home/user".to_string()),
             autocomplete_prompt: None,
@@ -343,6 +411,7 @@ mod tests {
                 delimiter: ":".to_string()
             })
         );
+        assert_eq!(post_processed_cli.json_field, Some(".name".to_string()));
         assert_eq!(post_processed_cli.tick_rate, Some(50.0));
         assert_eq!(post_processed_cli.frame_rate, Some(60.0));
         assert_eq!(
@@ -359,15 +428,25 @@ mod tests {
     #[allow(clippy::float_cmp)]
     fn test_from_cli_no_args() {
         let cli = Cli {
-            channel: ".".to_string(),
+            channel: Some(".".to_string()),
             preview: None,
             no_preview: false,
+            send_to_channel: false,
+            loop_mode: false,
+            print_index: false,
+            print_query: false,
+            debug: false,
+            layout: None,
             delimiter: ":".to_string(),
+            json_field: None,
             tick_rate: Some(50.0),
             frame_rate: Some(60.0),
             keybindings: None,
             passthrough_keybindings: None,
+            execute: None,
             input: None,
+            query_fifo: None,
+            preview_line: None,
             command: None,
             working_directory: None,
             autocomplete_prompt: None,
@@ -386,18 +465,60 @@ mod tests {
         assert_eq!(post_processed_cli.command, None);
     }
 
+    #[test]
+    fn test_from_cli_layout() {
+        let cli = Cli {
+            channel: Some("files".to_string()),
+            preview: None,
+            no_preview: false,
+            send_to_channel: false,
+            loop_mode: false,
+            print_index: false,
+            print_query: false,
+            debug: false,
+            layout: Some(LayoutOrientation::Bottom),
+            delimiter: ":".to_string(),
+            json_field: None,
+            tick_rate: None,
+            frame_rate: None,
+            keybindings: None,
+            passthrough_keybindings: None,
+            execute: None,
+            input: None,
+            query_fifo: None,
+            preview_line: None,
+            command: None,
+            working_directory: None,
+            autocomplete_prompt: None,
+        };
+
+        let post_processed_cli: PostProcessedCli = cli.into();
+
+        assert_eq!(post_processed_cli.layout, Some(InputPosition::Bottom));
+    }
+
     #[test]
     fn test_builtin_previewer_files() {
         let cli = Cli {
-            channel: "files".to_string(),
+            channel: Some("files".to_string()),
             preview: Some(":files:".to_string()),
             no_preview: false,
+            send_to_channel: false,
+            loop_mode: false,
+            print_index: false,
+            print_query: false,
+            debug: false,
+            layout: None,
             delimiter: ":".to_string(),
+            json_field: None,
             tick_rate: Some(50.0),
             frame_rate: Some(60.0),
             keybindings: None,
             passthrough_keybindings: None,
+            execute: None,
             input: None,
+            query_fifo: None,
+            preview_line: None,
             command: None,
             working_directory: None,
             autocomplete_prompt: None,
@@ -414,15 +535,25 @@ mod tests {
     #[test]
     fn test_builtin_previewer_env() {
         let cli = Cli {
-            channel: "files".to_string(),
+            channel: Some("files".to_string()),
             preview: Some(":env_var:".to_string()),
             no_preview: false,
+            send_to_channel: false,
+            loop_mode: false,
+            print_index: false,
+            print_query: false,
+            debug: false,
+            layout: None,
             delimiter: ":".to_string(),
+            json_field: None,
             tick_rate: Some(50.0),
             frame_rate: Some(60.0),
             keybindings: None,
             passthrough_keybindings: None,
+            execute: None,
             input: None,
+            query_fifo: None,
+            preview_line: None,
             command: None,
             working_directory: None,
             autocomplete_prompt: None,
@@ -439,10 +570,17 @@ mod tests {
     #[test]
     fn test_custom_keybindings() {
         let cli = Cli {
-            channel: "files".to_string(),
+            channel: Some("files".to_string()),
             preview: Some(":env_var:".to_string()),
             no_preview: false,
+            send_to_channel: false,
+            loop_mode: false,
+            print_index: false,
+            print_query: false,
+            debug: false,
+            layout: None,
             delimiter: ":".to_string(),
+            json_field: None,
             tick_rate: Some(50.0),
             frame_rate: Some(60.0),
             keybindings: Some(
@@ -450,7 +588,10 @@ mod tests {
                     .to_string(),
             ),
             passthrough_keybindings: None,
+            execute: None,
             input: None,
+            query_fifo: None,
+            preview_line: None,
             command: None,
             working_directory: None,
             autocomplete_prompt: None,
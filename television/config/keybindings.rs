@@ -2,7 +2,8 @@ use crate::action::Action;
 use crate::event::{convert_raw_event_to_key, Key};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use rustc_hash::FxHashMap;
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
 use std::fmt::Display;
 use std::hash::Hash;
 use std::ops::{Deref, DerefMut};
@@ -78,7 +79,178 @@ pub fn merge_keybindings(
     keybindings
 }
 
-#[derive(Clone, Debug, Deserialize)]
+/// Which of `Tab` and `Enter` selects entries and which one accepts them,
+/// for users used to finders doing it one way or the other.
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SelectionKeys {
+    /// `Tab` toggles the selection of an entry and `Enter` accepts.
+    #[default]
+    TabSelects,
+    /// `Enter` toggles the selection of an entry and `Tab` accepts.
+    TabAccepts,
+}
+
+impl SelectionKeys {
+    /// The bindings of the preset, overriding the default ones.
+    pub fn keybindings(self) -> KeyBindings {
+        let (select, accept) = match self {
+            SelectionKeys::TabSelects => (Key::Tab, Key::Enter),
+            SelectionKeys::TabAccepts => (Key::Enter, Key::Tab),
+        };
+        KeyBindings::from([
+            (Action::ToggleSelectionDown, Binding::SingleKey(select)),
+            (Action::ConfirmSelection, Binding::SingleKey(accept)),
+        ])
+    }
+}
+
+impl Binding {
+    /// Whether the given key triggers this binding.
+    pub fn contains(&self, key: &Key) -> bool {
+        match self {
+            Binding::SingleKey(k) => k == key,
+            Binding::MultipleKeys(keys) => keys.contains(key),
+        }
+    }
+}
+
+/// Two-key sequences (e.g. `g g`) bound to actions, the second key having
+/// to be pressed within `AppConfig::chord_timeout_ms` of the first one.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Chords(pub FxHashMap<Action, (Key, Key)>);
+
+impl<I> From<I> for Chords
+where
+    I: IntoIterator<Item = (Action, (Key, Key))>,
+{
+    fn from(iter: I) -> Self {
+        Chords(iter.into_iter().collect())
+    }
+}
+
+impl Hash for Chords {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        // same as `KeyBindings`, this is only a placeholder
+        state.write_u8(0);
+    }
+}
+
+impl Deref for Chords {
+    type Target = FxHashMap<Action, (Key, Key)>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'de> Deserialize<'de> for Chords {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        FxHashMap::<Action, String>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(action, raw)| {
+                parse_chord(&raw)
+                    .map(|chord| (action, chord))
+                    .map_err(serde::de::Error::custom)
+            })
+            .collect::<Result<_, _>>()
+            .map(Chords)
+    }
+}
+
+impl Serialize for Chords {
+    /// Serialize the chords in the configuration file format, sorted by
+    /// action.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let key_repr =
+            |key: Key| serialize_key(key).unwrap_or(key.to_string());
+        self.iter()
+            .map(|(action, (first, second))| {
+                (
+                    action.to_string(),
+                    format!("{} {}", key_repr(*first), key_repr(*second)),
+                )
+            })
+            .collect::<BTreeMap<_, _>>()
+            .serialize(serializer)
+    }
+}
+
+/// Parse a chord written as two whitespace-separated keys (e.g. `g g` or
+/// `ctrl-x ctrl-s`).
+pub fn parse_chord(raw: &str) -> Result<(Key, Key), String> {
+    let keys = raw
+        .split_whitespace()
+        .map(parse_key)
+        .collect::<Result<Vec<_>, _>>()?;
+    match keys[..] {
+        [first, second] => Ok((first, second)),
+        _ => Err(format!("`{raw}` isn't a sequence of two keys")),
+    }
+}
+
+/// The reasons why a key can't be bound to an action.
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum RebindError {
+    /// The key is already bound to one or more other actions.
+    #[error(
+        "{key} is already bound to {}",
+        actions.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+    )]
+    Conflict { key: Key, actions: Vec<Action> },
+    /// The key can't be written to a configuration file.
+    #[error("{0} can't be used as a keybinding")]
+    InvalidKey(Key),
+}
+
+impl KeyBindings {
+    /// Get the actions, other than `action`, that are triggered by `key`.
+    ///
+    /// The returned actions are sorted so that conflicts are reported in a
+    /// stable order.
+    pub fn conflicts(&self, action: &Action, key: &Key) -> Vec<Action> {
+        let mut conflicts: Vec<Action> = self
+            .iter()
+            .filter(|(a, binding)| *a != action && binding.contains(key))
+            .map(|(a, _)| a.clone())
+            .collect();
+        conflicts.sort();
+        conflicts
+    }
+
+    /// Bind `key` to `action`, replacing any previous binding for that
+    /// action.
+    ///
+    /// The keybindings are left untouched if `key` is already bound to
+    /// another action.
+    pub fn rebind(
+        &mut self,
+        action: &Action,
+        key: Key,
+    ) -> Result<(), RebindError> {
+        if serialize_key(key).is_none() {
+            return Err(RebindError::InvalidKey(key));
+        }
+        let conflicts = self.conflicts(action, &key);
+        if !conflicts.is_empty() {
+            return Err(RebindError::Conflict {
+                key,
+                actions: conflicts,
+            });
+        }
+        self.insert(action.clone(), Binding::SingleKey(key));
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum SerializedBinding {
     SingleKey(String),
@@ -119,6 +291,36 @@ impl<'de> Deserialize<'de> for KeyBindings {
     }
 }
 
+impl Serialize for KeyBindings {
+    /// Serialize the keybindings in the configuration file format, sorted by
+    /// action.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let key_repr =
+            |key: &Key| serialize_key(*key).unwrap_or(key.to_string());
+        self.iter()
+            .map(|(action, binding)| {
+                (
+                    action.to_string(),
+                    match binding {
+                        Binding::SingleKey(key) => {
+                            SerializedBinding::SingleKey(key_repr(key))
+                        }
+                        Binding::MultipleKeys(keys) => {
+                            SerializedBinding::MultipleKeys(
+                                keys.iter().map(key_repr).collect(),
+                            )
+                        }
+                    },
+                )
+            })
+            .collect::<BTreeMap<_, _>>()
+            .serialize(serializer)
+    }
+}
+
 pub fn parse_key_event(raw: &str) -> anyhow::Result<KeyEvent, String> {
     let raw_lower = raw.to_ascii_lowercase();
     let (remaining, modifiers) = extract_modifiers(&raw_lower);
@@ -280,6 +482,69 @@ pub fn parse_key(raw: &str) -> anyhow::Result<Key, String> {
     Ok(convert_raw_event_to_key(key_event))
 }
 
+/// Serialize a key into the format used in configuration files.
+///
+/// This is the inverse of [`parse_key`] and returns `None` for keys that
+/// can't be expressed in a configuration file.
+pub fn serialize_key(key: Key) -> Option<String> {
+    let raw = match key {
+        Key::Backspace => "backspace".to_string(),
+        Key::Enter => "enter".to_string(),
+        Key::Left => "left".to_string(),
+        Key::Right => "right".to_string(),
+        Key::Up => "up".to_string(),
+        Key::Down => "down".to_string(),
+        Key::CtrlSpace => "ctrl-space".to_string(),
+        Key::CtrlBackspace => "ctrl-backspace".to_string(),
+        Key::CtrlEnter => "ctrl-enter".to_string(),
+        Key::CtrlLeft => "ctrl-left".to_string(),
+        Key::CtrlRight => "ctrl-right".to_string(),
+        Key::CtrlUp => "ctrl-up".to_string(),
+        Key::CtrlDown => "ctrl-down".to_string(),
+        Key::CtrlDelete => "ctrl-delete".to_string(),
+        Key::AltSpace => "alt-space".to_string(),
+        Key::AltEnter => "alt-enter".to_string(),
+        Key::AltBackspace => "alt-backspace".to_string(),
+        Key::AltDelete => "alt-delete".to_string(),
+        Key::AltUp => "alt-up".to_string(),
+        Key::AltDown => "alt-down".to_string(),
+        Key::AltLeft => "alt-left".to_string(),
+        Key::AltRight => "alt-right".to_string(),
+        Key::Home => "home".to_string(),
+        Key::End => "end".to_string(),
+        Key::PageUp => "pageup".to_string(),
+        Key::PageDown => "pagedown".to_string(),
+        Key::BackTab => "backtab".to_string(),
+        Key::Delete => "delete".to_string(),
+        Key::Insert => "insert".to_string(),
+        Key::F(k) if (1..=12).contains(&k) => format!("f{k}"),
+        Key::Char(' ') => "space".to_string(),
+        Key::Char('-') => "minus".to_string(),
+        Key::Char(c) if c.is_ascii_uppercase() => {
+            format!("shift-{}", c.to_ascii_lowercase())
+        }
+        Key::Char(c) if c.is_ascii_graphic() => c.to_string(),
+        Key::Alt('-') => "alt-minus".to_string(),
+        Key::Alt(c) if is_serializable_modified_char(c) => {
+            format!("alt-{c}")
+        }
+        Key::Ctrl('-') => "ctrl-minus".to_string(),
+        Key::Ctrl(c) if is_serializable_modified_char(c) => {
+            format!("ctrl-{c}")
+        }
+        Key::Esc => "esc".to_string(),
+        Key::Tab => "tab".to_string(),
+        _ => return None,
+    };
+    Some(raw)
+}
+
+/// Modifier combinations are parsed case-insensitively, so only characters
+/// that survive lowercasing can be written back alongside a modifier.
+fn is_serializable_modified_char(c: char) -> bool {
+    c.is_ascii_graphic() && !c.is_ascii_uppercase()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -457,6 +722,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_chord() {
+        assert_eq!(parse_chord("g g"), Ok((Key::Char('g'), Key::Char('g'))));
+        assert_eq!(
+            parse_chord("ctrl-x  ctrl-s"),
+            Ok((Key::Ctrl('x'), Key::Ctrl('s')))
+        );
+        assert!(parse_chord("g").is_err());
+        assert!(parse_chord("g g g").is_err());
+        assert!(parse_chord("g invalid-key").is_err());
+    }
+
+    #[test]
+    fn test_chords_round_trip() {
+        let chords: Chords = toml::from_str(
+            r#"
+                scroll_preview_top = "g g"
+                scroll_preview_bottom = "shift-g shift-g"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            chords,
+            Chords::from(vec![
+                (Action::ScrollPreviewTop, (Key::Char('g'), Key::Char('g'))),
+                (
+                    Action::ScrollPreviewBottom,
+                    (Key::Char('G'), Key::Char('G'))
+                ),
+            ])
+        );
+        let printed = toml::to_string(&chords).unwrap();
+        assert_eq!(toml::from_str::<Chords>(&printed).unwrap(), chords);
+    }
+
     #[test]
     fn test_merge_keybindings() {
         let base_keybindings = KeyBindings::from(vec![
@@ -491,4 +791,85 @@ mod tests {
             ])
         );
     }
+
+    #[test]
+    fn test_rebind_with_conflict() {
+        let mut keybindings = KeyBindings::from(vec![
+            (
+                Action::Quit,
+                Binding::MultipleKeys(vec![Key::Esc, Key::Ctrl('c')]),
+            ),
+            (Action::ToggleHelp, Binding::SingleKey(Key::Ctrl('g'))),
+            (Action::TogglePreview, Binding::SingleKey(Key::Ctrl('o'))),
+        ]);
+
+        assert_eq!(
+            keybindings.conflicts(&Action::ToggleHelp, &Key::Ctrl('c')),
+            vec![Action::Quit]
+        );
+        // an action never conflicts with itself
+        assert!(keybindings
+            .conflicts(&Action::ToggleHelp, &Key::Ctrl('g'))
+            .is_empty());
+
+        let err = keybindings
+            .rebind(&Action::ToggleHelp, Key::Ctrl('c'))
+            .unwrap_err();
+        assert_eq!(
+            err,
+            RebindError::Conflict {
+                key: Key::Ctrl('c'),
+                actions: vec![Action::Quit],
+            }
+        );
+        assert_eq!(err.to_string(), "Ctrl-c is already bound to quit");
+        // the conflicting rebind must leave the keybindings untouched
+        assert_eq!(
+            keybindings.get(&Action::ToggleHelp),
+            Some(&Binding::SingleKey(Key::Ctrl('g')))
+        );
+    }
+
+    #[test]
+    fn test_rebind_without_conflict() {
+        let mut keybindings = KeyBindings::from(vec![
+            (Action::Quit, Binding::SingleKey(Key::Esc)),
+            (
+                Action::ToggleHelp,
+                Binding::MultipleKeys(vec![Key::Ctrl('g'), Key::F(1)]),
+            ),
+        ]);
+
+        keybindings.rebind(&Action::ToggleHelp, Key::F(2)).unwrap();
+        assert_eq!(
+            keybindings.get(&Action::ToggleHelp),
+            Some(&Binding::SingleKey(Key::F(2)))
+        );
+
+        assert_eq!(
+            keybindings.rebind(&Action::ToggleHelp, Key::Null),
+            Err(RebindError::InvalidKey(Key::Null))
+        );
+    }
+
+    #[test]
+    fn test_serialize_key_round_trip() {
+        for key in [
+            Key::Char('a'),
+            Key::Char('A'),
+            Key::Char(' '),
+            Key::Char('-'),
+            Key::Ctrl('j'),
+            Key::Alt('x'),
+            Key::CtrlDelete,
+            Key::AltUp,
+            Key::BackTab,
+            Key::F(5),
+            Key::Esc,
+        ] {
+            let raw = serialize_key(key).unwrap();
+            assert_eq!(parse_key(&raw).unwrap(), key, "round trip of {raw}");
+        }
+        assert_eq!(serialize_key(Key::Null), None);
+    }
 }
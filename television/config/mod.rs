@@ -1,6 +1,8 @@
 #![allow(clippy::module_name_repetitions, clippy::ref_option)]
 use std::{
+    collections::BTreeMap,
     env,
+    fmt::Display,
     hash::Hash,
     path::{Path, PathBuf},
 };
@@ -8,14 +10,28 @@ use std::{
 use anyhow::{Context, Result};
 use directories::ProjectDirs;
 pub use keybindings::merge_keybindings;
-pub use keybindings::{parse_key, Binding, KeyBindings};
+use keybindings::serialize_key;
+pub use keybindings::{
+    parse_key, Binding, Chords, KeyBindings, RebindError, SelectionKeys,
+};
+pub use previewers::AlternativePreviewer;
 use previewers::PreviewersConfig;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use shell_integration::ShellIntegrationConfig;
 pub use themes::Theme;
 use tracing::{debug, warn};
 pub use ui::UiConfig;
 
+use crate::action::Action;
+use crate::channels::entry::OutputField;
+use crate::channels::env::EnvOptions;
+use crate::channels::files::DEFAULT_IGNORE_PATTERNS;
+use crate::channels::ChannelConfig;
+use crate::matcher::{
+    self,
+    config::{Dedup, InitialSort, ScoreNormalization},
+};
+
 mod keybindings;
 mod previewers;
 pub mod shell_integration;
@@ -24,8 +40,28 @@ mod ui;
 
 const DEFAULT_CONFIG: &str = include_str!("../../.config/config.toml");
 
-#[allow(dead_code, clippy::module_name_repetitions)]
-#[derive(Clone, Debug, Deserialize, Default, PartialEq)]
+/// Deserialize a map of options by channel name with the names in
+/// lowercase, which is how channels are looked up (see
+/// `TelevisionChannel::config_name`).
+fn lowercase_keys<'de, D, V>(
+    deserializer: D,
+) -> Result<BTreeMap<String, V>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    V: Deserialize<'de>,
+{
+    Ok(BTreeMap::<String, V>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(name, value)| (name.to_lowercase(), value))
+        .collect())
+}
+
+#[allow(
+    dead_code,
+    clippy::module_name_repetitions,
+    clippy::struct_excessive_bools
+)]
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct AppConfig {
     #[serde(default = "get_data_dir")]
@@ -36,6 +72,171 @@ pub struct AppConfig {
     pub frame_rate: f64,
     #[serde(default = "default_tick_rate")]
     pub tick_rate: f64,
+    #[serde(default = "default_diacritic_folding")]
+    pub diacritic_folding: bool,
+    #[serde(default)]
+    pub transliteration: bool,
+    #[serde(default)]
+    pub execute_command: Option<String>,
+    #[serde(default)]
+    pub idle_accept_ms: Option<u64>,
+    #[serde(default)]
+    pub score_normalization: ScoreNormalization,
+    #[serde(default)]
+    pub initial_sort: InitialSort,
+    #[serde(default)]
+    pub max_gap: Option<u32>,
+    #[serde(default = "default_ignore_patterns")]
+    pub ignore_patterns: Vec<String>,
+    /// Whether the files channel shows the first line of each file matching
+    /// the query next to its path.
+    #[serde(default)]
+    pub content_snippets: bool,
+    #[serde(default)]
+    pub remember_last_channel: bool,
+    /// Whether the entries selected in a channel stay selected after
+    /// switching to another one, the entries of all channels being output.
+    ///
+    /// The entries selected in a channel show up as selected again when
+    /// switching back to it.
+    #[serde(default)]
+    pub persistent_selection: bool,
+    #[serde(default)]
+    pub async_channel_init: bool,
+    /// The channels in which `execute_command` asks for confirmation before
+    /// running.
+    #[serde(default)]
+    pub confirm_execute_command: Vec<String>,
+    #[serde(default)]
+    pub on_missing_entry: MissingEntryBehavior,
+    #[serde(default)]
+    pub send_to_channel_mode: SendToChannelMode,
+    #[serde(default)]
+    pub scorer_command: Option<String>,
+    /// A command run with the selected entry each time the selection
+    /// changes.
+    #[serde(default)]
+    pub on_change_command: Option<String>,
+    /// The directory `copy_relative_path` computes paths relative to, the
+    /// current working directory if unset.
+    #[serde(default)]
+    pub relative_path_base: Option<PathBuf>,
+    /// How long to wait for the second key of a chord before handling the
+    /// first one alone.
+    #[serde(default = "default_chord_timeout_ms")]
+    pub chord_timeout_ms: u64,
+    /// Whether to trim the query and strip quotes around it before matching
+    /// (see `sanitize_query`).
+    #[serde(default)]
+    pub sanitize_query: bool,
+    /// The line separating the query from the selection when both are
+    /// printed (see `--print-query`).
+    #[serde(default = "default_query_separator")]
+    pub query_separator: String,
+    /// What `reveal_in_parent` does with the selected entry's directory.
+    #[serde(default)]
+    pub reveal_in_parent: RevealMode,
+    /// Which part of the accepted entries is output.
+    #[serde(default)]
+    pub output_field: OutputField,
+    /// What separates the entries copied by `copy_all_selected`.
+    #[serde(default)]
+    pub copy_all_separator: ClipboardSeparator,
+    /// How the entries of each channel are deduplicated, by channel name.
+    #[serde(default)]
+    pub dedup: BTreeMap<String, Dedup>,
+    /// Which variables the env channel lists and in which order.
+    #[serde(default)]
+    pub env: EnvOptions,
+    /// Which of `Tab` and `Enter` selects entries and which one accepts
+    /// them, overriding the bindings of `ToggleSelectionDown` and
+    /// `ConfirmSelection` unless left to the default.
+    #[serde(default)]
+    pub selection_keys: SelectionKeys,
+}
+
+/// What separates the entries copied together to the clipboard.
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ClipboardSeparator {
+    #[default]
+    Newline,
+    /// A NUL character, e.g. for `xargs -0`.
+    Nul,
+}
+
+impl ClipboardSeparator {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ClipboardSeparator::Newline => "\n",
+            ClipboardSeparator::Nul => "\0",
+        }
+    }
+}
+
+/// What to do with the directory containing the selected entry when
+/// revealing it.
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum RevealMode {
+    /// Open it with the platform's default application, typically the file
+    /// manager.
+    #[default]
+    Open,
+    /// Accept its path as the selection, which is output like any other
+    /// accepted entry.
+    Print,
+}
+
+/// What to do when accepting entries of a file-backed channel whose files
+/// no longer exist (e.g. they were deleted while `tv` was running).
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum MissingEntryBehavior {
+    /// Show an error and keep the application running.
+    #[default]
+    Refuse,
+    /// Output the entries anyway and print a warning.
+    Warn,
+}
+
+/// What happens to the channel that entries are sent to when sending to it
+/// again.
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SendToChannelMode {
+    /// Start over with only the entries being sent.
+    #[default]
+    Replace,
+    /// Add the entries being sent to the ones sent previously.
+    Append,
+}
+
+impl Display for SendToChannelMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SendToChannelMode::Replace => write!(f, "replace"),
+            SendToChannelMode::Append => write!(f, "append"),
+        }
+    }
+}
+
+impl SendToChannelMode {
+    #[must_use]
+    pub fn toggled(self) -> Self {
+        match self {
+            SendToChannelMode::Replace => SendToChannelMode::Append,
+            SendToChannelMode::Append => SendToChannelMode::Replace,
+        }
+    }
 }
 
 impl Hash for AppConfig {
@@ -44,11 +245,60 @@ impl Hash for AppConfig {
         self.config_dir.hash(state);
         self.frame_rate.to_bits().hash(state);
         self.tick_rate.to_bits().hash(state);
+        self.diacritic_folding.hash(state);
+        self.transliteration.hash(state);
+        self.execute_command.hash(state);
+        self.idle_accept_ms.hash(state);
+        self.score_normalization.hash(state);
+        self.initial_sort.hash(state);
+        self.max_gap.hash(state);
+        self.ignore_patterns.hash(state);
+        self.content_snippets.hash(state);
+        self.remember_last_channel.hash(state);
+        self.persistent_selection.hash(state);
+        self.async_channel_init.hash(state);
+        self.confirm_execute_command.hash(state);
+        self.on_missing_entry.hash(state);
+        self.send_to_channel_mode.hash(state);
+        self.scorer_command.hash(state);
+        self.on_change_command.hash(state);
+        self.relative_path_base.hash(state);
+        self.chord_timeout_ms.hash(state);
+        self.sanitize_query.hash(state);
+        self.query_separator.hash(state);
+        self.reveal_in_parent.hash(state);
+        self.selection_keys.hash(state);
+        self.dedup.hash(state);
+        self.env.hash(state);
+        self.copy_all_separator.hash(state);
+        self.output_field.hash(state);
+    }
+}
+
+impl AppConfig {
+    /// The settings the channels are created with.
+    pub fn channel_config(&self) -> ChannelConfig {
+        ChannelConfig {
+            ignore_patterns: self.ignore_patterns.clone(),
+            content_snippets: self.content_snippets,
+            dedup: self.dedup.clone(),
+            env: self.env.clone(),
+            data_dir: Some(self.data_dir.clone()),
+            // a UI setting (see `Config::channel_config`)
+            group_results: false,
+            matcher: matcher::config::Config::default()
+                .normalize(self.diacritic_folding)
+                .score_normalization(self.score_normalization)
+                .transliterate(self.transliteration)
+                .initial_sort(self.initial_sort)
+                .max_gap(self.max_gap)
+                .scorer_command(self.scorer_command.as_deref()),
+        }
     }
 }
 
 #[allow(dead_code)]
-#[derive(Clone, Debug, Deserialize, Default, PartialEq, Hash)]
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, Hash)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     /// General application configuration
@@ -58,6 +308,9 @@ pub struct Config {
     /// Keybindings configuration
     #[serde(default)]
     pub keybindings: KeyBindings,
+    /// Two-key sequences bound to actions
+    #[serde(default)]
+    pub chords: Chords,
     /// UI configuration
     #[serde(default)]
     pub ui: UiConfig,
@@ -112,6 +365,15 @@ const USER_CONFIG_ERROR_MSG: &str = "
 ╚══════════════════════════════════════════════════════════════════════════════╝";
 
 impl Config {
+    /// The settings the channels are created with, including the UI
+    /// settings they depend on.
+    pub fn channel_config(&self) -> ChannelConfig {
+        ChannelConfig {
+            group_results: self.ui.group_results,
+            ..self.application.channel_config()
+        }
+    }
+
     #[allow(clippy::missing_panics_doc, clippy::missing_errors_doc)]
     pub fn new(config_env: &ConfigEnv) -> Result<Self> {
         // Load the default_config values as base defaults
@@ -142,6 +404,13 @@ impl Config {
         }
     }
 
+    /// The configuration in the configuration file format, e.g. to inspect
+    /// the result of merging the user's configuration with the defaults.
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string_pretty(self)
+            .context("Failed serializing configuration")
+    }
+
     fn load_user_config(config_dir: &Path) -> Result<Self> {
         let path = config_dir.join(CONFIG_FILE_NAME);
         let contents = std::fs::read_to_string(&path)?;
@@ -181,13 +450,22 @@ impl Config {
         user.shell_integration.keybindings = merged_keybindings;
 
         // merge keybindings with default keybindings
-        let keybindings =
+        let mut keybindings =
             merge_keybindings(default.keybindings.clone(), &user.keybindings);
+        // the default preset is the one of the default keybindings, which
+        // may have been customized since
+        if user.application.selection_keys != SelectionKeys::default() {
+            keybindings = merge_keybindings(
+                keybindings,
+                &user.application.selection_keys.keybindings(),
+            );
+        }
         user.keybindings = keybindings;
 
         Config {
             application: user.application,
             keybindings: user.keybindings,
+            chords: user.chords,
             ui: user.ui,
             previewers: user.previewers,
             shell_integration: user.shell_integration,
@@ -195,6 +473,69 @@ impl Config {
     }
 }
 
+/// Write a single keybinding to the user's configuration file.
+///
+/// The file is edited in place so that the rest of its content, comments
+/// included, is preserved. Any existing entry for the same action (including
+/// one written using an alias) is replaced.
+pub fn save_keybinding(
+    config_dir: &Path,
+    action: &Action,
+    binding: &Binding,
+) -> Result<()> {
+    let path = config_dir.join(CONFIG_FILE_NAME);
+    let contents = if path.is_file() {
+        std::fs::read_to_string(&path)?
+    } else {
+        String::new()
+    };
+    let mut document = contents
+        .parse::<toml_edit::DocumentMut>()
+        .context(format!("Error parsing {}", path.display()))?;
+
+    let value: toml_edit::Value = match binding {
+        Binding::SingleKey(key) => serialize_key(*key)
+            .context(format!("Unable to serialize key {key}"))?
+            .into(),
+        Binding::MultipleKeys(keys) => keys
+            .iter()
+            .map(|key| {
+                serialize_key(*key)
+                    .context(format!("Unable to serialize key {key}"))
+            })
+            .collect::<Result<toml_edit::Array>>()?
+            .into(),
+    };
+
+    let keybindings = document
+        .entry("keybindings")
+        .or_insert(toml_edit::table())
+        .as_table_like_mut()
+        .context("`keybindings` is not a table")?;
+    let name = action.to_string();
+    let aliases: Vec<String> = keybindings
+        .iter()
+        .map(|(k, _)| k.to_string())
+        .filter(|k| {
+            *k != name
+                && toml::Value::String(k.clone()).try_into::<Action>().ok()
+                    == Some(action.clone())
+        })
+        .collect();
+    for alias in aliases {
+        keybindings.remove(&alias);
+    }
+    // update existing entries in place to keep their surrounding comments
+    if let Some(item) = keybindings.get_mut(&name) {
+        *item = toml_edit::Item::Value(value);
+    } else {
+        keybindings.insert(&name, toml_edit::Item::Value(value));
+    }
+
+    std::fs::write(&path, document.to_string())?;
+    Ok(())
+}
+
 pub fn get_data_dir() -> PathBuf {
     // if `TELEVISION_DATA` is set, use that as the data directory
     let data_folder =
@@ -266,10 +607,30 @@ fn default_tick_rate() -> f64 {
     50.0
 }
 
+fn default_diacritic_folding() -> bool {
+    true
+}
+
+fn default_ignore_patterns() -> Vec<String> {
+    DEFAULT_IGNORE_PATTERNS
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+fn default_chord_timeout_ms() -> u64 {
+    500
+}
+
+fn default_query_separator() -> String {
+    String::from("---selection---")
+}
+
 #[cfg(test)]
 mod tests {
     use crate::action::Action;
     use crate::event::Key;
+    use crate::screen::layout::{LineNumbers, Truncation};
 
     use super::*;
     use rustc_hash::FxHashMap;
@@ -303,6 +664,21 @@ mod tests {
         assert_eq!(config, toml::from_str(DEFAULT_CONFIG).unwrap());
     }
 
+    #[test]
+    fn test_printed_config_round_trips() {
+        let mut config = default_config_from_file().unwrap();
+        config.application.execute_command = Some(String::from("less {}"));
+        config.ui.show_line_numbers = Some(LineNumbers::Relative);
+        config.keybindings.insert(
+            Action::ToggleHelp,
+            Binding::MultipleKeys(vec![Key::Ctrl('g'), Key::F(1)]),
+        );
+
+        let printed = config.to_toml().unwrap();
+        assert!(printed.contains("toggle_help = [\n    \"ctrl-g\",\n"));
+        assert_eq!(toml::from_str::<Config>(&printed).unwrap(), config);
+    }
+
     #[test]
     fn test_config_new_empty_user_cfg() {
         // write user config to a file
@@ -415,6 +791,163 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_channel_config_follows_application_settings() {
+        let default_config = default_config_from_file().unwrap();
+        assert!(
+            default_config
+                .application
+                .channel_config()
+                .matcher
+                .normalize
+        );
+
+        let config = Config::merge_user_with_default(
+            default_config,
+            toml::from_str(
+                "diacritic_folding = false\n\
+                 score_normalization = \"length\"\n\
+                 transliteration = true\n\
+                 initial_sort = \"mtime\"\n\
+                 max_gap = 2\n\
+                 scorer_command = \"my-ranker\"\n\
+                 ignore_patterns = [\"target\"]\n\
+                 content_snippets = true\n\
+                 [env]\n\
+                 limit = 3\n\
+                 [dedup]\n\
+                 MyChannel = \"exact\"\n\
+                 [ui]\n\
+                 group_results = true",
+            )
+            .unwrap(),
+        );
+        let channel_config = config.channel_config();
+        assert!(!channel_config.matcher.normalize);
+        assert!(channel_config.matcher.transliterate);
+        assert_eq!(channel_config.matcher.initial_sort, InitialSort::Mtime);
+        assert_eq!(channel_config.matcher.max_gap, Some(2));
+        assert_eq!(
+            channel_config.matcher.scorer_command.as_deref(),
+            Some("my-ranker")
+        );
+        assert!(channel_config.group_results);
+        assert_eq!(channel_config.ignore_patterns, ["target"]);
+        assert!(channel_config.content_snippets);
+        assert_eq!(channel_config.env.limit, Some(3));
+        assert_eq!(channel_config.data_dir, Some(get_data_dir()));
+        // channels are looked up by their exact name
+        assert_eq!(
+            channel_config.matcher_config("MyChannel").dedup,
+            Dedup::Exact
+        );
+        assert_eq!(
+            channel_config.matcher_config("mychannel").dedup,
+            Dedup::None
+        );
+        assert_eq!(
+            channel_config.matcher.score_normalization,
+            ScoreNormalization::Length
+        );
+    }
+
+    #[test]
+    fn test_per_channel_options_ignore_case() {
+        let ui: UiConfig = toml::from_str(
+            "[themes]\n\
+             MyChannel = \"dracula\"\n\
+             [truncations]\n\
+             Stdin = \"left\"\n\
+             [no_results_messages]\n\
+             GIT-REPOS = \"No repositories\"",
+        )
+        .unwrap();
+        assert_eq!(ui.theme("mychannel"), "dracula");
+        assert_eq!(ui.truncation("stdin"), Truncation::Left);
+        assert_eq!(ui.no_results_message("git-repos"), "No repositories");
+
+        let previewers: PreviewersConfig = toml::from_str(
+            "[file]\n\
+             [[alternatives.MyChannel]]\n\
+             name = \"hexdump\"\n\
+             command = \"xxd {}\"",
+        )
+        .unwrap();
+        assert!(previewers.alternatives.contains_key("mychannel"));
+    }
+
+    #[test]
+    fn test_selection_keys_preset() {
+        let default_config = default_config_from_file().unwrap();
+        let bound_action = |config: &Config, key: Key| {
+            config
+                .keybindings
+                .iter()
+                .filter(|(_, binding)| binding.contains(&key))
+                .map(|(action, _)| action.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let config = Config::merge_user_with_default(
+            default_config.clone(),
+            toml::from_str("selection_keys = \"tab_accepts\"").unwrap(),
+        );
+        assert_eq!(
+            bound_action(&config, Key::Tab),
+            vec![Action::ConfirmSelection]
+        );
+        assert_eq!(
+            bound_action(&config, Key::Enter),
+            vec![Action::ToggleSelectionDown]
+        );
+
+        let config = Config::merge_user_with_default(
+            default_config.clone(),
+            toml::from_str("selection_keys = \"tab_selects\"").unwrap(),
+        );
+        assert_eq!(
+            bound_action(&config, Key::Tab),
+            vec![Action::ToggleSelectionDown]
+        );
+        assert_eq!(
+            bound_action(&config, Key::Enter),
+            vec![Action::ConfirmSelection]
+        );
+
+        // the default preset leaves customized bindings alone
+        let config = Config::merge_user_with_default(
+            default_config.clone(),
+            toml::from_str(
+                r#"
+                [keybindings]
+                confirm_selection = "ctrl-s"
+                "#,
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            config.keybindings.get(&Action::ConfirmSelection),
+            Some(&Binding::SingleKey(Key::Ctrl('s')))
+        );
+
+        // other presets override them
+        let config = Config::merge_user_with_default(
+            default_config,
+            toml::from_str(
+                r#"
+                selection_keys = "tab_accepts"
+                [keybindings]
+                confirm_selection = "ctrl-s"
+                "#,
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            bound_action(&config, Key::Tab),
+            vec![Action::ConfirmSelection]
+        );
+    }
+
     #[test]
     fn test_setting_user_shell_integration_triggers_overrides_default() {
         let user_config = r#"
@@ -473,4 +1006,76 @@ mod tests {
             .collect()
         );
     }
+
+    #[test]
+    fn test_save_keybinding() {
+        let user_config = r#"
+[keybindings]
+# Toggle the help bar
+toggle_help = "ctrl-g"
+SelectNextEntry = "down"
+
+[ui]
+ui_scale = 90
+"#;
+
+        let dir = tempdir().unwrap();
+        let config_dir = dir.path();
+        std::fs::write(config_dir.join(CONFIG_FILE_NAME), user_config)
+            .unwrap();
+
+        save_keybinding(
+            config_dir,
+            &Action::ToggleHelp,
+            &Binding::SingleKey(Key::F(2)),
+        )
+        .unwrap();
+        save_keybinding(
+            config_dir,
+            &Action::SelectNextEntry,
+            &Binding::MultipleKeys(vec![Key::Down, Key::Ctrl('j')]),
+        )
+        .unwrap();
+
+        let contents =
+            std::fs::read_to_string(config_dir.join(CONFIG_FILE_NAME))
+                .unwrap();
+        // comments and unrelated settings are preserved
+        assert!(
+            contents.contains("# Toggle the help bar\ntoggle_help = \"f2\"")
+        );
+        assert!(contents.contains("ui_scale = 90"));
+        // entries written using an alias are replaced
+        assert!(!contents.contains("SelectNextEntry"));
+
+        let config = Config::load_user_config(config_dir).unwrap();
+        assert_eq!(
+            config.keybindings.get(&Action::ToggleHelp),
+            Some(&Binding::SingleKey(Key::F(2)))
+        );
+        assert_eq!(
+            config.keybindings.get(&Action::SelectNextEntry),
+            Some(&Binding::MultipleKeys(vec![Key::Down, Key::Ctrl('j')]))
+        );
+        assert_eq!(config.ui.ui_scale, 90);
+    }
+
+    #[test]
+    fn test_save_keybinding_without_config_file() {
+        let dir = tempdir().unwrap();
+        let config_dir = dir.path();
+
+        save_keybinding(
+            config_dir,
+            &Action::TogglePreview,
+            &Binding::SingleKey(Key::Alt('p')),
+        )
+        .unwrap();
+
+        let config = Config::load_user_config(config_dir).unwrap();
+        assert_eq!(
+            config.keybindings.get(&Action::TogglePreview),
+            Some(&Binding::SingleKey(Key::Alt('p')))
+        );
+    }
 }
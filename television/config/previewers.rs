@@ -1,40 +1,89 @@
+use std::collections::BTreeMap;
+
 use crate::preview::{previewers, PreviewerConfig};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Deserialize, Default, PartialEq, Hash)]
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, Hash)]
 pub struct PreviewersConfig {
     #[serde(default)]
     pub basic: BasicPreviewerConfig,
     pub file: FilePreviewerConfig,
     #[serde(default)]
     pub env_var: EnvVarPreviewerConfig,
+    #[serde(default)]
+    pub command: CommandPreviewerConfig,
+    /// Preview commands the `cycle_previewer` action rotates through after
+    /// the channel's own previewer, by channel name.
+    #[serde(default, deserialize_with = "super::lowercase_keys")]
+    pub alternatives: BTreeMap<String, Vec<AlternativePreviewer>>,
+}
+
+/// A preview command that can be cycled to with `Action::CyclePreviewer`.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+pub struct AlternativePreviewer {
+    /// The name shown in the preview's title while it is active.
+    pub name: String,
+    /// The command previewing the entry, formatted as cable channels'
+    /// preview commands are (e.g. `xxd {}`).
+    pub command: String,
 }
 
 impl From<PreviewersConfig> for PreviewerConfig {
     fn from(val: PreviewersConfig) -> Self {
         PreviewerConfig::default()
-            .file(previewers::files::FilePreviewerConfig::new(val.file.theme))
+            .file(
+                previewers::files::FilePreviewerConfig::new(
+                    val.file.theme,
+                    val.file.max_preview_bytes,
+                )
+                .directory_tree_depth(val.file.directory_tree_depth),
+            )
+            .command(
+                previewers::command::CommandPreviewerConfig::default()
+                    .max_concurrent(val.command.max_concurrent),
+            )
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Default, PartialEq, Hash)]
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, Hash)]
 pub struct BasicPreviewerConfig {}
 
-#[derive(Clone, Debug, Deserialize, PartialEq, Hash)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Hash)]
 #[serde(default)]
 pub struct FilePreviewerConfig {
-    //pub max_file_size: u64,
     pub theme: String,
+    pub max_preview_bytes: u64,
+    /// How many levels deep directories are previewed as a tree, directories
+    /// being previewed as usual if unset.
+    pub directory_tree_depth: Option<usize>,
 }
 
 impl Default for FilePreviewerConfig {
     fn default() -> Self {
         Self {
-            //max_file_size: 1024 * 1024,
             theme: String::from("TwoDark"),
+            max_preview_bytes: previewers::files::DEFAULT_MAX_PREVIEW_BYTES,
+            directory_tree_depth: None,
         }
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Default, PartialEq, Hash)]
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, Hash)]
 pub struct EnvVarPreviewerConfig {}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Hash)]
+#[serde(default)]
+pub struct CommandPreviewerConfig {
+    /// How many preview commands may run at the same time, the oldest ones
+    /// being cancelled to make room for new ones.
+    pub max_concurrent: usize,
+}
+
+impl Default for CommandPreviewerConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent:
+                previewers::command::DEFAULT_MAX_CONCURRENT_PREVIEWS,
+        }
+    }
+}
@@ -92,11 +92,16 @@ pub struct Theme {
     // general
     pub background: Option<Color>,
     pub border_fg: Color,
+    // per pane border colors, falling back to `border_fg`
+    pub results_border_fg: Option<Color>,
+    pub preview_border_fg: Option<Color>,
+    pub input_border_fg: Option<Color>,
     pub text_fg: Color,
     pub dimmed_text_fg: Color,
     // input
     pub input_text_fg: Color,
     pub result_count_fg: Color,
+    pub error_fg: Color,
     // results
     pub result_name_fg: Color,
     pub result_line_number_fg: Color,
@@ -160,12 +165,18 @@ struct Inner {
     // general
     background: Option<String>,
     border_fg: String,
+    results_border_fg: Option<String>,
+    preview_border_fg: Option<String>,
+    input_border_fg: Option<String>,
     // info
     text_fg: String,
     dimmed_text_fg: String,
     // input
     input_text_fg: String,
     result_count_fg: String,
+    // this is made optional for theme backwards compatibility
+    // and falls back to red
+    error_fg: Option<String>,
     //results
     result_name_fg: String,
     result_line_number_fg: String,
@@ -207,6 +218,30 @@ impl<'de> Deserialize<'de> for Theme {
                     &inner.border_fg
                 ))
             })?,
+            results_border_fg: inner
+                .results_border_fg
+                .map(|s| {
+                    Color::from_str(&s).ok_or_else(|| {
+                        serde::de::Error::custom(format!("invalid color {s}"))
+                    })
+                })
+                .transpose()?,
+            preview_border_fg: inner
+                .preview_border_fg
+                .map(|s| {
+                    Color::from_str(&s).ok_or_else(|| {
+                        serde::de::Error::custom(format!("invalid color {s}"))
+                    })
+                })
+                .transpose()?,
+            input_border_fg: inner
+                .input_border_fg
+                .map(|s| {
+                    Color::from_str(&s).ok_or_else(|| {
+                        serde::de::Error::custom(format!("invalid color {s}"))
+                    })
+                })
+                .transpose()?,
             text_fg: Color::from_str(&inner.text_fg).ok_or_else(|| {
                 serde::de::Error::custom(format!(
                     "invalid color {}",
@@ -235,6 +270,13 @@ impl<'de> Deserialize<'de> for Theme {
                         &inner.result_count_fg
                     ))
                 })?,
+            // this is optional for theme backwards compatibility and falls back to red
+            error_fg: match inner.error_fg {
+                Some(s) => Color::from_str(&s).ok_or_else(|| {
+                    serde::de::Error::custom(format!("invalid color {}", &s))
+                })?,
+                None => Color::Ansi(ANSIColor::Red),
+            },
             result_name_fg: Color::from_str(&inner.result_name_fg)
                 .ok_or_else(|| {
                     serde::de::Error::custom(format!(
@@ -400,6 +442,11 @@ impl Into<HelpColorscheme> for &Theme {
 impl Into<ResultsColorscheme> for &Theme {
     fn into(self) -> ResultsColorscheme {
         ResultsColorscheme {
+            border_fg: self
+                .results_border_fg
+                .as_ref()
+                .unwrap_or(&self.border_fg)
+                .into(),
             result_name_fg: (&self.result_name_fg).into(),
             result_preview_fg: (&self.result_value_fg).into(),
             result_line_number_fg: (&self.result_line_number_fg).into(),
@@ -414,11 +461,17 @@ impl Into<ResultsColorscheme> for &Theme {
 impl Into<PreviewColorscheme> for &Theme {
     fn into(self) -> PreviewColorscheme {
         PreviewColorscheme {
+            border_fg: self
+                .preview_border_fg
+                .as_ref()
+                .unwrap_or(&self.border_fg)
+                .into(),
             title_fg: (&self.preview_title_fg).into(),
             highlight_bg: (&self.selection_bg).into(),
             content_fg: (&self.text_fg).into(),
             gutter_fg: (&self.dimmed_text_fg).into(),
             gutter_selected_fg: (&self.match_fg).into(),
+            match_fg: (&self.match_fg).into(),
         }
     }
 }
@@ -427,8 +480,14 @@ impl Into<PreviewColorscheme> for &Theme {
 impl Into<InputColorscheme> for &Theme {
     fn into(self) -> InputColorscheme {
         InputColorscheme {
+            border_fg: self
+                .input_border_fg
+                .as_ref()
+                .unwrap_or(&self.border_fg)
+                .into(),
             input_fg: (&self.input_text_fg).into(),
             results_count_fg: (&self.result_count_fg).into(),
+            error_fg: (&self.error_fg).into(),
         }
     }
 }
@@ -553,5 +612,47 @@ mod tests {
             theme.send_to_channel_mode_fg,
             Color::Ansi(ANSIColor::BrightWhite)
         );
+        // missing from the theme file
+        assert_eq!(theme.error_fg, Color::Ansi(ANSIColor::Red));
+    }
+
+    #[test]
+    fn test_pane_border_colors_fall_back_to_border_fg() {
+        let theme = Theme {
+            border_fg: Color::Ansi(ANSIColor::Red),
+            preview_border_fg: Some(Color::Ansi(ANSIColor::Blue)),
+            ..Default::default()
+        };
+        let colorscheme: Colorscheme = (&theme).into();
+        assert_eq!(colorscheme.results.border_fg, RatatuiColor::Red);
+        assert_eq!(colorscheme.input.border_fg, RatatuiColor::Red);
+        assert_eq!(colorscheme.preview.border_fg, RatatuiColor::Blue);
+    }
+
+    #[test]
+    fn test_theme_file_into_colorscheme() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("custom.toml");
+        let theme_content = include_str!("../../themes/default.toml")
+            .lines()
+            .filter(|line| {
+                !line.starts_with("dimmed_text_fg")
+                    && !line.starts_with("match_fg")
+            })
+            .chain(["dimmed_text_fg = \"#102030\"", "match_fg = \"red\""])
+            .collect::<Vec<_>>()
+            .join("\n");
+        std::fs::write(&path, theme_content).unwrap();
+
+        let theme = Theme::from_path(&path).unwrap();
+        let colorscheme: Colorscheme = (&theme).into();
+        assert_eq!(
+            colorscheme.help.metadata_field_name_fg,
+            RatatuiColor::Rgb(0x10, 0x20, 0x30)
+        );
+        assert_eq!(
+            colorscheme.results.match_foreground_color,
+            RatatuiColor::Red
+        );
     }
 }
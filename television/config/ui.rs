@@ -1,12 +1,21 @@
-use serde::Deserialize;
+use std::collections::BTreeMap;
 
-use crate::screen::layout::{InputPosition, PreviewTitlePosition};
+use serde::{Deserialize, Serialize};
+
+use crate::screen::input::{CursorShape, PromptSymbols};
+use crate::screen::layout::{
+    BorderKind, InputPosition, LineNumbers, PreviewTitlePosition, Truncation,
+};
 
 use super::themes::DEFAULT_THEME;
 
 const DEFAULT_UI_SCALE: u16 = 100;
+const DEFAULT_HELP_COLUMN_RATIO: [u16; 2] = [1, 2];
+const DEFAULT_GUIDE_PREVIEW_ENTRIES: u32 = 20;
+const DEFAULT_NO_RESULTS_MESSAGE: &str = "No results";
 
-#[derive(Clone, Debug, Deserialize, PartialEq, Hash)]
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Hash)]
 #[serde(default)]
 pub struct UiConfig {
     pub use_nerd_font_icons: bool,
@@ -16,7 +25,92 @@ pub struct UiConfig {
     #[serde(default)]
     pub input_bar_position: InputPosition,
     pub preview_title_position: Option<PreviewTitlePosition>,
+    pub border_type: BorderKind,
     pub theme: String,
+    pub max_keys_shown: Option<usize>,
+    pub keep_selection_on_query_change: bool,
+    pub preview_on_demand: bool,
+    pub results_padding_left: u16,
+    pub results_padding_right: u16,
+    pub results_row_spacing: u16,
+    /// Whether to show the line number and value of each result dimmed on
+    /// a second line below its name.
+    pub two_line_results: bool,
+    pub show_preview_header: bool,
+    pub shorten_paths: bool,
+    pub scroll_off: u16,
+    /// Whether the results list scrolls smoothly over a few ticks rather
+    /// than at once when the selection jumps far away.
+    pub scroll_animation: bool,
+    /// The maximum number of results listed, however many match, the
+    /// selection only moving among the listed ones.
+    pub max_shown_results: Option<u32>,
+    pub strip_common_prefix: bool,
+    pub show_line_numbers: Option<LineNumbers>,
+    /// The relative widths of the action and key columns of the help
+    /// tables.
+    pub help_column_ratio: [u16; 2],
+    /// The number of entries listed when previewing a channel from the
+    /// remote control.
+    pub guide_preview_entries: u32,
+    /// Whether to highlight the terms of the query in the preview.
+    pub highlight_query_in_preview: bool,
+    /// The message shown when the query matches none of the channel's
+    /// entries.
+    pub no_results_message: String,
+    /// Channel specific messages overriding `no_results_message`, by
+    /// channel name.
+    #[serde(deserialize_with = "super::lowercase_keys")]
+    pub no_results_messages: BTreeMap<String, String>,
+    /// Which end of the results too wide for the results list gets cut off.
+    pub truncation: Truncation,
+    /// Channel specific truncations overriding `truncation`, by channel
+    /// name.
+    #[serde(deserialize_with = "super::lowercase_keys")]
+    pub truncations: BTreeMap<String, Truncation>,
+    /// Channel specific themes overriding `theme`, by channel name.
+    #[serde(deserialize_with = "super::lowercase_keys")]
+    pub themes: BTreeMap<String, String>,
+    /// Whether to list the results under collapsible headers for channels
+    /// whose entries belong to groups.
+    pub group_results: bool,
+    /// The shape of the input's cursor, the terminal's own cursor being
+    /// used if unset.
+    pub input_cursor_shape: Option<CursorShape>,
+    /// Whether the input's cursor blinks (along with `input_cursor_shape`).
+    pub input_cursor_blink: bool,
+    /// The symbols shown before the query depending on the state of the
+    /// channel and the mode.
+    pub prompt_symbols: PromptSymbols,
+    /// Whether to wrap long queries over several lines, growing the input
+    /// box, rather than scrolling them horizontally.
+    pub wrap_input: bool,
+}
+
+impl UiConfig {
+    /// The message shown when the query matches none of the entries of the
+    /// given channel, by its `TelevisionChannel::config_name`.
+    pub fn no_results_message(&self, config_name: &str) -> &str {
+        self.no_results_messages
+            .get(config_name)
+            .unwrap_or(&self.no_results_message)
+    }
+
+    /// Which end of the results of the given channel, by its
+    /// `TelevisionChannel::config_name`, gets cut off when they're too wide
+    /// for the results list.
+    pub fn truncation(&self, config_name: &str) -> Truncation {
+        self.truncations
+            .get(config_name)
+            .copied()
+            .unwrap_or(self.truncation)
+    }
+
+    /// The name of the theme used while the given channel, by its
+    /// `TelevisionChannel::config_name`, is active.
+    pub fn theme(&self, config_name: &str) -> &str {
+        self.themes.get(config_name).unwrap_or(&self.theme)
+    }
 }
 
 impl Default for UiConfig {
@@ -28,7 +122,35 @@ impl Default for UiConfig {
             show_preview_panel: true,
             input_bar_position: InputPosition::Top,
             preview_title_position: None,
+            border_type: BorderKind::Rounded,
             theme: String::from(DEFAULT_THEME),
+            max_keys_shown: None,
+            keep_selection_on_query_change: false,
+            preview_on_demand: false,
+            results_padding_left: 0,
+            results_padding_right: 0,
+            results_row_spacing: 0,
+            two_line_results: false,
+            show_preview_header: false,
+            shorten_paths: false,
+            scroll_off: 0,
+            scroll_animation: false,
+            max_shown_results: None,
+            strip_common_prefix: false,
+            show_line_numbers: None,
+            help_column_ratio: DEFAULT_HELP_COLUMN_RATIO,
+            guide_preview_entries: DEFAULT_GUIDE_PREVIEW_ENTRIES,
+            highlight_query_in_preview: false,
+            no_results_message: String::from(DEFAULT_NO_RESULTS_MESSAGE),
+            no_results_messages: BTreeMap::new(),
+            truncation: Truncation::Right,
+            truncations: BTreeMap::new(),
+            themes: BTreeMap::new(),
+            group_results: false,
+            input_cursor_shape: None,
+            input_cursor_blink: true,
+            prompt_symbols: PromptSymbols::default(),
+            wrap_input: false,
         }
     }
 }
@@ -0,0 +1,56 @@
+use crate::event::Key;
+
+/// The outcome of answering a confirmation prompt with a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    Confirmed,
+    Cancelled,
+    /// The key isn't an answer and the prompt keeps waiting for one.
+    Pending,
+}
+
+/// A prompt asking the user to confirm a command before it runs.
+///
+/// Only `y` confirms the command while `n`, `Esc` and `Enter` cancel it,
+/// making cancellation the default answer.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Confirmation {
+    pub command: String,
+}
+
+impl Confirmation {
+    pub fn new(command: String) -> Self {
+        Self { command }
+    }
+
+    /// The question displayed to the user.
+    pub fn message(&self) -> String {
+        format!("Run `{}`? [y/N]", self.command)
+    }
+
+    pub fn answer(&self, key: Key) -> Answer {
+        match key {
+            Key::Char('y' | 'Y') => Answer::Confirmed,
+            Key::Char('n' | 'N') | Key::Esc | Key::Enter => Answer::Cancelled,
+            _ => Answer::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answers() {
+        let confirmation = Confirmation::new("kill 1234".to_string());
+        assert_eq!(confirmation.message(), "Run `kill 1234`? [y/N]");
+        assert_eq!(confirmation.answer(Key::Char('y')), Answer::Confirmed);
+        assert_eq!(confirmation.answer(Key::Char('Y')), Answer::Confirmed);
+        assert_eq!(confirmation.answer(Key::Char('n')), Answer::Cancelled);
+        assert_eq!(confirmation.answer(Key::Esc), Answer::Cancelled);
+        assert_eq!(confirmation.answer(Key::Enter), Answer::Cancelled);
+        assert_eq!(confirmation.answer(Key::Char('x')), Answer::Pending);
+        assert_eq!(confirmation.answer(Key::Down), Answer::Pending);
+    }
+}
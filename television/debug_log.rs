@@ -0,0 +1,126 @@
+//! A log of the latest keys received and actions dispatched, shown in the
+//! debug overlay to diagnose keybinding issues.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::action::Action;
+use crate::event::Key;
+
+/// The number of records kept in the log.
+pub const DEBUG_LOG_CAPACITY: usize = 20;
+
+/// A key or an action recorded in the log.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DebugEvent {
+    Key(Key),
+    Action(Action),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DebugRecord {
+    /// The time elapsed between the start of the log and the record.
+    pub elapsed: Duration,
+    pub event: DebugEvent,
+}
+
+/// A ring buffer of the latest keys and actions, the oldest records being
+/// dropped once it is full.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DebugLog {
+    start: Instant,
+    capacity: usize,
+    records: VecDeque<DebugRecord>,
+}
+
+impl Default for DebugLog {
+    fn default() -> Self {
+        Self::new(DEBUG_LOG_CAPACITY)
+    }
+}
+
+impl DebugLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            start: Instant::now(),
+            capacity,
+            records: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn record_key(&mut self, key: Key) {
+        self.push(DebugEvent::Key(key));
+    }
+
+    /// Record a dispatched action.
+    ///
+    /// Ticks and render requests, which are sent continuously, aren't
+    /// recorded.
+    pub fn record_action(&mut self, action: &Action) {
+        if !matches!(action, Action::Tick | Action::Render) {
+            self.push(DebugEvent::Action(action.clone()));
+        }
+    }
+
+    fn push(&mut self, event: DebugEvent) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.records.len() == self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(DebugRecord {
+            elapsed: self.start.elapsed(),
+            event,
+        });
+    }
+
+    /// The records from the oldest to the latest.
+    pub fn records(&self) -> impl DoubleEndedIterator<Item = &DebugRecord> {
+        self.records.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events(log: &DebugLog) -> Vec<DebugEvent> {
+        log.records().map(|record| record.event.clone()).collect()
+    }
+
+    #[test]
+    fn test_records_keys_and_actions() {
+        let mut log = DebugLog::default();
+        log.record_key(Key::Ctrl('j'));
+        log.record_action(&Action::SelectNextEntry);
+        log.record_action(&Action::Tick);
+        log.record_action(&Action::Render);
+
+        assert_eq!(
+            events(&log),
+            [
+                DebugEvent::Key(Key::Ctrl('j')),
+                DebugEvent::Action(Action::SelectNextEntry),
+            ]
+        );
+        let elapsed = log.records().map(|r| r.elapsed).collect::<Vec<_>>();
+        assert!(elapsed[0] <= elapsed[1]);
+    }
+
+    #[test]
+    fn test_oldest_records_are_dropped() {
+        let mut log = DebugLog::new(2);
+        for c in ['a', 'b', 'c'] {
+            log.record_action(&Action::AddInputChar(c));
+        }
+
+        assert_eq!(
+            events(&log),
+            [
+                DebugEvent::Action(Action::AddInputChar('b')),
+                DebugEvent::Action(Action::AddInputChar('c')),
+            ]
+        );
+    }
+}
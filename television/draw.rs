@@ -7,14 +7,32 @@ use rustc_hash::FxHashSet;
 use crate::{
     action::Action,
     channels::entry::Entry,
-    config::Config,
+    config::{Config, SendToChannelMode},
+    confirmation::Confirmation,
+    debug_log::DebugLog,
+    keybinding_editor::KeybindingEditor,
+    matcher::config::MatchTarget,
     picker::Picker,
-    preview::PreviewState,
+    preview::{search::query_terms, PreviewState},
+    quicknav::Quicknav,
     screen::{
-        colors::Colorscheme, help::draw_help_bar, input::draw_input_box,
-        keybindings::build_keybindings_table, layout::Layout,
-        preview::draw_preview_content_block,
-        remote_control::draw_remote_control, results::draw_results_list,
+        colors::Colorscheme,
+        confirmation::{draw_confirmation_prompt, draw_error, draw_notice},
+        debug::draw_debug_overlay,
+        help::{draw_help_bar, draw_help_overlay},
+        input::{
+            draw_input_box, input_field_width, prompt_span,
+            wrapped_input_height, PromptState,
+        },
+        keybinding_editor::draw_keybinding_editor,
+        keybindings::build_keybindings_table,
+        layout::{Layout, LineNumbers},
+        preview::{draw_preview_content_block, preview_header},
+        remote_control::draw_remote_control,
+        results::{
+            capped_results_notice, draw_results_list, empty_results_message,
+            RowPrefix,
+        },
         spinner::Spinner,
     },
     television::Mode,
@@ -27,23 +45,41 @@ use crate::{
 /// This struct is passed along to the UI thread as part of the `TvState` struct.
 pub struct ChannelState {
     pub current_channel_name: String,
+    /// The name the channel's options are looked up by in the
+    /// configuration (see `TelevisionChannel::config_name`).
+    pub config_name: String,
     pub selected_entries: FxHashSet<Entry>,
     pub total_count: u32,
     pub running: bool,
+    pub pinned_filters: Vec<String>,
+    /// The directory prefix shared by all the results, stripped from their
+    /// names when displayed.
+    pub common_prefix: Option<String>,
+    /// What the entries are matched against.
+    pub match_target: MatchTarget,
 }
 
 impl ChannelState {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         current_channel_name: String,
+        config_name: String,
         selected_entries: FxHashSet<Entry>,
         total_count: u32,
         running: bool,
+        pinned_filters: Vec<String>,
+        common_prefix: Option<String>,
+        match_target: MatchTarget,
     ) -> Self {
         Self {
             current_channel_name,
+            config_name,
             selected_entries,
             total_count,
             running,
+            pinned_filters,
+            common_prefix,
+            match_target,
         }
     }
 }
@@ -51,11 +87,49 @@ impl ChannelState {
 impl Hash for ChannelState {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.current_channel_name.hash(state);
+        self.config_name.hash(state);
         self.selected_entries
             .iter()
             .for_each(|entry| entry.hash(state));
         self.total_count.hash(state);
         self.running.hash(state);
+        self.pinned_filters.hash(state);
+        self.common_prefix.hash(state);
+        self.match_target.hash(state);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Hash)]
+/// The state of the right pane in split search mode.
+///
+/// This struct is passed along to the UI thread as part of the `TvState` struct.
+pub struct SplitPaneState {
+    pub picker: Picker,
+    pub channel_name: String,
+    /// See `ChannelState::config_name`.
+    pub config_name: String,
+    pub total_count: u32,
+    pub running: bool,
+    pub focused: bool,
+}
+
+impl SplitPaneState {
+    pub fn new(
+        picker: Picker,
+        channel_name: String,
+        config_name: String,
+        total_count: u32,
+        running: bool,
+        focused: bool,
+    ) -> Self {
+        Self {
+            picker,
+            channel_name,
+            config_name,
+            total_count,
+            running,
+            focused,
+        }
     }
 }
 
@@ -65,33 +139,64 @@ impl Hash for ChannelState {
 /// This struct is passed along to the UI thread as part of the `Ctx` struct.
 pub struct TvState {
     pub mode: Mode,
+    pub send_to_channel_mode: SendToChannelMode,
     pub selected_entry: Option<Entry>,
     pub results_picker: Picker,
     pub rc_picker: Picker,
     pub channel_state: ChannelState,
     pub spinner: Spinner,
     pub preview_state: PreviewState,
+    pub keybinding_editor: Option<KeybindingEditor>,
+    pub help_visible: bool,
+    /// The debug log, if the debug overlay is shown.
+    pub debug_log: Option<DebugLog>,
+    pub preview_fullscreen: bool,
+    pub split_pane: Option<SplitPaneState>,
+    pub confirmation: Option<Confirmation>,
+    pub error: Option<String>,
+    pub notice: Option<String>,
+    pub quicknav: Option<Quicknav>,
 }
 
 impl TvState {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         mode: Mode,
+        send_to_channel_mode: SendToChannelMode,
         selected_entry: Option<Entry>,
         results_picker: Picker,
         rc_picker: Picker,
         channel_state: ChannelState,
         spinner: Spinner,
         preview_state: PreviewState,
+        keybinding_editor: Option<KeybindingEditor>,
+        help_visible: bool,
+        debug_log: Option<DebugLog>,
+        preview_fullscreen: bool,
+        split_pane: Option<SplitPaneState>,
+        confirmation: Option<Confirmation>,
+        error: Option<String>,
+        notice: Option<String>,
+        quicknav: Option<Quicknav>,
     ) -> Self {
         Self {
             mode,
+            send_to_channel_mode,
             selected_entry,
             results_picker,
             rc_picker,
             channel_state,
             spinner,
             preview_state,
+            keybinding_editor,
+            help_visible,
+            debug_log,
+            preview_fullscreen,
+            split_pane,
+            confirmation,
+            error,
+            notice,
+            quicknav,
         }
     }
 }
@@ -171,8 +276,8 @@ impl Ord for Ctx {
 /// This function is executed by the UI thread whenever it receives a render message from the main
 /// thread.
 ///
-/// It will draw the help bar, the results list, the input box, the preview content block, and the
-/// remote control.
+/// It will draw the help bar, the results list, the input box, the preview content block, the
+/// remote control and, when open, the keybinding editor.
 ///
 /// # Returns
 /// A `Result` containing the layout of the current frame if the drawing was successful.
@@ -181,10 +286,45 @@ impl Ord for Ctx {
 pub fn draw(ctx: &Ctx, f: &mut Frame<'_>, area: Rect) -> Result<Layout> {
     let show_preview =
         ctx.config.ui.show_preview_panel && ctx.tv_state.preview_state.enabled;
-    let show_remote = !matches!(ctx.tv_state.mode, Mode::Channel);
+    let fullscreen = show_preview && ctx.tv_state.preview_fullscreen;
+    let show_remote =
+        !fullscreen && !matches!(ctx.tv_state.mode, Mode::Channel);
 
-    let layout =
+    let mut layout =
         Layout::build(area, &ctx.config.ui, show_remote, show_preview);
+    let split_pane = ctx
+        .tv_state
+        .split_pane
+        .as_ref()
+        .filter(|_| ctx.tv_state.mode == Mode::Channel);
+    if split_pane.is_some() {
+        layout = layout.with_split();
+    }
+    if ctx.config.ui.wrap_input {
+        let height = wrapped_input_height(
+            ctx.tv_state.results_picker.input.value(),
+            input_field_width(
+                layout.input,
+                ctx.tv_state.channel_state.total_count,
+            ),
+        );
+        let split_height =
+            split_pane.zip(layout.split).map_or(0, |(pane, split)| {
+                wrapped_input_height(
+                    pane.picker.input.value(),
+                    input_field_width(split.input, pane.total_count),
+                )
+            });
+        layout = layout.with_input_height(height.max(split_height));
+    }
+    if fullscreen {
+        layout = layout.with_fullscreen_preview(area);
+    }
+
+    let displayable_keybindings = ctx
+        .config
+        .keybindings
+        .to_displayable(ctx.config.ui.max_keys_shown);
 
     // help bar (metadata, keymaps, logo)
     draw_help_bar(
@@ -192,62 +332,221 @@ pub fn draw(ctx: &Ctx, f: &mut Frame<'_>, area: Rect) -> Result<Layout> {
         &layout.help_bar,
         &ctx.tv_state.channel_state.current_channel_name,
         build_keybindings_table(
-            &ctx.config.keybindings.to_displayable(),
+            &displayable_keybindings,
             ctx.tv_state.mode,
             &ctx.colorscheme,
+            ctx.config.ui.help_column_ratio,
         ),
         ctx.tv_state.mode,
         &ctx.app_metadata,
         &ctx.colorscheme,
     );
 
-    // results list
-    draw_results_list(
-        f,
-        layout.results,
-        &ctx.tv_state.results_picker.entries,
-        &ctx.tv_state.channel_state.selected_entries,
-        &mut ctx.tv_state.results_picker.relative_state.clone(),
-        ctx.config.ui.input_bar_position,
-        ctx.config.ui.use_nerd_font_icons,
-        &ctx.colorscheme,
-        &ctx.config
-            .keybindings
-            .get(&Action::ToggleHelp)
-            // just display the first keybinding
-            .unwrap()
-            .to_string(),
-        &ctx.config
-            .keybindings
-            .get(&Action::TogglePreview)
-            // just display the first keybinding
-            .unwrap()
-            .to_string(),
-        // only show the preview keybinding hint if there's actually something to preview
-        ctx.tv_state.preview_state.enabled,
-    )?;
+    let help_keybinding = ctx
+        .config
+        .keybindings
+        .get(&Action::ToggleHelp)
+        // just display the first keybinding
+        .unwrap()
+        .to_string();
+    let preview_keybinding = ctx
+        .config
+        .keybindings
+        .get(&Action::TogglePreview)
+        // just display the first keybinding
+        .unwrap()
+        .to_string();
 
-    // input box
-    draw_input_box(
-        f,
-        layout.input,
-        ctx.tv_state.results_picker.total_items,
-        ctx.tv_state.channel_state.total_count,
-        &ctx.tv_state.results_picker.input,
-        &ctx.tv_state.results_picker.state,
-        ctx.tv_state.channel_state.running,
-        &ctx.tv_state.channel_state.current_channel_name,
-        &ctx.tv_state.spinner,
-        &ctx.colorscheme,
-    )?;
+    // results list and input boxes, hidden by the fullscreen preview
+    if !fullscreen {
+        let quicknav_labels =
+            ctx.tv_state.quicknav.as_ref().map(Quicknav::visible_labels);
+        draw_results_list(
+            f,
+            layout.results,
+            &ctx.tv_state.results_picker.entries,
+            &ctx.tv_state.channel_state.selected_entries,
+            &mut ctx.tv_state.results_picker.relative_state.clone(),
+            ctx.config.ui.input_bar_position,
+            ctx.config.ui.use_nerd_font_icons,
+            ctx.config.ui.shorten_paths,
+            ctx.config
+                .ui
+                .truncation(&ctx.tv_state.channel_state.config_name),
+            &ctx.colorscheme,
+            ctx.config.ui.border_type,
+            (
+                ctx.config.ui.results_padding_left,
+                ctx.config.ui.results_padding_right,
+            ),
+            ctx.config.ui.results_row_spacing,
+            ctx.config.ui.two_line_results,
+            quicknav_labels
+                .as_deref()
+                .map(RowPrefix::Labels)
+                .or_else(|| {
+                    ctx.config.ui.show_line_numbers.map(|numbers| {
+                        RowPrefix::Numbers(match numbers {
+                            LineNumbers::Absolute => {
+                                ctx.tv_state.results_picker.view_offset() + 1
+                            }
+                            LineNumbers::Relative => 1,
+                        })
+                    })
+                }),
+            ctx.tv_state.channel_state.common_prefix.as_deref(),
+            ctx.config
+                .ui
+                .max_shown_results
+                .filter(|_| !ctx.config.ui.group_results)
+                .and_then(|shown| {
+                    capped_results_notice(
+                        shown,
+                        ctx.tv_state.results_picker.total_items,
+                    )
+                })
+                .as_deref(),
+            empty_results_message(
+                !ctx.tv_state.results_picker.input.value().is_empty()
+                    || !ctx.tv_state.channel_state.pinned_filters.is_empty(),
+                ctx.tv_state.results_picker.total_items,
+                ctx.tv_state.channel_state.running,
+                ctx.config.ui.no_results_message(
+                    &ctx.tv_state.channel_state.config_name,
+                ),
+            ),
+            &help_keybinding,
+            &preview_keybinding,
+            // only show the preview keybinding hint if there's actually something to preview
+            ctx.tv_state.preview_state.enabled,
+        )?;
+
+        // right pane of the split search
+        let split = split_pane.zip(layout.split);
+        if let Some((split_pane, split_layout)) = split {
+            draw_results_list(
+                f,
+                split_layout.results,
+                &split_pane.picker.entries,
+                &FxHashSet::default(),
+                &mut split_pane.picker.relative_state.clone(),
+                ctx.config.ui.input_bar_position,
+                ctx.config.ui.use_nerd_font_icons,
+                ctx.config.ui.shorten_paths,
+                ctx.config.ui.truncation(&split_pane.config_name),
+                &ctx.colorscheme,
+                ctx.config.ui.border_type,
+                (
+                    ctx.config.ui.results_padding_left,
+                    ctx.config.ui.results_padding_right,
+                ),
+                ctx.config.ui.results_row_spacing,
+                ctx.config.ui.two_line_results,
+                None,
+                None,
+                None,
+                empty_results_message(
+                    !split_pane.picker.input.value().is_empty(),
+                    split_pane.picker.total_items,
+                    split_pane.running,
+                    ctx.config.ui.no_results_message(&split_pane.config_name),
+                ),
+                &help_keybinding,
+                &preview_keybinding,
+                ctx.tv_state.preview_state.enabled,
+            )?;
+        }
+
+        // input boxes, the focused one is drawn last so that it gets the cursor
+        let draw_split_input = |f: &mut Frame<'_>| -> Result<()> {
+            let Some((split_pane, split_layout)) = split else {
+                return Ok(());
+            };
+            draw_input_box(
+                f,
+                split_layout.input,
+                split_pane.picker.total_items,
+                split_pane.total_count,
+                &split_pane.picker.input,
+                &split_pane.picker.state,
+                split_pane.running,
+                &split_pane.channel_name,
+                &[],
+                MatchTarget::default(),
+                prompt_span(
+                    PromptState::new(Mode::Channel, split_pane.running, false),
+                    &ctx.config.ui.prompt_symbols,
+                    &ctx.colorscheme,
+                ),
+                &ctx.tv_state.spinner,
+                &ctx.colorscheme,
+                ctx.config.ui.border_type,
+                ctx.config.ui.wrap_input,
+            )
+        };
+        let split_focused = split.is_some_and(|(pane, _)| pane.focused);
+        if !split_focused {
+            draw_split_input(f)?;
+        }
+        draw_input_box(
+            f,
+            layout.input,
+            ctx.tv_state.results_picker.total_items,
+            ctx.tv_state.channel_state.total_count,
+            &ctx.tv_state.results_picker.input,
+            &ctx.tv_state.results_picker.state,
+            ctx.tv_state.channel_state.running,
+            &ctx.tv_state.channel_state.current_channel_name,
+            &ctx.tv_state.channel_state.pinned_filters,
+            ctx.tv_state.channel_state.match_target,
+            prompt_span(
+                PromptState::new(
+                    ctx.tv_state.mode,
+                    ctx.tv_state.channel_state.running,
+                    ctx.tv_state.error.is_some(),
+                ),
+                &ctx.config.ui.prompt_symbols,
+                &ctx.colorscheme,
+            ),
+            &ctx.tv_state.spinner,
+            &ctx.colorscheme,
+            ctx.config.ui.border_type,
+            ctx.config.ui.wrap_input,
+        )?;
+        if split_focused {
+            draw_split_input(f)?;
+        }
+    }
 
     if layout.preview_window.is_some() {
+        let query_terms = if ctx.config.ui.highlight_query_in_preview
+            && ctx.tv_state.mode == Mode::Channel
+        {
+            let pinned_filters = &ctx.tv_state.channel_state.pinned_filters;
+            query_terms(&format!(
+                "{} {}",
+                pinned_filters.join(" "),
+                ctx.tv_state.results_picker.input.value()
+            ))
+        } else {
+            Vec::new()
+        };
         draw_preview_content_block(
             f,
             layout.preview_window.unwrap(),
             &ctx.tv_state.preview_state,
             ctx.config.ui.use_nerd_font_icons,
             &ctx.colorscheme,
+            ctx.config.ui.border_type,
+            ctx.tv_state
+                .selected_entry
+                .as_ref()
+                .filter(|_| ctx.config.ui.show_preview_header)
+                .map(|entry| {
+                    preview_header(entry, &ctx.tv_state.preview_state.preview)
+                })
+                .as_deref(),
+            &query_terms,
         )?;
     }
 
@@ -261,9 +560,50 @@ pub fn draw(ctx: &Ctx, f: &mut Frame<'_>, area: Rect) -> Result<Layout> {
             &mut ctx.tv_state.rc_picker.state.clone(),
             &mut ctx.tv_state.rc_picker.input.clone(),
             &ctx.tv_state.mode,
+            ctx.tv_state.send_to_channel_mode,
             &ctx.colorscheme,
         )?;
     }
 
+    // help overlay, drawn on top of the results
+    if ctx.tv_state.help_visible && !fullscreen {
+        draw_help_overlay(
+            f,
+            layout.results,
+            build_keybindings_table(
+                &displayable_keybindings,
+                ctx.tv_state.mode,
+                &ctx.colorscheme,
+                ctx.config.ui.help_column_ratio,
+            ),
+            &ctx.colorscheme.general,
+        );
+    }
+
+    if let Some(confirmation) = &ctx.tv_state.confirmation {
+        draw_confirmation_prompt(f, area, confirmation, &ctx.colorscheme);
+    }
+    if let Some(error) = &ctx.tv_state.error {
+        draw_error(f, area, error, &ctx.colorscheme);
+    }
+    if let Some(notice) = &ctx.tv_state.notice {
+        draw_notice(f, area, notice, &ctx.colorscheme);
+    }
+
+    // keybinding editor, drawn on top of everything else
+    if let Some(editor) = &ctx.tv_state.keybinding_editor {
+        draw_keybinding_editor(
+            f,
+            area,
+            editor,
+            &ctx.config.keybindings,
+            &ctx.colorscheme,
+        );
+    }
+
+    if let Some(debug_log) = &ctx.tv_state.debug_log {
+        draw_debug_overlay(f, area, debug_log, &ctx.colorscheme);
+    }
+
     Ok(layout)
 }
@@ -0,0 +1,268 @@
+//! Grouping of the results under collapsible headers (see
+//! `UiConfig::group_results`).
+//!
+//! Only a window of results is laid out at a time, the headers counting the
+//! results of their group within that window.
+
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::channels::entry::{Entry, EntryKind, PreviewType};
+
+/// A row of the results list when results are grouped.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum GroupRow {
+    /// The header of a group holding `len` results.
+    Header {
+        group: String,
+        len: usize,
+        collapsed: bool,
+    },
+    /// A result, listed under the header of its group if it has one.
+    Result(Entry),
+}
+
+impl GroupRow {
+    /// Whether the row can be selected.
+    ///
+    /// Headers are skipped when moving the selection, except for the ones
+    /// of collapsed groups which couldn't be expanded again otherwise.
+    pub fn selectable(&self) -> bool {
+        match self {
+            GroupRow::Header { collapsed, .. } => *collapsed,
+            GroupRow::Result(_) => true,
+        }
+    }
+
+    /// The group the row belongs to, if any.
+    pub fn group(&self) -> Option<&str> {
+        match self {
+            GroupRow::Header { group, .. } => Some(group),
+            GroupRow::Result(entry) => entry.group.as_deref(),
+        }
+    }
+
+    /// The entry displaying the row in the results list.
+    pub fn to_entry(&self) -> Entry {
+        match self {
+            GroupRow::Header {
+                group,
+                len,
+                collapsed,
+            } => Entry::new(format!("{group} ({len})"), PreviewType::None)
+                .with_kind(EntryKind::GroupHeader {
+                    collapsed: *collapsed,
+                })
+                .with_previewable(false),
+            GroupRow::Result(entry) => entry.clone(),
+        }
+    }
+
+    /// The number of rows taken in the results list.
+    pub fn display_height(&self) -> usize {
+        match self {
+            GroupRow::Header { .. } => 1,
+            GroupRow::Result(entry) => entry.display_height(),
+        }
+    }
+}
+
+/// Lay the given results out by group.
+///
+/// Groups are listed in the order of their first result, each result
+/// keeping its rank within its group. Results without a group aren't listed
+/// under any header, and the results of collapsed groups are left out.
+pub fn group_rows(
+    results: Vec<Entry>,
+    collapsed: &FxHashSet<String>,
+) -> Vec<GroupRow> {
+    let mut order: Vec<Option<String>> = Vec::new();
+    let mut members: FxHashMap<Option<String>, Vec<Entry>> =
+        FxHashMap::default();
+    for entry in results {
+        members
+            .entry(entry.group.clone())
+            .or_insert_with(|| {
+                order.push(entry.group.clone());
+                Vec::new()
+            })
+            .push(entry);
+    }
+    let mut rows = Vec::new();
+    for group in order {
+        let entries = members.remove(&group).unwrap_or_default();
+        let Some(group) = group else {
+            rows.extend(entries.into_iter().map(GroupRow::Result));
+            continue;
+        };
+        let is_collapsed = collapsed.contains(&group);
+        rows.push(GroupRow::Header {
+            group,
+            len: entries.len(),
+            collapsed: is_collapsed,
+        });
+        if !is_collapsed {
+            rows.extend(entries.into_iter().map(GroupRow::Result));
+        }
+    }
+    rows
+}
+
+/// The grouped layout of a window of a channel's results along with the
+/// groups the user collapsed.
+#[derive(Debug, Default)]
+pub struct ResultGroups {
+    collapsed: FxHashSet<String>,
+    rows: Vec<GroupRow>,
+    /// The group of each result of the window, in the order of the results.
+    groups: Vec<Option<String>>,
+    /// The index of the first result of the window.
+    start: u32,
+}
+
+impl ResultGroups {
+    /// Lay out the results of the window.
+    pub fn update(&mut self, results: Vec<Entry>) {
+        self.groups =
+            results.iter().map(|entry| entry.group.clone()).collect();
+        self.rows = group_rows(results, &self.collapsed);
+    }
+
+    /// The index among all the results of the result on row `index`, if
+    /// the row isn't a header.
+    pub fn result_index(&self, index: usize) -> Option<u32> {
+        let GroupRow::Result(entry) = self.rows.get(index)? else {
+            return None;
+        };
+        // results keep their rank within their group
+        let rank = self.rows[..index]
+            .iter()
+            .filter(|row| {
+                matches!(row, GroupRow::Result(e) if e.group == entry.group)
+            })
+            .count();
+        let position = self
+            .groups
+            .iter()
+            .enumerate()
+            .filter(|(_, group)| **group == entry.group)
+            .nth(rank)?
+            .0;
+        Some(self.start + u32::try_from(position).ok()?)
+    }
+
+    /// The index of the first result of the window.
+    pub fn start(&self) -> u32 {
+        self.start
+    }
+
+    /// Move the window to the results starting at index `start`, the rows
+    /// being laid out on the next update.
+    pub fn set_start(&mut self, start: u32) {
+        self.start = start;
+    }
+
+    pub fn rows(&self) -> &[GroupRow] {
+        &self.rows
+    }
+
+    pub fn row(&self, index: usize) -> Option<&GroupRow> {
+        self.rows.get(index)
+    }
+
+    /// Collapse or expand `group`, the rows being recomputed on the next
+    /// update.
+    pub fn toggle(&mut self, group: &str) {
+        if !self.collapsed.remove(group) {
+            self.collapsed.insert(group.to_string());
+        }
+    }
+
+    /// The index of the header of `group`.
+    pub fn header_index(&self, group: &str) -> Option<usize> {
+        self.rows.iter().position(|row| {
+            matches!(row, GroupRow::Header { group: g, .. } if g == group)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, group: Option<&str>) -> Entry {
+        let entry = Entry::new(name.to_string(), PreviewType::Basic);
+        match group {
+            Some(group) => entry.with_group(group.to_string()),
+            None => entry,
+        }
+    }
+
+    fn header(group: &str, len: usize, collapsed: bool) -> GroupRow {
+        GroupRow::Header {
+            group: group.to_string(),
+            len,
+            collapsed,
+        }
+    }
+
+    #[test]
+    fn test_group_rows() {
+        let results = vec![
+            entry("b/1", Some("b")),
+            entry("a/1", Some("a")),
+            entry("loose", None),
+            entry("b/2", Some("b")),
+        ];
+
+        assert_eq!(
+            group_rows(results.clone(), &FxHashSet::default()),
+            vec![
+                header("b", 2, false),
+                GroupRow::Result(entry("b/1", Some("b"))),
+                GroupRow::Result(entry("b/2", Some("b"))),
+                header("a", 1, false),
+                GroupRow::Result(entry("a/1", Some("a"))),
+                GroupRow::Result(entry("loose", None)),
+            ]
+        );
+
+        let collapsed = FxHashSet::from_iter([String::from("b")]);
+        assert_eq!(
+            group_rows(results, &collapsed),
+            vec![
+                header("b", 2, true),
+                header("a", 1, false),
+                GroupRow::Result(entry("a/1", Some("a"))),
+                GroupRow::Result(entry("loose", None)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_result_index_skips_headers() {
+        let mut groups = ResultGroups::default();
+        groups.set_start(10);
+        groups.update(vec![
+            entry("b/1", Some("b")),
+            entry("a/1", Some("a")),
+            entry("loose", None),
+            entry("b/2", Some("b")),
+        ]);
+
+        let indices = (0..groups.rows().len())
+            .map(|row| groups.result_index(row))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            indices,
+            [None, Some(10), Some(13), None, Some(11), Some(12)]
+        );
+    }
+
+    #[test]
+    fn test_only_collapsed_headers_are_selectable() {
+        assert!(header("a", 1, true).selectable());
+        assert!(!header("a", 1, false).selectable());
+        assert!(GroupRow::Result(entry("a/1", Some("a"))).selectable());
+    }
+}
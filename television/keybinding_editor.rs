@@ -0,0 +1,200 @@
+use crate::action::Action;
+use crate::config::{Binding, KeyBindings};
+use crate::event::Key;
+
+/// The actions that can be rebound from the keybinding editor, in the order
+/// in which they are listed.
+pub const REBINDABLE_ACTIONS: &[Action] = &[
+    Action::SelectNextEntry,
+    Action::SelectPrevEntry,
+    Action::SelectNextPage,
+    Action::SelectPrevPage,
+    Action::ToggleSelectionDown,
+    Action::ToggleSelectionUp,
+    Action::InvertSelection,
+    Action::ConfirmSelection,
+    Action::SelectAndExit,
+    Action::CopyEntryToClipboard,
+    Action::CopyAllSelected,
+    Action::CopyRelativePath,
+    Action::SetRelativePathBase,
+    Action::RevealInParent,
+    Action::UseSelectionAsQuery,
+    Action::CompleteCommonPrefix,
+    Action::ToggleQuicknav,
+    Action::PushFilter,
+    Action::PopFilter,
+    Action::ToggleSortDirection,
+    Action::ToggleGroup,
+    Action::ResetChannel,
+    Action::ReloadSource,
+    Action::TogglePauseScanning,
+    Action::ToggleMatchTarget,
+    Action::ToggleSplitSearch,
+    Action::SwitchSplitFocus,
+    Action::ExecuteCommand,
+    Action::ScrollPreviewUp,
+    Action::ScrollPreviewDown,
+    Action::ScrollPreviewHalfPageUp,
+    Action::ScrollPreviewHalfPageDown,
+    Action::ScrollPreviewTop,
+    Action::ScrollPreviewBottom,
+    Action::PreviewCursorDown,
+    Action::PreviewCursorUp,
+    Action::LockPreviewScroll,
+    Action::ShowPreviewForCurrent,
+    Action::TogglePreviewSearch,
+    Action::NextPreviewMatch,
+    Action::PrevPreviewMatch,
+    Action::GoToInputStart,
+    Action::GoToInputEnd,
+    Action::ToggleRemoteControl,
+    Action::ToggleSendToChannel,
+    Action::ToggleSendToChannelMode,
+    Action::ToggleHelp,
+    Action::WidenHelpActions,
+    Action::WidenHelpKeys,
+    Action::TogglePreview,
+    Action::ToggleInputWrap,
+    Action::ToggleDebugOverlay,
+    Action::TogglePreviewFullscreen,
+    Action::CyclePreviewer,
+    Action::ToggleKeybindingEditor,
+    Action::Quit,
+];
+
+/// The state of the interactive keybinding editor.
+///
+/// The editor lists every rebindable action along with its current keys.
+/// Confirming an action puts the editor in capture mode where the next key
+/// pressed becomes the action's new binding, unless that key is already
+/// bound to another action.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct KeybindingEditor {
+    /// The index of the currently selected action.
+    pub selected: usize,
+    /// Whether the editor is waiting for a key to bind.
+    pub capturing: bool,
+    /// A status message to display to the user (e.g. a conflict).
+    pub message: Option<String>,
+}
+
+impl KeybindingEditor {
+    pub fn selected_action(&self) -> &'static Action {
+        &REBINDABLE_ACTIONS[self.selected]
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % REBINDABLE_ACTIONS.len();
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = (self.selected + REBINDABLE_ACTIONS.len() - 1)
+            % REBINDABLE_ACTIONS.len();
+    }
+
+    pub fn start_capture(&mut self) {
+        self.capturing = true;
+        self.message = Some(format!(
+            "Press a key to bind to {} (esc to cancel)",
+            self.selected_action()
+        ));
+    }
+
+    /// Bind the captured key to the selected action.
+    ///
+    /// Pressing `Esc` cancels the capture. If the key conflicts with another
+    /// action's binding, the keybindings are left untouched and the conflict
+    /// is reported through the editor's message.
+    ///
+    /// # Returns
+    /// The selected action's new binding if the rebind succeeded.
+    pub fn capture(
+        &mut self,
+        key: Key,
+        keybindings: &mut KeyBindings,
+    ) -> Option<Binding> {
+        self.capturing = false;
+        if key == Key::Esc {
+            self.message = None;
+            return None;
+        }
+        let action = self.selected_action();
+        match keybindings.rebind(action, key) {
+            Ok(()) => {
+                self.message = Some(format!("{action} bound to {key}"));
+                keybindings.get(action).cloned()
+            }
+            Err(e) => {
+                self.message = Some(e.to_string());
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selection_wraps_around() {
+        let mut editor = KeybindingEditor::default();
+        editor.select_prev();
+        assert_eq!(
+            editor.selected_action(),
+            REBINDABLE_ACTIONS.last().unwrap()
+        );
+        editor.select_next();
+        assert_eq!(editor.selected_action(), &REBINDABLE_ACTIONS[0]);
+    }
+
+    #[test]
+    fn test_capture_with_conflict() {
+        let mut keybindings = KeyBindings::from(vec![
+            (Action::SelectNextEntry, Binding::SingleKey(Key::Down)),
+            (Action::SelectPrevEntry, Binding::SingleKey(Key::Up)),
+        ]);
+        let mut editor = KeybindingEditor::default();
+        assert_eq!(editor.selected_action(), &Action::SelectNextEntry);
+
+        editor.start_capture();
+        assert!(editor.capturing);
+        assert_eq!(editor.capture(Key::Up, &mut keybindings), None);
+        assert!(!editor.capturing);
+        assert_eq!(
+            editor.message.as_deref(),
+            Some("Up is already bound to select_prev_entry")
+        );
+        assert_eq!(
+            keybindings.get(&Action::SelectNextEntry),
+            Some(&Binding::SingleKey(Key::Down))
+        );
+
+        editor.start_capture();
+        assert_eq!(
+            editor.capture(Key::Ctrl('n'), &mut keybindings),
+            Some(Binding::SingleKey(Key::Ctrl('n')))
+        );
+        assert_eq!(
+            keybindings.get(&Action::SelectNextEntry),
+            Some(&Binding::SingleKey(Key::Ctrl('n')))
+        );
+    }
+
+    #[test]
+    fn test_capture_cancelled_with_esc() {
+        let mut keybindings = KeyBindings::from(vec![(
+            Action::SelectNextEntry,
+            Binding::SingleKey(Key::Down),
+        )]);
+        let mut editor = KeybindingEditor::default();
+        editor.start_capture();
+        assert_eq!(editor.capture(Key::Esc, &mut keybindings), None);
+        assert_eq!(editor.message, None);
+        assert_eq!(
+            keybindings.get(&Action::SelectNextEntry),
+            Some(&Binding::SingleKey(Key::Down))
+        );
+    }
+}
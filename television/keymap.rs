@@ -1,8 +1,9 @@
 use rustc_hash::FxHashMap;
 use std::ops::Deref;
+use std::time::{Duration, Instant};
 
 use crate::action::Action;
-use crate::config::{Binding, KeyBindings};
+use crate::config::{Binding, Chords, KeyBindings};
 use crate::event::Key;
 
 #[derive(Default, Debug)]
@@ -48,3 +49,143 @@ impl From<&KeyBindings> for Keymap {
         Self(keymap)
     }
 }
+
+/// The outcome of feeding a key to a `ChordBuffer`.
+#[derive(Debug, PartialEq)]
+pub enum Chorded {
+    /// A key to handle on its own, as if no chord were configured.
+    Key(Key),
+    /// The action of a completed chord.
+    Action(Action),
+}
+
+/// Recognizes the two-key sequences of `Chords` in the stream of key
+/// events.
+///
+/// A key starting a chord is held back until either the key completing it
+/// arrives or the timeout elapses, in which case it is handled alone. The
+/// current time is always passed in by the caller, which keeps the buffer
+/// independent from the system clock.
+#[derive(Debug)]
+pub struct ChordBuffer {
+    chords: FxHashMap<(Key, Key), Action>,
+    timeout: Duration,
+    pending: Option<(Key, Instant)>,
+}
+
+impl ChordBuffer {
+    pub fn new(chords: &Chords, timeout: Duration) -> Self {
+        Self {
+            chords: chords
+                .iter()
+                .map(|(action, keys)| (*keys, action.clone()))
+                .collect(),
+            timeout,
+            pending: None,
+        }
+    }
+
+    /// Feed a key pressed at `now`, returning what should be handled in
+    /// the order it should be handled in.
+    pub fn feed(&mut self, key: Key, now: Instant) -> Vec<Chorded> {
+        let mut resolved = Vec::new();
+        if let Some(first) = self.expire(now) {
+            resolved.push(Chorded::Key(first));
+        }
+        if let Some((first, _)) = self.pending.take() {
+            if let Some(action) = self.chords.get(&(first, key)) {
+                resolved.push(Chorded::Action(action.clone()));
+                return resolved;
+            }
+            resolved.push(Chorded::Key(first));
+        }
+        if self.chords.keys().any(|(first, _)| *first == key) {
+            self.pending = Some((key, now));
+        } else {
+            resolved.push(Chorded::Key(key));
+        }
+        resolved
+    }
+
+    /// The key held back, if the second key of its chord didn't arrive in
+    /// time.
+    pub fn expire(&mut self, now: Instant) -> Option<Key> {
+        match self.pending {
+            Some((key, pressed))
+                if now.saturating_duration_since(pressed) >= self.timeout =>
+            {
+                self.pending = None;
+                Some(key)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_millis(500);
+
+    fn buffer() -> ChordBuffer {
+        ChordBuffer::new(
+            &Chords::from(vec![(
+                Action::ScrollPreviewTop,
+                (Key::Char('g'), Key::Char('g')),
+            )]),
+            TIMEOUT,
+        )
+    }
+
+    #[test]
+    fn test_chord_is_recognized() {
+        let mut buffer = buffer();
+        let start = Instant::now();
+
+        assert!(buffer.feed(Key::Char('g'), start).is_empty());
+        assert_eq!(
+            buffer.feed(Key::Char('g'), start + Duration::from_millis(100)),
+            vec![Chorded::Action(Action::ScrollPreviewTop)]
+        );
+        // other keys go straight through
+        assert_eq!(
+            buffer.feed(Key::Char('x'), start + Duration::from_millis(200)),
+            vec![Chorded::Key(Key::Char('x'))]
+        );
+    }
+
+    #[test]
+    fn test_other_second_key_releases_both_keys() {
+        let mut buffer = buffer();
+        let start = Instant::now();
+
+        assert!(buffer.feed(Key::Char('g'), start).is_empty());
+        assert_eq!(
+            buffer.feed(Key::Char('o'), start),
+            vec![Chorded::Key(Key::Char('g')), Chorded::Key(Key::Char('o'))]
+        );
+    }
+
+    #[test]
+    fn test_first_key_is_handled_alone_after_timeout() {
+        let mut buffer = buffer();
+        let start = Instant::now();
+
+        assert!(buffer.feed(Key::Char('g'), start).is_empty());
+        assert_eq!(buffer.expire(start + Duration::from_millis(499)), None);
+        assert_eq!(buffer.expire(start + TIMEOUT), Some(Key::Char('g')));
+        assert_eq!(buffer.expire(start + TIMEOUT), None);
+
+        // a second key arriving too late starts over
+        assert!(buffer.feed(Key::Char('g'), start).is_empty());
+        assert_eq!(
+            buffer.feed(Key::Char('g'), start + TIMEOUT),
+            vec![Chorded::Key(Key::Char('g'))]
+        );
+        assert_eq!(
+            buffer.feed(Key::Char('g'), start + TIMEOUT),
+            vec![Chorded::Action(Action::ScrollPreviewTop)]
+        );
+    }
+}
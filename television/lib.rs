@@ -4,17 +4,23 @@ pub mod cable;
 pub mod channels;
 pub mod cli;
 pub mod config;
+pub mod confirmation;
+pub mod debug_log;
 pub mod draw;
 pub mod errors;
 pub mod event;
+pub mod groups;
 pub mod input;
+pub mod keybinding_editor;
 pub mod keymap;
 pub mod logging;
 pub mod matcher;
 pub mod picker;
 pub mod preview;
+pub mod quicknav;
 pub mod render;
 pub mod screen;
+pub mod split_search;
 pub mod television;
 pub mod tui;
 pub mod utils;
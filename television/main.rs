@@ -1,18 +1,26 @@
 use std::env;
 use std::io::{stdout, BufWriter, IsTerminal, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::exit;
 
 use anyhow::Result;
 use clap::Parser;
+use rustc_hash::FxHashSet;
 use television::channels::cable::PreviewKind;
 use television::cli::parse_channel;
 use television::utils::clipboard::CLIPBOARD;
-use tracing::{debug, error, info};
+use television::utils::last_channel::load_last_channel;
+use tracing::{debug, error, info, warn};
 
-use television::app::App;
+use television::app::{App, AppOutput};
 use television::channels::{
-    entry::PreviewType, stdin::Channel as StdinChannel, TelevisionChannel,
+    dirs::Channel as DirsChannel,
+    entry::{Entry, OutputField, PreviewType},
+    files::Channel as FilesChannel,
+    pending::PendingChannel,
+    stdin::Channel as StdinChannel,
+    text::Channel as TextChannel,
+    ChannelConfig, CliTvChannel, TelevisionChannel,
 };
 use television::cli::{
     args::{Cli, Command},
@@ -23,6 +31,7 @@ use television::cli::{
 use television::config::{merge_keybindings, Config, ConfigEnv};
 use television::utils::shell::render_autocomplete_script_template;
 use television::utils::{
+    json::JsonFieldPath,
     shell::{completion_script, Shell},
     stdin::is_readable_stdin,
 };
@@ -37,20 +46,26 @@ async fn main() -> Result<()> {
     // process the CLI arguments
     let cli = Cli::parse();
     debug!("CLI: {:?}", cli);
-    let args: PostProcessedCli = cli.into();
+    let mut args: PostProcessedCli = cli.into();
     debug!("PostProcessedCli: {:?}", args);
 
     // load the configuration file
     debug!("Loading configuration...");
     let mut config = Config::new(&ConfigEnv::init()?)?;
 
+    // optionally open the channel set in the environment or the last one used
+    resolve_default_channel(&mut args, &config);
+
     // optionally handle subcommands
     debug!("Handling subcommands...");
     args.command
         .as_ref()
         .map(|x| handle_subcommands(x, &config));
 
-    // optionally change the working directory
+    // channels crawling the file system search the given directory, all
+    // channels being started in it so that the output paths are relative
+    // to it
+    let search_root = search_root(&args);
     args.working_directory.as_ref().map(set_current_dir);
 
     // optionally override configuration values with CLI arguments
@@ -59,29 +74,142 @@ async fn main() -> Result<()> {
 
     // determine the channel to use based on the CLI arguments and configuration
     debug!("Determining channel...");
-    let channel =
-        determine_channel(args.clone(), &config, is_readable_stdin())?;
+    let readable_stdin = is_readable_stdin();
+    let pending_channel =
+        if config.application.async_channel_init && !readable_stdin {
+            let channel = determine_cli_channel(args.clone(), &config)?;
+            let channel_config = config.channel_config();
+            let search_root = search_root.clone();
+            Some(PendingChannel::spawn(channel.name(), move || {
+                cli_channel(channel, search_root, &channel_config)
+            }))
+        } else {
+            None
+        };
+    let channel = if pending_channel.is_some() {
+        // an empty channel standing in until the actual one is ready
+        TelevisionChannel::Text(TextChannel::from_file_paths(
+            Vec::new(),
+            &config.channel_config(),
+        ))
+    } else {
+        determine_channel(args.clone(), &config, readable_stdin, search_root)?
+    };
 
     CLIPBOARD.with(<_>::default);
 
+    let query_separator = config.application.query_separator.clone();
+    let output_field = config.application.output_field.clone();
+
     debug!("Creating application...");
     let mut app =
         App::new(channel, config, &args.passthrough_keybindings, args.input);
+    if let Some(pending) = pending_channel {
+        app.load_channel(pending);
+    }
+    if args.send_to_channel {
+        app.start_in_send_to_channel();
+    }
+    if args.debug {
+        app.show_debug_overlay();
+    }
+    if let Some(path) = &args.query_fifo {
+        app.read_queries_from(PathBuf::from(path));
+    }
+    if let Some(line) = args.preview_line {
+        app.open_preview_at_line(line);
+    }
+    let output_options = OutputOptions {
+        json_field: args.json_field.is_some(),
+        output_field,
+        print_index: args.print_index,
+        print_query: args.print_query,
+        query_separator,
+    };
+    if args.loop_mode {
+        let output_options = output_options.clone();
+        app.loop_on_accept(Box::new(move |output| {
+            let mut stdout = stdout().lock();
+            write_output(&mut stdout, &output, &output_options)?;
+            // an empty line separates the successive selections
+            writeln!(stdout)?;
+            stdout.flush()?;
+            Ok(())
+        }));
+    }
     stdout().flush()?;
     let output = app.run(stdout().is_terminal(), false).await?;
     info!("{:?}", output);
+    for warning in &output.warnings {
+        eprintln!("warning: {warning}");
+    }
     let stdout_handle = stdout().lock();
     let mut bufwriter = BufWriter::new(stdout_handle);
-    if let Some(passthrough) = output.passthrough {
-        writeln!(bufwriter, "{passthrough}")?;
+    write_output(&mut bufwriter, &output, &output_options)?;
+    bufwriter.flush()?;
+    exit(0);
+}
+
+/// What gets printed on top of the selected entries.
+#[derive(Clone, Debug, Default)]
+struct OutputOptions {
+    json_field: bool,
+    /// Which part of the selected entries is written.
+    output_field: OutputField,
+    print_index: bool,
+    print_query: bool,
+    /// The line separating the query from the selection.
+    query_separator: String,
+}
+
+/// Write the output of the application: the passthrough key if any, then the
+/// query followed by the separator line if asked for, and finally the rank
+/// of the accepted entry if asked for and the selected entries.
+fn write_output(
+    writer: &mut impl Write,
+    output: &AppOutput,
+    options: &OutputOptions,
+) -> Result<()> {
+    if let Some(passthrough) = &output.passthrough {
+        writeln!(writer, "{passthrough}")?;
+    }
+    if let Some(query) = output.query.as_ref().filter(|_| options.print_query)
+    {
+        writeln!(writer, "{query}")?;
+        writeln!(writer, "{}", options.query_separator)?;
     }
-    if let Some(entries) = output.selected_entries {
-        for entry in &entries {
-            writeln!(bufwriter, "{}", entry.stdout_repr())?;
+    if let Some(index) = output.selected_index.filter(|_| options.print_index)
+    {
+        writeln!(writer, "{index}")?;
+    }
+    if let Some(entries) = &output.selected_entries {
+        write_entries(
+            writer,
+            entries,
+            options.json_field,
+            &options.output_field,
+        )?;
+    }
+    Ok(())
+}
+
+/// Write the given field of the selected entries, one per line.
+///
+/// When a JSON field was selected, the full JSON objects are written.
+fn write_entries(
+    writer: &mut impl Write,
+    entries: &FxHashSet<Entry>,
+    json_field: bool,
+    output_field: &OutputField,
+) -> Result<()> {
+    for entry in entries {
+        match &entry.value {
+            // output the full JSON object rather than the selected field
+            Some(value) if json_field => writeln!(writer, "{value}")?,
+            _ => writeln!(writer, "{}", entry.output(output_field))?,
         }
     }
-    bufwriter.flush()?;
-    exit(0);
+    Ok(())
 }
 
 /// Apply overrides from the CLI arguments to the configuration.
@@ -94,12 +222,62 @@ fn apply_cli_overrides(args: &PostProcessedCli, config: &mut Config) {
     if args.no_preview {
         config.ui.show_preview_panel = false;
     }
+    if let Some(layout) = args.layout {
+        config.ui.input_bar_position = layout;
+    }
+    if let Some(execute) = &args.execute {
+        config.application.execute_command = Some(execute.clone());
+    }
     if let Some(keybindings) = &args.keybindings {
         config.keybindings =
             merge_keybindings(config.keybindings.clone(), keybindings);
     }
 }
 
+/// The environment variable naming the channel opened when none is
+/// specified on the command line.
+const DEFAULT_CHANNEL_ENV_VAR: &str = "TV_DEFAULT_CHANNEL";
+
+/// Override the compiled default channel when no channel was specified on
+/// the command line (see `default_channel_override`).
+fn resolve_default_channel(args: &mut PostProcessedCli, config: &Config) {
+    if !args.default_channel {
+        return;
+    }
+    let env_channel = env::var(DEFAULT_CHANNEL_ENV_VAR).ok();
+    let last_channel = config
+        .application
+        .remember_last_channel
+        .then(|| load_last_channel(&config.application.data_dir))
+        .flatten();
+    if let Some(channel) = default_channel_override(env_channel, last_channel)
+    {
+        debug!("Defaulting to channel {:?}", channel);
+        args.channel = channel;
+    }
+}
+
+/// The channel opened when none was specified on the command line, by order
+/// of precedence the one named by `TV_DEFAULT_CHANNEL` and the last one used
+/// (if remembered), the compiled default being kept if neither is set.
+///
+/// Unknown channel names are skipped.
+fn default_channel_override(
+    env_channel: Option<String>,
+    last_channel: Option<String>,
+) -> Option<ParsedCliChannel> {
+    env_channel
+        .into_iter()
+        .chain(last_channel)
+        .find_map(|name| {
+            parse_channel(&name)
+                .inspect_err(|_| {
+                    warn!("Ignoring unknown default channel {name}");
+                })
+                .ok()
+        })
+}
+
 pub fn set_current_dir(path: &String) -> Result<()> {
     let path = Path::new(path);
     if !path.exists() {
@@ -114,12 +292,48 @@ pub fn set_current_dir(path: &String) -> Result<()> {
     Ok(())
 }
 
+/// The directory given as `PATH` argument to a channel crawling the file
+/// system, which is walked instead of the working directory.
+///
+/// The directory is made absolute so that it can still be walked once the
+/// working directory is changed to it.
+fn search_root(args: &PostProcessedCli) -> Option<PathBuf> {
+    let crawls = matches!(
+        args.channel,
+        ParsedCliChannel::Builtin(
+            CliTvChannel::Files | CliTvChannel::Dirs | CliTvChannel::Text
+        )
+    );
+    let root = args
+        .working_directory
+        .as_ref()
+        .filter(|_| crawls && args.autocomplete_prompt.is_none())
+        .map(PathBuf::from)?;
+    check_search_root(&root);
+    Some(std::path::absolute(&root).unwrap_or(root))
+}
+
+fn check_search_root(path: &Path) {
+    if !path.is_dir() {
+        error!("Search root \"{}\" is not a directory", path.display());
+        println!(
+            "Error: Search root \"{}\" is not a directory",
+            path.display()
+        );
+        exit(1);
+    }
+}
+
 pub fn handle_subcommands(command: &Command, config: &Config) -> Result<()> {
     match command {
         Command::ListChannels => {
             list_channels();
             exit(0);
         }
+        Command::PrintConfig => {
+            print!("{}", config.to_toml()?);
+            exit(0);
+        }
         Command::InitShell { shell } => {
             let target_shell = Shell::from(shell);
             // the completion scripts for the various shells are templated
@@ -140,19 +354,73 @@ pub fn determine_channel(
     args: PostProcessedCli,
     config: &Config,
     readable_stdin: bool,
+    search_root: Option<PathBuf>,
 ) -> Result<TelevisionChannel> {
+    let channel_config = config.channel_config();
     if readable_stdin {
         debug!("Using stdin channel");
-        Ok(TelevisionChannel::Stdin(StdinChannel::new(
+        let json_field = args.json_field.as_deref().map(JsonFieldPath::parse);
+        Ok(TelevisionChannel::Stdin(StdinChannel::with_json_field(
             match &args.preview_kind {
                 PreviewKind::Command(ref preview_command) => {
                     PreviewType::Command(preview_command.clone())
                 }
                 PreviewKind::Builtin(preview_type) => preview_type.clone(),
+                // preview the full JSON object by default
+                PreviewKind::None if json_field.is_some() => {
+                    PreviewType::Basic
+                }
                 PreviewKind::None => PreviewType::None,
             },
+            json_field,
+            &channel_config,
         )))
-    } else if let Some(prompt) = args.autocomplete_prompt {
+    } else {
+        Ok(cli_channel(
+            determine_cli_channel(args, config)?,
+            search_root,
+            &channel_config,
+        ))
+    }
+}
+
+/// Create the given channel, walking the search root instead of the working
+/// directory if it crawls the file system.
+fn cli_channel(
+    channel: ParsedCliChannel,
+    search_root: Option<PathBuf>,
+    channel_config: &ChannelConfig,
+) -> TelevisionChannel {
+    match (channel, search_root) {
+        (ParsedCliChannel::Builtin(CliTvChannel::Files), Some(root)) => {
+            TelevisionChannel::Files(FilesChannel::new(
+                vec![root],
+                channel_config,
+            ))
+        }
+        (ParsedCliChannel::Builtin(CliTvChannel::Dirs), Some(root)) => {
+            TelevisionChannel::Dirs(DirsChannel::new(
+                vec![root],
+                channel_config,
+            ))
+        }
+        (ParsedCliChannel::Builtin(CliTvChannel::Text), Some(root)) => {
+            TelevisionChannel::Text(TextChannel::new(
+                vec![root],
+                channel_config,
+            ))
+        }
+        (channel, _) => channel.to_channel(channel_config),
+    }
+}
+
+/// Determine the channel to use when not reading from stdin, either from
+/// the autocomplete prompt or from the CLI arguments.
+pub fn determine_cli_channel(
+    args: PostProcessedCli,
+    config: &Config,
+) -> Result<ParsedCliChannel> {
+    if let Some(prompt) = args.autocomplete_prompt {
         debug!("Using autocomplete prompt: {:?}", prompt);
         let channel = guess_channel_from_prompt(
             &prompt,
@@ -160,26 +428,18 @@ pub fn determine_channel(
             parse_channel(&config.shell_integration.fallback_channel)?,
         )?;
         debug!("Using guessed channel: {:?}", channel);
-        match channel {
-            ParsedCliChannel::Builtin(c) => Ok(c.to_channel()),
-            ParsedCliChannel::Cable(c) => {
-                Ok(TelevisionChannel::Cable(c.into()))
-            }
-        }
+        Ok(channel)
     } else {
         debug!("Using {:?} channel", args.channel);
-        match args.channel {
-            ParsedCliChannel::Builtin(c) => Ok(c.to_channel()),
-            ParsedCliChannel::Cable(c) => {
-                Ok(TelevisionChannel::Cable(c.into()))
-            }
-        }
+        Ok(args.channel)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use rustc_hash::FxHashMap;
+    use television::channels::{ChannelConfig, OnAir};
+    use television::screen::layout::InputPosition;
 
     use super::*;
 
@@ -190,7 +450,8 @@ mod tests {
         expected_channel: &TelevisionChannel,
     ) {
         let channel =
-            determine_channel(args.clone(), config, readable_stdin).unwrap();
+            determine_channel(args.clone(), config, readable_stdin, None)
+                .unwrap();
 
         assert!(
             channel.name() == expected_channel.name(),
@@ -214,15 +475,62 @@ mod tests {
             &args,
             &config,
             true,
-            &TelevisionChannel::Stdin(StdinChannel::new(PreviewType::None)),
+            &TelevisionChannel::Stdin(StdinChannel::new(
+                PreviewType::None,
+                &ChannelConfig::default(),
+            )),
         );
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_channel_walks_search_root() {
+        let absolute = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("target_dir");
+        for root in [
+            absolute.to_string_lossy().to_string(),
+            String::from("tests/target_dir"),
+        ] {
+            let args = PostProcessedCli {
+                channel: ParsedCliChannel::Builtin(CliTvChannel::Files),
+                working_directory: Some(root),
+                ..Default::default()
+            };
+            let search_root = search_root(&args);
+            let mut channel = determine_channel(
+                args,
+                &Config::default(),
+                false,
+                search_root,
+            )
+            .unwrap();
+            for _ in 0..100 {
+                channel.results(10, 0);
+                if !channel.running() && channel.result_count() == 2 {
+                    break;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+            let mut names = channel
+                .results(10, 0)
+                .into_iter()
+                .map(|entry| entry.name)
+                .collect::<Vec<_>>();
+            names.sort();
+            assert_eq!(
+                names,
+                ["tests/target_dir/file1.txt", "tests/target_dir/file2.txt"]
+            );
+        }
+    }
+
     #[tokio::test]
     async fn test_determine_channel_autocomplete_prompt() {
         let autocomplete_prompt = Some("cd".to_string());
         let expected_channel = television::channels::TelevisionChannel::Dirs(
-            television::channels::dirs::Channel::default(),
+            television::channels::dirs::Channel::from(
+                &ChannelConfig::default(),
+            ),
         );
         let args = PostProcessedCli {
             autocomplete_prompt,
@@ -262,22 +570,97 @@ mod tests {
             &config,
             false,
             &TelevisionChannel::Dirs(
-                television::channels::dirs::Channel::default(),
+                television::channels::dirs::Channel::from(
+                    &ChannelConfig::default(),
+                ),
             ),
         );
     }
 
+    #[test]
+    fn test_write_output_separates_query_from_selection() {
+        let output = AppOutput {
+            selected_entries: Some(FxHashSet::from_iter([Entry::new(
+                String::from("src/main.rs"),
+                PreviewType::Files,
+            )])),
+            passthrough: Some(String::from("ctrl-t")),
+            selected_index: Some(3),
+            query: Some(String::from("main")),
+            warnings: Vec::new(),
+        };
+        let mut options = OutputOptions {
+            print_index: true,
+            print_query: true,
+            query_separator: String::from("---selection---"),
+            ..Default::default()
+        };
+
+        let mut written = Vec::new();
+        write_output(&mut written, &output, &options).unwrap();
+        assert_eq!(
+            String::from_utf8(written).unwrap(),
+            "ctrl-t\nmain\n---selection---\n3\nsrc/main.rs\n"
+        );
+
+        options.print_query = false;
+        let mut written = Vec::new();
+        write_output(&mut written, &output, &options).unwrap();
+        assert_eq!(
+            String::from_utf8(written).unwrap(),
+            "ctrl-t\n3\nsrc/main.rs\n"
+        );
+    }
+
     #[test]
     fn test_apply_cli_overrides() {
         let mut config = Config::default();
         let args = PostProcessedCli {
             tick_rate: Some(100_f64),
             no_preview: true,
+            layout: Some(InputPosition::Bottom),
             ..Default::default()
         };
         apply_cli_overrides(&args, &mut config);
 
         assert_eq!(config.application.tick_rate, 100_f64);
         assert!(!config.ui.show_preview_panel);
+        assert_eq!(config.ui.input_bar_position, InputPosition::Bottom);
+    }
+
+    #[test]
+    fn test_default_channel_precedence() {
+        let builtin = |channel| Some(ParsedCliChannel::Builtin(channel));
+        let name = |name: &str| Some(name.to_string());
+
+        // the environment variable wins over the last channel used
+        assert_eq!(
+            default_channel_override(name("env"), name("git-repos")),
+            builtin(CliTvChannel::Env)
+        );
+        assert_eq!(
+            default_channel_override(None, name("git-repos")),
+            builtin(CliTvChannel::GitRepos)
+        );
+        // unknown channels are skipped
+        assert_eq!(
+            default_channel_override(name("not-a-channel"), name("env")),
+            builtin(CliTvChannel::Env)
+        );
+        // the compiled default is kept
+        assert_eq!(default_channel_override(None, None), None);
+
+        // a channel given on the command line is left untouched
+        let mut args = PostProcessedCli {
+            channel: ParsedCliChannel::Builtin(CliTvChannel::Text),
+            default_channel: false,
+            ..Default::default()
+        };
+        let config = Config::default();
+        resolve_default_channel(&mut args, &config);
+        assert_eq!(
+            args.channel,
+            ParsedCliChannel::Builtin(CliTvChannel::Text)
+        );
     }
 }
@@ -1,14 +1,135 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+/// Which items are dropped as duplicates of an item pushed before them.
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Dedup {
+    /// Keep every item.
+    #[default]
+    None,
+    /// Drop items identical to a previous one.
+    Exact,
+    /// Drop items identical to a previous one once leading and trailing
+    /// whitespace is trimmed, inner whitespace is collapsed and case is
+    /// ignored.
+    Normalized,
+}
+
+impl Dedup {
+    /// The key under which items are considered duplicates of each other,
+    /// if items are deduplicated.
+    ///
+    /// # Example
+    /// ```
+    /// use television::matcher::config::Dedup;
+    ///
+    /// assert_eq!(Dedup::None.key(" Foo  bar"), None);
+    /// assert_eq!(Dedup::Exact.key(" Foo  bar").as_deref(), Some(" Foo  bar"));
+    /// assert_eq!(
+    ///     Dedup::Normalized.key(" Foo  bar").as_deref(),
+    ///     Some("foo bar")
+    /// );
+    /// ```
+    pub fn key(self, item: &str) -> Option<String> {
+        match self {
+            Dedup::None => None,
+            Dedup::Exact => Some(item.to_string()),
+            Dedup::Normalized => Some(
+                item.split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" ")
+                    .to_lowercase(),
+            ),
+        }
+    }
+}
+
+/// What the items are matched against.
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchTarget {
+    /// Their full raw text, including ANSI escape sequences and the hidden
+    /// search text of entries.
+    #[default]
+    Full,
+    /// Only their text as displayed, without ANSI escape sequences.
+    Visible,
+}
+
+impl MatchTarget {
+    #[must_use]
+    pub fn toggled(self) -> Self {
+        match self {
+            MatchTarget::Full => MatchTarget::Visible,
+            MatchTarget::Visible => MatchTarget::Full,
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            MatchTarget::Full => "full text",
+            MatchTarget::Visible => "visible text",
+        }
+    }
+}
+
+/// How match scores are adjusted before ranking results.
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoreNormalization {
+    /// Rank results by their raw score.
+    ///
+    /// Longer candidates tend to accumulate more bonuses and may outrank
+    /// tighter matches in shorter ones.
+    #[default]
+    None,
+    /// Divide scores by the length of the candidate so that a tight match in
+    /// a short candidate can outrank a loose match in a long one.
+    Length,
+}
+
+/// How items are ordered while the pattern is empty, before any score is
+/// available to rank them.
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum InitialSort {
+    /// Keep the order in which the items were loaded, which may vary from
+    /// one run to the other (e.g. when crawling the file system).
+    #[default]
+    None,
+    /// Sort the items alphabetically, ignoring case.
+    Alphabetical,
+    /// Sort the items, taken as paths, from the most recently modified one
+    /// to the least recently modified one.
+    Mtime,
+    /// Sort the items, taken as paths, from the largest file to the
+    /// smallest one.
+    Size,
+}
+
 /// The configuration of the fuzzy matcher.
 ///
 /// This contains the number of threads to use, whether to ignore case, whether
-/// to prefer prefix matches, and whether to optimize for matching paths.
+/// to prefer prefix matches, whether to optimize for matching paths and
+/// whether to fold diacritics.
 ///
 /// The default configuration uses the default configuration of the `Nucleo`
 /// fuzzy matcher, e.g. case-insensitive matching, no preference for prefix
 /// matches, and no optimization for matching paths as well as using the
 /// default number of threads (which corresponds to the number of available logical
 /// cores on the current machine).
-#[derive(Copy, Clone, Debug)]
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone, Debug)]
 pub struct Config {
     /// The number of threads to use for the fuzzy matcher.
     pub n_threads: Option<usize>,
@@ -18,6 +139,34 @@ pub struct Config {
     pub prefer_prefix: bool,
     /// Whether to optimize for matching paths.
     pub match_paths: bool,
+    /// Whether to fold diacritics when matching (e.g. `cafe` matches `café`).
+    ///
+    /// This only affects matching, items are still displayed as is.
+    pub normalize: bool,
+    /// How scores are adjusted before ranking results.
+    pub score_normalization: ScoreNormalization,
+    /// Whether to match items and patterns written in non-Latin scripts by
+    /// their romanized form (e.g. `moskva` matches `Москва`).
+    ///
+    /// Like `normalize`, this only affects matching and requires the
+    /// `transliteration` feature.
+    pub transliterate: bool,
+    /// How items are ordered while the pattern is empty.
+    ///
+    /// Once there is a pattern, items are ranked by score regardless.
+    pub initial_sort: InitialSort,
+    /// The maximum number of characters between two consecutive matched
+    /// characters of a term, items whose matches are more spread out being
+    /// rejected.
+    ///
+    /// This keeps fuzzy matches from picking characters all over long
+    /// items, which mostly adds noise to large sets of items.
+    pub max_gap: Option<u32>,
+    /// Which items are dropped as duplicates of an item pushed before them.
+    pub dedup: Dedup,
+    /// The command ranking the items instead of the built-in matcher, if
+    /// any (see `scorer`).
+    pub scorer_command: Option<Arc<str>>,
 }
 
 impl Default for Config {
@@ -27,6 +176,13 @@ impl Default for Config {
             ignore_case: true,
             prefer_prefix: false,
             match_paths: false,
+            normalize: true,
+            score_normalization: ScoreNormalization::None,
+            transliterate: false,
+            initial_sort: InitialSort::None,
+            max_gap: None,
+            dedup: Dedup::None,
+            scorer_command: None,
         }
     }
 }
@@ -55,6 +211,51 @@ impl Config {
         self.match_paths = match_paths;
         self
     }
+
+    /// Set whether to fold diacritics.
+    pub fn normalize(mut self, normalize: bool) -> Self {
+        self.normalize = normalize;
+        self
+    }
+
+    /// Set how scores are adjusted before ranking results.
+    pub fn score_normalization(
+        mut self,
+        score_normalization: ScoreNormalization,
+    ) -> Self {
+        self.score_normalization = score_normalization;
+        self
+    }
+
+    /// Set whether to match romanized forms.
+    pub fn transliterate(mut self, transliterate: bool) -> Self {
+        self.transliterate = transliterate;
+        self
+    }
+
+    /// Set how items are ordered while the pattern is empty.
+    pub fn initial_sort(mut self, initial_sort: InitialSort) -> Self {
+        self.initial_sort = initial_sort;
+        self
+    }
+
+    /// Set the maximum gap between consecutive matched characters.
+    pub fn max_gap(mut self, max_gap: Option<u32>) -> Self {
+        self.max_gap = max_gap;
+        self
+    }
+
+    /// Set which items are dropped as duplicates.
+    pub fn dedup(mut self, dedup: Dedup) -> Self {
+        self.dedup = dedup;
+        self
+    }
+
+    /// Set the command ranking the items instead of the built-in matcher.
+    pub fn scorer_command(mut self, command: Option<&str>) -> Self {
+        self.scorer_command = command.map(Arc::from);
+        self
+    }
 }
 
 impl From<&Config> for nucleo::Config {
@@ -62,6 +263,7 @@ impl From<&Config> for nucleo::Config {
         let mut matcher_config = nucleo::Config::DEFAULT;
         matcher_config.ignore_case = config.ignore_case;
         matcher_config.prefer_prefix = config.prefer_prefix;
+        matcher_config.normalize = config.normalize;
         if config.match_paths {
             matcher_config = matcher_config.match_paths();
         }
@@ -1,3 +1,74 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+
+use parking_lot::RwLock;
+use rustc_hash::FxHashSet;
+
+use super::config::{Dedup, MatchTarget};
+use crate::channels::entry::Entry;
+use crate::utils::strings::strip_ansi_codes;
+
+/// The keys of the items pushed so far, shared by the injectors of a
+/// matcher to drop duplicates.
+pub type DedupKeys = Arc<Mutex<FxHashSet<String>>>;
+
+/// The `Nucleo` injector of a matcher, shared by its injectors so that they
+/// keep pushing items into it once it is restarted (see
+/// `Matcher::set_target`).
+pub type SharedInjector<I> = Arc<RwLock<nucleo::Injector<I>>>;
+
+/// What the items of a matcher are matched against, shared by its
+/// injectors.
+#[derive(Clone, Default)]
+pub struct SharedTarget(Arc<AtomicBool>);
+
+impl SharedTarget {
+    pub fn get(&self) -> MatchTarget {
+        if self.0.load(Ordering::Relaxed) {
+            MatchTarget::Visible
+        } else {
+            MatchTarget::Full
+        }
+    }
+
+    pub fn set(&self, target: MatchTarget) {
+        self.0
+            .store(target == MatchTarget::Visible, Ordering::Relaxed);
+    }
+}
+
+/// Whether the injectors of a matcher are paused, pushing an item blocking
+/// until they are resumed.
+///
+/// Items that may be paused are thus pushed from blocking threads (e.g.
+/// with `tokio::task::spawn_blocking`) rather than from async tasks.
+#[derive(Clone, Default)]
+pub struct Pause(Arc<(Mutex<bool>, Condvar)>);
+
+impl Pause {
+    pub fn set(&self, paused: bool) {
+        let (lock, resumed) = &*self.0;
+        if let Ok(mut guard) = lock.lock() {
+            *guard = paused;
+        }
+        if !paused {
+            resumed.notify_all();
+        }
+    }
+
+    pub fn paused(&self) -> bool {
+        self.0 .0.lock().is_ok_and(|paused| *paused)
+    }
+
+    /// Block until resumed if paused.
+    fn wait(&self) {
+        let (lock, resumed) = &*self.0;
+        if let Ok(guard) = lock.lock() {
+            drop(resumed.wait_while(guard, |paused| *paused));
+        }
+    }
+}
+
 /// An injector that can be used to push items of type `I` into the fuzzy matcher.
 ///
 /// This is a wrapper around the `Injector` type from the `Nucleo` fuzzy matcher.
@@ -10,15 +81,70 @@ where
     I: Sync + Send + Clone + 'static,
 {
     /// The inner `Injector` from the `Nucleo` fuzzy matcher.
-    inner: nucleo::Injector<I>,
+    inner: SharedInjector<I>,
+    /// Whether the strings to match against are romanized, in which case
+    /// the original strings are kept in a second column.
+    transliterate: bool,
+    /// Which items are dropped as duplicates of an item pushed before them.
+    dedup: Dedup,
+    dedup_keys: DedupKeys,
+    pause: Pause,
+    target: SharedTarget,
 }
 
 impl<I> Injector<I>
 where
     I: Sync + Send + Clone + 'static,
 {
-    pub fn new(inner: nucleo::Injector<I>) -> Self {
-        Self { inner }
+    pub fn new(inner: SharedInjector<I>, transliterate: bool) -> Self {
+        Self {
+            inner,
+            transliterate,
+            dedup: Dedup::None,
+            dedup_keys: DedupKeys::default(),
+            pause: Pause::default(),
+            target: SharedTarget::default(),
+        }
+    }
+
+    /// Match the items against what the given target designates.
+    pub fn with_target(mut self, target: SharedTarget) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// What the items are matched against.
+    pub fn target(&self) -> MatchTarget {
+        self.target.get()
+    }
+
+    /// Block pushing items while the given pause is on.
+    pub fn with_pause(mut self, pause: Pause) -> Self {
+        self.pause = pause;
+        self
+    }
+
+    /// Drop the items that are duplicates of an item pushed before them
+    /// through any injector sharing the same keys.
+    pub fn with_dedup(mut self, dedup: Dedup, keys: DedupKeys) -> Self {
+        self.dedup = dedup;
+        self.dedup_keys = keys;
+        self
+    }
+
+    /// Which items are dropped as duplicates.
+    pub fn dedup(&self) -> Dedup {
+        self.dedup
+    }
+
+    /// Whether no duplicate of the item with the given key was pushed yet,
+    /// recording it as pushed.
+    fn first_occurrence(&self, key: &str) -> bool {
+        self.dedup.key(key).map_or(true, |key| {
+            self.dedup_keys
+                .lock()
+                .map_or(true, |mut keys| keys.insert(key))
+        })
     }
 
     /// Push an item into the fuzzy matcher.
@@ -44,6 +170,94 @@ where
     where
         F: FnOnce(&I, &mut [nucleo::Utf32String]),
     {
-        self.inner.push(item, f);
+        if self.dedup == Dedup::None {
+            self.push_unchecked(item, f);
+            return;
+        }
+        // the item is a duplicate if the string to match against is
+        let mut cols = vec![
+            nucleo::Utf32String::default();
+            if self.transliterate { 2 } else { 1 }
+        ];
+        f(&item, &mut cols);
+        if self.first_occurrence(&cols[0].to_string()) {
+            self.push_unchecked(item, |_, item_cols| {
+                item_cols.clone_from_slice(&cols);
+            });
+        }
+    }
+
+    /// Push an item into the fuzzy matcher unless another item with the
+    /// same key was pushed before it when deduplicating.
+    ///
+    /// This is for items whose duplicates can't be told by the string to
+    /// match against (e.g. paths to the same file through symlinks).
+    pub fn push_with_key<F>(&self, item: I, key: &str, f: F)
+    where
+        F: FnOnce(&I, &mut [nucleo::Utf32String]),
+    {
+        if self.first_occurrence(key) {
+            self.push_unchecked(item, f);
+        }
+    }
+
+    fn push_unchecked<F>(&self, item: I, f: F)
+    where
+        F: FnOnce(&I, &mut [nucleo::Utf32String]),
+    {
+        self.pause.wait();
+        // the target can't change while pushing
+        let inner = self.inner.read();
+        push_into(&inner, self.transliterate, self.target(), item, f);
+    }
+}
+
+/// Push an item into the given `Nucleo` injector, the strings to match
+/// against produced by `f` being adjusted to the target and romanized when
+/// transliterating.
+pub(super) fn push_into<I, F>(
+    inner: &nucleo::Injector<I>,
+    transliterate: bool,
+    target: MatchTarget,
+    item: I,
+    f: F,
+) where
+    I: Sync + Send + Clone + 'static,
+    F: FnOnce(&I, &mut [nucleo::Utf32String]),
+{
+    let visible = target == MatchTarget::Visible;
+    if !transliterate && !visible {
+        inner.push(item, f);
+        return;
+    }
+    inner.push(item, |item, cols| {
+        f(item, cols);
+        if visible {
+            let text = cols[0].to_string();
+            if let std::borrow::Cow::Owned(stripped) = strip_ansi_codes(&text)
+            {
+                cols[0] = stripped.into();
+            }
+        }
+        if !transliterate {
+            return;
+        }
+        if let Some(romanized) = super::romanize(&cols[0].to_string()) {
+            cols[1] = std::mem::replace(&mut cols[0], romanized.into());
+        }
+    });
+}
+
+impl Injector<Entry> {
+    /// Push an entry into the fuzzy matcher.
+    ///
+    /// The entry is matched against its search text if it has one, and
+    /// against its name otherwise (see `Entry::matchable_text`), unless
+    /// only the visible text is matched.
+    pub fn push_entry(&self, entry: Entry) {
+        let target = self.target();
+        self.push(entry, |e, cols| {
+            cols[0] = e.target_text(target).into();
+        });
     }
 }
@@ -1,3 +1,5 @@
+use crate::channels::entry::Entry;
+
 /// A matched item.
 ///
 /// This contains the matched item, the dimension against which it was matched,
@@ -18,3 +20,17 @@ where
     /// The indices of the matched characters.
     pub match_indices: Vec<(u32, u32)>,
 }
+
+impl MatchedItem<Entry> {
+    /// Convert the matched item back into an entry ready to be displayed.
+    ///
+    /// Match ranges only make sense when the entry was matched against its
+    /// name: entries matched against a hidden search text are returned
+    /// without any highlighting.
+    pub fn into_entry(self) -> Entry {
+        if self.inner.search_text.is_some() {
+            return self.inner;
+        }
+        self.inner.with_name_match_ranges(&self.match_indices)
+    }
+}
@@ -1,13 +1,38 @@
-use injector::Injector;
-use std::sync::Arc;
+use injector::{DedupKeys, Injector, Pause, SharedInjector, SharedTarget};
+use parking_lot::RwLock;
+use rustc_hash::FxHashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+use tracing::warn;
+#[cfg(feature = "transliteration")]
+use transliteration::{original_indices, romanize};
 
 pub mod config;
 pub mod injector;
 pub mod lazy;
 pub mod matched_item;
+pub mod scorer;
+#[cfg(feature = "transliteration")]
+pub mod transliteration;
 
 const MATCHER_TICK_TIMEOUT: u64 = 2;
 
+/// How often the external scorer is run again while items are being added.
+const SCORER_REFRESH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long the external scorer may run before it is killed and the
+/// built-in matcher is used instead.
+const SCORER_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// The number of patterns the rankings of the external scorer are kept
+/// for.
+const SCORER_CACHE_SIZE: usize = 16;
+
+/// How often items are sorted again while they are being added and the
+/// pattern is empty.
+const INITIAL_SORT_REFRESH_INTERVAL: Duration = Duration::from_millis(500);
+
 /// The status of the fuzzy matcher.
 ///
 /// This currently only contains a boolean indicating whether the matcher is
@@ -50,6 +75,230 @@ where
     pub status: Status,
     /// The last pattern that was matched against.
     pub last_pattern: String,
+    /// How the pattern should be normalized before matching.
+    normalization: nucleo::pattern::Normalization,
+    /// How scores are adjusted before ranking results.
+    score_normalization: config::ScoreNormalization,
+    /// The maximum gap between consecutive matched characters of a term.
+    max_gap: Option<u32>,
+    /// The ranking of the matched items by normalized score and/or left
+    /// once the ones exceeding `max_gap` are filtered out.
+    matched_ranking: Option<Arc<MatchedRanking>>,
+    /// The ranking of the matched items being computed in the background.
+    pending_matched_ranking: Option<PendingMatchedRanking>,
+    /// The command ranking the items instead of the built-in matcher, if
+    /// any (see `scorer`).
+    scorer_command: Option<Arc<str>>,
+    /// The rankings of the items by the external scorer, by pattern.
+    external_rankings: FxHashMap<String, ExternalRanking>,
+    /// The ranking the external scorer is computing in the background.
+    pending_external_ranking: Option<PendingExternalRanking>,
+    /// Whether items and patterns are matched by their romanized form (see
+    /// `config::Config::transliterate`).
+    transliterate: bool,
+    /// How items are ordered while the pattern is empty.
+    initial_sort: config::InitialSort,
+    /// The ranking of the items by `initial_sort`.
+    initial_ranking: Option<InitialRanking>,
+    /// Which items are dropped as duplicates.
+    dedup: config::Dedup,
+    /// The keys of the items pushed so far when deduplicating, shared by
+    /// all injectors.
+    dedup_keys: DedupKeys,
+    /// Whether pushing items through the injectors is paused.
+    pause: Pause,
+    /// The `Nucleo` injector the injectors push items into.
+    shared_injector: SharedInjector<I>,
+    /// What the items are matched against, shared by all injectors.
+    target: SharedTarget,
+    /// The number of threads pushing the items again after the target
+    /// changed (see `set_target`).
+    rebuilds: Arc<AtomicUsize>,
+}
+
+/// The indices of the items in their initial order (see
+/// `config::InitialSort`), along with the sort keys read for the items
+/// ranked so far.
+#[derive(Default)]
+struct InitialRanking {
+    /// The sort key of each item ranked so far, read from the file system
+    /// for sorts by modification time or size.
+    keys: Vec<Option<u128>>,
+    computed_at: Option<Instant>,
+    indices: Vec<u32>,
+    /// The ranking being computed on a separate thread.
+    pending: Option<mpsc::Receiver<InitialRanking>>,
+}
+
+impl InitialRanking {
+    fn is_complete(&self, total: u32) -> bool {
+        self.pending.is_none() && self.indices.len() == total as usize
+    }
+
+    fn is_stale(&self, total: u32) -> bool {
+        !self.is_complete(total)
+            && self.pending.is_none()
+            && self.computed_at.map_or(true, |computed_at| {
+                computed_at.elapsed() >= INITIAL_SORT_REFRESH_INTERVAL
+            })
+    }
+
+    /// The index of the item ranked `position`, the items added since the
+    /// ranking was computed being ranked last in the order they were added.
+    fn item_index(&self, position: u32) -> u32 {
+        self.indices
+            .get(position as usize)
+            .copied()
+            .unwrap_or(position)
+    }
+}
+
+/// The indices of the items matched by the external scorer, from the best
+/// one to the worst one, along with the number of items they were computed
+/// for.
+struct ExternalRanking {
+    total_item_count: u32,
+    computed_at: Instant,
+    indices: Vec<u32>,
+}
+
+impl ExternalRanking {
+    fn is_stale(&self, total: u32) -> bool {
+        self.total_item_count != total
+            && self.computed_at.elapsed() >= SCORER_REFRESH_INTERVAL
+    }
+}
+
+/// A ranking of the items the external scorer is computing on a separate
+/// thread.
+struct PendingExternalRanking {
+    pattern: String,
+    total_item_count: u32,
+    receiver: mpsc::Receiver<anyhow::Result<Vec<u32>>>,
+}
+
+/// The items matching a pattern ordered by normalized score and/or filtered
+/// by maximum gap, which is extended with the items added since it was last
+/// updated rather than computed again.
+///
+/// Rankings are computed on a separate thread (see `Matcher::tick`) as
+/// every item is matched again whenever the pattern changes.
+#[derive(Default)]
+struct MatchedRanking {
+    /// The atoms of the pattern the items were matched against.
+    atoms: Vec<nucleo::pattern::Atom>,
+    /// The number of items matched so far.
+    item_count: u32,
+    /// The matching items, best first.
+    ranked: Vec<RankedItem>,
+    /// The indices of the matching items, best first.
+    indices: Vec<u32>,
+}
+
+#[derive(Clone, Copy)]
+struct RankedItem {
+    score: f64,
+    /// The length of the item, shorter items being ranked first on equal
+    /// scores as `Nucleo` does.
+    len: usize,
+    index: u32,
+}
+
+impl RankedItem {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        other
+            .score
+            .total_cmp(&self.score)
+            .then(self.len.cmp(&other.len))
+            .then(self.index.cmp(&other.index))
+    }
+}
+
+/// A ranking of the matched items being computed on a separate thread.
+struct PendingMatchedRanking {
+    atoms: Vec<nucleo::pattern::Atom>,
+    /// Set to stop the computation once the pattern changed.
+    canceled: Arc<AtomicBool>,
+    receiver: mpsc::Receiver<MatchedRanking>,
+}
+
+impl MatchedRanking {
+    /// The ranking extended with the items added since it was computed, up
+    /// to `item_count`, or `None` if `canceled` was set in the meantime.
+    fn extended<I>(
+        &self,
+        items: &nucleo::Injector<I>,
+        item_count: u32,
+        score_normalization: config::ScoreNormalization,
+        normalization: nucleo::pattern::Normalization,
+        max_gap: Option<u32>,
+        canceled: &AtomicBool,
+    ) -> Option<MatchedRanking>
+    where
+        I: Sync + Send + Clone + 'static,
+    {
+        let mut pattern = nucleo::pattern::Pattern::default();
+        pattern.atoms.clone_from(&self.atoms);
+        let mut matcher = nucleo::Matcher::default();
+        let mut gap_check = max_gap
+            .map(|max_gap| GapCheck::new(&pattern, normalization, max_gap));
+        let mut next_index = self.item_count;
+        let mut added = Vec::new();
+        while next_index < item_count {
+            if canceled.load(Ordering::Relaxed) {
+                return None;
+            }
+            // items may be initialized out of order, the missing ones
+            // are matched on the next update
+            let Some(item) = items.get(next_index) else {
+                break;
+            };
+            let index = next_index;
+            next_index += 1;
+            let haystack = item.matcher_columns[0].slice(..);
+            let Some(score) = pattern.score(haystack, &mut matcher) else {
+                continue;
+            };
+            if gap_check
+                .as_mut()
+                .is_some_and(|check| !check.within_max_gap(haystack))
+            {
+                continue;
+            }
+            let len = item
+                .matcher_columns
+                .iter()
+                .map(nucleo::Utf32String::len)
+                .sum();
+            #[allow(clippy::cast_precision_loss)]
+            let score = match score_normalization {
+                config::ScoreNormalization::None => f64::from(score),
+                config::ScoreNormalization::Length => {
+                    f64::from(score) / haystack.len().max(1) as f64
+                }
+            };
+            added.push(RankedItem { score, len, index });
+        }
+        added.sort_by(RankedItem::cmp);
+        let mut ranked: Vec<RankedItem> =
+            Vec::with_capacity(self.ranked.len() + added.len());
+        let (mut old, mut new) =
+            (self.ranked.iter().peekable(), added.iter().peekable());
+        while let (Some(a), Some(b)) = (old.peek(), new.peek()) {
+            if a.cmp(b).is_le() {
+                ranked.extend(old.next());
+            } else {
+                ranked.extend(new.next());
+            }
+        }
+        ranked.extend(old.chain(new));
+        Some(MatchedRanking {
+            atoms: self.atoms.clone(),
+            item_count: next_index,
+            indices: ranked.iter().map(|item| item.index).collect(),
+            ranked,
+        })
+    }
 }
 
 impl<I> Matcher<I>
@@ -58,25 +307,181 @@ where
 {
     /// Create a new fuzzy matcher with the given configuration.
     pub fn new(config: config::Config) -> Self {
+        let transliterate =
+            config.transliterate && cfg!(feature = "transliteration");
+        let inner = nucleo::Nucleo::new(
+            (&config).into(),
+            Arc::new(|| {}),
+            config.n_threads,
+            // the original strings of romanized items
+            if transliterate { 2 } else { 1 },
+        );
         Self {
-            inner: nucleo::Nucleo::new(
-                (&config).into(),
-                Arc::new(|| {}),
-                config.n_threads,
-                1,
-            ),
+            shared_injector: Arc::new(RwLock::new(inner.injector())),
+            target: SharedTarget::default(),
+            rebuilds: Arc::default(),
+            inner,
             total_item_count: 0,
             matched_item_count: 0,
             status: Status::default(),
             last_pattern: String::new(),
+            normalization: if config.normalize {
+                nucleo::pattern::Normalization::Smart
+            } else {
+                nucleo::pattern::Normalization::Never
+            },
+            score_normalization: config.score_normalization,
+            max_gap: config.max_gap,
+            matched_ranking: None,
+            pending_matched_ranking: None,
+            scorer_command: config.scorer_command,
+            external_rankings: FxHashMap::default(),
+            pending_external_ranking: None,
+            transliterate,
+            initial_sort: config.initial_sort,
+            initial_ranking: None,
+            dedup: config.dedup,
+            dedup_keys: DedupKeys::default(),
+            pause: Pause::default(),
         }
     }
 
     /// Tick the fuzzy matcher.
     ///
     /// This should be called periodically to update the state of the matcher.
+    /// The matched items are also ranked by normalized score and/or
+    /// filtered by maximum gap, and the items sorted by
+    /// `config::Config::initial_sort` while the pattern is empty, on
+    /// separate threads when needed, the matcher being reported as running
+    /// until they are.
     pub fn tick(&mut self) {
+        // the items pushed again before the tick are all matched by it
+        let rebuilding = self.rebuilds.load(Ordering::Acquire) > 0;
         self.status = self.inner.tick(MATCHER_TICK_TIMEOUT).into();
+        self.update_matched_ranking();
+        self.update_initial_ranking();
+        let item_count = self.inner.snapshot().item_count();
+        self.status.running |= rebuilding
+            || self.pending_matched_ranking.is_some()
+            || self
+                .initial_ranking()
+                .is_some_and(|ranking| !ranking.is_complete(item_count));
+    }
+
+    /// Take in the ranking of the items by `initial_sort` computed in the
+    /// background if it is done, and start computing it again if items
+    /// were added since, at most every `INITIAL_SORT_REFRESH_INTERVAL`.
+    fn update_initial_ranking(&mut self) {
+        if self.initial_sort == config::InitialSort::None
+            || !self.last_pattern.is_empty()
+        {
+            return;
+        }
+        let item_count = self.inner.snapshot().item_count();
+        let ranking =
+            self.initial_ranking.get_or_insert_with(Default::default);
+        if let Some(receiver) = &ranking.pending {
+            match receiver.try_recv() {
+                Ok(computed) => {
+                    *ranking = InitialRanking {
+                        computed_at: Some(Instant::now()),
+                        ..computed
+                    };
+                }
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    ranking.pending = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => {}
+            }
+        }
+        if !ranking.is_stale(item_count) {
+            return;
+        }
+        let (sender, receiver) = mpsc::channel();
+        ranking.pending = Some(receiver);
+        let mut keys = std::mem::take(&mut ranking.keys);
+        let items = self.inner.injector();
+        let sort = self.initial_sort;
+        std::thread::spawn(move || {
+            let indices =
+                rank_by_initial_sort(&items, item_count, sort, &mut keys);
+            let _ = sender.send(InitialRanking {
+                keys,
+                indices,
+                ..Default::default()
+            });
+        });
+    }
+
+    /// Take in the ranking of the matched items computed in the background
+    /// if it is done, and start computing it again if the pattern changed
+    /// or items were added since.
+    fn update_matched_ranking(&mut self) {
+        if let Some(pending) = &self.pending_matched_ranking {
+            match pending.receiver.try_recv() {
+                Ok(ranking) => {
+                    self.matched_ranking = Some(Arc::new(ranking));
+                    self.pending_matched_ranking = None;
+                }
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    self.pending_matched_ranking = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => {}
+            }
+        }
+        if !self.ranks_matched_items() {
+            self.cancel_matched_ranking();
+            return;
+        }
+        let snapshot = self.inner.snapshot();
+        let atoms = snapshot.pattern().column_pattern(0).atoms.clone();
+        let item_count = snapshot.item_count();
+        if let Some(pending) = &self.pending_matched_ranking {
+            if pending.atoms == atoms {
+                return;
+            }
+            self.cancel_matched_ranking();
+        }
+        let base = match &self.matched_ranking {
+            Some(ranking) if ranking.atoms == atoms => {
+                if ranking.item_count >= item_count {
+                    return;
+                }
+                ranking.clone()
+            }
+            _ => Arc::new(MatchedRanking {
+                atoms: atoms.clone(),
+                ..Default::default()
+            }),
+        };
+        let canceled = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+        self.pending_matched_ranking = Some(PendingMatchedRanking {
+            atoms,
+            canceled: canceled.clone(),
+            receiver,
+        });
+        let items = self.inner.injector();
+        let (score_normalization, normalization, max_gap) =
+            (self.score_normalization, self.normalization, self.max_gap);
+        std::thread::spawn(move || {
+            if let Some(ranking) = base.extended(
+                &items,
+                item_count,
+                score_normalization,
+                normalization,
+                max_gap,
+                &canceled,
+            ) {
+                let _ = sender.send(ranking);
+            }
+        });
+    }
+
+    fn cancel_matched_ranking(&mut self) {
+        if let Some(pending) = self.pending_matched_ranking.take() {
+            pending.canceled.store(true, Ordering::Relaxed);
+        }
     }
 
     /// Get an injector that can be used to push items into the fuzzy matcher.
@@ -98,30 +503,199 @@ where
     /// );
     /// ```
     pub fn injector(&self) -> Injector<I> {
-        Injector::new(self.inner.injector())
+        Injector::new(self.shared_injector.clone(), self.transliterate)
+            .with_dedup(self.dedup, self.dedup_keys.clone())
+            .with_pause(self.pause.clone())
+            .with_target(self.target.clone())
+    }
+
+    /// What the items are matched against.
+    pub fn target(&self) -> config::MatchTarget {
+        self.target.get()
+    }
+
+    /// Match the items against what the given target designates.
+    ///
+    /// The items pushed so far are pushed again on a separate thread, `f`
+    /// producing the strings to match against as when pushing them (see
+    /// `Injector::push`), the matcher being reported as running until they
+    /// all are.
+    pub fn set_target<F>(&mut self, target: config::MatchTarget, f: F)
+    where
+        F: Fn(&I, &mut [nucleo::Utf32String]) + Send + 'static,
+    {
+        if target == self.target.get() {
+            return;
+        }
+        self.target.set(target);
+        self.inner.restart(true);
+        let restarted = self.inner.injector();
+        let shared = self.shared_injector.clone();
+        let transliterate = self.transliterate;
+        let rebuilds = self.rebuilds.clone();
+        rebuilds.fetch_add(1, Ordering::AcqRel);
+        std::thread::spawn(move || {
+            // the injectors wait for the items pushed before them, which
+            // keep their order
+            let mut shared = shared.write();
+            let previous = std::mem::replace(&mut *shared, restarted);
+            for i in 0..previous.injected_items() {
+                if let Some(item) = previous.get(i) {
+                    injector::push_into(
+                        &shared,
+                        transliterate,
+                        target,
+                        item.data.clone(),
+                        &f,
+                    );
+                }
+            }
+            drop(shared);
+            rebuilds.fetch_sub(1, Ordering::AcqRel);
+        });
+        self.matched_ranking = None;
+        self.cancel_matched_ranking();
+        self.external_rankings.clear();
+        self.pending_external_ranking = None;
+        self.initial_ranking = None;
+        self.reparse(&self.last_pattern.clone(), false);
+    }
+
+    /// Pause pushing items, the injectors blocking until `resume` is
+    /// called, e.g. to stop a crawl from consuming CPU.
+    pub fn pause(&self) {
+        self.pause.set(true);
+    }
+
+    pub fn resume(&self) {
+        self.pause.set(false);
+    }
+
+    pub fn paused(&self) -> bool {
+        self.pause.paused()
     }
 
     /// Find items that match the given pattern.
     ///
     /// This should be called whenever the pattern changes.
+    /// Whitespace separated terms must all match and a term may be anchored
+    /// with a leading `^` and/or a trailing `$` to match the start and/or the
+    /// end of an item exactly rather than fuzzily (e.g. `^src main rs$`).
     /// The `Matcher` will keep track of the last pattern and only reparse the
     /// pattern if it has changed, allowing for more efficient matching when
     /// `self.last_pattern` is a prefix of the new `pattern`.
     pub fn find(&mut self, pattern: &str) {
         if pattern != self.last_pattern {
-            self.inner.pattern.reparse(
-                0,
-                pattern,
-                nucleo::pattern::CaseMatching::Smart,
-                nucleo::pattern::Normalization::Smart,
-                pattern.starts_with(&self.last_pattern),
-            );
+            self.reparse(pattern, pattern.starts_with(&self.last_pattern));
             self.last_pattern = pattern.to_string();
         }
     }
 
+    /// Match the items against the given pattern, only the items matching
+    /// the previous one being matched again when `append` is set.
+    fn reparse(&mut self, pattern: &str, append: bool) {
+        let romanized = romanize(pattern).filter(|_| self.transliterate);
+        self.inner.pattern.reparse(
+            0,
+            romanized.as_deref().unwrap_or(pattern),
+            nucleo::pattern::CaseMatching::Smart,
+            self.normalization,
+            append,
+        );
+    }
+
+    /// The indices of the items matched by the external scorer, if it
+    /// ranked them for the current pattern.
+    fn external_ranking(&self) -> Option<&[u32]> {
+        self.external_rankings
+            .get(&self.last_pattern)
+            .filter(|_| {
+                self.scorer_command.is_some() && !self.last_pattern.is_empty()
+            })
+            .map(|ranking| ranking.indices.as_slice())
+    }
+
+    /// Take in the ranking computed by the external scorer, if it is done.
+    fn receive_external_ranking(&mut self) {
+        let Some(pending) = &self.pending_external_ranking else {
+            return;
+        };
+        match pending.receiver.try_recv() {
+            Ok(Ok(indices)) => {
+                let pending = self.pending_external_ranking.take().unwrap();
+                if self.external_rankings.len() >= SCORER_CACHE_SIZE {
+                    let oldest = self
+                        .external_rankings
+                        .iter()
+                        .min_by_key(|(_, ranking)| ranking.computed_at)
+                        .map(|(pattern, _)| pattern.clone());
+                    if let Some(oldest) = oldest {
+                        self.external_rankings.remove(&oldest);
+                    }
+                }
+                self.external_rankings.insert(
+                    pending.pattern,
+                    ExternalRanking {
+                        total_item_count: pending.total_item_count,
+                        computed_at: Instant::now(),
+                        indices,
+                    },
+                );
+            }
+            Ok(Err(e)) => {
+                warn!("{e:?}, falling back to the built-in matcher");
+                self.scorer_command = None;
+                self.external_rankings.clear();
+                self.pending_external_ranking = None;
+            }
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.pending_external_ranking = None;
+            }
+            Err(mpsc::TryRecvError::Empty) => {}
+        }
+    }
+
+    /// Whether the matched items are ranked by normalized score or filtered
+    /// by maximum gap rather than taken as ranked by `Nucleo`.
+    fn ranks_matched_items(&self) -> bool {
+        (self.score_normalization == config::ScoreNormalization::Length
+            || self.max_gap.is_some())
+            && !self.last_pattern.is_empty()
+    }
+
+    /// The indices of the matched items ordered by normalized score and/or
+    /// filtered by maximum gap, if they are and were ranked for the current
+    /// pattern.
+    fn ranking(&self) -> Option<&[u32]> {
+        self.matched_ranking
+            .as_ref()
+            .filter(|ranking| {
+                self.ranks_matched_items()
+                    && ranking.atoms
+                        == self
+                            .inner
+                            .snapshot()
+                            .pattern()
+                            .column_pattern(0)
+                            .atoms
+            })
+            .map(|ranking| ranking.indices.as_slice())
+    }
+
+    /// The ranking of the items by `initial_sort`, if the pattern is empty
+    /// and the items are sorted.
+    fn initial_ranking(&self) -> Option<&InitialRanking> {
+        self.initial_ranking.as_ref().filter(|_| {
+            self.initial_sort != config::InitialSort::None
+                && self.last_pattern.is_empty()
+        })
+    }
+
     /// Get the matched items.
     ///
+    /// While the pattern is empty, the items are listed in the order set by
+    /// `config::Config::initial_sort`.
+    ///
     /// This should be called to retrieve the matched items after calling
     /// `find`.
     ///
@@ -155,6 +729,11 @@ where
         num_entries: u32,
         offset: u32,
     ) -> Vec<matched_item::MatchedItem<I>> {
+        // the external scorer runs in the background, the items being
+        // ranked by the built-in matcher until it ranked them for the
+        // current pattern
+        self.receive_external_ranking();
+
         let snapshot = self.inner.snapshot();
         self.total_item_count = snapshot.item_count();
         self.matched_item_count = snapshot.matched_item_count();
@@ -162,10 +741,78 @@ where
         let mut col_indices = Vec::new();
         let mut matcher = lazy::MATCHER.lock();
 
-        snapshot
-            .matched_items(
-                offset..(num_entries + offset).min(self.matched_item_count),
-            )
+        if let Some(command) = self
+            .scorer_command
+            .clone()
+            .filter(|_| !self.last_pattern.is_empty())
+        {
+            if self.pending_external_ranking.is_none()
+                && self
+                    .external_rankings
+                    .get(&self.last_pattern)
+                    .map_or(true, |ranking| {
+                        ranking.is_stale(self.total_item_count)
+                    })
+            {
+                // the items are read on the scorer's thread, the injector
+                // sharing them with the snapshot
+                let items = self.inner.injector();
+                let item_count = self.total_item_count;
+                let (sender, receiver) = mpsc::channel();
+                let pattern = self.last_pattern.clone();
+                self.pending_external_ranking = Some(PendingExternalRanking {
+                    pattern: pattern.clone(),
+                    total_item_count: self.total_item_count,
+                    receiver,
+                });
+                std::thread::spawn(move || {
+                    let candidates = (0..item_count)
+                        .map_while(|i| items.get(i))
+                        .map(|item| original_string(item.matcher_columns))
+                        .collect::<Vec<_>>();
+                    let _ = sender.send(scorer::rank(
+                        &command,
+                        &pattern,
+                        &candidates,
+                        SCORER_TIMEOUT,
+                    ));
+                });
+            }
+        }
+        if let Some(indices) = self.external_ranking() {
+            self.matched_item_count =
+                u32::try_from(indices.len()).unwrap_or(u32::MAX);
+        }
+
+        if self.external_ranking().is_none() {
+            if let Some(indices) = self.ranking() {
+                self.matched_item_count =
+                    u32::try_from(indices.len()).unwrap_or(u32::MAX);
+            }
+        }
+        let range =
+            offset..(num_entries + offset).min(self.matched_item_count);
+        let items: Vec<_> = if let Some(ranking) = self.initial_ranking() {
+            range
+                .filter_map(|i| snapshot.get_item(ranking.item_index(i)))
+                .collect()
+        } else if let Some(indices) = self.external_ranking() {
+            range
+                .filter_map(|i| indices.get(i as usize))
+                .filter_map(|&i| snapshot.get_item(i))
+                .collect()
+        } else {
+            match self.ranking() {
+                Some(indices) => range
+                    .filter_map(|i| indices.get(i as usize))
+                    .filter_map(|&i| snapshot.get_item(i))
+                    .collect(),
+                None => snapshot.matched_items(range).collect(),
+            }
+        };
+
+        items
+            .into_iter()
             .map(move |item| {
                 snapshot.pattern().column_pattern(0).indices(
                     item.matcher_columns[0].slice(..),
@@ -175,9 +822,13 @@ where
                 col_indices.sort_unstable();
                 col_indices.dedup();
 
+                let matched_string = original_string(item.matcher_columns);
+                if is_romanized(item.matcher_columns) {
+                    col_indices =
+                        original_indices(&matched_string, &col_indices);
+                }
                 let indices = col_indices.drain(..);
 
-                let matched_string = item.matcher_columns[0].to_string();
                 matched_item::MatchedItem {
                     inner: item.data.clone(),
                     matched_string,
@@ -207,8 +858,30 @@ where
         index: u32,
     ) -> Option<matched_item::MatchedItem<I>> {
         let snapshot = self.inner.snapshot();
-        snapshot.get_matched_item(index).map(|item| {
-            let matched_string = item.matcher_columns[0].to_string();
+        if let Some(ranking) = self.initial_ranking() {
+            return snapshot.get_item(ranking.item_index(index)).map(|item| {
+                matched_item::MatchedItem {
+                    inner: item.data.clone(),
+                    matched_string: original_string(item.matcher_columns),
+                    match_indices: Vec::new(),
+                }
+            });
+        }
+        if let Some(indices) = self.external_ranking() {
+            return snapshot.get_item(*indices.get(index as usize)?).map(
+                |item| matched_item::MatchedItem {
+                    inner: item.data.clone(),
+                    matched_string: original_string(item.matcher_columns),
+                    match_indices: Vec::new(),
+                },
+            );
+        }
+        let item = match self.ranking() {
+            Some(indices) => snapshot.get_item(*indices.get(index as usize)?),
+            None => snapshot.get_matched_item(index),
+        };
+        item.map(|item| {
+            let matched_string = original_string(item.matcher_columns);
             matched_item::MatchedItem {
                 inner: item.data.clone(),
                 matched_string,
@@ -217,3 +890,785 @@ where
         })
     }
 }
+
+/// Whether the string an item is matched against is the romanized form of
+/// its original string, which is then kept in the second column.
+fn is_romanized(columns: &[nucleo::Utf32String]) -> bool {
+    columns.get(1).is_some_and(|original| !original.is_empty())
+}
+
+/// The string an item was pushed with, before any romanization.
+fn original_string(columns: &[nucleo::Utf32String]) -> String {
+    if is_romanized(columns) {
+        columns[1].to_string()
+    } else {
+        columns[0].to_string()
+    }
+}
+
+#[cfg(not(feature = "transliteration"))]
+fn romanize(_text: &str) -> Option<String> {
+    None
+}
+
+#[cfg(not(feature = "transliteration"))]
+fn original_indices(_text: &str, indices: &[u32]) -> Vec<u32> {
+    indices.to_vec()
+}
+
+/// The fuzzy terms of a pattern folded once, to be compared to each item
+/// by `within_max_gap`.
+struct GapCheck {
+    /// The folded characters of each term and whether its case is ignored.
+    terms: Vec<(Vec<char>, bool)>,
+    normalization: nucleo::pattern::Normalization,
+    max_gap: usize,
+    /// The buffer `has_tight_alignment` keeps track of the alignments in,
+    /// reused from one item to the next.
+    ends: Vec<Option<usize>>,
+}
+
+impl GapCheck {
+    fn new(
+        pattern: &nucleo::pattern::Pattern,
+        normalization: nucleo::pattern::Normalization,
+        max_gap: u32,
+    ) -> Self {
+        let terms = pattern
+            .atoms
+            .iter()
+            .filter(|atom| {
+                !atom.negative && atom.kind == nucleo::pattern::AtomKind::Fuzzy
+            })
+            .map(|atom| {
+                // smart case, as set up in `find`
+                let ignore_case = !atom
+                    .needle_text()
+                    .chars()
+                    .any(nucleo::chars::is_upper_case);
+                let needle = atom
+                    .needle_text()
+                    .chars()
+                    .map(|c| fold(c, normalization, ignore_case))
+                    .collect();
+                (needle, ignore_case)
+            })
+            .collect();
+        Self {
+            terms,
+            normalization,
+            max_gap: max_gap as usize,
+            ends: Vec::new(),
+        }
+    }
+
+    /// Whether every term of the pattern matches `haystack` with at most
+    /// `max_gap` characters between two consecutive matched characters.
+    ///
+    /// The tightest alignment of each fuzzy term is looked for rather than
+    /// the best scoring one `Nucleo` picks, which may be spread out even
+    /// though a tighter one exists, the other kinds of terms matching
+    /// contiguous characters.
+    fn within_max_gap(&mut self, haystack: nucleo::Utf32Str<'_>) -> bool {
+        let normalization = self.normalization;
+        self.terms.iter().all(|(needle, ignore_case)| {
+            has_tight_alignment(
+                needle,
+                haystack
+                    .chars()
+                    .map(|c| fold(c, normalization, *ignore_case)),
+                self.max_gap,
+                &mut self.ends,
+            )
+        })
+    }
+}
+
+/// A character as compared by `GapCheck`, i.e. normalized and/or in lower
+/// case as `Nucleo` compares it.
+fn fold(
+    c: char,
+    normalization: nucleo::pattern::Normalization,
+    ignore_case: bool,
+) -> char {
+    let c = if normalization == nucleo::pattern::Normalization::Smart {
+        nucleo::chars::normalize(c)
+    } else {
+        c
+    };
+    if ignore_case {
+        nucleo::chars::to_lower_case(c)
+    } else {
+        c
+    }
+}
+
+/// Whether the characters of `needle` appear in order in `haystack` with at
+/// most `max_gap` characters between two consecutive ones.
+///
+/// Only the latest position each part of the needle can end at is kept in
+/// `ends`, a later end leaving at least as much room for the next character
+/// as an earlier one, so the haystack is gone through once.
+fn has_tight_alignment(
+    needle: &[char],
+    haystack: impl Iterator<Item = char>,
+    max_gap: usize,
+    ends: &mut Vec<Option<usize>>,
+) -> bool {
+    if needle.is_empty() {
+        return true;
+    }
+    ends.clear();
+    ends.resize(needle.len(), None);
+    for (p, h) in haystack.enumerate() {
+        // from the end so that a character only extends the parts matched
+        // before it
+        for k in (0..needle.len()).rev() {
+            if needle[k] == h
+                && (k == 0
+                    || ends[k - 1].is_some_and(|end| p - end - 1 <= max_gap))
+            {
+                ends[k] = Some(p);
+            }
+        }
+        if ends[needle.len() - 1].is_some() {
+            return true;
+        }
+    }
+    false
+}
+
+/// Rank the first `item_count` items by `sort`, ties keeping their
+/// original order.
+///
+/// Items are ranked by modification time or size using the keys read from
+/// the file system, the keys of the items they haven't been read for yet
+/// being added to `keys`, items which aren't paths to existing files being
+/// ranked last.
+fn rank_by_initial_sort<I>(
+    items: &nucleo::Injector<I>,
+    item_count: u32,
+    sort: config::InitialSort,
+    keys: &mut Vec<Option<u128>>,
+) -> Vec<u32>
+where
+    I: Sync + Send + Clone + 'static,
+{
+    let mut indices = (0..item_count).collect::<Vec<_>>();
+    let string = |i: u32| {
+        items
+            .get(i)
+            .map(|item| original_string(item.matcher_columns))
+            .unwrap_or_default()
+    };
+    if sort != config::InitialSort::Alphabetical {
+        let key_count = u32::try_from(keys.len()).unwrap_or(u32::MAX);
+        keys.extend(
+            (key_count..item_count).map(|i| read_sort_key(&string(i), sort)),
+        );
+    }
+    if sort == config::InitialSort::Alphabetical {
+        indices.sort_by_cached_key(|&i| string(i).to_lowercase());
+        return indices;
+    }
+    // the most recent or largest first
+    indices.sort_by_key(|&i| {
+        std::cmp::Reverse(keys.get(i as usize).copied().flatten())
+    });
+    indices
+}
+
+/// The key the file at `path` is sorted by, if it exists.
+fn read_sort_key(path: &str, sort: config::InitialSort) -> Option<u128> {
+    let metadata = std::fs::metadata(path).ok()?;
+    match sort {
+        config::InitialSort::Mtime => metadata
+            .modified()
+            .ok()?
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()
+            .map(|mtime| mtime.as_nanos()),
+        _ => Some(u128::from(metadata.len())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::channels::entry::{Entry, PreviewType};
+
+    use super::*;
+
+    fn matched_entries(entries: Vec<Entry>, pattern: &str) -> Vec<Entry> {
+        matched_entries_with_config(
+            entries,
+            pattern,
+            config::Config::default(),
+        )
+    }
+
+    fn matched_entries_with_config(
+        entries: Vec<Entry>,
+        pattern: &str,
+        config: config::Config,
+    ) -> Vec<Entry> {
+        let mut matcher = Matcher::new(config);
+        let injector = matcher.injector();
+        for entry in entries {
+            injector.push_entry(entry);
+        }
+        matcher.find(pattern);
+        matcher.tick();
+        while matcher.status.running {
+            matcher.tick();
+        }
+        matcher
+            .results(10, 0)
+            .into_iter()
+            .map(matched_item::MatchedItem::into_entry)
+            .collect()
+    }
+
+    #[test]
+    fn test_match_target_switches_between_visible_and_full_text() {
+        let mut matcher = Matcher::<String>::new(config::Config::default());
+        let injector = matcher.injector();
+        for line in ["\x1b[31mred\x1b[0m apple", "plain 31m banana"] {
+            injector.push(line.to_string(), |line, cols| {
+                cols[0] = line.as_str().into();
+            });
+        }
+        let matched = |matcher: &mut Matcher<String>, pattern: &str| {
+            matcher.find(pattern);
+            matcher.tick();
+            while matcher.status.running {
+                matcher.tick();
+            }
+            matcher
+                .results(10, 0)
+                .into_iter()
+                .map(|item| item.inner)
+                .collect::<Vec<_>>()
+        };
+        let set_target = |matcher: &mut Matcher<String>, target| {
+            matcher.set_target(target, |line: &String, cols| {
+                cols[0] = line.as_str().into();
+            });
+        };
+
+        assert_eq!(matcher.target(), config::MatchTarget::Full);
+        assert_eq!(matched(&mut matcher, "'31m").len(), 2);
+        assert_eq!(
+            matched(&mut matcher, "'[0m"),
+            ["\x1b[31mred\x1b[0m apple"]
+        );
+
+        set_target(&mut matcher, config::MatchTarget::Visible);
+        assert_eq!(matched(&mut matcher, "'31m"), ["plain 31m banana"]);
+        assert!(matched(&mut matcher, "'[0m").is_empty());
+        // items pushed after the switch are matched against the new target
+        injector.push("\x1b[1m31m\x1b[0m".to_string(), |line, cols| {
+            cols[0] = line.as_str().into();
+        });
+        assert_eq!(matched(&mut matcher, "'31m").len(), 2);
+
+        set_target(&mut matcher, config::MatchTarget::Full);
+        assert_eq!(matched(&mut matcher, "'31m").len(), 3);
+    }
+
+    #[test]
+    fn test_dedup_keeps_first_occurrences_in_order() {
+        let names = |entries: Vec<Entry>| {
+            entries.into_iter().map(|e| e.name).collect::<Vec<_>>()
+        };
+        let entries = ["beta", "alpha", "beta", " Alpha  ", "gamma", "alpha"]
+            .map(|name| Entry::new(name.into(), PreviewType::Basic))
+            .to_vec();
+
+        assert_eq!(
+            names(matched_entries_with_config(
+                entries.clone(),
+                "",
+                config::Config::default().dedup(config::Dedup::Exact),
+            )),
+            ["beta", "alpha", " Alpha  ", "gamma"]
+        );
+        assert_eq!(
+            names(matched_entries_with_config(
+                entries.clone(),
+                "",
+                config::Config::default().dedup(config::Dedup::Normalized),
+            )),
+            ["beta", "alpha", "gamma"]
+        );
+        assert_eq!(names(matched_entries(entries, "")).len(), 6);
+    }
+
+    fn matched_names_with_scorer(command: &str, pattern: &str) -> Vec<String> {
+        let mut matcher = Matcher::new(
+            config::Config::default().scorer_command(Some(command)),
+        );
+        let injector = matcher.injector();
+        for name in ["alpha", "beta", "gamma"] {
+            injector.push_entry(Entry::new(name.into(), PreviewType::Basic));
+        }
+        matcher.find(pattern);
+        matcher.tick();
+        while matcher.status.running {
+            matcher.tick();
+        }
+        let names = |matcher: &mut Matcher<Entry>| {
+            matcher
+                .results(10, 0)
+                .into_iter()
+                .map(|item| item.matched_string)
+                .collect::<Vec<_>>()
+        };
+        // the built-in matcher ranks the items until the scorer is done
+        assert_eq!(names(&mut matcher), ["alpha"]);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while matcher.pending_external_ranking.is_some()
+            && Instant::now() < deadline
+        {
+            std::thread::sleep(Duration::from_millis(10));
+            matcher.receive_external_ranking();
+        }
+        names(&mut matcher)
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_external_scorer_ranks_items() {
+        // the scorer ignores the query and ranks the last items first
+        assert_eq!(
+            matched_names_with_scorer("printf '1 1\\n2 2\\n'", "alpha"),
+            vec!["gamma", "beta"]
+        );
+        // a failing scorer falls back to the built-in matcher
+        assert_eq!(
+            matched_names_with_scorer("exit 1", "alpha"),
+            vec!["alpha"]
+        );
+    }
+
+    fn names_with_initial_sort(
+        names: &[String],
+        sort: config::InitialSort,
+        pattern: &str,
+    ) -> Vec<String> {
+        let mut matcher =
+            Matcher::new(config::Config::default().initial_sort(sort));
+        let injector = matcher.injector();
+        for name in names {
+            injector.push_entry(Entry::new(name.clone(), PreviewType::Basic));
+        }
+        matcher.find(pattern);
+        matcher.tick();
+        // the items are sorted in the background
+        while matcher.status.running {
+            matcher.tick();
+        }
+        let names = matcher
+            .results(10, 0)
+            .into_iter()
+            .map(|item| item.matched_string)
+            .collect::<Vec<_>>();
+        if let Some(first) = names.first() {
+            assert_eq!(matcher.get_result(0).unwrap().matched_string, *first);
+        }
+        names
+    }
+
+    #[test]
+    fn test_empty_query_follows_initial_sort() {
+        let names = ["beta", "Gamma", "alpha"].map(String::from);
+
+        assert_eq!(
+            names_with_initial_sort(
+                &names,
+                config::InitialSort::Alphabetical,
+                ""
+            ),
+            ["alpha", "beta", "Gamma"]
+        );
+        // items are ranked by score once there is a query
+        assert_eq!(
+            names_with_initial_sort(
+                &names,
+                config::InitialSort::Alphabetical,
+                "gam"
+            ),
+            ["Gamma"]
+        );
+    }
+
+    #[test]
+    fn test_empty_query_follows_initial_sort_by_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = std::time::SystemTime::now();
+        let path =
+            |name: &str| dir.path().join(name).to_string_lossy().to_string();
+        for (name, size, age) in
+            [("old", 3, 60), ("new", 1, 0), ("middle", 2, 30)]
+        {
+            let file = std::fs::File::create(path(name)).unwrap();
+            file.set_len(size).unwrap();
+            file.set_modified(now - Duration::from_secs(age)).unwrap();
+        }
+        let names =
+            [path("missing"), path("middle"), path("old"), path("new")];
+
+        assert_eq!(
+            names_with_initial_sort(&names, config::InitialSort::Mtime, ""),
+            [path("new"), path("middle"), path("old"), path("missing")]
+        );
+        assert_eq!(
+            names_with_initial_sort(&names, config::InitialSort::Size, ""),
+            [path("old"), path("middle"), path("new"), path("missing")]
+        );
+    }
+
+    #[test]
+    fn test_has_tight_alignment() {
+        let aligned = |haystack: &str, max_gap| {
+            let needle = ['a', 'b', 'c'];
+            has_tight_alignment(
+                &needle,
+                haystack.chars(),
+                max_gap,
+                &mut vec![],
+            )
+        };
+        // the second `a` leaves room for the `c`
+        assert!(aligned("a_xab__c", 2));
+        assert!(!aligned("a_xab__c", 1));
+        assert!(aligned("abc", 0));
+        assert!(!aligned("acb", 5));
+        assert!(!aligned("", 5));
+    }
+
+    #[test]
+    fn test_max_gap_rejects_spread_out_matches() {
+        let entries = || {
+            ["src/abc.rs", "a_b__c.rs", "a___b_c.rs"]
+                .map(|name| Entry::new(name.to_string(), PreviewType::Basic))
+                .to_vec()
+        };
+        let names = |config| {
+            matched_entries_with_config(entries(), "abc", config)
+                .into_iter()
+                .map(|entry| entry.name)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names(config::Config::default()).len(), 3);
+        let mut tight = names(config::Config::default().max_gap(Some(2)));
+        tight.sort();
+        assert_eq!(tight, ["a_b__c.rs", "src/abc.rs"]);
+        assert_eq!(
+            names(config::Config::default().max_gap(Some(0))),
+            ["src/abc.rs"]
+        );
+
+        // a tight alignment is enough even when the best scoring one is
+        // spread out
+        let entries = vec![Entry::new(
+            "alpha/beta/cappa_xabcx".to_string(),
+            PreviewType::Basic,
+        )];
+        assert_eq!(
+            matched_entries_with_config(
+                entries,
+                "abc",
+                config::Config::default().max_gap(Some(0)),
+            )
+            .len(),
+            1
+        );
+
+        // the cap applies to each term on its own
+        let entries = vec![Entry::new(
+            "abc/some/long/path/def".to_string(),
+            PreviewType::Basic,
+        )];
+        assert_eq!(
+            matched_entries_with_config(
+                entries,
+                "abc def",
+                config::Config::default().max_gap(Some(0)),
+            )
+            .len(),
+            1
+        );
+    }
+
+    #[cfg(feature = "transliteration")]
+    #[test]
+    fn test_romanized_query_matches_cyrillic() {
+        let entries = vec![
+            Entry::new("Москва.txt".to_string(), PreviewType::Basic),
+            Entry::new("Минск.txt".to_string(), PreviewType::Basic),
+        ];
+        let config = config::Config::default().transliterate(true);
+
+        let results =
+            matched_entries_with_config(entries, "moskva", config.clone());
+
+        assert_eq!(results.len(), 1);
+        // the entry is displayed as is with the letters matched highlighted
+        assert_eq!(results[0].name, "Москва.txt");
+        assert_eq!(results[0].name_match_ranges, Some(vec![(0, 6)]));
+
+        // cyrillic queries still match
+        let entries =
+            vec![Entry::new("Жук.txt".to_string(), PreviewType::Basic)];
+        let results = matched_entries_with_config(entries, "жук", config);
+        assert_eq!(results[0].name_match_ranges, Some(vec![(0, 3)]));
+
+        // transliteration is opt-in
+        let entries =
+            vec![Entry::new("Москва.txt".to_string(), PreviewType::Basic)];
+        assert!(matched_entries(entries, "moskva").is_empty());
+    }
+
+    #[test]
+    fn test_matches_hidden_search_text() {
+        let entries = vec![
+            Entry::new("Rust book".to_string(), PreviewType::Basic)
+                .with_search_text("programming tutorial".to_string()),
+            Entry::new("Cooking blog".to_string(), PreviewType::Basic)
+                .with_search_text("recipes food".to_string()),
+        ];
+
+        let results = matched_entries(entries, "tutorial");
+
+        assert_eq!(results.len(), 1);
+        // the row displays the entry's name, not the hidden search text
+        assert_eq!(results[0].name, "Rust book");
+        assert_eq!(results[0].name_match_ranges, None);
+    }
+
+    #[test]
+    fn test_falls_back_to_name() {
+        let entries = vec![
+            Entry::new("Rust book".to_string(), PreviewType::Basic),
+            Entry::new("Cooking blog".to_string(), PreviewType::Basic),
+        ];
+
+        let results = matched_entries(entries, "rust");
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "Rust book");
+        assert_eq!(results[0].name_match_ranges, Some(vec![(0, 4)]));
+    }
+
+    #[test]
+    fn test_hidden_search_text_replaces_name() {
+        let entries =
+            vec![Entry::new("Rust book".to_string(), PreviewType::Basic)
+                .with_search_text("programming tutorial".to_string())];
+
+        let results = matched_entries(entries, "rust");
+
+        assert!(results.is_empty());
+    }
+
+    fn entries(names: &[&str]) -> Vec<Entry> {
+        names
+            .iter()
+            .map(|name| Entry::new((*name).to_string(), PreviewType::Basic))
+            .collect()
+    }
+
+    #[test]
+    fn test_diacritic_folding_enabled() {
+        let config = config::Config::default().normalize(true);
+
+        let results = matched_entries_with_config(
+            entries(&["naïve", "other"]),
+            "naive",
+            config.clone(),
+        );
+        assert_eq!(results.len(), 1);
+        // the entry is still displayed with its accents
+        assert_eq!(results[0].name, "naïve");
+
+        let results = matched_entries_with_config(
+            entries(&["café", "other"]),
+            "cafe",
+            config,
+        );
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "café");
+    }
+
+    #[test]
+    fn test_diacritic_folding_disabled() {
+        let config = config::Config::default().normalize(false);
+
+        assert!(matched_entries_with_config(
+            entries(&["naïve", "other"]),
+            "naive",
+            config.clone(),
+        )
+        .is_empty());
+        assert!(matched_entries_with_config(
+            entries(&["café", "other"]),
+            "cafe",
+            config.clone(),
+        )
+        .is_empty());
+        // accented queries still match accented entries
+        assert_eq!(
+            matched_entries_with_config(entries(&["café"]), "café", config)
+                .len(),
+            1
+        );
+    }
+
+    fn matched_names(names: &[&str], pattern: &str) -> Vec<String> {
+        let mut names: Vec<String> = matched_entries(entries(names), pattern)
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_prefix_anchor() {
+        assert_eq!(
+            matched_names(&["src/main.rs", "tests/src.rs", "ssrc"], "^src"),
+            vec!["src/main.rs"]
+        );
+    }
+
+    #[test]
+    fn test_suffix_anchor() {
+        assert_eq!(
+            matched_names(&["main.rs", "rs.toml", "readme.rsx"], "rs$"),
+            vec!["main.rs"]
+        );
+    }
+
+    #[test]
+    fn test_exact_anchors() {
+        assert_eq!(
+            matched_names(&["main", "main.rs", "domain"], "^main$"),
+            vec!["main"]
+        );
+    }
+
+    #[test]
+    fn test_anchors_combine_with_other_terms() {
+        assert_eq!(
+            matched_names(
+                &["src/main.rs", "src/lib.rs", "src/main.toml", "main.rs"],
+                "^src main rs$"
+            ),
+            vec!["src/main.rs"]
+        );
+    }
+
+    #[test]
+    fn test_anchor_appended_to_previous_pattern() {
+        let mut matcher = Matcher::new(config::Config::default());
+        let injector = matcher.injector();
+        for entry in entries(&["main.rs", "rs.toml"]) {
+            injector.push_entry(entry);
+        }
+        for pattern in ["rs", "rs$"] {
+            matcher.find(pattern);
+            matcher.tick();
+            while matcher.status.running {
+                matcher.tick();
+            }
+        }
+        let results = matcher.results(10, 0);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].matched_string, "main.rs");
+    }
+
+    #[test]
+    fn test_length_score_normalization() {
+        let names = ["a_b_c_very_long_string_here", "xabc"];
+
+        // the long loose match gets the higher raw score
+        let results = matched_entries(entries(&names), "abc");
+        assert_eq!(results[0].name, "a_b_c_very_long_string_here");
+
+        let config = config::Config::default()
+            .score_normalization(config::ScoreNormalization::Length);
+        let results =
+            matched_entries_with_config(entries(&names), "abc", config);
+        assert_eq!(results[0].name, "xabc");
+        assert_eq!(results[1].name, "a_b_c_very_long_string_here");
+        // match ranges still line up with the reordered entries
+        assert_eq!(results[0].name_match_ranges, Some(vec![(1, 4)]));
+    }
+
+    #[test]
+    fn test_normalized_ranking_is_computed_off_results() {
+        let config = config::Config::default()
+            .score_normalization(config::ScoreNormalization::Length);
+        let mut matcher = Matcher::new(config);
+        let injector = matcher.injector();
+        for i in 0..20_000 {
+            injector.push_entry(Entry::new(
+                format!("some/dir_{i}/file_{i}.rs"),
+                PreviewType::Basic,
+            ));
+        }
+        matcher.find("file");
+        // let `Nucleo` match the items without ranking them
+        while matcher.inner.tick(MATCHER_TICK_TIMEOUT).running {}
+
+        // fetching results doesn't go over the whole snapshot, the items
+        // being listed as ranked by `Nucleo` in the meantime
+        assert_eq!(matcher.results(10, 0).len(), 10);
+        assert!(matcher.matched_ranking.is_none());
+        assert!(matcher.pending_matched_ranking.is_none());
+
+        matcher.tick();
+        assert!(matcher.status.running);
+        while matcher.status.running {
+            matcher.tick();
+        }
+        assert_eq!(matcher.ranking().map(<[u32]>::len), Some(20_000));
+        assert_eq!(matcher.results(10, 0).len(), 10);
+    }
+
+    #[test]
+    fn test_normalized_ranking_takes_in_items_pushed_later() {
+        let config = config::Config::default()
+            .score_normalization(config::ScoreNormalization::Length);
+        let mut matcher = Matcher::new(config);
+        let injector = matcher.injector();
+        let names = |matcher: &mut Matcher<Entry>| {
+            while {
+                matcher.tick();
+                matcher.status.running
+            } {}
+            matcher
+                .results(10, 0)
+                .into_iter()
+                .map(|item| item.into_entry().name)
+                .collect::<Vec<_>>()
+        };
+        injector.push_entry(Entry::new(
+            "a_b_c_very_long_string_here".to_string(),
+            PreviewType::Basic,
+        ));
+        injector
+            .push_entry(Entry::new("nope".to_string(), PreviewType::Basic));
+        matcher.find("abc");
+        assert_eq!(names(&mut matcher), ["a_b_c_very_long_string_here"]);
+
+        injector
+            .push_entry(Entry::new("xabc".to_string(), PreviewType::Basic));
+        assert_eq!(
+            names(&mut matcher),
+            ["xabc", "a_b_c_very_long_string_here"]
+        );
+        assert_eq!(matcher.matched_item_count, 2);
+    }
+}
@@ -0,0 +1,178 @@
+//! Delegation of the ranking of the matcher's items to an external program
+//! (see `AppConfig::scorer_command`).
+//!
+//! The program receives the query on the first line of its standard input
+//! followed by the candidates, one per line. It writes one line per matching
+//! candidate on its standard output, made of the candidate's 0-based
+//! position among the candidates and its score separated by whitespace,
+//! e.g. `3 0.75`. Candidates it doesn't list don't match and the others are
+//! ranked from the highest score to the lowest one.
+use std::io::{Read, Write};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::utils::command::shell_command;
+
+/// Build the scorer's input for the given query and candidates.
+///
+/// Line breaks within the query or the candidates are replaced with spaces
+/// so that each of them fits on a single line.
+pub fn serialize_request<'a>(
+    query: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> String {
+    let mut request = single_line(query);
+    request.push('\n');
+    for candidate in candidates {
+        request.push_str(&single_line(candidate));
+        request.push('\n');
+    }
+    request
+}
+
+fn single_line(s: &str) -> String {
+    s.replace(['\n', '\r'], " ")
+}
+
+/// Parse the scorer's output into the positions of the matching
+/// candidates, from the best one to the worst one.
+///
+/// Ties keep the candidates' original order and candidates listed several
+/// times keep their first score.
+///
+/// # Errors
+/// If a line isn't made of a valid candidate position and a score.
+pub fn parse_response(
+    output: &str,
+    candidate_count: usize,
+) -> Result<Vec<u32>> {
+    let mut seen = vec![false; candidate_count];
+    let mut scored = Vec::new();
+    for line in output.lines().filter(|line| !line.trim().is_empty()) {
+        let mut fields = line.split_whitespace();
+        let (Some(index), Some(score), None) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            bail!("Malformed scorer output line {line:?}");
+        };
+        let index = index
+            .parse::<u32>()
+            .ok()
+            .filter(|&i| (i as usize) < candidate_count)
+            .ok_or_else(|| anyhow!("Invalid candidate position {index:?}"))?;
+        let score = score
+            .parse::<f64>()
+            .ok()
+            .filter(|score| !score.is_nan())
+            .ok_or_else(|| anyhow!("Invalid score {score:?}"))?;
+        if !std::mem::replace(&mut seen[index as usize], true) {
+            scored.push((index, score));
+        }
+    }
+    scored.sort_by(|(a_index, a), (b_index, b)| {
+        b.total_cmp(a).then(a_index.cmp(b_index))
+    });
+    Ok(scored.into_iter().map(|(index, _)| index).collect())
+}
+
+/// Run the scorer `command` on the given query and candidates, killing it
+/// if it doesn't complete within `timeout`.
+///
+/// # Errors
+/// If the command can't be run, fails, times out or its output can't be
+/// parsed.
+pub fn rank(
+    command: &str,
+    query: &str,
+    candidates: &[String],
+    timeout: Duration,
+) -> Result<Vec<u32>> {
+    let mut child = shell_command()
+        .arg(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to run scorer {command:?}"))?;
+    let request =
+        serialize_request(query, candidates.iter().map(String::as_str));
+    let mut stdin = child.stdin.take().unwrap();
+    // write from another thread so that a scorer writing its output before
+    // reading all of its input doesn't deadlock
+    let writer = std::thread::spawn(move || {
+        // the scorer may legitimately stop reading early
+        let _ = stdin.write_all(request.as_bytes());
+    });
+    let mut stdout = child.stdout.take().unwrap();
+    let reader = std::thread::spawn(move || {
+        let mut output = Vec::new();
+        let _ = stdout.read_to_end(&mut output);
+        output
+    });
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            bail!("Scorer {command:?} timed out after {timeout:?}");
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    };
+    let _ = writer.join();
+    let output = reader.join().unwrap_or_default();
+    if !status.success() {
+        bail!("Scorer {command:?} exited with {status}");
+    }
+    parse_response(&String::from_utf8_lossy(&output), candidates.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_request() {
+        assert_eq!(
+            serialize_request("ma in", ["src/main.rs", "multi\nline"]),
+            "ma in\nsrc/main.rs\nmulti line\n"
+        );
+        assert_eq!(serialize_request("", []), "\n");
+    }
+
+    #[test]
+    fn test_parse_response() {
+        assert_eq!(
+            parse_response("2 0.5\n0 1.5\n\n3\t0.5\n2 9\n", 4).unwrap(),
+            vec![0, 2, 3]
+        );
+        assert_eq!(parse_response("", 4).unwrap(), Vec::<u32>::new());
+        assert!(parse_response("4 1.0", 4).is_err());
+        assert!(parse_response("1", 4).is_err());
+        assert!(parse_response("1 high", 4).is_err());
+        assert!(parse_response("1 1.0 extra", 4).is_err());
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_rank_with_failing_command() {
+        let timeout = Duration::from_secs(5);
+        let candidates = [String::from("a")];
+        assert!(rank("exit 1", "query", &candidates, timeout).is_err());
+        assert_eq!(
+            rank("echo '0 1'", "query", &candidates, timeout).unwrap(),
+            vec![0]
+        );
+        assert!(rank(
+            "sleep 5",
+            "query",
+            &candidates,
+            Duration::from_millis(50)
+        )
+        .is_err());
+    }
+}
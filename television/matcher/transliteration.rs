@@ -0,0 +1,129 @@
+//! Romanization of non-Latin scripts so that they can be matched with a
+//! Latin keyboard (e.g. `moskva` matches `Москва`).
+//!
+//! Cyrillic and Greek letters are supported, other characters are kept as
+//! is.
+
+/// The romanized form of `text`, if it contains any character to romanize.
+pub fn romanize(text: &str) -> Option<String> {
+    if !text.chars().any(|c| romanize_char(c).is_some()) {
+        return None;
+    }
+    let mut romanized = String::with_capacity(text.len());
+    for c in text.chars() {
+        match romanize_char(c) {
+            Some(latin) => romanized.push_str(&latin),
+            None => romanized.push(c),
+        }
+    }
+    Some(romanized)
+}
+
+/// Map indices of characters of the romanized form of `text` back to the
+/// indices of the characters of `text` they were romanized from.
+///
+/// The returned indices are sorted and deduplicated since several romanized
+/// characters may come from the same character (e.g. `zh` from `ж`).
+pub fn original_indices(text: &str, romanized_indices: &[u32]) -> Vec<u32> {
+    let origins = text
+        .chars()
+        .enumerate()
+        .flat_map(|(i, c)| {
+            let len =
+                romanize_char(c).map_or(1, |latin| latin.chars().count());
+            std::iter::repeat(u32::try_from(i).unwrap_or(u32::MAX)).take(len)
+        })
+        .collect::<Vec<_>>();
+    let mut indices = romanized_indices
+        .iter()
+        .filter_map(|&i| origins.get(i as usize).copied())
+        .collect::<Vec<_>>();
+    indices.sort_unstable();
+    indices.dedup();
+    indices
+}
+
+/// The romanized form of a single character, keeping its case.
+fn romanize_char(c: char) -> Option<String> {
+    let lowercase = c.to_lowercase().next()?;
+    let latin = romanize_lowercase(lowercase)?;
+    if lowercase == c {
+        return Some(latin.to_string());
+    }
+    let mut chars = latin.chars();
+    Some(
+        chars
+            .next()
+            .map(|first| first.to_uppercase().chain(chars).collect())
+            .unwrap_or_default(),
+    )
+}
+
+/// The romanized form of a lowercase Cyrillic or Greek letter.
+fn romanize_lowercase(c: char) -> Option<&'static str> {
+    Some(match c {
+        'а' | 'α' | 'ά' => "a",
+        'б' => "b",
+        'ћ' => "c",
+        'ч' | 'χ' => "ch",
+        'д' | 'δ' => "d",
+        'ђ' => "dj",
+        'џ' => "dz",
+        'е' | 'ё' | 'э' | 'ε' | 'έ' => "e",
+        'ф' | 'φ' => "f",
+        'г' | 'ґ' | 'γ' => "g",
+        'и' | 'і' | 'η' | 'ή' | 'ι' | 'ί' | 'ϊ' | 'ΐ' => "i",
+        'ј' => "j",
+        'к' | 'κ' => "k",
+        'х' => "kh",
+        'л' | 'λ' => "l",
+        'љ' => "lj",
+        'м' | 'μ' => "m",
+        'н' | 'ν' => "n",
+        'њ' => "nj",
+        'о' | 'ο' | 'ό' | 'ω' | 'ώ' => "o",
+        'п' | 'π' => "p",
+        'ψ' => "ps",
+        'р' | 'ρ' => "r",
+        'с' | 'σ' | 'ς' => "s",
+        'ш' => "sh",
+        'щ' => "shch",
+        'т' | 'τ' => "t",
+        'θ' => "th",
+        'ц' => "ts",
+        'у' | 'ў' => "u",
+        'в' | 'β' => "v",
+        'ξ' => "x",
+        'й' | 'ы' | 'υ' | 'ύ' | 'ϋ' | 'ΰ' => "y",
+        'я' => "ya",
+        'є' => "ye",
+        'ї' => "yi",
+        'ю' => "yu",
+        'з' | 'ζ' => "z",
+        'ж' => "zh",
+        // hard and soft signs
+        'ъ' | 'ь' => "",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_romanize() {
+        assert_eq!(romanize("Москва.txt").as_deref(), Some("Moskva.txt"));
+        assert_eq!(romanize("Жук").as_deref(), Some("Zhuk"));
+        assert_eq!(romanize("Αθήνα").as_deref(), Some("Athina"));
+        assert_eq!(romanize("plain.rs"), None);
+    }
+
+    #[test]
+    fn test_original_indices() {
+        // `Zhuk`: `Z` and `h` both come from `Ж`
+        assert_eq!(original_indices("Жук", &[0, 1, 3]), vec![0, 2]);
+        // the hard sign vanishes from the romanized form
+        assert_eq!(original_indices("объект", &[0, 1, 2]), vec![0, 1, 3]);
+    }
+}
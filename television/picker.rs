@@ -9,9 +9,59 @@ pub struct Picker {
     pub(crate) state: ListState,
     pub(crate) relative_state: ListState,
     inverted: bool,
+    /// The number of rows kept visible above and below the selection when
+    /// scrolling (see `UiConfig::scroll_off`).
+    scroll_off: usize,
     pub(crate) input: Input,
     pub entries: Vec<Entry>,
     pub total_items: u32,
+    /// The window of visible entries scrolling towards the selection after
+    /// a jump (see `UiConfig::scroll_animation`).
+    animation: Option<ScrollAnimation>,
+}
+
+/// The window of visible entries scrolling from one offset to another over
+/// a few frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct ScrollAnimation {
+    from: usize,
+    to: usize,
+    frame: u16,
+    frames: u16,
+}
+
+impl ScrollAnimation {
+    fn offset(&self) -> usize {
+        interpolate_offset(self.from, self.to, self.frame, self.frames)
+    }
+
+    /// Move on to the next frame, returning whether the animation is still
+    /// running.
+    fn advance(&mut self) -> bool {
+        self.frame = self.frame.saturating_add(1);
+        self.frame < self.frames
+    }
+}
+
+/// The offset reached `frame` frames into scrolling from `from` to `to` in
+/// `frames` frames, the scrolling slowing down when nearing `to`.
+pub(crate) fn interpolate_offset(
+    from: usize,
+    to: usize,
+    frame: u16,
+    frames: u16,
+) -> usize {
+    if frame >= frames {
+        return to;
+    }
+    // the remaining distance shrinks quadratically
+    let remaining = usize::from(frames - frame).pow(2);
+    let left = from.abs_diff(to) * remaining / usize::from(frames).pow(2);
+    if from < to {
+        to - left
+    } else {
+        to + left
+    }
 }
 
 impl Default for Picker {
@@ -26,9 +76,11 @@ impl Picker {
             state: ListState::default(),
             relative_state: ListState::default(),
             inverted: false,
+            scroll_off: 0,
             input: Input::new(input.unwrap_or(EMPTY_STRING.to_string())),
             entries: Vec::new(),
             total_items: 0,
+            animation: None,
         }
     }
 
@@ -38,16 +90,126 @@ impl Picker {
             .saturating_sub(self.relative_selected().unwrap_or(0))
     }
 
+    /// The offset of the window of visible entries, which lags behind
+    /// `offset` while a scroll animation is running.
+    pub(crate) fn view_offset(&self) -> usize {
+        self.animation
+            .map_or_else(|| self.offset(), |animation| animation.offset())
+    }
+
+    /// Scroll the window of visible entries from offset `from` to the
+    /// current one over `frames` frames if they are more than `threshold`
+    /// entries apart.
+    pub(crate) fn animate_scroll(
+        &mut self,
+        from: usize,
+        frames: u16,
+        threshold: usize,
+    ) {
+        let to = self.offset();
+        self.animation = (frames > 0 && from.abs_diff(to) > threshold)
+            .then_some(ScrollAnimation {
+                from,
+                to,
+                frame: 0,
+                frames,
+            });
+    }
+
+    /// Whether a scroll animation is running.
+    pub(crate) fn animating(&self) -> bool {
+        self.animation.is_some()
+    }
+
+    /// Move the scroll animation on to its next frame.
+    pub(crate) fn tick_animation(&mut self) {
+        if let Some(animation) = self.animation.as_mut() {
+            if !animation.advance() {
+                self.animation = None;
+            }
+        }
+    }
+
+    /// Stop the scroll animation if the selection has moved the window of
+    /// visible entries elsewhere since it started.
+    pub(crate) fn settle_animation(&mut self) {
+        let offset = self.offset();
+        if self
+            .animation
+            .is_some_and(|animation| animation.to != offset)
+        {
+            self.animation = None;
+        }
+    }
+
+    /// A copy of the picker whose relative selection is relative to the
+    /// window currently shown, the selection being highlighted only once it
+    /// is in sight.
+    pub(crate) fn animated_view(&self) -> Self {
+        let mut view = self.clone();
+        if self.animating() {
+            view.relative_select(
+                self.selected()
+                    .and_then(|i| i.checked_sub(self.view_offset()))
+                    .filter(|&i| i < self.entries.len()),
+            );
+        }
+        view
+    }
+
+    /// Whether selecting the next entry moves towards the first one.
+    pub(crate) fn is_inverted(&self) -> bool {
+        self.inverted
+    }
+
     pub(crate) fn inverted(mut self) -> Self {
         self.inverted = !self.inverted;
         self
     }
 
+    pub(crate) fn with_scroll_off(mut self, scroll_off: usize) -> Self {
+        self.scroll_off = scroll_off;
+        self
+    }
+
+    /// The scroll-off margin that fits in a window of `height` entries.
+    fn margin(&self, height: usize) -> usize {
+        self.scroll_off.min(height.saturating_sub(1) / 2)
+    }
+
     pub(crate) fn reset_selection(&mut self) {
         self.state.select(Some(0));
         self.relative_state.select(Some(0));
     }
 
+    /// Select the entry at the given index, keeping it within a window of
+    /// `height` visible entries.
+    pub(crate) fn select_index(&mut self, index: usize, height: usize) {
+        self.select(Some(index));
+        self.relative_select(Some(index.min(height.saturating_sub(1))));
+    }
+
+    /// Select the entry at position `relative_index` in the window of
+    /// `height` currently visible entries.
+    ///
+    /// # Returns
+    /// Whether the position was in range, in which case the selection was
+    /// updated.
+    pub(crate) fn select_visible(
+        &mut self,
+        relative_index: usize,
+        total_items: usize,
+        height: usize,
+    ) -> bool {
+        let index = self.offset() + relative_index;
+        if relative_index >= height || index >= total_items {
+            return false;
+        }
+        self.select(Some(index));
+        self.relative_select(Some(relative_index));
+        true
+    }
+
     pub(crate) fn reset_input(&mut self) {
         self.input.reset();
     }
@@ -74,15 +236,7 @@ impl Picker {
         total_items: usize,
         height: usize,
     ) {
-        if self.inverted {
-            for _ in 0..step {
-                self.inner_prev(total_items, height);
-            }
-        } else {
-            for _ in 0..step {
-                self.inner_next(total_items, height);
-            }
-        }
+        self.select_next_with_heights(step, total_items, height, |_| 1);
     }
 
     pub(crate) fn select_prev(
@@ -91,35 +245,99 @@ impl Picker {
         total_items: usize,
         height: usize,
     ) {
-        if self.inverted {
-            for _ in 0..step {
-                self.inner_next(total_items, height);
+        self.select_prev_with_heights(step, total_items, height, |_| 1);
+    }
+
+    /// Select the next entry in a list whose entries may span several rows.
+    ///
+    /// `height` is the number of rows of the window and `item_height` gives
+    /// the number of rows taken by the entry at a given index, each entry
+    /// being a single selectable unit whatever its height.
+    pub(crate) fn select_next_with_heights(
+        &mut self,
+        step: u32,
+        total_items: usize,
+        height: usize,
+        mut item_height: impl FnMut(usize) -> usize,
+    ) {
+        for _ in 0..step {
+            if self.inverted {
+                self.inner_prev(total_items, height, &mut item_height);
+            } else {
+                self.inner_next(total_items, height, &mut item_height);
             }
-        } else {
-            for _ in 0..step {
-                self.inner_prev(total_items, height);
+        }
+    }
+
+    /// Select the previous entry in a list whose entries may span several
+    /// rows (see [`Picker::select_next_with_heights`]).
+    pub(crate) fn select_prev_with_heights(
+        &mut self,
+        step: u32,
+        total_items: usize,
+        height: usize,
+        mut item_height: impl FnMut(usize) -> usize,
+    ) {
+        for _ in 0..step {
+            if self.inverted {
+                self.inner_next(total_items, height, &mut item_height);
+            } else {
+                self.inner_prev(total_items, height, &mut item_height);
             }
         }
     }
 
-    fn inner_next(&mut self, total_items: usize, height: usize) {
+    fn inner_next(
+        &mut self,
+        total_items: usize,
+        height: usize,
+        item_height: &mut impl FnMut(usize) -> usize,
+    ) {
         let selected = self.selected().unwrap_or(0);
-        let relative_selected = self.relative_selected().unwrap_or(0);
-        self.select(Some(selected.saturating_add(1) % total_items));
-        self.relative_select(Some((relative_selected + 1).min(height - 1)));
-        if self.selected().unwrap() == 0 {
+        let mut offset = self.offset();
+        let next = selected.saturating_add(1) % total_items;
+        self.select(Some(next));
+        if next == 0 {
             self.relative_select(Some(0));
+            return;
         }
+        // keep the margin below the selection unless the list ends first
+        let last_visible =
+            next + self.margin(height).min(total_items - 1 - next);
+        // scroll down until everything up to the last visible entry fits
+        let mut rows: usize =
+            (offset..=last_visible).map(&mut *item_height).sum();
+        while rows > height && offset < next {
+            rows -= item_height(offset);
+            offset += 1;
+        }
+        self.relative_select(Some(next - offset));
     }
 
-    fn inner_prev(&mut self, total_items: usize, height: usize) {
+    fn inner_prev(
+        &mut self,
+        total_items: usize,
+        height: usize,
+        item_height: &mut impl FnMut(usize) -> usize,
+    ) {
         let selected = self.selected().unwrap_or(0);
-        let relative_selected = self.relative_selected().unwrap_or(0);
-        self.select(Some((selected + (total_items - 1)) % total_items));
-        self.relative_select(Some(relative_selected.saturating_sub(1)));
-        if self.selected().unwrap() == total_items - 1 {
-            self.relative_select(Some((height - 1).min(total_items - 1)));
+        let offset = self.offset();
+        let prev = (selected + (total_items - 1)) % total_items;
+        self.select(Some(prev));
+        if prev == total_items - 1 {
+            // fill the window with the entries preceding the last one
+            let mut first = prev;
+            let mut rows = item_height(prev);
+            while first > 0 && rows + item_height(first - 1) <= height {
+                first -= 1;
+                rows += item_height(first);
+            }
+            self.relative_select(Some(prev - first));
+            return;
         }
+        // keep the margin above the selection unless the list starts first
+        let first_visible = prev - self.margin(height).min(prev);
+        self.relative_select(Some(prev - offset.min(first_visible)));
     }
 }
 
@@ -253,6 +471,133 @@ mod tests {
         assert_eq!(picker.relative_selected(), Some(1), "relative_selected");
     }
 
+    /// - item 0         *
+    /// - item 1 S     R *
+    /// - item 2 next    *
+    /// - item 3         * height
+    /// - item 4
+    #[test]
+    fn test_picker_select_next_scroll_off() {
+        let mut picker = Picker::default().with_scroll_off(1);
+        picker.select(Some(1));
+        picker.relative_select(Some(1));
+        picker.select_next(1, 10, 4);
+        assert_eq!(picker.selected(), Some(2), "selected");
+        assert_eq!(picker.relative_selected(), Some(2), "relative_selected");
+        // the viewport scrolls one row before reaching the edge
+        picker.select_next(1, 10, 4);
+        assert_eq!(picker.selected(), Some(3), "selected");
+        assert_eq!(picker.relative_selected(), Some(2), "relative_selected");
+        assert_eq!(picker.offset(), 1, "offset");
+    }
+
+    /// - item 6
+    /// - item 7       R *
+    /// - item 8 S       *
+    /// - item 9 next    *
+    #[test]
+    fn test_picker_select_next_scroll_off_end_of_list() {
+        let mut picker = Picker::default().with_scroll_off(1);
+        picker.select(Some(8));
+        picker.relative_select(Some(1));
+        picker.select_next(1, 10, 3);
+        // the margin doesn't apply past the last entry
+        assert_eq!(picker.selected(), Some(9), "selected");
+        assert_eq!(picker.relative_selected(), Some(2), "relative_selected");
+        assert_eq!(picker.offset(), 7, "offset");
+    }
+
+    /// - item 4
+    /// - item 5 prev
+    /// - item 6 S     R *
+    /// - item 7         *
+    /// - item 8         * height
+    #[test]
+    fn test_picker_select_prev_scroll_off() {
+        let mut picker = Picker::default().with_scroll_off(1);
+        picker.select(Some(6));
+        picker.relative_select(Some(1));
+        picker.select_prev(1, 10, 3);
+        assert_eq!(picker.selected(), Some(5), "selected");
+        assert_eq!(picker.relative_selected(), Some(1), "relative_selected");
+        assert_eq!(picker.offset(), 4, "offset");
+        // down to the first entry, the margin doesn't apply anymore
+        picker.select_prev(5, 10, 3);
+        assert_eq!(picker.selected(), Some(0), "selected");
+        assert_eq!(picker.relative_selected(), Some(0), "relative_selected");
+    }
+
+    #[test]
+    fn test_picker_scroll_off_larger_than_height() {
+        let mut picker = Picker::default().with_scroll_off(10);
+        picker.select(Some(0));
+        picker.relative_select(Some(0));
+        picker.select_next(3, 10, 5);
+        // the margin is capped to half the window
+        assert_eq!(picker.selected(), Some(3), "selected");
+        assert_eq!(picker.relative_selected(), Some(2), "relative_selected");
+        // wrapping around still works
+        picker.select_prev(4, 10, 5);
+        assert_eq!(picker.selected(), Some(9), "selected");
+        assert_eq!(picker.relative_selected(), Some(4), "relative_selected");
+    }
+
+    /// - item 0   (1 row)         *
+    /// - item 1   (3 rows) S     R *
+    /// - item 2   (1 row)  next    * height (5 rows)
+    /// - item 3   (2 rows)
+    #[test]
+    fn test_picker_select_with_multi_line_entries() {
+        let heights = [1, 3, 1, 2, 1];
+        let item_height = |i: usize| heights[i];
+        let mut picker = Picker::default();
+        picker.select(Some(1));
+        picker.relative_select(Some(1));
+
+        picker.select_next_with_heights(1, 5, 5, item_height);
+        assert_eq!(picker.selected(), Some(2), "selected");
+        assert_eq!(picker.relative_selected(), Some(2), "relative_selected");
+        // item 3 doesn't fit below item 2: scroll until it does
+        picker.select_next_with_heights(1, 5, 5, item_height);
+        assert_eq!(picker.selected(), Some(3), "selected");
+        assert_eq!(picker.relative_selected(), Some(1), "relative_selected");
+        assert_eq!(picker.offset(), 2, "offset");
+        picker.select_next_with_heights(1, 5, 5, item_height);
+        assert_eq!(picker.selected(), Some(4), "selected");
+        assert_eq!(picker.offset(), 2, "offset");
+
+        // wrapping around to the top
+        picker.select_next_with_heights(1, 5, 5, item_height);
+        assert_eq!(picker.selected(), Some(0), "selected");
+        assert_eq!(picker.relative_selected(), Some(0), "relative_selected");
+        // and back to the bottom, showing as many entries as fit
+        picker.select_prev_with_heights(1, 5, 5, item_height);
+        assert_eq!(picker.selected(), Some(4), "selected");
+        assert_eq!(picker.offset(), 2, "offset");
+        // moving up only scrolls once the top of the window is reached
+        picker.select_prev_with_heights(2, 5, 5, item_height);
+        assert_eq!(picker.selected(), Some(2), "selected");
+        assert_eq!(picker.relative_selected(), Some(0), "relative_selected");
+        picker.select_prev_with_heights(1, 5, 5, item_height);
+        assert_eq!(picker.selected(), Some(1), "selected");
+        assert_eq!(picker.relative_selected(), Some(0), "relative_selected");
+    }
+
+    #[test]
+    fn test_picker_select_entry_taller_than_window() {
+        let heights = [1, 6, 1];
+        let mut picker = Picker::default();
+        picker.select(Some(0));
+        picker.relative_select(Some(0));
+        picker.select_next_with_heights(1, 3, 4, |i| heights[i]);
+        // the entry is selected at the top of the window
+        assert_eq!(picker.selected(), Some(1), "selected");
+        assert_eq!(picker.relative_selected(), Some(0), "relative_selected");
+        picker.select_next_with_heights(1, 3, 4, |i| heights[i]);
+        assert_eq!(picker.selected(), Some(2), "selected");
+        assert_eq!(picker.relative_selected(), Some(0), "relative_selected");
+    }
+
     #[test]
     fn test_picker_offset_default() {
         let picker = Picker::default();
@@ -287,4 +632,72 @@ mod tests {
         assert_eq!(picker.selected(), Some(0), "selected");
         assert_eq!(picker.relative_selected(), Some(0), "relative_selected");
     }
+
+    /// - item 3
+    /// - item 4 S     R *
+    /// - item 5         *
+    /// - item 6         * height
+    /// - item 7
+    #[test]
+    fn test_picker_select_visible() {
+        let mut picker = Picker::default();
+        picker.select(Some(4));
+        picker.relative_select(Some(0));
+        assert!(picker.select_visible(2, 8, 3));
+        assert_eq!(picker.selected(), Some(6), "selected");
+        assert_eq!(picker.relative_selected(), Some(2), "relative_selected");
+        assert_eq!(picker.offset(), 4, "offset");
+    }
+
+    #[test]
+    fn test_interpolate_offset() {
+        let steps = (0..=6)
+            .map(|frame| interpolate_offset(10, 70, frame, 6))
+            .collect::<Vec<_>>();
+        assert_eq!(steps, vec![10, 29, 44, 55, 64, 69, 70]);
+        // scrolling up
+        let steps = (0..=6)
+            .map(|frame| interpolate_offset(70, 10, frame, 6))
+            .collect::<Vec<_>>();
+        assert_eq!(steps, vec![70, 51, 36, 25, 16, 11, 10]);
+        assert_eq!(interpolate_offset(10, 70, 9, 6), 70);
+        assert_eq!(interpolate_offset(10, 70, 0, 0), 70);
+    }
+
+    #[test]
+    fn test_picker_animate_scroll() {
+        let mut picker = Picker::default();
+        picker.select(Some(50));
+        picker.relative_select(Some(0));
+        // small jumps aren't animated
+        picker.animate_scroll(48, 2, 3);
+        assert!(!picker.animating());
+
+        picker.animate_scroll(10, 2, 3);
+        assert_eq!(picker.view_offset(), 10);
+        picker.tick_animation();
+        assert_eq!(picker.view_offset(), 40);
+        picker.tick_animation();
+        assert!(!picker.animating());
+        assert_eq!(picker.view_offset(), 50);
+
+        // the animation stops when the selection moves elsewhere
+        picker.animate_scroll(10, 2, 3);
+        picker.select(Some(0));
+        picker.settle_animation();
+        assert!(!picker.animating());
+    }
+
+    #[test]
+    fn test_picker_select_visible_out_of_range() {
+        let mut picker = Picker::default();
+        picker.select(Some(4));
+        picker.relative_select(Some(1));
+        // beyond the visible window
+        assert!(!picker.select_visible(3, 8, 3));
+        // beyond the last entry
+        assert!(!picker.select_visible(2, 5, 3));
+        assert_eq!(picker.selected(), Some(4), "selected");
+        assert_eq!(picker.relative_selected(), Some(1), "relative_selected");
+    }
 }
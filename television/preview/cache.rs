@@ -45,6 +45,11 @@ impl PreviewCache {
         }
     }
 
+    /// Remove the preview for the given key, if any.
+    pub fn remove(&mut self, key: &str) {
+        self.entries.remove(key);
+    }
+
     /// Get the preview for the given key, or insert a new preview if it doesn't exist.
     #[allow(dead_code)]
     pub fn get_or_insert<F>(&mut self, key: String, f: F) -> Arc<Preview>
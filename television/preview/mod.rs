@@ -1,12 +1,17 @@
 use std::sync::Arc;
 
-use crate::channels::entry::{Entry, PreviewType};
+use crate::action::Action;
+use crate::channels::entry::{Entry, EntryKind, PreviewCommand, PreviewType};
+use crate::channels::OnAir;
 use devicons::FileIcon;
 use ratatui::layout::Rect;
+use rustc_hash::FxHashMap;
+use search::PreviewSearch;
 
 pub mod ansi;
 pub mod cache;
 pub mod previewers;
+pub mod search;
 
 // previewer types
 use crate::utils::cache::RingSet;
@@ -20,6 +25,8 @@ pub use previewers::env::EnvVarPreviewer;
 pub use previewers::env::EnvVarPreviewerConfig;
 pub use previewers::files::FilePreviewer;
 pub use previewers::files::FilePreviewerConfig;
+pub use previewers::guide::GuidePreviewer;
+use previewers::meta;
 
 #[derive(Clone, Debug, PartialEq, Hash)]
 pub enum PreviewContent {
@@ -29,6 +36,7 @@ pub enum PreviewContent {
     Loading,
     Timeout,
     NotSupported,
+    Unavailable,
     PlainText(Vec<String>),
     PlainTextWrapped(String),
     AnsiText(String),
@@ -53,6 +61,22 @@ impl PreviewContent {
             _ => 0,
         }
     }
+
+    /// The lines of text the preview search looks into.
+    ///
+    /// Only previews made of lines of plain or highlighted text can be
+    /// searched.
+    pub fn searchable_lines(&self) -> Vec<String> {
+        match self {
+            PreviewContent::PlainText(lines) => lines.clone(),
+            PreviewContent::SyntectHighlightedText(hl_lines) => hl_lines
+                .lines
+                .iter()
+                .map(|line| line.iter().map(|(_, s)| s.as_str()).collect())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
 }
 
 pub const PREVIEW_NOT_SUPPORTED_MSG: &str =
@@ -60,6 +84,8 @@ pub const PREVIEW_NOT_SUPPORTED_MSG: &str =
 pub const FILE_TOO_LARGE_MSG: &str = "File too large";
 pub const LOADING_MSG: &str = "Loading...";
 pub const TIMEOUT_MSG: &str = "Preview timed out";
+pub const BINARY_FILE_MSG: &str = "Binary file";
+pub const NO_PREVIEW_MSG: &str = "No preview available";
 
 /// A preview of an entry.
 ///
@@ -74,6 +100,10 @@ pub struct Preview {
     /// If the preview is partial, this field contains the byte offset
     /// up to which the preview holds.
     pub partial_offset: Option<usize>,
+    /// The header shown above the preview (see
+    /// `UiConfig::show_preview_header`), the entry's display string being
+    /// shown if there is none.
+    pub header: Option<String>,
     pub total_lines: u16,
 }
 
@@ -84,6 +114,7 @@ impl Default for Preview {
             content: PreviewContent::Empty,
             icon: None,
             partial_offset: None,
+            header: None,
             total_lines: 0,
         }
     }
@@ -102,6 +133,7 @@ impl Preview {
             content,
             icon,
             partial_offset,
+            header: None,
             total_lines,
         }
     }
@@ -113,6 +145,10 @@ pub struct PreviewState {
     pub preview: Arc<Preview>,
     pub scroll: u16,
     pub target_line: Option<u16>,
+    /// Whether the scroll offset is kept when the previewed entry changes.
+    pub scroll_locked: bool,
+    /// The search within the preview's content, if any.
+    pub search: Option<PreviewSearch>,
 }
 
 const PREVIEW_MIN_SCROLL_LINES: u16 = 3;
@@ -129,6 +165,8 @@ impl PreviewState {
             preview,
             scroll,
             target_line,
+            scroll_locked: false,
+            search: None,
         }
     }
 
@@ -144,10 +182,145 @@ impl PreviewState {
         self.scroll = self.scroll.saturating_sub(offset);
     }
 
+    pub fn scroll_to_top(&mut self) {
+        self.scroll = 0;
+    }
+
+    /// Scroll to the end of the preview, making sure the last line sits at the
+    /// bottom of a pane of the given height rather than at its top.
+    pub fn scroll_to_bottom(&mut self, pane_height: u16) {
+        self.scroll =
+            bottom_scroll_offset(self.preview.total_lines, pane_height);
+    }
+
+    /// Move the preview's current line down by `offset` lines.
+    ///
+    /// If no line is current yet, the cursor starts from the first visible
+    /// line. The preview is scrolled so that the current line stays within a
+    /// pane of the given height.
+    pub fn cursor_down(&mut self, offset: u16, pane_height: u16) {
+        let line = self
+            .target_line
+            .map_or(self.scroll.saturating_add(1), |l| {
+                l.saturating_add(offset)
+            })
+            .min(self.preview.total_lines.max(1));
+        self.target_line = Some(line);
+        self.follow_cursor(pane_height);
+    }
+
+    /// Move the preview's current line up by `offset` lines.
+    ///
+    /// See [`PreviewState::cursor_down`].
+    pub fn cursor_up(&mut self, offset: u16, pane_height: u16) {
+        let line = self
+            .target_line
+            .map_or(self.scroll.saturating_add(1), |l| {
+                l.saturating_sub(offset)
+            })
+            .max(1);
+        self.target_line = Some(line);
+        self.follow_cursor(pane_height);
+    }
+
+    /// Scroll the preview just enough for the current line to be visible.
+    fn follow_cursor(&mut self, pane_height: u16) {
+        let Some(line) = self.target_line else {
+            return;
+        };
+        // `target_line` is 1-based while `scroll` is the index of the first
+        // visible line
+        let index = line.saturating_sub(1);
+        if index < self.scroll {
+            self.scroll = index;
+        } else if index >= self.scroll.saturating_add(pane_height) {
+            self.scroll = index.saturating_sub(pane_height.saturating_sub(1));
+        }
+    }
+
+    pub fn toggle_scroll_lock(&mut self) {
+        self.scroll_locked = !self.scroll_locked;
+    }
+
+    /// Start typing a search query, keeping the previous one if any.
+    pub fn start_search(&mut self) {
+        match self.search.as_mut() {
+            Some(search) => search.editing = true,
+            None => self.search = Some(PreviewSearch::new()),
+        }
+    }
+
+    pub fn close_search(&mut self) {
+        self.search = None;
+    }
+
+    /// Edit the search query with the given input action and scroll to the
+    /// first match of the new query.
+    pub fn handle_search_input(&mut self, action: &Action, pane_height: u16) {
+        let Some(search) = self.search.as_mut() else {
+            return;
+        };
+        if search.handle_input_action(action) {
+            self.refresh_search();
+            self.scroll_to_search_match(pane_height);
+        }
+    }
+
+    /// Stop typing the search query and scroll to the current match.
+    pub fn confirm_search(&mut self, pane_height: u16) {
+        if let Some(search) = self.search.as_mut() {
+            search.editing = false;
+            self.scroll_to_search_match(pane_height);
+        }
+    }
+
+    /// Move to the next search match, scrolling the preview to it.
+    pub fn next_search_match(&mut self, pane_height: u16) {
+        if let Some(search) = self.search.as_mut() {
+            search.editing = false;
+            search.next_match();
+            self.scroll_to_search_match(pane_height);
+        }
+    }
+
+    /// Move to the previous search match, scrolling the preview to it.
+    pub fn prev_search_match(&mut self, pane_height: u16) {
+        if let Some(search) = self.search.as_mut() {
+            search.editing = false;
+            search.prev_match();
+            self.scroll_to_search_match(pane_height);
+        }
+    }
+
+    /// Recompute the search matches against the current preview.
+    fn refresh_search(&mut self) {
+        if let Some(search) = self.search.as_mut() {
+            search.update_matches(&self.preview.content.searchable_lines());
+        }
+    }
+
+    /// Center the current search match in a pane of the given height unless
+    /// it is already visible.
+    fn scroll_to_search_match(&mut self, pane_height: u16) {
+        let Some(m) = self.search.as_ref().and_then(PreviewSearch::current)
+        else {
+            return;
+        };
+        let line = u16::try_from(m.line).unwrap_or(u16::MAX);
+        if line < self.scroll
+            || line >= self.scroll.saturating_add(pane_height)
+        {
+            self.scroll = line.saturating_sub(pane_height / 2);
+        }
+    }
+
     pub fn reset(&mut self) {
         self.preview = Arc::new(Preview::default());
-        self.scroll = 0;
+        if !self.scroll_locked {
+            self.scroll = 0;
+        }
         self.target_line = None;
+        self.refresh_search();
     }
 
     pub fn update(
@@ -158,19 +331,40 @@ impl PreviewState {
     ) {
         if self.preview.title != preview.title {
             self.preview = preview;
-            self.scroll = scroll;
+            if !self.scroll_locked {
+                self.scroll = scroll;
+            }
             self.target_line = target_line;
+            self.refresh_search();
         }
     }
 }
 
+/// The scroll offset at which `line` is displayed in the middle of a pane
+/// of `pane_height` rows.
+pub fn centered_scroll_offset(line: u16, pane_height: u16) -> u16 {
+    line.saturating_sub(pane_height / 2)
+}
+
+/// The scroll offset at which the last line of a preview made of
+/// `total_lines` lines is displayed on the last row of a pane of
+/// `pane_height` rows.
+pub fn bottom_scroll_offset(total_lines: u16, pane_height: u16) -> u16 {
+    total_lines.saturating_sub(pane_height)
+}
+
 #[derive(Debug, Default)]
 pub struct Previewer {
     basic: BasicPreviewer,
     file: FilePreviewer,
     env_var: EnvVarPreviewer,
     command: CommandPreviewer,
+    guide: GuidePreviewer,
     requests: RingSet<Entry>,
+    command_config: CommandPreviewerConfig,
+    /// The previewers of the commands cycled to with
+    /// `Action::CyclePreviewer`, by command, each having its own cache.
+    alternatives: FxHashMap<String, CommandPreviewer>,
 }
 
 #[derive(Debug, Default)]
@@ -196,6 +390,11 @@ impl PreviewerConfig {
         self.env_var = config;
         self
     }
+
+    pub fn command(mut self, config: CommandPreviewerConfig) -> Self {
+        self.command = config;
+        self
+    }
 }
 
 const REQUEST_STACK_SIZE: usize = 10;
@@ -207,16 +406,39 @@ impl Previewer {
             basic: BasicPreviewer::new(Some(config.basic)),
             file: FilePreviewer::new(Some(config.file)),
             env_var: EnvVarPreviewer::new(Some(config.env_var)),
-            command: CommandPreviewer::new(Some(config.command)),
+            command: CommandPreviewer::new(Some(config.command.clone())),
+            guide: GuidePreviewer::default(),
             requests: RingSet::with_capacity(REQUEST_STACK_SIZE),
+            command_config: config.command,
+            alternatives: FxHashMap::default(),
         }
     }
 
+    /// Preview the entry with the given command rather than with its own
+    /// preview type (see `Action::CyclePreviewer`).
+    pub fn preview_with_command(
+        &mut self,
+        entry: &Entry,
+        command: &PreviewCommand,
+    ) -> Option<Arc<Preview>> {
+        if !entry.previewable {
+            return Some(meta::unavailable(&entry.name));
+        }
+        let config = self.command_config.clone();
+        self.alternatives
+            .entry(command.command.clone())
+            .or_insert_with(|| CommandPreviewer::new(Some(config)))
+            .preview(entry, command)
+    }
+
     fn dispatch_request(
         &mut self,
         entry: &Entry,
         preview_window: Option<Rect>,
     ) -> Option<Arc<Preview>> {
+        if self.previews_as_tree(entry) {
+            return self.file.preview(entry, preview_window);
+        }
         match &entry.preview_type {
             PreviewType::Basic => Some(self.basic.preview(entry)),
             PreviewType::EnvVar => Some(self.env_var.preview(entry)),
@@ -226,7 +448,21 @@ impl Previewer {
         }
     }
 
+    /// Whether a preview was requested for the given entry.
+    pub fn is_requested(&self, entry: &Entry) -> bool {
+        self.requests.contains(entry)
+    }
+
+    /// Whether the entry is a directory previewed as a tree by the file
+    /// previewer rather than by its own preview type.
+    fn previews_as_tree(&self, entry: &Entry) -> bool {
+        entry.kind == EntryKind::Directory && self.file.previews_directories()
+    }
+
     fn cached(&self, entry: &Entry) -> Option<Arc<Preview>> {
+        if self.previews_as_tree(entry) {
+            return self.file.cached(entry);
+        }
         match &entry.preview_type {
             PreviewType::Basic => Some(self.basic.preview(entry)),
             PreviewType::EnvVar => Some(self.env_var.preview(entry)),
@@ -245,6 +481,9 @@ impl Previewer {
         entry: &Entry,
         preview_window: Option<Rect>,
     ) -> Option<Arc<Preview>> {
+        if !entry.previewable {
+            return Some(meta::unavailable(&entry.name));
+        }
         // check if we have a preview for the current request
         if let Some(preview) = self.cached(entry) {
             return Some(preview);
@@ -264,9 +503,216 @@ impl Previewer {
         None
     }
 
+    /// The preview of a channel highlighted in the remote control, see
+    /// [`GuidePreviewer::preview`].
+    pub fn guide_preview<F, C>(
+        &mut self,
+        channel_name: &str,
+        num_entries: u32,
+        zap: F,
+    ) -> Option<Arc<Preview>>
+    where
+        F: FnOnce() -> anyhow::Result<C> + Send + 'static,
+        C: OnAir,
+    {
+        self.guide.preview(channel_name, num_entries, zap)
+    }
+
+    /// Drop the preview of the given channel of the remote control so that
+    /// its entries are sampled again.
+    pub fn invalidate_guide_preview(&mut self, channel_name: &str) {
+        self.guide.invalidate(channel_name);
+    }
+
     pub fn set_config(&mut self, config: PreviewerConfig) {
         self.basic = BasicPreviewer::new(Some(config.basic));
         self.file = FilePreviewer::new(Some(config.file));
         self.env_var = EnvVarPreviewer::new(Some(config.env_var));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preview_state_with_lines(total_lines: u16) -> PreviewState {
+        PreviewState::new(
+            true,
+            Arc::new(Preview::new(
+                "title".to_string(),
+                PreviewContent::Empty,
+                None,
+                None,
+                total_lines,
+            )),
+            0,
+            None,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_non_previewable_entry_yields_placeholder() {
+        let mut previewer = Previewer::new(None);
+        let entry = Entry::new(String::from("Cargo.toml"), PreviewType::Files)
+            .with_previewable(false);
+        let preview = previewer.preview(&entry, None).unwrap();
+        assert_eq!(preview.title, "Cargo.toml");
+        assert!(matches!(preview.content, PreviewContent::Unavailable));
+        // no preview generation was attempted
+        assert!(!previewer.is_requested(&entry));
+    }
+
+    #[test]
+    fn test_centered_scroll_offset() {
+        // e.g. `--preview-line 120` with a 40 rows high preview
+        assert_eq!(centered_scroll_offset(120, 40), 100);
+        assert_eq!(centered_scroll_offset(120, 41), 100);
+        // lines near the top don't scroll past the start
+        assert_eq!(centered_scroll_offset(15, 40), 0);
+        assert_eq!(centered_scroll_offset(0, 0), 0);
+    }
+
+    #[test]
+    fn test_bottom_scroll_offset() {
+        assert_eq!(bottom_scroll_offset(100, 20), 80);
+        assert_eq!(bottom_scroll_offset(20, 20), 0);
+        // content shorter than the pane shouldn't scroll at all
+        assert_eq!(bottom_scroll_offset(5, 20), 0);
+        assert_eq!(bottom_scroll_offset(0, 0), 0);
+    }
+
+    #[test]
+    fn test_cursor_starts_at_first_visible_line() {
+        let mut state = preview_state_with_lines(100);
+        state.scroll = 10;
+
+        state.cursor_down(1, 20);
+        assert_eq!(state.target_line, Some(11));
+        assert_eq!(state.scroll, 10);
+    }
+
+    #[test]
+    fn test_cursor_auto_scrolls_on_edges() {
+        let mut state = preview_state_with_lines(100);
+
+        // moving within the pane doesn't scroll
+        for _ in 0..20 {
+            state.cursor_down(1, 20);
+        }
+        assert_eq!(state.target_line, Some(20));
+        assert_eq!(state.scroll, 0);
+
+        // going past the bottom edge scrolls by one line
+        state.cursor_down(1, 20);
+        assert_eq!(state.target_line, Some(21));
+        assert_eq!(state.scroll, 1);
+
+        // moving back up within the pane doesn't scroll
+        state.cursor_up(19, 20);
+        assert_eq!(state.target_line, Some(2));
+        assert_eq!(state.scroll, 1);
+
+        // going past the top edge scrolls back up
+        state.cursor_up(1, 20);
+        assert_eq!(state.target_line, Some(1));
+        assert_eq!(state.scroll, 0);
+    }
+
+    #[test]
+    fn test_cursor_is_clamped_to_content() {
+        let mut state = preview_state_with_lines(30);
+
+        state.cursor_up(5, 20);
+        assert_eq!(state.target_line, Some(1));
+
+        state.cursor_down(100, 20);
+        assert_eq!(state.target_line, Some(30));
+        assert_eq!(state.scroll, 10);
+    }
+
+    #[test]
+    fn test_scroll_to_bottom_and_top() {
+        let mut state = preview_state_with_lines(100);
+
+        state.scroll_to_bottom(20);
+        assert_eq!(state.scroll, 80);
+
+        state.scroll_to_top();
+        assert_eq!(state.scroll, 0);
+    }
+
+    #[test]
+    fn test_search_scrolls_to_matches() {
+        let lines = (0..100)
+            .map(|i| if i % 40 == 10 { "needle" } else { "hay" }.to_string())
+            .collect::<Vec<_>>();
+        let mut state = PreviewState::new(
+            true,
+            Arc::new(Preview::new(
+                "title".to_string(),
+                PreviewContent::PlainText(lines),
+                None,
+                None,
+                100,
+            )),
+            0,
+            None,
+        );
+
+        state.start_search();
+        for c in "needle".chars() {
+            state.handle_search_input(&Action::AddInputChar(c), 20);
+        }
+        let search = state.search.as_ref().unwrap();
+        assert_eq!(search.matches().len(), 3);
+        // the first match is already visible
+        assert_eq!(state.scroll, 0);
+
+        state.next_search_match(20);
+        assert!(!state.search.as_ref().unwrap().editing);
+        assert_eq!(state.scroll, 40);
+        state.next_search_match(20);
+        assert_eq!(state.scroll, 80);
+        // wrapping around to the first match
+        state.next_search_match(20);
+        assert_eq!(state.scroll, 0);
+        state.prev_search_match(20);
+        assert_eq!(state.scroll, 80);
+
+        // matches follow the previewed content
+        state.reset();
+        assert!(state.search.as_ref().unwrap().matches().is_empty());
+    }
+
+    #[test]
+    fn test_scroll_lock_keeps_offset_across_entries() {
+        let other_preview = |title: &str| {
+            Arc::new(Preview::new(
+                title.to_string(),
+                PreviewContent::Empty,
+                None,
+                None,
+                100,
+            ))
+        };
+
+        // unlocked: selecting another entry resets the offset
+        let mut state = preview_state_with_lines(100);
+        state.scroll = 42;
+        state.reset();
+        state.update(other_preview("other"), 0, None);
+        assert_eq!(state.scroll, 0);
+
+        // locked: the offset survives the change of entry
+        let mut state = preview_state_with_lines(100);
+        state.scroll = 42;
+        state.toggle_scroll_lock();
+        state.reset();
+        state.update(other_preview("other"), 0, None);
+        assert_eq!(state.scroll, 42);
+
+        state.toggle_scroll_lock();
+        state.update(other_preview("another"), 0, None);
+        assert_eq!(state.scroll, 0);
+    }
+}
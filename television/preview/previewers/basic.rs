@@ -21,9 +21,12 @@ impl BasicPreviewer {
     pub fn preview(&self, entry: &Entry) -> Arc<Preview> {
         Arc::new(Preview {
             title: entry.name.clone(),
-            content: PreviewContent::PlainTextWrapped(entry.name.clone()),
+            content: PreviewContent::PlainTextWrapped(
+                entry.value.clone().unwrap_or_else(|| entry.name.clone()),
+            ),
             icon: entry.icon,
             partial_offset: None,
+            header: None,
             total_lines: 1,
         })
     }
@@ -5,7 +5,9 @@ use crate::utils::command::shell_command;
 use parking_lot::Mutex;
 use regex::Regex;
 use rustc_hash::FxHashSet;
-use std::sync::atomic::{AtomicU8, Ordering};
+use std::collections::VecDeque;
+use std::io::Read;
+use std::process::{Child, ExitStatus, Stdio};
 use std::sync::Arc;
 use tracing::debug;
 
@@ -14,7 +16,7 @@ use tracing::debug;
 pub struct CommandPreviewer {
     cache: Arc<Mutex<PreviewCache>>,
     config: CommandPreviewerConfig,
-    concurrent_preview_tasks: Arc<AtomicU8>,
+    pool: Arc<PreviewPool>,
     in_flight_previews: Arc<Mutex<FxHashSet<String>>>,
     command_re: Regex,
 }
@@ -29,14 +31,19 @@ impl Default for CommandPreviewer {
 #[derive(Debug, Clone)]
 pub struct CommandPreviewerConfig {
     delimiter: String,
+    max_concurrent: usize,
 }
 
 const DEFAULT_DELIMITER: &str = " ";
 
+/// The default number of preview commands that may run at the same time.
+pub const DEFAULT_MAX_CONCURRENT_PREVIEWS: usize = 3;
+
 impl Default for CommandPreviewerConfig {
     fn default() -> Self {
         CommandPreviewerConfig {
             delimiter: String::from(DEFAULT_DELIMITER),
+            max_concurrent: DEFAULT_MAX_CONCURRENT_PREVIEWS,
         }
     }
 }
@@ -45,21 +52,124 @@ impl CommandPreviewerConfig {
     pub fn new(delimiter: &str) -> Self {
         CommandPreviewerConfig {
             delimiter: String::from(delimiter),
+            ..Default::default()
         }
     }
+
+    /// Set how many preview commands may run at the same time.
+    pub fn max_concurrent(mut self, max_concurrent: usize) -> Self {
+        self.max_concurrent = max_concurrent;
+        self
+    }
+}
+
+/// The preview commands currently running, the oldest ones being killed to
+/// make room for new ones once `capacity` of them are running.
+///
+/// When navigating quickly, the previews of the entries scrolled past are
+/// superseded by the ones of the entries selected afterwards, so it is the
+/// oldest commands that are cancelled rather than the new ones.
+#[derive(Debug)]
+pub struct PreviewPool {
+    capacity: usize,
+    jobs: Mutex<PoolJobs>,
 }
 
-const MAX_CONCURRENT_PREVIEW_TASKS: u8 = 3;
+#[derive(Debug, Default)]
+struct PoolJobs {
+    next_id: u64,
+    running: VecDeque<(u64, Child)>,
+}
+
+impl PreviewPool {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            jobs: Mutex::new(PoolJobs::default()),
+        }
+    }
+
+    /// Start running `command`, killing the oldest commands first if the
+    /// pool is full.
+    ///
+    /// Returns the job's id along with the command's output once it exits,
+    /// or `None` if it was cancelled in the meantime.
+    pub fn run(&self, command: &str) -> std::io::Result<Option<PoolOutput>> {
+        let mut child = shell_command()
+            .arg(command)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let mut stdout = child.stdout.take();
+        let mut stderr = child.stderr.take();
+        let id = {
+            let mut jobs = self.jobs.lock();
+            while jobs.running.len() >= self.capacity {
+                if let Some((id, mut oldest)) = jobs.running.pop_front() {
+                    debug!("Cancelling superseded preview command {id}");
+                    let _ = oldest.kill();
+                    let _ = oldest.wait();
+                }
+            }
+            let id = jobs.next_id;
+            jobs.next_id += 1;
+            jobs.running.push_back((id, child));
+            id
+        };
+
+        // read both streams concurrently so that neither of them fills up
+        let stderr_reader = std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(stderr) = stderr.as_mut() {
+                let _ = stderr.read_to_end(&mut buf);
+            }
+            buf
+        });
+        let mut out = Vec::new();
+        if let Some(stdout) = stdout.as_mut() {
+            let _ = stdout.read_to_end(&mut out);
+        }
+        let err = stderr_reader.join().unwrap_or_default();
+
+        let child = {
+            let mut jobs = self.jobs.lock();
+            let position = jobs.running.iter().position(|(i, _)| *i == id);
+            position.and_then(|position| jobs.running.remove(position))
+        };
+        // the command was killed if it is no longer in the pool
+        let Some((_, mut child)) = child else {
+            return Ok(None);
+        };
+        Ok(Some(PoolOutput {
+            status: child.wait()?,
+            stdout: out,
+            stderr: err,
+        }))
+    }
+
+    /// The number of commands currently running.
+    pub fn running(&self) -> usize {
+        self.jobs.lock().running.len()
+    }
+}
+
+/// The output of a command run by a `PreviewPool`.
+pub struct PoolOutput {
+    pub status: ExitStatus,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+}
 
 impl CommandPreviewer {
     pub fn new(config: Option<CommandPreviewerConfig>) -> Self {
         let config = config.unwrap_or_default();
         CommandPreviewer {
             cache: Arc::new(Mutex::new(PreviewCache::default())),
+            pool: Arc::new(PreviewPool::new(config.max_concurrent)),
             config,
-            concurrent_preview_tasks: Arc::new(AtomicU8::new(0)),
             in_flight_previews: Arc::new(Mutex::new(FxHashSet::default())),
-            command_re: Regex::new(r"\{(\d+)\}").unwrap(),
+            command_re: Regex::new(r"\{(-?\d+)\}").unwrap(),
         }
     }
 
@@ -92,34 +202,23 @@ impl CommandPreviewer {
             return;
         }
 
-        if self.concurrent_preview_tasks.load(Ordering::Relaxed)
-            < MAX_CONCURRENT_PREVIEW_TASKS
-        {
-            self.in_flight_previews.lock().insert(entry.name.clone());
-            self.concurrent_preview_tasks
-                .fetch_add(1, Ordering::Relaxed);
-            let cache = self.cache.clone();
-            let entry_c = entry.clone();
-            let concurrent_tasks = self.concurrent_preview_tasks.clone();
-            let command = command.clone();
-            let in_flight_previews = self.in_flight_previews.clone();
-            let command_re = self.command_re.clone();
-            tokio::spawn(async move {
-                try_preview(
-                    &command,
-                    &entry_c,
-                    &cache,
-                    &concurrent_tasks,
-                    &in_flight_previews,
-                    &command_re,
-                );
-            });
-        } else {
-            debug!(
-                "Too many concurrent preview tasks, skipping {:?}",
-                entry.name
+        self.in_flight_previews.lock().insert(entry.name.clone());
+        let cache = self.cache.clone();
+        let entry_c = entry.clone();
+        let pool = self.pool.clone();
+        let command = command.clone();
+        let in_flight_previews = self.in_flight_previews.clone();
+        let command_re = self.command_re.clone();
+        tokio::task::spawn_blocking(move || {
+            try_preview(
+                &command,
+                &entry_c,
+                &cache,
+                &pool,
+                &in_flight_previews,
+                &command_re,
             );
-        }
+        });
     }
 }
 
@@ -154,19 +253,35 @@ pub fn format_command(
     formatted_command = command_re
         .replace_all(&formatted_command, |caps: &regex::Captures| {
             let index =
-                caps.get(1).unwrap().as_str().parse::<usize>().unwrap();
-            format!("'{}'", parts[index])
+                caps.get(1).unwrap().as_str().parse::<isize>().unwrap();
+            format!("'{}'", field(&parts, index))
         })
         .to_string();
 
     formatted_command
 }
 
+/// Get the field at the given index, negative indices counting from the
+/// end (`-1` being the last field) as in fzf's field index expressions.
+///
+/// Indices that are out of range resolve to an empty field.
+fn field<'a>(parts: &[&'a str], index: isize) -> &'a str {
+    let index = if index < 0 {
+        parts.len().checked_sub(index.unsigned_abs())
+    } else {
+        Some(index.unsigned_abs())
+    };
+    index
+        .and_then(|i| parts.get(i))
+        .copied()
+        .unwrap_or_default()
+}
+
 pub fn try_preview(
     command: &PreviewCommand,
     entry: &Entry,
     cache: &Arc<Mutex<PreviewCache>>,
-    concurrent_tasks: &Arc<AtomicU8>,
+    pool: &PreviewPool,
     in_flight_previews: &Arc<Mutex<FxHashSet<String>>>,
     command_re: &Regex,
 ) {
@@ -174,10 +289,19 @@ pub fn try_preview(
     let command = format_command(command, entry, command_re);
     debug!("Formatted preview command: {:?}", command);
 
-    let child = shell_command()
-        .arg(&command)
-        .output()
-        .expect("failed to execute process");
+    let child = match pool.run(&command) {
+        Ok(Some(output)) => output,
+        Ok(None) => {
+            // superseded by more recent previews, requested again if needed
+            in_flight_previews.lock().remove(&entry.name);
+            return;
+        }
+        Err(e) => {
+            debug!("Failed to run preview command {:?}: {:?}", command, e);
+            in_flight_previews.lock().remove(&entry.name);
+            return;
+        }
+    };
 
     if child.status.success() {
         let content = String::from_utf8_lossy(&child.stdout);
@@ -202,7 +326,6 @@ pub fn try_preview(
         cache.lock().insert(entry.name.clone(), &preview);
     }
 
-    concurrent_tasks.fetch_sub(1, Ordering::Relaxed);
     in_flight_previews.lock().remove(&entry.name);
 }
 
@@ -211,6 +334,40 @@ mod tests {
     use super::*;
     use crate::channels::entry::{Entry, PreviewType};
 
+    #[cfg(unix)]
+    #[test]
+    fn test_pool_cancels_oldest_jobs_beyond_capacity() {
+        let pool = Arc::new(PreviewPool::new(2));
+        let jobs = (0..4)
+            .map(|i| {
+                let job_pool = pool.clone();
+                let handle = std::thread::spawn(move || {
+                    job_pool.run(&format!("sleep 0.5; echo {i}")).unwrap()
+                });
+                // start the jobs in order
+                std::thread::sleep(std::time::Duration::from_millis(50));
+                assert!(pool.running() <= 2);
+                handle
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(pool.running(), 2);
+
+        let outputs = jobs
+            .into_iter()
+            .map(|job| {
+                job.join()
+                    .unwrap()
+                    .map(|output| String::from_utf8(output.stdout).unwrap())
+            })
+            .collect::<Vec<_>>();
+        // the first two were superseded by the last two
+        assert_eq!(
+            outputs,
+            [None, None, Some("2\n".to_string()), Some("3\n".to_string())]
+        );
+        assert_eq!(pool.running(), 0);
+    }
+
     #[test]
     fn test_format_command() {
         let command = PreviewCommand {
@@ -289,4 +446,23 @@ mod tests {
 
         assert_eq!(formatted_command, "something 'an' -t 'to'");
     }
+
+    #[test]
+    fn test_format_command_with_tab_delimited_fields() {
+        let command = PreviewCommand {
+            command: "git show {2} -- {-1} {5}".to_string(),
+            delimiter: "\t".to_string(),
+        };
+        let entry = Entry::new(
+            "main\tfeature\t3f2a1bc\tREADME.md".to_string(),
+            PreviewType::Command(command.clone()),
+        );
+        let formatted_command = format_command(
+            &command,
+            &entry,
+            &Regex::new(r"\{(-?\d+)\}").unwrap(),
+        );
+
+        assert_eq!(formatted_command, "git show '3f2a1bc' -- 'README.md' ''");
+    }
 }
@@ -35,6 +35,7 @@ impl EnvVarPreviewer {
             },
             icon: entry.icon,
             partial_offset: None,
+            header: None,
             total_lines,
         })
     }
@@ -1,23 +1,32 @@
-use crate::utils::files::{read_into_lines_capped, ReadResult};
+use crate::utils::files::{is_binary, read_into_lines_capped, ReadResult};
 use crate::utils::syntax::HighlightedLines;
 use image::ImageReader;
 use parking_lot::Mutex;
 use ratatui::layout::Rect;
 use rustc_hash::{FxBuildHasher, FxHashSet};
 use std::collections::HashSet;
-use std::fs::File;
-use std::io::{BufRead, BufReader, Seek};
+use std::fs::{File, Metadata};
+use std::io::{BufRead, BufReader, Read, Seek};
 use std::path::PathBuf;
 use std::sync::{
     atomic::{AtomicU8, Ordering},
     Arc,
 };
-use syntect::{highlighting::Theme, parsing::SyntaxSet};
+use std::time::SystemTime;
+use syntect::{
+    highlighting::{Color, FontStyle, Style, Theme},
+    parsing::SyntaxSet,
+};
+use time::{
+    format_description::BorrowedFormatItem, macros::format_description,
+    OffsetDateTime,
+};
 use tracing::{debug, trace, warn};
 
 use crate::channels::entry;
 use crate::preview::cache::PreviewCache;
-use crate::preview::{previewers::meta, Preview, PreviewContent};
+use crate::preview::previewers::{meta, tree};
+use crate::preview::{Preview, PreviewContent};
 use crate::utils::image::ImagePreviewWidget;
 use crate::utils::{
     files::FileType,
@@ -32,16 +41,47 @@ pub struct FilePreviewer {
     pub syntax_theme: Arc<Theme>,
     concurrent_preview_tasks: Arc<AtomicU8>,
     in_flight_previews: Arc<Mutex<FxHashSet<String>>>,
+    max_preview_bytes: u64,
+    directory_tree_depth: Option<usize>,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct FilePreviewerConfig {
     pub theme: String,
+    /// Files larger than this are only previewed up to this many bytes.
+    pub max_preview_bytes: u64,
+    /// How many levels deep directories are previewed as a tree, if they
+    /// are.
+    pub directory_tree_depth: Option<usize>,
+}
+
+/// The default maximum number of bytes of a file to preview.
+pub const DEFAULT_MAX_PREVIEW_BYTES: u64 = 5 * 1024 * 1024;
+
+impl Default for FilePreviewerConfig {
+    fn default() -> Self {
+        FilePreviewerConfig {
+            theme: String::default(),
+            max_preview_bytes: DEFAULT_MAX_PREVIEW_BYTES,
+            directory_tree_depth: None,
+        }
+    }
 }
 
 impl FilePreviewerConfig {
-    pub fn new(theme: String) -> Self {
-        FilePreviewerConfig { theme }
+    pub fn new(theme: String, max_preview_bytes: u64) -> Self {
+        FilePreviewerConfig {
+            theme,
+            max_preview_bytes,
+            directory_tree_depth: None,
+        }
+    }
+
+    /// Preview directories as a tree up to `depth` levels deep.
+    #[must_use]
+    pub fn directory_tree_depth(mut self, depth: Option<usize>) -> Self {
+        self.directory_tree_depth = depth;
+        self
     }
 }
 
@@ -54,6 +94,11 @@ impl FilePreviewer {
         let hl_assets = load_highlighting_assets();
         let syntax_set = hl_assets.get_syntax_set().unwrap().clone();
 
+        let max_preview_bytes = config
+            .as_ref()
+            .map_or(DEFAULT_MAX_PREVIEW_BYTES, |c| c.max_preview_bytes);
+        let directory_tree_depth =
+            config.as_ref().and_then(|c| c.directory_tree_depth);
         let theme_name = match std::env::var(BAT_THEME_ENV_VAR) {
             Ok(t) => t,
             Err(_) => match config {
@@ -73,9 +118,16 @@ impl FilePreviewer {
             in_flight_previews: Arc::new(Mutex::new(HashSet::with_hasher(
                 FxBuildHasher,
             ))),
+            max_preview_bytes,
+            directory_tree_depth,
         }
     }
 
+    /// Whether directories are previewed as a tree.
+    pub fn previews_directories(&self) -> bool {
+        self.directory_tree_depth.is_some()
+    }
+
     pub fn cached(&self, entry: &entry::Entry) -> Option<Arc<Preview>> {
         self.cache.lock().get(&entry.name)
     }
@@ -129,16 +181,20 @@ impl FilePreviewer {
             let syntax_theme = self.syntax_theme.clone();
             let concurrent_tasks = self.concurrent_preview_tasks.clone();
             let in_flight_previews = self.in_flight_previews.clone();
+            let max_preview_bytes = self.max_preview_bytes;
+            let directory_tree_depth = self.directory_tree_depth;
             tokio::spawn(async move {
                 try_preview(
                     &entry_c,
-                    partial_preview,
+                    partial_preview.as_deref(),
                     &cache,
                     &syntax_set,
                     &syntax_theme,
                     &concurrent_tasks,
                     &in_flight_previews,
                     preview_window,
+                    max_preview_bytes,
+                    directory_tree_depth,
                 );
             });
         }
@@ -157,105 +213,76 @@ const PARTIAL_BUFREAD_SIZE: usize = 5 * 1024 * 1024;
 #[allow(clippy::too_many_arguments)]
 pub fn try_preview(
     entry: &entry::Entry,
-    partial_preview: Option<Arc<Preview>>,
+    partial_preview: Option<&Preview>,
     cache: &Arc<Mutex<PreviewCache>>,
     syntax_set: &Arc<SyntaxSet>,
     syntax_theme: &Arc<Theme>,
     concurrent_tasks: &Arc<AtomicU8>,
     in_flight_previews: &Arc<Mutex<FxHashSet<String>>>,
     preview_window: Option<Rect>,
+    max_preview_bytes: u64,
+    directory_tree_depth: Option<usize>,
 ) {
     debug!("Computing preview for {:?}", entry.name);
     let path = PathBuf::from(&entry.name);
+    let metadata = std::fs::metadata(&path).ok();
+    let file_size = metadata.as_ref().map_or(0, Metadata::len);
+    let header = metadata.map(|metadata| file_header(entry, &metadata));
+    let cache_preview = |preview: Arc<Preview>| {
+        let preview = Preview {
+            header: header.clone(),
+            ..Arc::unwrap_or_clone(preview)
+        };
+        cache.lock().insert(entry.name.clone(), &Arc::new(preview));
+    };
 
-    // if we're dealing with a partial preview, no need to re-check for textual content
-    if partial_preview.is_some()
+    if let Some(depth) = directory_tree_depth.filter(|_| path.is_dir()) {
+        debug!("Previewing directory {:?} as a tree", entry.name);
+        let preview = tree::preview(entry, depth);
+        cache_preview(preview);
+    } else if partial_preview.is_some()
+        // if we're dealing with a partial preview, no need to re-check for
+        // textual content
         || matches!(FileType::from(&path), FileType::Text)
     {
         debug!("File is text-based: {:?}", entry.name);
         match File::open(path) {
             Ok(mut file) => {
-                // if we're dealing with a partial preview, seek to the provided offset
-                // and use the previous state to compute the next chunk of the preview
-                let cached_lines = if let Some(p) = partial_preview {
-                    if let PreviewContent::SyntectHighlightedText(hl) =
-                        &p.content
-                    {
-                        let _ = file.seek(std::io::SeekFrom::Start(
-                            // this is always Some in this case
-                            p.partial_offset.unwrap() as u64,
-                        ));
-                        Some(hl.clone())
-                    } else {
-                        None
-                    }
+                let head = if partial_preview.is_none() {
+                    read_head(&mut file)
                 } else {
                     None
                 };
-                // compute the highlighted version in the background
-                match read_into_lines_capped(file, PARTIAL_BUFREAD_SIZE) {
-                    ReadResult::Full(lines) => {
-                        if let Some(content) = compute_highlighted_text_preview(
-                            entry,
-                            &lines
-                                .iter()
-                                .map(|l| preprocess_line(l).0 + "\n")
-                                .collect::<Vec<_>>(),
-                            syntax_set,
-                            syntax_theme,
-                            cached_lines.as_ref(),
-                        ) {
-                            let total_lines = content.total_lines();
-                            let preview = Arc::new(Preview::new(
-                                entry.name.clone(),
-                                content,
-                                entry.icon,
-                                None,
-                                total_lines,
-                            ));
-                            cache.lock().insert(entry.name.clone(), &preview);
-                        }
-                    }
-                    ReadResult::Partial(p) => {
-                        if let Some(content) = compute_highlighted_text_preview(
-                            entry,
-                            &p.lines
-                                .iter()
-                                .map(|l| preprocess_line(l).0 + "\n")
-                                .collect::<Vec<_>>(),
-                            syntax_set,
-                            syntax_theme,
-                            cached_lines.as_ref(),
-                        ) {
-                            let total_lines = content.total_lines();
-                            let preview = Arc::new(Preview::new(
-                                entry.name.clone(),
-                                content,
-                                entry.icon,
-                                Some(p.bytes_read),
-                                total_lines,
-                            ));
-                            cache.lock().insert(entry.name.clone(), &preview);
-                        }
-                    }
-                    ReadResult::Error(e) => {
-                        warn!("Error reading file: {:?}", e);
-                        let p = meta::not_supported(&entry.name);
-                        cache.lock().insert(entry.name.clone(), &p);
-                    }
+                if head.is_some_and(|head| is_binary(&head)) {
+                    debug!("File is binary: {:?}", entry.name);
+                    let p = binary_preview(
+                        entry,
+                        &mut file,
+                        file_size,
+                        max_preview_bytes,
+                        preview_window,
+                    );
+                    cache_preview(p);
+                } else if let Some(preview) = text_preview(
+                    entry,
+                    file,
+                    partial_preview,
+                    file_size,
+                    max_preview_bytes,
+                    syntax_set,
+                    syntax_theme,
+                ) {
+                    cache_preview(preview);
                 }
             }
             Err(e) => {
                 warn!("Error opening file: {:?}", e);
                 let p = meta::not_supported(&entry.name);
-                cache.lock().insert(entry.name.clone(), &p);
+                cache_preview(p);
             }
         }
     } else if matches!(FileType::from(&path), FileType::Image) {
-        cache.lock().insert(
-            entry.name.clone(),
-            &meta::loading(&format!("Loading {}", entry.name)),
-        );
+        cache_preview(meta::loading(&format!("Loading {}", entry.name)));
 
         debug!("File {:?} is an image", entry.name);
         let option_image = match ImageReader::open(path) {
@@ -304,20 +331,230 @@ pub fn try_preview(
                 None,
                 total_lines,
             ));
-            cache.lock().insert(entry.name.clone(), &preview);
+            cache_preview(preview);
         } else {
             let p = meta::not_supported(&entry.name);
-            cache.lock().insert(entry.name.clone(), &p);
+            cache_preview(p);
         }
+    } else if let Some(mut file) =
+        File::open(&path).ok().and_then(|mut file| {
+            read_head(&mut file)
+                .filter(|head| is_binary(head))
+                .map(|_| file)
+        })
+    {
+        debug!("File is binary: {:?}", entry.name);
+        let preview = binary_preview(
+            entry,
+            &mut file,
+            file_size,
+            max_preview_bytes,
+            preview_window,
+        );
+        cache_preview(preview);
     } else {
         debug!("File format isn't supported for preview: {:?}", entry.name);
         let preview = meta::not_supported(&entry.name);
-        cache.lock().insert(entry.name.clone(), &preview);
+        cache_preview(preview);
     }
     concurrent_tasks.fetch_sub(1, Ordering::Relaxed);
     in_flight_previews.lock().remove(&entry.name);
 }
 
+/// The header of the preview of a file: its full path followed by its size
+/// and modification time.
+fn file_header(entry: &entry::Entry, metadata: &Metadata) -> String {
+    let path = std::path::absolute(&entry.name).map_or_else(
+        |_| entry.stdout_repr(),
+        |path| {
+            let mut repr = path.to_string_lossy().to_string();
+            if let Some(line_number) = entry.line_number {
+                repr.push_str(&format!(":{line_number}"));
+            }
+            repr
+        },
+    );
+    format_header(&path, Some(metadata.len()), metadata.modified().ok())
+}
+
+const HEADER_SEPARATOR: &str = " · ";
+
+fn format_header(
+    display: &str,
+    size: Option<u64>,
+    modified: Option<SystemTime>,
+) -> String {
+    let mut header = display.to_string();
+    if let Some(size) = size {
+        header.push_str(HEADER_SEPARATOR);
+        header.push_str(&format_size(size));
+    }
+    if let Some(modified) = modified.and_then(format_timestamp) {
+        header.push_str(HEADER_SEPARATOR);
+        header.push_str(&modified);
+    }
+    header
+}
+
+/// Format a number of bytes using binary units (e.g. `1.5 KiB`).
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["KiB", "MiB", "GiB", "TiB", "PiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+const TIMESTAMP_FORMAT: &[BorrowedFormatItem] =
+    format_description!("[year]-[month]-[day] [hour]:[minute] UTC");
+
+fn format_timestamp(time: SystemTime) -> Option<String> {
+    OffsetDateTime::from(time).format(TIMESTAMP_FORMAT).ok()
+}
+
+/// The number of bytes at the start of a file used to detect binary files.
+const BINARY_HEAD_SIZE: usize = 512;
+
+/// The maximum number of bytes shown in the hex dump of a binary file, on top
+/// of `max_preview_bytes`, which keeps the number of lines manageable.
+const MAX_HEX_DUMP_BYTES: u64 = 1024 * 1024;
+
+/// The columns of the preview window that aren't available to its text
+/// (borders, padding and line numbers gutter).
+const PREVIEW_TEXT_MARGIN: u16 = 12;
+
+/// Preview a binary file as a hex dump of its first bytes, laid out to fit
+/// the preview window.
+fn binary_preview(
+    entry: &entry::Entry,
+    file: &mut File,
+    file_size: u64,
+    max_preview_bytes: u64,
+    preview_window: Option<Rect>,
+) -> Arc<Preview> {
+    let mut bytes = Vec::new();
+    if let Err(e) = file
+        .by_ref()
+        .take(max_preview_bytes.min(MAX_HEX_DUMP_BYTES))
+        .read_to_end(&mut bytes)
+    {
+        warn!("Error reading file: {:?}", e);
+        return meta::not_supported(&entry.name);
+    }
+    let width = preview_window.map(|rect| {
+        usize::from(rect.width.saturating_sub(PREVIEW_TEXT_MARGIN))
+    });
+    meta::binary(&entry.name, &bytes, file_size, width)
+}
+
+/// Read the first bytes of a file, rewinding it afterwards.
+fn read_head(file: &mut File) -> Option<Vec<u8>> {
+    let mut head = Vec::with_capacity(BINARY_HEAD_SIZE);
+    file.by_ref()
+        .take(BINARY_HEAD_SIZE as u64)
+        .read_to_end(&mut head)
+        .ok()?;
+    file.rewind().ok()?;
+    Some(head)
+}
+
+pub fn truncation_notice(shown_bytes: u64, total_bytes: u64) -> String {
+    format!(
+        "[preview truncated: showing the first {shown_bytes} of {total_bytes} bytes]"
+    )
+}
+
+/// Preview the next chunk of a text file, picking up where the given
+/// partial preview left off.
+///
+/// Files larger than `max_bytes` are only previewed up to it, followed by a
+/// notice saying so.
+fn text_preview(
+    entry: &entry::Entry,
+    mut file: File,
+    partial_preview: Option<&Preview>,
+    file_size: u64,
+    max_bytes: u64,
+    syntax_set: &SyntaxSet,
+    syntax_theme: &Theme,
+) -> Option<Arc<Preview>> {
+    // if we're dealing with a partial preview, seek to the provided offset
+    // and use the previous state to compute the next chunk of the preview
+    let (offset, cached_lines) = match partial_preview
+        .map(|p| (&p.content, p.partial_offset))
+    {
+        Some((PreviewContent::SyntectHighlightedText(hl), Some(offset))) => {
+            let offset = offset as u64;
+            let _ = file.seek(std::io::SeekFrom::Start(offset));
+            (offset, Some(hl))
+        }
+        _ => (0, None),
+    };
+    let remaining = max_bytes.saturating_sub(offset);
+    let chunk_size = usize::try_from(remaining)
+        .map_or(PARTIAL_BUFREAD_SIZE, |r| r.min(PARTIAL_BUFREAD_SIZE));
+    let (lines, partial_offset) =
+        match read_into_lines_capped(file.take(remaining), chunk_size) {
+            ReadResult::Full(lines) => (lines, None),
+            ReadResult::Partial(p) => (
+                p.lines,
+                usize::try_from(offset).ok().map(|o| o + p.bytes_read),
+            ),
+            ReadResult::Error(e) => {
+                warn!("Error reading file: {:?}", e);
+                return Some(meta::not_supported(&entry.name));
+            }
+        };
+    let mut content = compute_highlighted_text_preview(
+        entry,
+        &lines
+            .iter()
+            .map(|l| preprocess_line(l).0 + "\n")
+            .collect::<Vec<_>>(),
+        syntax_set,
+        syntax_theme,
+        cached_lines,
+    )?;
+    if partial_offset.is_none() && file_size > max_bytes {
+        debug!(
+            "Truncated preview of {:?} to {} bytes",
+            entry.name, max_bytes
+        );
+        if let PreviewContent::SyntectHighlightedText(hl) = &mut content {
+            let notice_style = Style {
+                foreground: syntax_theme
+                    .settings
+                    .foreground
+                    .unwrap_or(Color::WHITE),
+                background: syntax_theme
+                    .settings
+                    .background
+                    .unwrap_or(Color::BLACK),
+                font_style: FontStyle::ITALIC,
+            };
+            hl.lines.push(vec![(
+                notice_style,
+                truncation_notice(max_bytes, file_size),
+            )]);
+        }
+    }
+    let total_lines = content.total_lines();
+    Some(Arc::new(Preview::new(
+        entry.name.clone(),
+        content,
+        entry.icon,
+        partial_offset,
+        total_lines,
+    )))
+}
+
 fn compute_highlighted_text_preview(
     entry: &entry::Entry,
     lines: &[String],
@@ -377,3 +614,170 @@ fn plain_text_preview(title: &str, reader: BufReader<&File>) -> Arc<Preview> {
         total_lines,
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::*;
+    use crate::channels::entry::{Entry, PreviewType};
+    use crate::preview::BINARY_FILE_MSG;
+
+    fn preview_file(
+        contents: &[u8],
+        extension: &str,
+        max_preview_bytes: u64,
+    ) -> Arc<Preview> {
+        let mut file = tempfile::Builder::new()
+            .suffix(extension)
+            .tempfile()
+            .unwrap();
+        file.write_all(contents).unwrap();
+        let entry = Entry::new(
+            file.path().to_string_lossy().to_string(),
+            PreviewType::Files,
+        );
+        let previewer = FilePreviewer::new(Some(FilePreviewerConfig::new(
+            String::from("TwoDark"),
+            max_preview_bytes,
+        )));
+        previewer
+            .in_flight_previews
+            .lock()
+            .insert(entry.name.clone());
+        previewer
+            .concurrent_preview_tasks
+            .fetch_add(1, Ordering::Relaxed);
+        try_preview(
+            &entry,
+            None,
+            &previewer.cache,
+            &previewer.syntax_set,
+            &previewer.syntax_theme,
+            &previewer.concurrent_preview_tasks,
+            &previewer.in_flight_previews,
+            None,
+            max_preview_bytes,
+            None,
+        );
+        previewer.cached(&entry).unwrap()
+    }
+
+    fn text_lines(preview: &Preview) -> Vec<String> {
+        match &preview.content {
+            PreviewContent::SyntectHighlightedText(hl) => hl
+                .lines
+                .iter()
+                .map(|line| {
+                    line.iter().map(|(_, s)| s.as_str()).collect::<String>()
+                })
+                .collect(),
+            PreviewContent::PlainText(lines) => lines.clone(),
+            content => panic!("unexpected preview content: {content:?}"),
+        }
+    }
+
+    #[test]
+    fn test_file_previews_have_a_header() {
+        let preview = preview_file(b"hello", ".txt", 1024);
+        let header = preview.header.as_deref().unwrap();
+        assert!(header.starts_with(std::path::MAIN_SEPARATOR));
+        assert!(header.contains(".txt · 5 B · "));
+        assert!(header.ends_with(" UTC"));
+    }
+
+    #[test]
+    fn test_format_header() {
+        let modified = UNIX_EPOCH + Duration::from_secs(1_709_296_440);
+        assert_eq!(
+            format_header("/tmp/notes.md", Some(1536), Some(modified)),
+            "/tmp/notes.md · 1.5 KiB · 2024-03-01 12:34 UTC"
+        );
+        assert_eq!(
+            format_header("/tmp/empty", Some(0), None),
+            "/tmp/empty · 0 B"
+        );
+        assert_eq!(format_header("HOME", None, None), "HOME");
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1024), "1.0 KiB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MiB");
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(
+            format_timestamp(UNIX_EPOCH).as_deref(),
+            Some("1970-01-01 00:00 UTC")
+        );
+        // leap day
+        let leap_day = UNIX_EPOCH + Duration::from_secs(19_782 * 86400);
+        assert_eq!(
+            format_timestamp(leap_day).as_deref(),
+            Some("2024-02-29 00:00 UTC")
+        );
+    }
+
+    #[test]
+    fn test_large_file_preview_is_truncated() {
+        let contents = "a line of text\n".repeat(10);
+        let preview = preview_file(contents.as_bytes(), ".txt", 30);
+        let lines = text_lines(&preview);
+
+        assert_eq!(preview.partial_offset, None);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].trim_end(), "a line of text");
+        assert_eq!(lines[2], truncation_notice(30, 150));
+    }
+
+    #[test]
+    fn test_small_file_preview_is_not_truncated() {
+        let contents = "a line of text\n".repeat(10);
+        let preview = preview_file(contents.as_bytes(), ".txt", 1024);
+        let lines = text_lines(&preview);
+
+        assert_eq!(lines.len(), 10);
+        assert!(lines.iter().all(|line| line.trim_end() == "a line of text"));
+    }
+
+    #[test]
+    fn test_binary_file_preview() {
+        // binary content behind a text extension
+        let contents = b"\x7fELF\x02\x01\x01\x00\x00\x00";
+        let preview = preview_file(contents, ".txt", 1024);
+        let lines = text_lines(&preview);
+
+        assert_eq!(lines[0], format!("{BINARY_FILE_MSG} (10 bytes)"));
+        assert_eq!(
+            lines[2],
+            format!(
+                "00000000: 7f45 4c46 0201 0100 0000{}.ELF......",
+                " ".repeat(17)
+            )
+        );
+    }
+
+    #[test]
+    fn test_binary_file_preview_is_capped() {
+        let contents = [0u8; 100];
+        let preview = preview_file(&contents, ".bin", 40);
+        let lines = text_lines(&preview);
+
+        assert_eq!(
+            lines[0],
+            format!("{BINARY_FILE_MSG} (showing the first 40 of 100 bytes)")
+        );
+        // 2 full lines of 16 bytes and 8 remaining bytes
+        assert_eq!(lines.len(), 5);
+    }
+
+    #[test]
+    fn test_is_binary() {
+        assert!(is_binary(b"\x00\x01\x02"));
+        assert!(!is_binary("plain text, café".as_bytes()));
+    }
+}
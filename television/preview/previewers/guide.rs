@@ -0,0 +1,166 @@
+use std::sync::Arc;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use parking_lot::Mutex;
+use rustc_hash::FxHashSet;
+use tracing::debug;
+
+use crate::channels::entry::Entry;
+use crate::channels::OnAir;
+use crate::preview::cache::PreviewCache;
+use crate::preview::{Preview, PreviewContent};
+
+/// How long to wait for a channel to come up with its first entries before
+/// previewing whatever it found so far.
+const SAMPLE_TIMEOUT: Duration = Duration::from_millis(500);
+const SAMPLE_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+const GUIDE_PREVIEW_CACHE_SIZE: usize = 20;
+
+/// Previews the channels of the remote control (the "guide") by listing a
+/// few sample entries of each one.
+///
+/// Channels are constructed and sampled in the background so that slow
+/// channels don't block the UI.
+#[derive(Debug)]
+pub struct GuidePreviewer {
+    cache: Arc<Mutex<PreviewCache>>,
+    in_flight_previews: Arc<Mutex<FxHashSet<String>>>,
+}
+
+impl Default for GuidePreviewer {
+    fn default() -> Self {
+        GuidePreviewer {
+            cache: Arc::new(Mutex::new(PreviewCache::new(
+                GUIDE_PREVIEW_CACHE_SIZE,
+            ))),
+            in_flight_previews: Arc::new(Mutex::new(FxHashSet::default())),
+        }
+    }
+}
+
+impl GuidePreviewer {
+    pub fn cached(&self, channel_name: &str) -> Option<Arc<Preview>> {
+        self.cache.lock().get(channel_name)
+    }
+
+    /// Drop the preview of the given channel, if any.
+    pub fn invalidate(&mut self, channel_name: &str) {
+        self.cache.lock().remove(channel_name);
+    }
+
+    /// The preview of the given channel, listing at most `num_entries` of
+    /// its entries.
+    ///
+    /// If the preview isn't ready yet, the channel is built with `zap` and
+    /// sampled in the background and `None` is returned in the meantime.
+    pub fn preview<F, C>(
+        &mut self,
+        channel_name: &str,
+        num_entries: u32,
+        zap: F,
+    ) -> Option<Arc<Preview>>
+    where
+        F: FnOnce() -> Result<C> + Send + 'static,
+        C: OnAir,
+    {
+        if let Some(preview) = self.cached(channel_name) {
+            return Some(preview);
+        }
+        if !self
+            .in_flight_previews
+            .lock()
+            .insert(channel_name.to_string())
+        {
+            return None;
+        }
+        debug!("Sampling entries of channel {:?}", channel_name);
+        let cache = self.cache.clone();
+        let in_flight_previews = self.in_flight_previews.clone();
+        let channel_name = channel_name.to_string();
+        tokio::task::spawn_blocking(move || {
+            let lines = match zap() {
+                Ok(mut channel) => {
+                    let entries = sample_entries(
+                        &mut channel,
+                        num_entries,
+                        SAMPLE_TIMEOUT,
+                    );
+                    channel.shutdown();
+                    entries.into_iter().map(|entry| entry.name).collect()
+                }
+                Err(e) => vec![e.to_string()],
+            };
+            let total_lines = u16::try_from(lines.len()).unwrap_or(u16::MAX);
+            let preview = Arc::new(Preview::new(
+                channel_name.clone(),
+                PreviewContent::PlainText(lines),
+                None,
+                None,
+                total_lines,
+            ));
+            cache.lock().insert(channel_name.clone(), &preview);
+            in_flight_previews.lock().remove(&channel_name);
+        });
+        None
+    }
+}
+
+/// The first `num_entries` entries of the given channel for an empty
+/// pattern.
+///
+/// Gives the channel up to `timeout` to load that many entries, after which
+/// only the entries loaded so far are returned.
+pub fn sample_entries<C: OnAir>(
+    channel: &mut C,
+    num_entries: u32,
+    timeout: Duration,
+) -> Vec<Entry> {
+    channel.find("");
+    let start = Instant::now();
+    // the first call also lets the channel's matcher catch up
+    let mut entries = channel.results(num_entries, 0);
+    while entries.len() < num_entries as usize
+        && channel.running()
+        && start.elapsed() < timeout
+    {
+        sleep(SAMPLE_POLL_INTERVAL);
+        entries = channel.results(num_entries, 0);
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::remote_control::RemoteControl;
+
+    #[test]
+    fn test_sample_entries_is_capped() {
+        let mut channel = RemoteControl::default();
+
+        let entries = sample_entries(&mut channel, 3, SAMPLE_TIMEOUT);
+        assert!(channel.total_count() > 3);
+        assert_eq!(entries.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_guide_preview_lists_at_most_n_entries() {
+        let mut previewer = GuidePreviewer::default();
+        let zap = || Ok(RemoteControl::default());
+        assert!(previewer.preview("remote", 3, zap).is_none());
+
+        let start = Instant::now();
+        let preview = loop {
+            if let Some(preview) = previewer.preview("remote", 3, zap) {
+                break preview;
+            }
+            assert!(start.elapsed() < Duration::from_secs(5));
+            tokio::time::sleep(SAMPLE_POLL_INTERVAL).await;
+        };
+        assert_eq!(preview.title, "remote");
+        assert_eq!(preview.total_lines, 3);
+    }
+}
@@ -1,4 +1,5 @@
-use crate::preview::{Preview, PreviewContent};
+use crate::preview::{Preview, PreviewContent, BINARY_FILE_MSG};
+use std::fmt::Write;
 use std::sync::Arc;
 
 pub fn not_supported(title: &str) -> Arc<Preview> {
@@ -11,6 +12,16 @@ pub fn not_supported(title: &str) -> Arc<Preview> {
     ))
 }
 
+pub fn unavailable(title: &str) -> Arc<Preview> {
+    Arc::new(Preview::new(
+        title.to_string(),
+        PreviewContent::Unavailable,
+        None,
+        None,
+        1,
+    ))
+}
+
 pub fn file_too_large(title: &str) -> Arc<Preview> {
     Arc::new(Preview::new(
         title.to_string(),
@@ -21,7 +32,6 @@ pub fn file_too_large(title: &str) -> Arc<Preview> {
     ))
 }
 
-#[allow(dead_code)]
 pub fn loading(title: &str) -> Arc<Preview> {
     Arc::new(Preview::new(
         title.to_string(),
@@ -41,3 +51,109 @@ pub fn timeout(title: &str) -> Arc<Preview> {
         1,
     ))
 }
+
+/// A preview of a binary file showing a hex dump of its first bytes.
+///
+/// `file_size` is the total size of the file, of which `bytes` may only be
+/// the start, and `width` the number of columns the dump should fit in.
+pub fn binary(
+    title: &str,
+    bytes: &[u8],
+    file_size: u64,
+    width: Option<usize>,
+) -> Arc<Preview> {
+    let header = if (bytes.len() as u64) < file_size {
+        format!(
+            "{BINARY_FILE_MSG} (showing the first {} of {file_size} bytes)",
+            bytes.len()
+        )
+    } else {
+        format!("{BINARY_FILE_MSG} ({} bytes)", bytes.len())
+    };
+    let mut lines = vec![header, String::new()];
+    lines.extend(hex_dump(
+        bytes,
+        width.map_or(DEFAULT_HEX_DUMP_WIDTH, hex_dump_width),
+    ));
+    let total_lines = u16::try_from(lines.len()).unwrap_or(u16::MAX);
+    Arc::new(Preview::new(
+        title.to_string(),
+        PreviewContent::PlainText(lines),
+        None,
+        None,
+        total_lines,
+    ))
+}
+
+const DEFAULT_HEX_DUMP_WIDTH: usize = 16;
+
+/// The number of bytes per line of a hex dump fitting in `columns`.
+///
+/// Every line is made of a 9 columns offset, 5 columns per group of 2 bytes,
+/// a 2 columns gap and 1 column per byte in the ASCII gutter. The result is
+/// rounded down to a multiple of 8 bytes, with a minimum of 8.
+pub fn hex_dump_width(columns: usize) -> usize {
+    let groups = columns.saturating_sub(11) / 7;
+    (groups * 2 / 8 * 8).max(8)
+}
+
+/// Format bytes like `xxd` does with `bytes_per_line` bytes per line, e.g.
+/// `00000000: 7f45 4c46 0201 0100 0000 0000 0000 0000  .ELF............`
+///
+/// `bytes_per_line` must be even.
+pub fn hex_dump(bytes: &[u8], bytes_per_line: usize) -> Vec<String> {
+    bytes
+        .chunks(bytes_per_line)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let mut line = format!("{:08x}:", i * bytes_per_line);
+            for (j, byte) in chunk.iter().enumerate() {
+                if j % 2 == 0 {
+                    line.push(' ');
+                }
+                let _ = write!(line, "{byte:02x}");
+            }
+            let missing = bytes_per_line - chunk.len();
+            line.push_str(&" ".repeat(missing * 2 + missing / 2 + 2));
+            line.extend(chunk.iter().map(|&byte| {
+                if byte.is_ascii_graphic() || byte == b' ' {
+                    byte as char
+                } else {
+                    '.'
+                }
+            }));
+            line
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_dump() {
+        let bytes = b"\x7fELF\x02\x01\x01\x00hello, world!\n";
+        assert_eq!(
+            hex_dump(bytes, 8),
+            vec![
+                "00000000: 7f45 4c46 0201 0100  .ELF....",
+                "00000008: 6865 6c6c 6f2c 2077  hello, w",
+                "00000010: 6f72 6c64 210a       orld!.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hex_dump_width() {
+        let bytes = [0u8; 64];
+        for columns in [0, 40, 80, 120, 200] {
+            let bytes_per_line = hex_dump_width(columns);
+            assert_eq!(bytes_per_line % 8, 0);
+            let line = &hex_dump(&bytes, bytes_per_line)[0];
+            assert!(bytes_per_line == 8 || line.len() <= columns);
+        }
+        assert_eq!(hex_dump_width(80), 16);
+        assert_eq!(hex_dump_width(120), 24);
+    }
+}
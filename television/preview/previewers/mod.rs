@@ -2,4 +2,6 @@ pub mod basic;
 pub mod command;
 pub mod env;
 pub mod files;
+pub mod guide;
 pub mod meta;
+pub mod tree;
@@ -0,0 +1,208 @@
+use std::io::ErrorKind;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::channels::entry::Entry;
+use crate::preview::{Preview, PreviewContent};
+
+/// The maximum number of lines of a directory tree, which keeps large
+/// directories from being walked in full.
+pub const MAX_TREE_LINES: usize = 1000;
+
+const TRUNCATED_MSG: &str = "…";
+
+/// Preview a directory as a tree of its content up to `max_depth` levels
+/// deep (see `directory_tree`).
+pub fn preview(entry: &Entry, max_depth: usize) -> Arc<Preview> {
+    let lines =
+        directory_tree(Path::new(&entry.name), max_depth, MAX_TREE_LINES);
+    let total_lines = u16::try_from(lines.len()).unwrap_or(u16::MAX);
+    Arc::new(Preview::new(
+        entry.name.clone(),
+        PreviewContent::PlainText(lines),
+        entry.icon,
+        None,
+        total_lines,
+    ))
+}
+
+/// The lines of a `tree`-style listing of `root`, up to `max_depth` levels
+/// deep and `max_lines` lines long.
+///
+/// Directories are listed with a trailing slash and the ones that can't be
+/// read (e.g. for lack of permissions) are annotated with the reason why,
+/// the rest of the tree being listed regardless. Symbolic links aren't
+/// followed.
+pub fn directory_tree(
+    root: &Path,
+    max_depth: usize,
+    max_lines: usize,
+) -> Vec<String> {
+    let mut lines = vec![format!("{}/", root.display())];
+    if !walk(root, "", max_depth, max_lines, &mut lines) {
+        lines.push(TRUNCATED_MSG.to_string());
+    }
+    lines
+}
+
+/// List the content of `dir` below its own line, returning whether it could
+/// be listed without going over `max_lines`.
+fn walk(
+    dir: &Path,
+    prefix: &str,
+    depth: usize,
+    max_lines: usize,
+    lines: &mut Vec<String>,
+) -> bool {
+    if depth == 0 {
+        return true;
+    }
+    let mut children = match std::fs::read_dir(dir) {
+        Ok(read_dir) => read_dir
+            .filter_map(Result::ok)
+            .map(|child| {
+                let is_dir = child.file_type().is_ok_and(|t| t.is_dir());
+                (child.file_name().to_string_lossy().to_string(), is_dir)
+            })
+            .collect::<Vec<_>>(),
+        Err(e) => {
+            if let Some(line) = lines.last_mut() {
+                line.push_str(&format!(" [{}]", describe_error(&e)));
+            }
+            return true;
+        }
+    };
+    children.sort_unstable();
+    let count = children.len();
+    for (i, (name, is_dir)) in children.into_iter().enumerate() {
+        if lines.len() >= max_lines {
+            return false;
+        }
+        let last = i + 1 == count;
+        let connector = if last { "└── " } else { "├── " };
+        let slash = if is_dir { "/" } else { "" };
+        lines.push(format!("{prefix}{connector}{name}{slash}"));
+        if is_dir {
+            let indent = if last { "    " } else { "│   " };
+            if !walk(
+                &dir.join(&name),
+                &format!("{prefix}{indent}"),
+                depth - 1,
+                max_lines,
+                lines,
+            ) {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+fn describe_error(error: &std::io::Error) -> String {
+    match error.kind() {
+        ErrorKind::PermissionDenied => String::from("permission denied"),
+        _ => String::from("error opening dir"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for path in ["src/main.rs", "src/utils/mod.rs", "Cargo.toml"] {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "").unwrap();
+        }
+        std::fs::create_dir(dir.path().join("empty")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_directory_tree() {
+        let dir = fixture();
+
+        let lines = directory_tree(dir.path(), 3, MAX_TREE_LINES);
+        assert_eq!(lines[0], format!("{}/", dir.path().display()));
+        assert_eq!(
+            lines[1..],
+            [
+                "├── Cargo.toml",
+                "├── empty/",
+                "└── src/",
+                "    ├── main.rs",
+                "    └── utils/",
+                "        └── mod.rs",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_directory_tree_depth_and_lines_are_capped() {
+        let dir = fixture();
+
+        let lines = directory_tree(dir.path(), 1, MAX_TREE_LINES);
+        assert_eq!(lines[1..], ["├── Cargo.toml", "├── empty/", "└── src/"]);
+
+        let lines = directory_tree(dir.path(), 3, 3);
+        assert_eq!(
+            lines[1..],
+            ["├── Cargo.toml", "├── empty/", TRUNCATED_MSG]
+        );
+    }
+
+    #[test]
+    fn test_missing_directory_is_annotated() {
+        let dir = fixture();
+        let missing = dir.path().join("missing");
+
+        let lines = directory_tree(&missing, 3, MAX_TREE_LINES);
+        assert_eq!(
+            lines,
+            [format!("{}/ [error opening dir]", missing.display())]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unreadable_directory_is_annotated() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = fixture();
+        let src = dir.path().join("src");
+        std::fs::set_permissions(&src, std::fs::Permissions::from_mode(0o0))
+            .unwrap();
+        let readable = std::fs::read_dir(&src).is_ok();
+
+        let lines = directory_tree(dir.path(), 3, MAX_TREE_LINES);
+        std::fs::set_permissions(&src, std::fs::Permissions::from_mode(0o755))
+            .unwrap();
+        // permissions don't apply when running as root
+        if readable {
+            eprintln!("skipped: directory permissions aren't enforced");
+            return;
+        }
+        assert_eq!(
+            lines[1..],
+            [
+                "├── Cargo.toml",
+                "├── empty/",
+                "└── src/ [permission denied]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_describe_error() {
+        assert_eq!(
+            describe_error(&ErrorKind::PermissionDenied.into()),
+            "permission denied"
+        );
+        assert_eq!(
+            describe_error(&ErrorKind::NotFound.into()),
+            "error opening dir"
+        );
+    }
+}
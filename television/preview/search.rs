@@ -0,0 +1,325 @@
+use crate::action::Action;
+
+/// An occurrence of the preview search query.
+///
+/// `line` is the 0-based index of the line in the preview's content while
+/// `start` and `end` delimit the matching bytes of that line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PreviewMatch {
+    pub line: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// The state of a search within the preview's content.
+///
+/// Matches are recomputed every time the query or the previewed content
+/// changes and one of them is current at any time, which the preview
+/// scrolls to when cycling through them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PreviewSearch {
+    pub query: String,
+    /// Whether the query is being typed, in which case input actions edit
+    /// it instead of the prompt.
+    pub editing: bool,
+    matches: Vec<PreviewMatch>,
+    current: Option<usize>,
+}
+
+impl PreviewSearch {
+    pub fn new() -> Self {
+        Self {
+            editing: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn matches(&self) -> &[PreviewMatch] {
+        &self.matches
+    }
+
+    /// The index of the current match within [`PreviewSearch::matches`].
+    pub fn current_index(&self) -> Option<usize> {
+        self.current
+    }
+
+    pub fn current(&self) -> Option<&PreviewMatch> {
+        self.current.map(|i| &self.matches[i])
+    }
+
+    /// The matches found on the given line, along with their index.
+    pub fn line_matches(
+        &self,
+        line: usize,
+    ) -> impl Iterator<Item = (usize, &PreviewMatch)> {
+        let first = self.matches.partition_point(|m| m.line < line);
+        self.matches[first..]
+            .iter()
+            .take_while(move |m| m.line == line)
+            .enumerate()
+            .map(move |(i, m)| (first + i, m))
+    }
+
+    /// Edit the query with the given input action.
+    ///
+    /// # Returns
+    /// Whether the query changed.
+    pub fn handle_input_action(&mut self, action: &Action) -> bool {
+        match action {
+            Action::AddInputChar(c) => self.query.push(*c),
+            Action::DeletePrevChar => {
+                return self.query.pop().is_some();
+            }
+            Action::DeletePrevWord => {
+                let len = self.query.len();
+                let trimmed = self.query.trim_end();
+                let word_start =
+                    trimmed.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+                self.query.truncate(word_start);
+                return self.query.len() != len;
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Recompute the matches against the given lines and make the first
+    /// one current.
+    pub fn update_matches(&mut self, lines: &[String]) {
+        self.matches = find_matches(lines, &self.query);
+        self.current = if self.matches.is_empty() {
+            None
+        } else {
+            Some(0)
+        };
+    }
+
+    /// Make the next match current, wrapping around to the first one.
+    pub fn next_match(&mut self) -> Option<&PreviewMatch> {
+        if self.matches.is_empty() {
+            return None;
+        }
+        self.current =
+            Some(self.current.map_or(0, |i| (i + 1) % self.matches.len()));
+        self.current()
+    }
+
+    /// Make the previous match current, wrapping around to the last one.
+    pub fn prev_match(&mut self) -> Option<&PreviewMatch> {
+        if self.matches.is_empty() {
+            return None;
+        }
+        let len = self.matches.len();
+        self.current =
+            Some(self.current.map_or(len - 1, |i| (i + len - 1) % len));
+        self.current()
+    }
+
+    /// A short description of the search, e.g. `/query [2/5]`.
+    pub fn status(&self) -> String {
+        let position = match self.current {
+            Some(i) => format!("{}/{}", i + 1, self.matches.len()),
+            None if self.query.is_empty() => String::new(),
+            None => String::from("no match"),
+        };
+        if position.is_empty() {
+            format!("/{}", self.query)
+        } else {
+            format!("/{} [{position}]", self.query)
+        }
+    }
+}
+
+/// Find the non-overlapping occurrences of `query` in the given lines.
+///
+/// The search is smart-case: it ignores ASCII case unless the query
+/// contains an uppercase character.
+pub fn find_matches(lines: &[String], query: &str) -> Vec<PreviewMatch> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let case_sensitive = query.chars().any(char::is_uppercase);
+    let query = if case_sensitive {
+        query.to_string()
+    } else {
+        query.to_ascii_lowercase()
+    };
+    let mut matches = Vec::new();
+    for (line_index, line) in lines.iter().enumerate() {
+        // ASCII lowercasing keeps byte offsets unchanged
+        let haystack = if case_sensitive {
+            line.clone()
+        } else {
+            line.to_ascii_lowercase()
+        };
+        matches.extend(haystack.match_indices(&query).map(|(start, m)| {
+            PreviewMatch {
+                line: line_index,
+                start,
+                end: start + m.len(),
+            }
+        }));
+    }
+    matches
+}
+
+/// The terms of a results query worth highlighting in a preview.
+///
+/// These are the query's whitespace separated terms stripped of the `'`, `^`
+/// and `$` anchors, leaving out the negated (`!term`) ones.
+pub fn query_terms(query: &str) -> Vec<String> {
+    query
+        .split_whitespace()
+        .filter(|term| !term.starts_with('!'))
+        .map(|term| {
+            let term = term.strip_prefix('\'').unwrap_or(term);
+            let term = term.strip_prefix('^').unwrap_or(term);
+            term.strip_suffix('$').unwrap_or(term).to_string()
+        })
+        .filter(|term| !term.is_empty())
+        .collect()
+}
+
+/// The byte ranges of the occurrences of any of the given terms in `line`.
+///
+/// Each term is looked up smart-case, as with [`find_matches`], and
+/// overlapping occurrences are merged so that the returned ranges are sorted
+/// and disjoint.
+pub fn find_term_occurrences(
+    line: &str,
+    terms: &[String],
+) -> Vec<(usize, usize)> {
+    let mut occurrences = terms
+        .iter()
+        .flat_map(|term| {
+            find_matches(&[line.to_string()], term)
+                .into_iter()
+                .map(|m| (m.start, m.end))
+        })
+        .collect::<Vec<_>>();
+    occurrences.sort_unstable();
+    let mut merged: Vec<(usize, usize)> =
+        Vec::with_capacity(occurrences.len());
+    for (start, end) in occurrences {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_find_matches() {
+        let lines = lines("fn main() {\n    Main::run();\n}\nmain main");
+        let matches = find_matches(&lines, "main");
+        assert_eq!(
+            matches,
+            vec![
+                PreviewMatch {
+                    line: 0,
+                    start: 3,
+                    end: 7
+                },
+                PreviewMatch {
+                    line: 1,
+                    start: 4,
+                    end: 8
+                },
+                PreviewMatch {
+                    line: 3,
+                    start: 0,
+                    end: 4
+                },
+                PreviewMatch {
+                    line: 3,
+                    start: 5,
+                    end: 9
+                },
+            ]
+        );
+        // an uppercase character makes the search case-sensitive
+        assert_eq!(find_matches(&lines, "Main").len(), 1);
+        assert!(find_matches(&lines, "").is_empty());
+    }
+
+    #[test]
+    fn test_next_and_prev_match_wrap_around() {
+        let mut search = PreviewSearch::new();
+        search.query = String::from("a");
+        search.update_matches(&lines("a\nb\naa"));
+        assert_eq!(search.matches().len(), 3);
+        assert_eq!(search.current_index(), Some(0));
+
+        assert_eq!(search.next_match().map(|m| m.line), Some(2));
+        assert_eq!(
+            search.next_match().map(|m| (m.line, m.start)),
+            Some((2, 1))
+        );
+        assert_eq!(search.next_match().map(|m| m.line), Some(0));
+        assert_eq!(
+            search.prev_match().map(|m| (m.line, m.start)),
+            Some((2, 1))
+        );
+        assert_eq!(search.status(), "/a [3/3]");
+
+        assert_eq!(
+            search.line_matches(2).map(|(i, _)| i).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert_eq!(search.line_matches(1).count(), 0);
+    }
+
+    #[test]
+    fn test_edit_query() {
+        let mut search = PreviewSearch::new();
+        for c in "foo bar".chars() {
+            assert!(search.handle_input_action(&Action::AddInputChar(c)));
+        }
+        assert!(search.handle_input_action(&Action::DeletePrevWord));
+        assert_eq!(search.query, "foo ");
+        assert!(search.handle_input_action(&Action::DeletePrevChar));
+        assert_eq!(search.query, "foo");
+        assert!(!search.handle_input_action(&Action::GoToInputEnd));
+    }
+
+    #[test]
+    fn test_query_terms() {
+        assert_eq!(
+            query_terms("^src  'main rs$ !test"),
+            vec!["src", "main", "rs"]
+        );
+        assert!(query_terms(" ^ $ ").is_empty());
+    }
+
+    #[test]
+    fn test_find_term_occurrences() {
+        let terms = query_terms("foo ob Bar");
+        // `ob` overlaps both occurrences of `foo` and `Bar` is case-sensitive
+        assert_eq!(
+            find_term_occurrences("foobar fooBar foo", &terms),
+            vec![(0, 4), (7, 13), (14, 17)]
+        );
+        assert!(find_term_occurrences("baz", &terms).is_empty());
+        assert!(find_term_occurrences("foo", &[]).is_empty());
+    }
+
+    #[test]
+    fn test_no_match() {
+        let mut search = PreviewSearch::new();
+        search.query = String::from("z");
+        search.update_matches(&lines("a\nb"));
+        assert_eq!(search.current(), None);
+        assert_eq!(search.next_match(), None);
+        assert_eq!(search.prev_match(), None);
+        assert_eq!(search.status(), "/z [no match]");
+    }
+}
@@ -0,0 +1,133 @@
+use crate::event::Key;
+
+/// The letters labels are made of, the easiest to reach first.
+const LABEL_ALPHABET: &str = "asdfghjklqwertyuiopzxcvbnm";
+
+/// The labels of `count` rows, in order.
+///
+/// Single letters are used when there are enough of them, two letters
+/// otherwise so that no label is the prefix of another. At most
+/// `LABEL_ALPHABET.len()²` rows are labelled.
+pub fn labels(count: usize) -> Vec<String> {
+    let letters = LABEL_ALPHABET.chars();
+    if count <= LABEL_ALPHABET.len() {
+        return letters.take(count).map(String::from).collect();
+    }
+    letters
+        .flat_map(|first| {
+            LABEL_ALPHABET.chars().map(move |second| {
+                let mut label = String::from(first);
+                label.push(second);
+                label
+            })
+        })
+        .take(count)
+        .collect()
+}
+
+/// The outcome of typing a key while the labels are shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Jump {
+    /// The label of the row at the given position among the visible ones
+    /// was typed.
+    To(usize),
+    /// The typed letters start some label, which needs more of them.
+    Pending,
+    /// The key doesn't lead to any label and the labels are dismissed.
+    Cancelled,
+}
+
+/// Letter labels shown next to the visible results, typing a label
+/// selecting the corresponding result (see `Action::ToggleQuicknav`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Quicknav {
+    labels: Vec<String>,
+    /// The letters typed so far.
+    typed: String,
+}
+
+impl Quicknav {
+    /// Label the given number of visible rows.
+    pub fn new(visible_rows: usize) -> Self {
+        Self {
+            labels: labels(visible_rows),
+            typed: String::new(),
+        }
+    }
+
+    /// The label of each visible row, blank for those whose label doesn't
+    /// start with the letters typed so far.
+    pub fn visible_labels(&self) -> Vec<&str> {
+        self.labels
+            .iter()
+            .map(|label| {
+                if label.starts_with(&self.typed) {
+                    label.as_str()
+                } else {
+                    ""
+                }
+            })
+            .collect()
+    }
+
+    pub fn feed(&mut self, key: Key) -> Jump {
+        match key {
+            Key::Char(c) => self.typed.push(c.to_ascii_lowercase()),
+            Key::Backspace if !self.typed.is_empty() => {
+                self.typed.pop();
+                return Jump::Pending;
+            }
+            _ => return Jump::Cancelled,
+        }
+        if let Some(index) = self.labels.iter().position(|l| *l == self.typed)
+        {
+            Jump::To(index)
+        } else if self.labels.iter().any(|l| l.starts_with(&self.typed)) {
+            Jump::Pending
+        } else {
+            Jump::Cancelled
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_labels() {
+        assert_eq!(labels(0), Vec::<String>::new());
+        assert_eq!(labels(3), vec!["a", "s", "d"]);
+        assert_eq!(labels(26).last().unwrap(), "m");
+        let labels = labels(30);
+        assert_eq!(&labels[..3], ["aa", "as", "ad"]);
+        assert_eq!(labels[26], "sa");
+        assert!(labels.iter().all(|label| label.len() == 2));
+        assert_eq!(self::labels(10_000).len(), 26 * 26);
+    }
+
+    #[test]
+    fn test_typing_a_label_jumps_to_its_row() {
+        let mut quicknav = Quicknav::new(3);
+        assert_eq!(quicknav.visible_labels(), ["a", "s", "d"]);
+        assert_eq!(quicknav.feed(Key::Char('d')), Jump::To(2));
+
+        let mut quicknav = Quicknav::new(3);
+        assert_eq!(quicknav.feed(Key::Char('z')), Jump::Cancelled);
+        let mut quicknav = Quicknav::new(3);
+        assert_eq!(quicknav.feed(Key::Esc), Jump::Cancelled);
+    }
+
+    #[test]
+    fn test_two_letter_labels() {
+        let mut quicknav = Quicknav::new(40);
+        assert_eq!(quicknav.feed(Key::Char('s')), Jump::Pending);
+        let visible = quicknav.visible_labels();
+        assert_eq!(visible[0], "");
+        assert_eq!(visible[26], "sa");
+        assert_eq!(quicknav.feed(Key::Backspace), Jump::Pending);
+        assert_eq!(quicknav.visible_labels()[0], "aa");
+        assert_eq!(quicknav.feed(Key::Char('S')), Jump::Pending);
+        assert_eq!(quicknav.feed(Key::Char('d')), Jump::To(28));
+    }
+}
@@ -3,12 +3,15 @@ use crossterm::terminal::{BeginSynchronizedUpdate, EndSynchronizedUpdate};
 use crossterm::{execute, queue};
 use ratatui::layout::Rect;
 use std::io::{stderr, stdout, LineWriter};
+use std::process::Stdio;
 use tracing::{debug, warn};
 
 use tokio::sync::mpsc;
 
 use crate::draw::Ctx;
+use crate::screen::input::cursor_style;
 use crate::screen::layout::Layout;
+use crate::utils::command::shell_command;
 use crate::{action::Action, draw::draw, tui::Tui};
 
 #[derive(Debug, PartialEq, PartialOrd, Ord, Eq, Clone)]
@@ -18,6 +21,7 @@ pub enum RenderingTask {
     Resize(u16, u16),
     Resume,
     Suspend,
+    Execute(String),
     Quit,
 }
 
@@ -52,6 +56,26 @@ impl UiState {
     }
 }
 
+/// Run a command in the foreground, with the terminal handed over to it.
+///
+/// When television's output is piped, the command's output is sent to stderr
+/// so that it doesn't end up mixed with the selected entries.
+fn execute_command(command: &str, is_output_tty: bool) {
+    debug!("Executing command: {:?}", command);
+    let mut cmd = shell_command();
+    cmd.arg(command);
+    if !is_output_tty {
+        cmd.stdout(Stdio::from(stderr()));
+    }
+    match cmd.status() {
+        Ok(status) if !status.success() => {
+            warn!("Command {:?} exited with {}", command, status);
+        }
+        Err(e) => warn!("Failed to execute {:?}: {:?}", command, e),
+        Ok(_) => {}
+    }
+}
+
 /// The main UI rendering task loop.
 ///
 /// This function is responsible for rendering the UI based on the rendering tasks it receives from
@@ -83,6 +107,9 @@ pub async fn render(
     tui.enter()?;
 
     let mut buffer = Vec::with_capacity(256);
+    // the cursor style last applied, re-applied after the terminal was
+    // handed over to another process
+    let mut cursor = None;
 
     // Rendering loop
     'rendering: while render_rx.recv_many(&mut buffer, 256).await > 0 {
@@ -95,6 +122,16 @@ pub async fn render(
                     tui.terminal.clear()?;
                 }
                 RenderingTask::Render(context) => {
+                    let ui = &context.config.ui;
+                    let style = (ui.input_cursor_shape, ui.input_cursor_blink);
+                    if cursor != Some(style) {
+                        execute!(
+                            tui.terminal.backend_mut(),
+                            cursor_style(style.0, style.1)
+                        )
+                        .ok();
+                        cursor = Some(style);
+                    }
                     if let Ok(size) = tui.size() {
                         // Ratatui uses `u16`s to encode terminal dimensions and its
                         // content for each terminal cell is stored linearly in a
@@ -129,6 +166,7 @@ pub async fn render(
                     action_tx.send(Action::Render)?;
                 }
                 RenderingTask::Suspend => {
+                    cursor = None;
                     tui.suspend()?;
                     action_tx.send(Action::Resume)?;
                     action_tx.send(Action::ClearScreen)?;
@@ -137,6 +175,13 @@ pub async fn render(
                 RenderingTask::Resume => {
                     tui.enter()?;
                 }
+                RenderingTask::Execute(command) => {
+                    cursor = None;
+                    tui.exit()?;
+                    execute_command(&command, is_output_tty);
+                    action_tx.send(Action::Resume)?;
+                    action_tx.send(Action::Render)?;
+                }
                 RenderingTask::Quit => {
                     debug!("Exiting rendering loop");
                     tui.exit()?;
@@ -24,6 +24,7 @@ pub struct HelpColorscheme {
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ResultsColorscheme {
+    pub border_fg: Color,
     pub result_name_fg: Color,
     pub result_preview_fg: Color,
     pub result_line_number_fg: Color,
@@ -34,17 +35,22 @@ pub struct ResultsColorscheme {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct PreviewColorscheme {
+    pub border_fg: Color,
     pub title_fg: Color,
     pub highlight_bg: Color,
     pub content_fg: Color,
     pub gutter_fg: Color,
     pub gutter_selected_fg: Color,
+    pub match_fg: Color,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct InputColorscheme {
+    pub border_fg: Color,
     pub input_fg: Color,
     pub results_count_fg: Color,
+    /// The color of the prompt while an error is shown.
+    pub error_fg: Color,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -0,0 +1,78 @@
+use ratatui::layout::{Alignment, Rect};
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::widgets::{
+    Block, BorderType, Borders, Clear, Padding, Paragraph,
+};
+use ratatui::Frame;
+
+use crate::confirmation::Confirmation;
+use crate::screen::colors::Colorscheme;
+use crate::screen::keybinding_editor::centered_rect;
+use crate::utils::strings::shrink_with_ellipsis;
+
+const PROMPT_WIDTH: u16 = 60;
+// borders + question line
+const PROMPT_HEIGHT: u16 = 3;
+
+/// Draw the confirmation prompt as a popup centered on `area`.
+pub fn draw_confirmation_prompt(
+    f: &mut Frame,
+    area: Rect,
+    confirmation: &Confirmation,
+    colorscheme: &Colorscheme,
+) {
+    draw_popup(f, area, " Confirm ", &confirmation.message(), colorscheme);
+}
+
+/// Draw an error message as a popup centered on `area`.
+pub fn draw_error(
+    f: &mut Frame,
+    area: Rect,
+    error: &str,
+    colorscheme: &Colorscheme,
+) {
+    draw_popup(f, area, " Error ", error, colorscheme);
+}
+
+/// Draw a message confirming an action as a popup centered on `area`.
+pub fn draw_notice(
+    f: &mut Frame,
+    area: Rect,
+    notice: &str,
+    colorscheme: &Colorscheme,
+) {
+    draw_popup(f, area, " Info ", notice, colorscheme);
+}
+
+fn draw_popup(
+    f: &mut Frame,
+    area: Rect,
+    title: &str,
+    message: &str,
+    colorscheme: &Colorscheme,
+) {
+    let popup = centered_rect(PROMPT_WIDTH, PROMPT_HEIGHT, area);
+    f.render_widget(Clear, popup);
+
+    let block = Block::default()
+        .title_top(Line::from(title).alignment(Alignment::Center))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(colorscheme.general.border_fg))
+        .style(
+            Style::default()
+                .bg(colorscheme.general.background.unwrap_or_default()),
+        )
+        .padding(Padding::horizontal(1));
+    let inner = block.inner(popup);
+    f.render_widget(block, popup);
+
+    let message = shrink_with_ellipsis(message, inner.width.into());
+    f.render_widget(
+        Paragraph::new(Line::from(message))
+            .style(Style::default().fg(colorscheme.input.input_fg))
+            .alignment(Alignment::Center),
+        inner,
+    );
+}
@@ -0,0 +1,101 @@
+use ratatui::layout::Rect;
+use ratatui::prelude::Style;
+use ratatui::style::Stylize;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{
+    Block, BorderType, Borders, Clear, Padding, Paragraph,
+};
+use ratatui::Frame;
+
+use crate::debug_log::{DebugEvent, DebugLog, DebugRecord};
+use crate::screen::colors::Colorscheme;
+
+const DEBUG_OVERLAY_WIDTH_PERCENT: u16 = 50;
+
+/// The line displaying a record, e.g. `  12.345s  key     Ctrl-j`.
+pub fn debug_record_line(record: &DebugRecord) -> String {
+    let (kind, event) = match &record.event {
+        DebugEvent::Key(key) => ("key", key.to_string()),
+        DebugEvent::Action(action) => ("action", format!("{action:?}")),
+    };
+    format!(
+        "{:>4}.{:03}s  {kind:<6}  {event}",
+        record.elapsed.as_secs(),
+        record.elapsed.subsec_millis()
+    )
+}
+
+/// Draw the latest keys and actions of the debug log in the bottom right
+/// corner of `area`, the latest record last.
+pub fn draw_debug_overlay(
+    f: &mut Frame,
+    area: Rect,
+    log: &DebugLog,
+    colorscheme: &Colorscheme,
+) {
+    let lines = log
+        .records()
+        .map(|record| {
+            let style = match record.event {
+                DebugEvent::Key(_) => {
+                    Style::default().fg(colorscheme.mode.channel)
+                }
+                DebugEvent::Action(_) => Style::default(),
+            };
+            Line::from(Span::styled(debug_record_line(record), style))
+        })
+        .collect::<Vec<_>>();
+    let width = area.width * DEBUG_OVERLAY_WIDTH_PERCENT / 100;
+    let height = u16::try_from(lines.len().max(1) + 2)
+        .unwrap_or(u16::MAX)
+        .min(area.height);
+    let popup =
+        Rect::new(area.right() - width, area.bottom() - height, width, height);
+    f.render_widget(Clear, popup);
+
+    let block = Block::default()
+        .title_top(Line::from(" Debug ").bold().centered())
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(colorscheme.general.border_fg))
+        .style(
+            Style::default()
+                .bg(colorscheme.general.background.unwrap_or_default()),
+        )
+        .padding(Padding::horizontal(1));
+    // keep the latest records in view
+    let scroll = u16::try_from(lines.len())
+        .unwrap_or(u16::MAX)
+        .saturating_sub(height.saturating_sub(2));
+    f.render_widget(
+        Paragraph::new(lines).block(block).scroll((scroll, 0)),
+        popup,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::action::Action;
+    use crate::event::Key;
+
+    #[test]
+    fn test_debug_record_line() {
+        let record = DebugRecord {
+            elapsed: Duration::from_millis(12_345),
+            event: DebugEvent::Key(Key::Ctrl('j')),
+        };
+        assert_eq!(debug_record_line(&record), "  12.345s  key     Ctrl-j");
+
+        let record = DebugRecord {
+            elapsed: Duration::from_millis(7),
+            event: DebugEvent::Action(Action::AddInputChar('a')),
+        };
+        assert_eq!(
+            debug_record_line(&record),
+            "   0.007s  action  AddInputChar('a')"
+        );
+    }
+}
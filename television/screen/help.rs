@@ -6,8 +6,9 @@ use crate::screen::mode::mode_color;
 use crate::television::Mode;
 use crate::utils::metadata::AppMetadata;
 use ratatui::layout::Rect;
-use ratatui::prelude::{Color, Style};
-use ratatui::widgets::{Block, BorderType, Borders, Padding, Table};
+use ratatui::prelude::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, BorderType, Borders, Clear, Padding, Table};
 use ratatui::Frame;
 
 pub fn draw_logo_block(
@@ -111,3 +112,39 @@ pub fn draw_help_bar(
         );
     }
 }
+
+const HELP_OVERLAY_WIDTH_PERCENT: u16 = 80;
+// the channel mode's keymaps + borders
+const HELP_OVERLAY_HEIGHT: u16 = 8;
+
+/// Draw the keymaps table in a popup centered on `area`, dimming the rest of
+/// the area underneath.
+pub fn draw_help_overlay(
+    f: &mut Frame,
+    area: Rect,
+    keymap_table: Table,
+    colorscheme: &GeneralColorscheme,
+) {
+    f.buffer_mut()
+        .set_style(area, Style::default().add_modifier(Modifier::DIM));
+
+    let width = area.width * HELP_OVERLAY_WIDTH_PERCENT / 100;
+    let height = HELP_OVERLAY_HEIGHT.min(area.height);
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+    f.render_widget(Clear, popup);
+
+    let help_block = Block::default()
+        .title_top(Line::from(" Help ").centered())
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(colorscheme.border_fg))
+        .style(Style::default().bg(colorscheme.background.unwrap_or_default()))
+        .padding(Padding::horizontal(1));
+
+    f.render_widget(keymap_table.block(help_block), popup);
+}
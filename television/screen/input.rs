@@ -1,16 +1,220 @@
+use std::rc::Rc;
+
+use crate::matcher::config::MatchTarget;
 use crate::utils::input::Input;
 use anyhow::Result;
+use crossterm::cursor::SetCursorStyle;
 use ratatui::{
     layout::{
         Alignment, Constraint, Direction, Layout as RatatuiLayout, Rect,
     },
     style::{Style, Stylize},
     text::{Line, Span},
-    widgets::{Block, BorderType, Borders, ListState, Paragraph},
+    widgets::{Block, Borders, ListState, Paragraph},
     Frame,
 };
+use serde::{Deserialize, Serialize};
+use unicode_width::UnicodeWidthChar;
+
+use crate::screen::{
+    colors::Colorscheme, layout::BorderKind, spinner::Spinner,
+};
+use crate::television::Mode;
+
+/// The shape of the input's cursor.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum CursorShape {
+    Block,
+    Bar,
+    Underline,
+}
+
+/// The terminal command giving the cursor the configured shape, the
+/// terminal's own cursor being kept when no shape is configured.
+pub fn cursor_style(
+    shape: Option<CursorShape>,
+    blink: bool,
+) -> SetCursorStyle {
+    match (shape, blink) {
+        (None, _) => SetCursorStyle::DefaultUserShape,
+        (Some(CursorShape::Block), true) => SetCursorStyle::BlinkingBlock,
+        (Some(CursorShape::Block), false) => SetCursorStyle::SteadyBlock,
+        (Some(CursorShape::Bar), true) => SetCursorStyle::BlinkingBar,
+        (Some(CursorShape::Bar), false) => SetCursorStyle::SteadyBar,
+        (Some(CursorShape::Underline), true) => {
+            SetCursorStyle::BlinkingUnderScore
+        }
+        (Some(CursorShape::Underline), false) => {
+            SetCursorStyle::SteadyUnderScore
+        }
+    }
+}
+
+/// What the prompt before the query reflects, states coming first taking
+/// precedence over the following ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptState {
+    /// An error is shown.
+    Error,
+    /// A channel is being picked from the remote control.
+    RemoteControl,
+    /// A channel to send the entries to is being picked.
+    SendToChannel,
+    /// The channel is still loading or matching entries.
+    Running,
+    Idle,
+}
+
+impl PromptState {
+    pub fn new(mode: Mode, running: bool, error: bool) -> Self {
+        match mode {
+            _ if error => PromptState::Error,
+            Mode::RemoteControl => PromptState::RemoteControl,
+            Mode::SendToChannel => PromptState::SendToChannel,
+            Mode::Channel if running => PromptState::Running,
+            Mode::Channel => PromptState::Idle,
+        }
+    }
+}
+
+/// The symbols shown before the query in each state, which should be a
+/// single column wide.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(default)]
+pub struct PromptSymbols {
+    pub idle: String,
+    pub running: String,
+    pub error: String,
+    pub remote_control: String,
+    pub send_to_channel: String,
+}
+
+impl Default for PromptSymbols {
+    fn default() -> Self {
+        Self {
+            idle: String::from(">"),
+            running: String::from(">"),
+            error: String::from("!"),
+            remote_control: String::from(">"),
+            send_to_channel: String::from(">"),
+        }
+    }
+}
 
-use crate::screen::{colors::Colorscheme, spinner::Spinner};
+/// The prompt before the query in the given state.
+///
+/// Besides their symbol, states are told apart by color: the prompt is
+/// dimmed while running, takes the theme's `error_fg` on errors and the
+/// color of the mode when picking a channel.
+pub fn prompt_span<'a>(
+    state: PromptState,
+    symbols: &'a PromptSymbols,
+    colorscheme: &Colorscheme,
+) -> Span<'a> {
+    let style = Style::default().bold();
+    let (symbol, style) = match state {
+        PromptState::Idle => {
+            (&symbols.idle, style.fg(colorscheme.input.input_fg))
+        }
+        PromptState::Running => {
+            (&symbols.running, style.fg(colorscheme.input.input_fg).dim())
+        }
+        PromptState::Error => {
+            (&symbols.error, style.fg(colorscheme.input.error_fg))
+        }
+        PromptState::RemoteControl => (
+            &symbols.remote_control,
+            style.fg(colorscheme.mode.remote_control),
+        ),
+        PromptState::SendToChannel => (
+            &symbols.send_to_channel,
+            style.fg(colorscheme.mode.send_to_channel),
+        ),
+    };
+    Span::styled(symbol.as_str(), style)
+}
+
+/// Split the inside of the input box into 4 parts: prompt symbol, input,
+/// result count and spinner.
+fn input_chunks(inner: Rect, total_count: u32) -> Rc<[Rect]> {
+    RatatuiLayout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            // prompt symbol
+            Constraint::Length(2),
+            // input field
+            Constraint::Fill(1),
+            // result count
+            Constraint::Length(
+                3 * (u16::try_from((total_count.max(1)).ilog10()).unwrap()
+                    + 1)
+                    + 3,
+            ),
+            // spinner
+            Constraint::Length(1),
+        ])
+        .split(inner)
+}
+
+/// The width of the field the query is typed in, for an input box drawn in
+/// `rect` with up to `total_count` results.
+pub fn input_field_width(rect: Rect, total_count: u32) -> u16 {
+    input_chunks(
+        Block::default().borders(Borders::ALL).inner(rect),
+        total_count,
+    )[1]
+    .width
+}
+
+/// Wrap `query` over lines at most `width` columns wide.
+///
+/// Lines are broken anywhere, not only between words, since the query is
+/// edited one character at a time.
+pub fn wrap_query(query: &str, width: u16) -> Vec<&str> {
+    let width = usize::from(width.max(1));
+    let mut lines = Vec::new();
+    let (mut start, mut line_width) = (0, 0);
+    for (i, c) in query.char_indices() {
+        let char_width = c.width().unwrap_or(0);
+        if line_width + char_width > width && line_width > 0 {
+            lines.push(&query[start..i]);
+            (start, line_width) = (i, 0);
+        }
+        line_width += char_width;
+    }
+    lines.push(&query[start..]);
+    lines
+}
+
+/// The row and column of the cursor within the wrapped query, given the
+/// text before the cursor.
+///
+/// A cursor right after a full line is put at the start of the next one.
+fn wrapped_cursor(before_cursor: &str, width: u16) -> (u16, u16) {
+    let lines = wrap_query(before_cursor, width);
+    let row = u16::try_from(lines.len() - 1).unwrap_or(u16::MAX);
+    let col = u16::try_from(lines.last().map_or(0, |line| {
+        line.chars()
+            .filter_map(UnicodeWidthChar::width)
+            .sum::<usize>()
+    }))
+    .unwrap_or(u16::MAX);
+    if col >= width.max(1) {
+        (row.saturating_add(1), 0)
+    } else {
+        (row, col)
+    }
+}
+
+/// The height of an input box, borders included, showing `query` wrapped
+/// over a field `width` columns wide with the cursor at its end.
+pub fn wrapped_input_height(query: &str, width: u16) -> u16 {
+    let lines =
+        u16::try_from(wrap_query(query, width).len()).unwrap_or(u16::MAX);
+    let (cursor_row, _) = wrapped_cursor(query, width);
+    lines.max(cursor_row.saturating_add(1)).saturating_add(2)
+}
 
 #[allow(clippy::too_many_arguments)]
 pub fn draw_input_box(
@@ -22,13 +226,18 @@ pub fn draw_input_box(
     results_picker_state: &ListState,
     matcher_running: bool,
     channel_name: &str,
+    pinned_filters: &[String],
+    match_target: MatchTarget,
+    prompt: Span,
     spinner: &Spinner,
     colorscheme: &Colorscheme,
+    border_kind: BorderKind,
+    wrap: bool,
 ) -> Result<()> {
-    let input_block = Block::default()
+    let mut input_block = Block::default()
         .borders(Borders::ALL)
-        .border_type(BorderType::Rounded)
-        .border_style(Style::default().fg(colorscheme.general.border_fg))
+        .border_set(border_kind.border_set())
+        .border_style(Style::default().fg(colorscheme.input.border_fg))
         .title_top(
             Line::from(String::from(" ") + channel_name + " ")
                 .style(Style::default().fg(colorscheme.mode.channel).bold())
@@ -38,6 +247,27 @@ pub fn draw_input_box(
             Style::default()
                 .bg(colorscheme.general.background.unwrap_or_default()),
         );
+    if !pinned_filters.is_empty() {
+        let mut filter_spans = vec![Span::from(" ")];
+        for filter in pinned_filters {
+            filter_spans.push(Span::styled(
+                format!("[{filter}]"),
+                Style::default().fg(colorscheme.input.input_fg).italic(),
+            ));
+            filter_spans.push(Span::from(" "));
+        }
+        input_block = input_block.title_bottom(Line::from(filter_spans));
+    }
+    // matching the full text is the default and goes without saying
+    if match_target != MatchTarget::default() {
+        input_block = input_block.title_bottom(
+            Line::from(format!(" match: {} ", match_target.description()))
+                .style(
+                    Style::default().fg(colorscheme.input.input_fg).italic(),
+                )
+                .right_aligned(),
+        );
+    }
 
     let input_block_inner = input_block.inner(rect);
     if input_block_inner.area() == 0 {
@@ -46,33 +276,50 @@ pub fn draw_input_box(
 
     f.render_widget(input_block, rect);
 
-    // split input block into 4 parts: prompt symbol, input, result count, spinner
-    let inner_input_chunks = RatatuiLayout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            // prompt symbol
-            Constraint::Length(2),
-            // input field
-            Constraint::Fill(1),
-            // result count
-            Constraint::Length(
-                3 * (u16::try_from((total_count.max(1)).ilog10()).unwrap()
-                    + 1)
-                    + 3,
-            ),
-            // spinner
-            Constraint::Length(1),
-        ])
-        .split(input_block_inner);
+    let inner_input_chunks = input_chunks(input_block_inner, total_count);
 
     let arrow_block = Block::default();
-    let arrow = Paragraph::new(Span::styled(
-        "> ",
-        Style::default().fg(colorscheme.input.input_fg).bold(),
-    ))
-    .block(arrow_block);
+    let arrow = Paragraph::new(prompt).block(arrow_block);
     f.render_widget(arrow, inner_input_chunks[0]);
 
+    let input_style = Style::default()
+        .fg(colorscheme.input.input_fg)
+        .bold()
+        .italic();
+    let field = inner_input_chunks[1];
+    if wrap {
+        let value = input_state.value();
+        let cursor = value
+            .char_indices()
+            .nth(input_state.cursor())
+            .map_or(value.len(), |(i, _)| i);
+        let (row, col) = wrapped_cursor(&value[..cursor], field.width);
+        // keep the cursor's row in view if the box couldn't grow enough
+        let scroll = row.saturating_sub(field.height.saturating_sub(1));
+        let lines = wrap_query(value, field.width)
+            .into_iter()
+            .map(Line::from)
+            .collect::<Vec<_>>();
+        f.render_widget(
+            Paragraph::new(lines).scroll((scroll, 0)).style(input_style),
+            field,
+        );
+        draw_input_status(
+            f,
+            &inner_input_chunks,
+            results_count,
+            results_picker_state,
+            matcher_running,
+            spinner,
+            colorscheme,
+        );
+        f.set_cursor_position((
+            field.x.saturating_add(col),
+            field.y.saturating_add(row - scroll),
+        ));
+        return Ok(());
+    }
+
     let interactive_input_block = Block::default();
     // keep 2 for borders and 1 for cursor
     let width = inner_input_chunks[1].width.max(3) - 3;
@@ -80,15 +327,42 @@ pub fn draw_input_box(
     let input = Paragraph::new(input_state.value())
         .scroll((0, u16::try_from(scroll)?))
         .block(interactive_input_block)
-        .style(
-            Style::default()
-                .fg(colorscheme.input.input_fg)
-                .bold()
-                .italic(),
-        )
+        .style(input_style)
         .alignment(Alignment::Left);
-    f.render_widget(input, inner_input_chunks[1]);
+    f.render_widget(input, field);
+    draw_input_status(
+        f,
+        &inner_input_chunks,
+        results_count,
+        results_picker_state,
+        matcher_running,
+        spinner,
+        colorscheme,
+    );
 
+    // Make the cursor visible and ask tui-rs to put it at the
+    // specified coordinates after rendering
+    f.set_cursor_position((
+        // Put cursor past the end of the input text
+        field.x.saturating_add(u16::try_from(
+            input_state.visual_cursor().max(scroll) - scroll,
+        )?),
+        // Move one line down, from the border to the input line
+        field.y,
+    ));
+    Ok(())
+}
+
+/// Draw the result count and the spinner next to the input field.
+fn draw_input_status(
+    f: &mut Frame,
+    inner_input_chunks: &[Rect],
+    results_count: u32,
+    results_picker_state: &ListState,
+    matcher_running: bool,
+    spinner: &Spinner,
+    colorscheme: &Colorscheme,
+) {
     if matcher_running {
         f.render_widget(spinner, inner_input_chunks[3]);
     }
@@ -111,16 +385,111 @@ pub fn draw_input_box(
     .block(result_count_block)
     .alignment(Alignment::Right);
     f.render_widget(result_count_paragraph, inner_input_chunks[2]);
+}
 
-    // Make the cursor visible and ask tui-rs to put it at the
-    // specified coordinates after rendering
-    f.set_cursor_position((
-        // Put cursor past the end of the input text
-        inner_input_chunks[1].x.saturating_add(u16::try_from(
-            input_state.visual_cursor().max(scroll) - scroll,
-        )?),
-        // Move one line down, from the border to the input line
-        inner_input_chunks[1].y,
-    ));
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::Command;
+    use ratatui::style::{Color, Modifier};
+
+    fn escape(shape: Option<CursorShape>, blink: bool) -> String {
+        let mut escape = String::new();
+        cursor_style(shape, blink).write_ansi(&mut escape).unwrap();
+        escape
+    }
+
+    #[test]
+    fn test_cursor_style_escapes() {
+        assert_eq!(escape(None, true), "\x1b[0 q");
+        assert_eq!(escape(None, false), "\x1b[0 q");
+        assert_eq!(escape(Some(CursorShape::Block), true), "\x1b[1 q");
+        assert_eq!(escape(Some(CursorShape::Block), false), "\x1b[2 q");
+        assert_eq!(escape(Some(CursorShape::Underline), true), "\x1b[3 q");
+        assert_eq!(escape(Some(CursorShape::Underline), false), "\x1b[4 q");
+        assert_eq!(escape(Some(CursorShape::Bar), true), "\x1b[5 q");
+        assert_eq!(escape(Some(CursorShape::Bar), false), "\x1b[6 q");
+    }
+
+    #[test]
+    fn test_wrap_query() {
+        assert_eq!(wrap_query("", 4), [""]);
+        assert_eq!(wrap_query("abcdefghij", 4), ["abcd", "efgh", "ij"]);
+        assert_eq!(wrap_query("abcdefgh", 4), ["abcd", "efgh"]);
+        // wide characters aren't split across lines
+        assert_eq!(wrap_query("ab日本", 3), ["ab", "日", "本"]);
+    }
+
+    #[test]
+    fn test_wrapped_cursor() {
+        assert_eq!(wrapped_cursor("", 4), (0, 0));
+        assert_eq!(wrapped_cursor("abcdef", 4), (1, 2));
+        assert_eq!(wrapped_cursor("abcd", 4), (1, 0));
+    }
+
+    #[test]
+    fn test_wrapped_input_height() {
+        // borders included
+        assert_eq!(wrapped_input_height("", 10), 3);
+        assert_eq!(wrapped_input_height("short", 10), 3);
+        let query = "a very long query that doesn't fit on one line";
+        assert_eq!(query.len(), 46);
+        assert_eq!(wrapped_input_height(query, 10), 7);
+        assert_eq!(wrapped_input_height(query, 46), 4);
+        assert_eq!(wrapped_input_height(query, 47), 3);
+    }
+
+    #[test]
+    fn test_input_field_width() {
+        // 2 borders, 2 for the prompt, 9 for the count and 1 for the spinner
+        assert_eq!(input_field_width(Rect::new(0, 0, 50, 3), 42), 36);
+        assert_eq!(input_field_width(Rect::new(0, 0, 50, 3), 4200), 30);
+    }
+
+    #[test]
+    fn test_prompt_reflects_state() {
+        assert_eq!(
+            PromptState::new(Mode::Channel, false, false),
+            PromptState::Idle
+        );
+        assert_eq!(
+            PromptState::new(Mode::Channel, true, false),
+            PromptState::Running
+        );
+        assert_eq!(
+            PromptState::new(Mode::RemoteControl, true, false),
+            PromptState::RemoteControl
+        );
+        assert_eq!(
+            PromptState::new(Mode::SendToChannel, false, true),
+            PromptState::Error
+        );
+
+        let symbols = PromptSymbols {
+            running: String::from("~"),
+            ..PromptSymbols::default()
+        };
+        let colorscheme: Colorscheme =
+            (&crate::config::Theme::default()).into();
+        let idle = prompt_span(PromptState::Idle, &symbols, &colorscheme);
+        assert_eq!(idle.content, ">");
+        assert!(!idle.style.add_modifier.contains(Modifier::DIM));
+        let running =
+            prompt_span(PromptState::Running, &symbols, &colorscheme);
+        assert_eq!(running.content, "~");
+        assert!(running.style.add_modifier.contains(Modifier::DIM));
+        let error = prompt_span(PromptState::Error, &symbols, &colorscheme);
+        assert_eq!(error.content, "!");
+        assert_eq!(error.style.fg, Some(Color::Red));
+    }
+
+    #[test]
+    fn test_deserialize_cursor_shape() {
+        #[derive(Deserialize)]
+        struct Ui {
+            shape: CursorShape,
+        }
+        let ui: Ui = toml::from_str("shape = \"underline\"").unwrap();
+        assert_eq!(ui.shape, CursorShape::Underline);
+    }
 }
@@ -0,0 +1,109 @@
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{
+    Block, BorderType, Borders, Cell, Clear, Padding, Paragraph, Row, Table,
+    TableState,
+};
+use ratatui::Frame;
+
+use crate::action::Action;
+use crate::config::KeyBindings;
+use crate::keybinding_editor::{KeybindingEditor, REBINDABLE_ACTIONS};
+use crate::screen::colors::Colorscheme;
+
+const EDITOR_WIDTH: u16 = 60;
+// borders + message line
+const EDITOR_CHROME_HEIGHT: u16 = 4;
+
+/// Draw the keybinding editor as a popup centered on `area`.
+pub fn draw_keybinding_editor(
+    f: &mut Frame,
+    area: Rect,
+    editor: &KeybindingEditor,
+    keybindings: &KeyBindings,
+    colorscheme: &Colorscheme,
+) {
+    let popup = centered_rect(
+        EDITOR_WIDTH,
+        u16::try_from(REBINDABLE_ACTIONS.len())
+            .unwrap_or(u16::MAX)
+            .saturating_add(EDITOR_CHROME_HEIGHT),
+        area,
+    );
+    f.render_widget(Clear, popup);
+
+    let block = Block::default()
+        .title_top(Line::from(" Keybindings ").alignment(Alignment::Center))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(colorscheme.general.border_fg))
+        .style(
+            Style::default()
+                .bg(colorscheme.general.background.unwrap_or_default()),
+        )
+        .padding(Padding::horizontal(1));
+    let inner = block.inner(popup);
+    f.render_widget(block, popup);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Fill(1), Constraint::Length(2)])
+        .split(inner);
+
+    let rows = REBINDABLE_ACTIONS.iter().map(|action| {
+        Row::new(vec![
+            Cell::from(Span::styled(
+                action.to_string(),
+                Style::default().fg(colorscheme.help.metadata_field_name_fg),
+            )),
+            Cell::from(Span::styled(
+                display_binding(keybindings, action),
+                Style::default().fg(colorscheme.help.metadata_field_value_fg),
+            )),
+        ])
+    });
+    let table = Table::new(
+        rows,
+        [Constraint::Percentage(55), Constraint::Percentage(45)],
+    )
+    .row_highlight_style(
+        Style::default()
+            .bg(colorscheme.results.result_selected_bg)
+            .fg(colorscheme.results.result_selected_fg)
+            .bold(),
+    );
+    let mut state = TableState::default().with_selected(editor.selected);
+    f.render_stateful_widget(table, chunks[0], &mut state);
+
+    let message = editor.message.clone().unwrap_or_else(|| {
+        format!(
+            "{}: rebind, {}: close",
+            display_binding(keybindings, &Action::ConfirmSelection),
+            display_binding(keybindings, &Action::Quit),
+        )
+    });
+    let message = Paragraph::new(Line::from(message))
+        .style(Style::default().fg(colorscheme.input.results_count_fg))
+        .alignment(Alignment::Center);
+    f.render_widget(message, chunks[1]);
+}
+
+fn display_binding(keybindings: &KeyBindings, action: &Action) -> String {
+    keybindings
+        .get(action)
+        .map(ToString::to_string)
+        .unwrap_or_default()
+}
+
+/// A rectangle of the given size centered on `area`, clamped to fit in it.
+pub fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
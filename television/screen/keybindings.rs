@@ -2,7 +2,9 @@ use rustc_hash::FxHashMap;
 use std::fmt::Display;
 
 use crate::action::Action;
+use crate::config::Binding;
 use crate::television::Mode;
+use crate::utils::strings::ELLIPSIS;
 use crate::{config::KeyBindings, screen::colors::Colorscheme};
 use ratatui::{
     layout::Constraint,
@@ -12,7 +14,14 @@ use ratatui::{
 };
 
 impl KeyBindings {
-    pub fn to_displayable(&self) -> FxHashMap<Mode, DisplayableKeybindings> {
+    /// Build the keybindings displayed in the help bar for each mode.
+    ///
+    /// `max_keys_shown` optionally limits the number of keys displayed for each
+    /// action (see `serialize_binding`).
+    pub fn to_displayable(
+        &self,
+        max_keys_shown: Option<usize>,
+    ) -> FxHashMap<Mode, DisplayableKeybindings> {
         // channel mode keybindings
         let channel_bindings: FxHashMap<DisplayableAction, Vec<String>> =
             FxHashMap::from_iter(vec![
@@ -20,6 +29,7 @@ impl KeyBindings {
                     DisplayableAction::ResultsNavigation,
                     serialized_keys_for_actions(
                         self,
+                        max_keys_shown,
                         &[
                             Action::SelectPrevEntry,
                             Action::SelectNextEntry,
@@ -32,9 +42,19 @@ impl KeyBindings {
                     DisplayableAction::PreviewNavigation,
                     serialized_keys_for_actions(
                         self,
+                        max_keys_shown,
                         &[
                             Action::ScrollPreviewHalfPageUp,
                             Action::ScrollPreviewHalfPageDown,
+                            Action::ScrollPreviewTop,
+                            Action::ScrollPreviewBottom,
+                            Action::PreviewCursorUp,
+                            Action::PreviewCursorDown,
+                            Action::LockPreviewScroll,
+                            Action::ShowPreviewForCurrent,
+                            Action::TogglePreviewSearch,
+                            Action::NextPreviewMatch,
+                            Action::PrevPreviewMatch,
                         ],
                     ),
                 ),
@@ -42,6 +62,7 @@ impl KeyBindings {
                     DisplayableAction::SelectEntry,
                     serialized_keys_for_actions(
                         self,
+                        max_keys_shown,
                         &[
                             Action::ConfirmSelection,
                             Action::ToggleSelectionDown,
@@ -53,6 +74,7 @@ impl KeyBindings {
                     DisplayableAction::CopyEntryToClipboard,
                     serialized_keys_for_actions(
                         self,
+                        max_keys_shown,
                         &[Action::CopyEntryToClipboard],
                     ),
                 ),
@@ -60,6 +82,7 @@ impl KeyBindings {
                     DisplayableAction::SendToChannel,
                     serialized_keys_for_actions(
                         self,
+                        max_keys_shown,
                         &[Action::ToggleSendToChannel],
                     ),
                 ),
@@ -67,12 +90,17 @@ impl KeyBindings {
                     DisplayableAction::ToggleRemoteControl,
                     serialized_keys_for_actions(
                         self,
+                        max_keys_shown,
                         &[Action::ToggleRemoteControl],
                     ),
                 ),
                 (
                     DisplayableAction::ToggleHelpBar,
-                    serialized_keys_for_actions(self, &[Action::ToggleHelp]),
+                    serialized_keys_for_actions(
+                        self,
+                        max_keys_shown,
+                        &[Action::ToggleHelp],
+                    ),
                 ),
             ]);
 
@@ -85,17 +113,23 @@ impl KeyBindings {
                 DisplayableAction::ResultsNavigation,
                 serialized_keys_for_actions(
                     self,
+                    max_keys_shown,
                     &[Action::SelectPrevEntry, Action::SelectNextEntry],
                 ),
             ),
             (
                 DisplayableAction::SelectEntry,
-                serialized_keys_for_actions(self, &[Action::ConfirmSelection]),
+                serialized_keys_for_actions(
+                    self,
+                    max_keys_shown,
+                    &[Action::ConfirmSelection],
+                ),
             ),
             (
                 DisplayableAction::ToggleRemoteControl,
                 serialized_keys_for_actions(
                     self,
+                    max_keys_shown,
                     &[Action::ToggleRemoteControl],
                 ),
             ),
@@ -110,17 +144,23 @@ impl KeyBindings {
                 DisplayableAction::ResultsNavigation,
                 serialized_keys_for_actions(
                     self,
+                    max_keys_shown,
                     &[Action::SelectPrevEntry, Action::SelectNextEntry],
                 ),
             ),
             (
                 DisplayableAction::SelectEntry,
-                serialized_keys_for_actions(self, &[Action::ConfirmSelection]),
+                serialized_keys_for_actions(
+                    self,
+                    max_keys_shown,
+                    &[Action::ConfirmSelection],
+                ),
             ),
             (
                 DisplayableAction::Cancel,
                 serialized_keys_for_actions(
                     self,
+                    max_keys_shown,
                     &[Action::ToggleSendToChannel],
                 ),
             ),
@@ -142,14 +182,39 @@ impl KeyBindings {
 
 fn serialized_keys_for_actions(
     keybindings: &KeyBindings,
+    max_keys_shown: Option<usize>,
     actions: &[Action],
 ) -> Vec<String> {
     actions
         .iter()
-        .map(|a| keybindings.get(a).unwrap().clone().to_string())
+        .map(|a| {
+            serialize_binding(keybindings.get(a).unwrap(), max_keys_shown)
+        })
         .collect()
 }
 
+/// Serialize a binding for display, showing at most `max_keys_shown` of its
+/// keys and appending an ellipsis if some of them were left out.
+fn serialize_binding(
+    binding: &Binding,
+    max_keys_shown: Option<usize>,
+) -> String {
+    match (binding, max_keys_shown) {
+        (Binding::MultipleKeys(keys), Some(max_keys))
+            if keys.len() > max_keys =>
+        {
+            let mut shown_keys = keys
+                .iter()
+                .take(max_keys)
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            shown_keys.push(ELLIPSIS.to_string());
+            shown_keys.join(", ")
+        }
+        _ => binding.to_string(),
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct DisplayableKeybindings {
     bindings: FxHashMap<DisplayableAction, Vec<String>>,
@@ -187,18 +252,53 @@ impl Display for DisplayableAction {
             DisplayableAction::ToggleRemoteControl => "Toggle Remote control",
             DisplayableAction::Cancel => "Cancel",
             DisplayableAction::Quit => "Quit",
-            DisplayableAction::ToggleHelpBar => "Toggle help bar",
+            DisplayableAction::ToggleHelpBar => "Toggle help",
         };
         write!(f, "{action}")
     }
 }
 
+/// The largest share of the help tables' width either column can take
+/// relative to the other one.
+const MAX_HELP_COLUMN_RATIO: u16 = 8;
+
+/// The column widths of the help tables given the relative widths of their
+/// action and key columns.
+pub fn help_table_widths(ratio: [u16; 2]) -> Vec<Constraint> {
+    ratio.into_iter().map(Constraint::Fill).collect()
+}
+
+/// Move the boundary between the action and key columns of the help tables
+/// by one step, towards the action column when `widen_keys` is set.
+///
+/// The narrower column's share shrinks down to 1 before the other one's
+/// grows, so that shifting back and forth goes through the same ratios
+/// (e.g. `[1, 2]`, `[1, 1]`, `[2, 1]`).
+pub fn shift_help_column_ratio(ratio: [u16; 2], widen_keys: bool) -> [u16; 2] {
+    let [mut narrow, mut wide] = if widen_keys {
+        ratio
+    } else {
+        [ratio[1], ratio[0]]
+    };
+    if narrow > 1 {
+        narrow -= 1;
+    } else {
+        wide = (wide + 1).min(MAX_HELP_COLUMN_RATIO);
+    }
+    if widen_keys {
+        [narrow, wide]
+    } else {
+        [wide, narrow]
+    }
+}
+
 pub fn build_keybindings_table<'a>(
     keybindings: &'a FxHashMap<Mode, DisplayableKeybindings>,
     mode: Mode,
     colorscheme: &'a Colorscheme,
+    column_ratio: [u16; 2],
 ) -> Table<'a> {
-    match mode {
+    let rows = match mode {
         Mode::Channel => build_keybindings_table_for_channel(
             &keybindings[&mode],
             colorscheme,
@@ -213,13 +313,14 @@ pub fn build_keybindings_table<'a>(
                 colorscheme,
             )
         }
-    }
+    };
+    Table::new(rows, help_table_widths(column_ratio))
 }
 
 fn build_keybindings_table_for_channel<'a>(
     keybindings: &'a DisplayableKeybindings,
     colorscheme: &'a Colorscheme,
-) -> Table<'a> {
+) -> Vec<Row<'a>> {
     // Results navigation
     let results_navigation_keys = keybindings
         .bindings
@@ -292,25 +393,20 @@ fn build_keybindings_table_for_channel<'a>(
         colorscheme.mode.channel,
     ));
 
-    let widths = vec![Constraint::Fill(1), Constraint::Fill(2)];
-
-    Table::new(
-        vec![
-            results_row,
-            preview_row,
-            select_entry_row,
-            copy_entry_row,
-            send_to_channel_row,
-            switch_channels_row,
-        ],
-        widths,
-    )
+    vec![
+        results_row,
+        preview_row,
+        select_entry_row,
+        copy_entry_row,
+        send_to_channel_row,
+        switch_channels_row,
+    ]
 }
 
 fn build_keybindings_table_for_channel_selection<'a>(
     keybindings: &'a DisplayableKeybindings,
     colorscheme: &'a Colorscheme,
-) -> Table<'a> {
+) -> Vec<Row<'a>> {
     // Results navigation
     let navigation_keys = keybindings
         .bindings
@@ -347,16 +443,13 @@ fn build_keybindings_table_for_channel_selection<'a>(
         colorscheme.mode.remote_control,
     ));
 
-    Table::new(
-        vec![results_row, select_entry_row, switch_channels_row],
-        vec![Constraint::Fill(1), Constraint::Fill(2)],
-    )
+    vec![results_row, select_entry_row, switch_channels_row]
 }
 
 fn build_keybindings_table_for_channel_transitions<'a>(
     keybindings: &'a DisplayableKeybindings,
     colorscheme: &'a Colorscheme,
-) -> Table<'a> {
+) -> Vec<Row<'a>> {
     // Results navigation
     let results_navigation_keys = keybindings
         .bindings
@@ -393,10 +486,7 @@ fn build_keybindings_table_for_channel_transitions<'a>(
         colorscheme.mode.send_to_channel,
     ));
 
-    Table::new(
-        vec![results_row, select_entry_row, cancel_row],
-        vec![Constraint::Fill(1), Constraint::Fill(2)],
-    )
+    vec![results_row, select_entry_row, cancel_row]
 }
 
 fn build_cells_for_group<'a>(
@@ -430,3 +520,87 @@ fn build_cells_for_group<'a>(
 
     cells
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{default_config_from_file, UiConfig};
+    use crate::event::Key;
+
+    use super::*;
+
+    fn multiple_keys() -> Binding {
+        Binding::MultipleKeys(vec![Key::Down, Key::Ctrl('n'), Key::Ctrl('j')])
+    }
+
+    #[test]
+    fn test_serialize_binding_without_limit() {
+        assert_eq!(
+            serialize_binding(&multiple_keys(), None),
+            "Down, Ctrl-n, Ctrl-j"
+        );
+    }
+
+    #[test]
+    fn test_serialize_binding_truncated() {
+        let serialized = serialize_binding(&multiple_keys(), Some(2));
+
+        assert_eq!(serialized, "Down, Ctrl-n, …");
+        // the ellipsis aside, only `max_keys_shown` keys are displayed
+        assert_eq!(serialized.split(", ").count(), 2 + 1);
+    }
+
+    #[test]
+    fn test_serialize_binding_within_limit() {
+        assert_eq!(
+            serialize_binding(&multiple_keys(), Some(3)),
+            "Down, Ctrl-n, Ctrl-j"
+        );
+        assert_eq!(
+            serialize_binding(&Binding::SingleKey(Key::Esc), Some(1)),
+            "Esc"
+        );
+    }
+
+    #[test]
+    fn test_help_table_widths_follow_configured_ratio() {
+        let ui: UiConfig =
+            toml::from_str("help_column_ratio = [2, 3]").unwrap();
+        assert_eq!(
+            help_table_widths(ui.help_column_ratio),
+            vec![Constraint::Fill(2), Constraint::Fill(3)]
+        );
+        assert_eq!(
+            help_table_widths(UiConfig::default().help_column_ratio),
+            vec![Constraint::Fill(1), Constraint::Fill(2)]
+        );
+    }
+
+    #[test]
+    fn test_shift_help_column_ratio() {
+        let widen_actions = |ratio| shift_help_column_ratio(ratio, false);
+        let widen_keys = |ratio| shift_help_column_ratio(ratio, true);
+        assert_eq!(widen_actions([1, 2]), [1, 1]);
+        assert_eq!(widen_actions([1, 1]), [2, 1]);
+        assert_eq!(widen_keys([2, 1]), [1, 1]);
+        assert_eq!(widen_keys([1, 1]), [1, 2]);
+        assert_eq!(widen_keys([1, 2]), [1, 3]);
+        assert_eq!(
+            widen_keys([1, MAX_HELP_COLUMN_RATIO]),
+            [1, MAX_HELP_COLUMN_RATIO]
+        );
+    }
+
+    #[test]
+    fn test_to_displayable_respects_max_keys_shown() {
+        let keybindings = default_config_from_file().unwrap().keybindings;
+
+        let displayable = keybindings.to_displayable(Some(1));
+        let navigation = &displayable[&Mode::RemoteControl].bindings
+            [&DisplayableAction::ResultsNavigation];
+
+        assert_eq!(
+            navigation,
+            &vec!["Up, …".to_string(), "Down, …".to_string()]
+        );
+    }
+}
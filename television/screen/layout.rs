@@ -2,7 +2,8 @@ use std::fmt::Display;
 
 use ratatui::layout;
 use ratatui::layout::{Constraint, Direction, Rect};
-use serde::Deserialize;
+use ratatui::symbols::border;
+use serde::{Deserialize, Serialize};
 
 use crate::config::UiConfig;
 
@@ -46,7 +47,9 @@ impl HelpBarLayout {
     }
 }
 
-#[derive(Debug, Clone, Copy, Deserialize, Default, PartialEq, Hash)]
+#[derive(
+    Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Hash,
+)]
 pub enum InputPosition {
     #[serde(rename = "top")]
     #[default]
@@ -64,7 +67,66 @@ impl Display for InputPosition {
     }
 }
 
-#[derive(Debug, Clone, Copy, Deserialize, Default, PartialEq, Hash)]
+/// How the rows of the results list are numbered.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Hash)]
+pub enum LineNumbers {
+    /// The position of the entry among all the results.
+    #[serde(rename = "absolute")]
+    Absolute,
+    /// The position of the entry among the visible results, as used when
+    /// jumping to a result with `alt-<digit>`.
+    #[serde(rename = "relative")]
+    Relative,
+}
+
+/// Which end of the results that are too wide for the results list gets
+/// cut off.
+#[derive(
+    Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq, Hash,
+)]
+pub enum Truncation {
+    /// Cut off the start of the name, keeping e.g. the file name of a path
+    /// visible.
+    #[serde(rename = "left")]
+    Left,
+    /// Cut off the end of the line.
+    #[default]
+    #[serde(rename = "right")]
+    Right,
+}
+
+/// The style of the borders drawn around the results, preview and input
+/// panes.
+#[derive(
+    Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq, Hash,
+)]
+pub enum BorderKind {
+    /// Borders are invisible but still take up space.
+    #[serde(rename = "none")]
+    None,
+    #[serde(rename = "single")]
+    Single,
+    #[serde(rename = "double")]
+    Double,
+    #[serde(rename = "rounded")]
+    #[default]
+    Rounded,
+}
+
+impl BorderKind {
+    pub fn border_set(self) -> border::Set {
+        match self {
+            BorderKind::None => border::EMPTY,
+            BorderKind::Single => border::PLAIN,
+            BorderKind::Double => border::DOUBLE,
+            BorderKind::Rounded => border::ROUNDED,
+        }
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Hash,
+)]
 pub enum PreviewTitlePosition {
     #[serde(rename = "top")]
     #[default]
@@ -82,6 +144,13 @@ impl Display for PreviewTitlePosition {
     }
 }
 
+/// The areas of the right pane in split search mode.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SplitLayout {
+    pub results: Rect,
+    pub input: Rect,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Layout {
     pub help_bar: Option<HelpBarLayout>,
@@ -89,6 +158,7 @@ pub struct Layout {
     pub input: Rect,
     pub preview_window: Option<Rect>,
     pub remote_control: Option<Rect>,
+    pub split: Option<SplitLayout>,
 }
 
 impl Default for Layout {
@@ -117,7 +187,67 @@ impl Layout {
             input,
             preview_window,
             remote_control,
+            split: None,
+        }
+    }
+
+    /// Split the results and input areas in two side by side panes for the
+    /// split search mode.
+    #[must_use]
+    pub fn with_split(mut self) -> Self {
+        let halves = |rect: Rect| {
+            let chunks = layout::Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Fill(1), Constraint::Fill(1)])
+                .split(rect);
+            (chunks[0], chunks[1])
+        };
+        let (results, split_results) = halves(self.results);
+        let (input, split_input) = halves(self.input);
+        self.results = results;
+        self.input = input;
+        self.split = Some(SplitLayout {
+            results: split_results,
+            input: split_input,
+        });
+        self
+    }
+
+    /// Grow the input areas to `height` rows (e.g. to wrap a long query),
+    /// the results areas shrinking accordingly while keeping at least 3
+    /// rows.
+    #[must_use]
+    pub fn with_input_height(mut self, height: u16) -> Self {
+        let extra = height
+            .saturating_sub(self.input.height)
+            .min(self.results.height.saturating_sub(3));
+        let input_on_top = self.input.y < self.results.y;
+        let grow = |input: &mut Rect, results: &mut Rect| {
+            input.height += extra;
+            results.height -= extra;
+            if input_on_top {
+                results.y += extra;
+            } else {
+                input.y -= extra;
+            }
+        };
+        grow(&mut self.input, &mut self.results);
+        if let Some(split) = &mut self.split {
+            grow(&mut split.input, &mut split.results);
         }
+        self
+    }
+
+    /// Expand the preview window to the whole `area`, hiding every other
+    /// area. The results area keeps its size so that the picker still knows
+    /// how many entries to show once the normal view is restored.
+    #[must_use]
+    pub fn with_fullscreen_preview(mut self, area: Rect) -> Self {
+        self.help_bar = None;
+        self.preview_window = Some(area);
+        self.remote_control = None;
+        self.split = None;
+        self
     }
 
     pub fn build(
@@ -248,3 +378,122 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
 // UI size
 const UI_WIDTH_PERCENT: u16 = 95;
 const UI_HEIGHT_PERCENT: u16 = 95;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_layout(input_bar_position: InputPosition) -> Layout {
+        let ui_config = UiConfig {
+            input_bar_position,
+            ..Default::default()
+        };
+        Layout::build(Rect::new(0, 0, 100, 50), &ui_config, false, true)
+    }
+
+    #[test]
+    fn test_layout_input_on_top() {
+        let layout = build_layout(InputPosition::Top);
+
+        assert!(layout.input.y < layout.results.y);
+        assert_eq!(layout.input.bottom(), layout.results.top());
+        assert_eq!(layout.input.height, 3);
+    }
+
+    #[test]
+    fn test_layout_input_on_bottom() {
+        let layout = build_layout(InputPosition::Bottom);
+
+        assert!(layout.results.y < layout.input.y);
+        assert_eq!(layout.results.bottom(), layout.input.top());
+        assert_eq!(layout.input.height, 3);
+    }
+
+    #[test]
+    fn test_layout_preview_spans_full_height() {
+        for position in [InputPosition::Top, InputPosition::Bottom] {
+            let layout = build_layout(position);
+            let preview = layout.preview_window.unwrap();
+
+            assert_eq!(
+                preview.top(),
+                layout.results.top().min(layout.input.top())
+            );
+            assert_eq!(
+                preview.bottom(),
+                layout.results.bottom().max(layout.input.bottom())
+            );
+        }
+    }
+
+    #[test]
+    fn test_layout_with_split() {
+        let layout = build_layout(InputPosition::Top);
+        let split_layout = layout.with_split();
+        let split = split_layout.split.unwrap();
+
+        assert_eq!(split_layout.results.left(), layout.results.left());
+        assert_eq!(split_layout.results.right(), split.results.left());
+        assert_eq!(split.results.right(), layout.results.right());
+        assert_eq!(split.results.y, layout.results.y);
+        assert_eq!(split_layout.input.right(), split.input.left());
+        assert_eq!(split.input.height, 3);
+    }
+
+    #[test]
+    fn test_layout_with_input_height() {
+        for position in [InputPosition::Top, InputPosition::Bottom] {
+            let layout = build_layout(position);
+            let grown = layout.with_input_height(5);
+
+            assert_eq!(grown.input.height, 5);
+            assert_eq!(grown.results.height, layout.results.height - 2);
+            assert_eq!(
+                grown.input.top().min(grown.results.top()),
+                layout.input.top().min(layout.results.top())
+            );
+            assert_eq!(
+                grown.input.bottom().max(grown.results.bottom()),
+                layout.input.bottom().max(layout.results.bottom())
+            );
+            // the results keep at least 3 rows
+            let grown = layout.with_input_height(u16::MAX);
+            assert_eq!(grown.results.height, 3);
+        }
+
+        let layout = build_layout(InputPosition::Top).with_split();
+        let split = layout.with_input_height(4).split.unwrap();
+        assert_eq!(split.input.height, 4);
+        assert_eq!(split.input.bottom(), split.results.top());
+    }
+
+    #[test]
+    fn test_layout_with_fullscreen_preview() {
+        let area = Rect::new(0, 0, 100, 40);
+        let layout = build_layout(InputPosition::Top).with_split();
+        let fullscreen = layout.with_fullscreen_preview(area);
+
+        assert_eq!(fullscreen.preview_window, Some(area));
+        assert!(fullscreen.help_bar.is_none());
+        assert!(fullscreen.remote_control.is_none());
+        assert!(fullscreen.split.is_none());
+        // the results keep their size for when the normal view is restored
+        assert_eq!(fullscreen.results, layout.results);
+    }
+
+    #[test]
+    fn test_border_kind_border_set() {
+        assert_eq!(BorderKind::None.border_set(), border::EMPTY);
+        assert_eq!(BorderKind::Single.border_set(), border::PLAIN);
+        assert_eq!(BorderKind::Double.border_set(), border::DOUBLE);
+        assert_eq!(BorderKind::Rounded.border_set(), border::ROUNDED);
+    }
+
+    #[test]
+    fn test_border_kind_from_config() {
+        let ui_config: UiConfig =
+            toml::from_str(r#"border_type = "double""#).unwrap();
+        assert_eq!(ui_config.border_type, BorderKind::Double);
+        assert_eq!(UiConfig::default().border_type, BorderKind::Rounded);
+    }
+}
@@ -1,7 +1,10 @@
 pub mod cache;
 pub mod colors;
+pub mod confirmation;
+pub mod debug;
 pub mod help;
 pub mod input;
+pub mod keybinding_editor;
 pub mod keybindings;
 pub mod layout;
 pub mod logo;
@@ -1,9 +1,12 @@
-use crate::preview::PreviewState;
+use crate::channels::entry::Entry;
+use crate::preview::search::{find_term_occurrences, PreviewSearch};
 use crate::preview::{
     ansi::IntoText, PreviewContent, FILE_TOO_LARGE_MSG, LOADING_MSG,
-    PREVIEW_NOT_SUPPORTED_MSG, TIMEOUT_MSG,
+    NO_PREVIEW_MSG, PREVIEW_NOT_SUPPORTED_MSG, TIMEOUT_MSG,
 };
+use crate::preview::{Preview, PreviewState};
 use crate::screen::colors::{Colorscheme, PreviewColorscheme};
+use crate::screen::layout::BorderKind;
 use crate::utils::image::ImagePreviewWidget;
 use crate::utils::strings::{
     replace_non_printable, shrink_with_ellipsis, ReplaceNonPrintableConfig,
@@ -12,9 +15,7 @@ use crate::utils::strings::{
 use anyhow::Result;
 use devicons::FileIcon;
 use ratatui::buffer::Buffer;
-use ratatui::widgets::{
-    Block, BorderType, Borders, Padding, Paragraph, Widget, Wrap,
-};
+use ratatui::widgets::{Block, Borders, Padding, Paragraph, Widget, Wrap};
 use ratatui::Frame;
 use ratatui::{
     layout::{Alignment, Rect},
@@ -49,21 +50,51 @@ pub fn draw_preview_content_block(
     preview_state: &PreviewState,
     use_nerd_font_icons: bool,
     colorscheme: &Colorscheme,
+    border_kind: BorderKind,
+    header: Option<&str>,
+    query_terms: &[String],
 ) -> Result<()> {
-    let inner = draw_content_outer_block(
+    let mut inner = draw_content_outer_block(
         f,
         rect,
         colorscheme,
+        border_kind,
+        preview_state.scroll_locked,
+        preview_state.search.as_ref(),
         preview_state.preview.icon,
         &preview_state.preview.title,
         use_nerd_font_icons,
     )?;
+    if let Some(header) = header.filter(|_| inner.height > 1) {
+        let header_rect = Rect { height: 1, ..inner };
+        inner.y += 1;
+        inner.height -= 1;
+        f.render_widget(
+            Paragraph::new(shrink_with_ellipsis(
+                &replace_non_printable(
+                    header.as_bytes(),
+                    &ReplaceNonPrintableConfig::default(),
+                )
+                .0,
+                header_rect.width.saturating_sub(2) as usize,
+            ))
+            .block(Block::default().padding(Padding::horizontal(1)))
+            .style(
+                Style::default()
+                    .fg(colorscheme.preview.title_fg)
+                    .add_modifier(Modifier::ITALIC | Modifier::UNDERLINED),
+            ),
+            header_rect,
+        );
+    }
     // render the preview content
     let rp = build_preview_widget(
         inner,
         &preview_state.preview.content,
         preview_state.target_line,
         preview_state.scroll,
+        preview_state.search.as_ref(),
+        query_terms,
         colorscheme,
     );
     f.render_widget(rp, inner);
@@ -76,6 +107,8 @@ pub fn build_preview_widget<'a>(
     preview_content: &'a PreviewContent,
     target_line: Option<u16>,
     preview_scroll: u16,
+    search: Option<&PreviewSearch>,
+    query_terms: &[String],
     colorscheme: &'a Colorscheme,
 ) -> PreviewWidget<'a> {
     let preview_block =
@@ -96,6 +129,8 @@ pub fn build_preview_widget<'a>(
                 preview_block,
                 target_line,
                 preview_scroll,
+                search,
+                query_terms,
                 colorscheme.preview,
             ))
         }
@@ -113,6 +148,8 @@ pub fn build_preview_widget<'a>(
                 preview_block,
                 target_line,
                 preview_scroll,
+                search,
+                query_terms,
                 colorscheme.preview,
                 inner.height,
             ))
@@ -136,6 +173,16 @@ pub fn build_preview_widget<'a>(
             .alignment(Alignment::Left)
             .style(Style::default().add_modifier(Modifier::ITALIC)),
         ),
+        PreviewContent::Unavailable => PreviewWidget::Paragraph(
+            build_meta_preview_paragraph(
+                inner,
+                NO_PREVIEW_MSG,
+                FILL_CHAR_EMPTY,
+            )
+            .block(preview_block)
+            .alignment(Alignment::Left)
+            .style(Style::default().add_modifier(Modifier::ITALIC)),
+        ),
         PreviewContent::FileTooLarge => PreviewWidget::Paragraph(
             build_meta_preview_paragraph(
                 inner,
@@ -199,35 +246,47 @@ fn build_plain_text_paragraph<'a>(
     preview_block: Block<'a>,
     target_line: Option<u16>,
     preview_scroll: u16,
+    search: Option<&PreviewSearch>,
+    query_terms: &[String],
     colorscheme: PreviewColorscheme,
 ) -> Paragraph<'a> {
     let mut lines = Vec::new();
     for (i, line) in text.iter().enumerate() {
-        lines.push(Line::from(vec![
+        let mut spans = vec![
             build_line_number_span(i + 1).style(Style::default().fg(
                 if matches!(
-                        target_line,
-                        Some(l) if l == u16::try_from(i).unwrap_or(0) + 1
-                    )
-                {
+                    target_line,
+                    Some(l) if l == u16::try_from(i).unwrap_or(0) + 1
+                ) {
                     colorscheme.gutter_selected_fg
                 } else {
                     colorscheme.gutter_fg
                 },
             )),
-            Span::styled(" │ ",
-                         Style::default().fg(colorscheme.gutter_fg).dim()),
             Span::styled(
-                line.to_string(),
-                Style::default().fg(colorscheme.content_fg).bg(
-                    if matches!(target_line, Some(l) if l == u16::try_from(i).unwrap() + 1) {
-                        colorscheme.highlight_bg
-                    } else {
-                        Color::Reset
-                    },
-                ),
+                " │ ",
+                Style::default().fg(colorscheme.gutter_fg).dim(),
+            ),
+        ];
+        spans.extend(highlight_search_matches(
+            highlight_query_terms(
+                vec![Span::styled(
+                    line.to_string(),
+                    Style::default().fg(colorscheme.content_fg).bg(
+                        if matches!(target_line, Some(l) if l == u16::try_from(i).unwrap() + 1) {
+                            colorscheme.highlight_bg
+                        } else {
+                            Color::Reset
+                        },
+                    ),
+                )],
+                query_terms,
+                colorscheme.match_fg,
             ),
-        ]));
+            i,
+            search,
+        ));
+        lines.push(Line::from(spans));
     }
     let text = Text::from(lines);
     Paragraph::new(text)
@@ -253,11 +312,14 @@ fn build_plain_text_wrapped_paragraph<'a>(
         .wrap(Wrap { trim: true })
 }
 
+#[allow(clippy::too_many_arguments)]
 fn build_syntect_highlighted_paragraph<'a>(
     highlighted_lines: &'a [Vec<(syntect::highlighting::Style, String)>],
     preview_block: Block<'a>,
     target_line: Option<u16>,
     preview_scroll: u16,
+    search: Option<&PreviewSearch>,
+    query_terms: &[String],
     colorscheme: PreviewColorscheme,
     height: u16,
 ) -> Paragraph<'a> {
@@ -265,6 +327,8 @@ fn build_syntect_highlighted_paragraph<'a>(
         highlighted_lines,
         target_line.map(|l| l as usize),
         preview_scroll,
+        search,
+        query_terms,
         colorscheme,
         height,
     )
@@ -326,10 +390,14 @@ pub fn build_meta_preview_paragraph<'a>(
     Paragraph::new(Text::from(lines))
 }
 
+#[allow(clippy::too_many_arguments)]
 fn draw_content_outer_block(
     f: &mut Frame,
     rect: Rect,
     colorscheme: &Colorscheme,
+    border_kind: BorderKind,
+    scroll_locked: bool,
+    search: Option<&PreviewSearch>,
     icon: Option<FileIcon>,
     title: &str,
     use_nerd_font_icons: bool,
@@ -362,26 +430,139 @@ fn draw_content_outer_block(
     preview_title_spans.push(Span::from(" "));
 
     // build the preview block
-    let preview_outer_block = Block::default()
+    let mut preview_outer_block = Block::default()
         .title_top(
             Line::from(preview_title_spans)
                 .alignment(Alignment::Center)
                 .style(Style::default().fg(colorscheme.preview.title_fg)),
         )
         .borders(Borders::ALL)
-        .border_type(BorderType::Rounded)
-        .border_style(Style::default().fg(colorscheme.general.border_fg))
+        .border_set(border_kind.border_set())
+        .border_style(Style::default().fg(colorscheme.preview.border_fg))
         .style(
             Style::default()
                 .bg(colorscheme.general.background.unwrap_or_default()),
         )
         .padding(Padding::new(0, 1, 1, 0));
+    if scroll_locked {
+        preview_outer_block = preview_outer_block.title_top(
+            Line::from(" locked ")
+                .alignment(Alignment::Right)
+                .style(Style::default().fg(colorscheme.preview.title_fg)),
+        );
+    }
+
+    if let Some(search) = search {
+        preview_outer_block = preview_outer_block.title_bottom(
+            Line::from(format!(" {} ", search.status()))
+                .alignment(Alignment::Left)
+                .style(
+                    Style::default()
+                        .fg(colorscheme.preview.title_fg)
+                        .add_modifier(if search.editing {
+                            Modifier::BOLD
+                        } else {
+                            Modifier::empty()
+                        }),
+                ),
+        );
+    }
 
     let inner = preview_outer_block.inner(rect);
     f.render_widget(preview_outer_block, rect);
     Ok(inner)
 }
 
+/// Restyle the parts of a line's content that match the preview search.
+///
+/// `spans` make up the content of the line at index `line`, without the
+/// gutter, and are split wherever a match starts or ends.
+fn highlight_search_matches<'a>(
+    spans: Vec<Span<'a>>,
+    line: usize,
+    search: Option<&PreviewSearch>,
+) -> Vec<Span<'a>> {
+    let Some(search) = search else {
+        return spans;
+    };
+    let ranges = search
+        .line_matches(line)
+        .map(|(i, m)| {
+            let modifier = if search.current_index() == Some(i) {
+                Modifier::REVERSED | Modifier::BOLD | Modifier::UNDERLINED
+            } else {
+                Modifier::REVERSED
+            };
+            (m.start, m.end, Style::default().add_modifier(modifier))
+        })
+        .collect::<Vec<_>>();
+    restyle_ranges(spans, &ranges)
+}
+
+/// Restyle the occurrences of the results query's terms in a line's
+/// content, see [`highlight_search_matches`].
+fn highlight_query_terms<'a>(
+    spans: Vec<Span<'a>>,
+    terms: &[String],
+    match_fg: Color,
+) -> Vec<Span<'a>> {
+    if terms.is_empty() {
+        return spans;
+    }
+    let content = spans.iter().map(|s| s.content.as_ref()).collect::<String>();
+    let style = Style::default().fg(match_fg).add_modifier(Modifier::BOLD);
+    let ranges = find_term_occurrences(&content, terms)
+        .into_iter()
+        .map(|(start, end)| (start, end, style))
+        .collect::<Vec<_>>();
+    restyle_ranges(spans, &ranges)
+}
+
+/// Patch the style of the given byte ranges of a line's content with the
+/// style they come with, splitting `spans` wherever a range starts or ends.
+///
+/// The ranges must be sorted and disjoint.
+fn restyle_ranges<'a>(
+    spans: Vec<Span<'a>>,
+    ranges: &[(usize, usize, Style)],
+) -> Vec<Span<'a>> {
+    if ranges.is_empty() {
+        return spans;
+    }
+    let mut highlighted = Vec::with_capacity(spans.len() + 2 * ranges.len());
+    let mut offset = 0;
+    for span in spans {
+        let content = span.content.as_ref();
+        let len = content.len();
+        let mut cursor = 0;
+        for &(start, end, style) in ranges {
+            // the match's range relative to the span
+            let start = start.clamp(offset, offset + len) - offset;
+            let end = end.clamp(offset, offset + len) - offset;
+            if start >= end {
+                continue;
+            }
+            if start > cursor {
+                highlighted.push(Span::styled(
+                    content[cursor..start].to_string(),
+                    span.style,
+                ));
+            }
+            highlighted.push(Span::styled(
+                content[start..end].to_string(),
+                span.style.patch(style),
+            ));
+            cursor = end;
+        }
+        if cursor < len {
+            highlighted
+                .push(Span::styled(content[cursor..].to_string(), span.style));
+        }
+        offset += len;
+    }
+    highlighted
+}
+
 fn build_line_number_span<'a>(line_number: usize) -> Span<'a> {
     Span::from(format!("{line_number:5} "))
 }
@@ -390,6 +571,8 @@ fn compute_paragraph_from_highlighted_lines(
     highlighted_lines: &[Vec<(syntect::highlighting::Style, String)>],
     line_specifier: Option<usize>,
     preview_scroll: u16,
+    search: Option<&PreviewSearch>,
+    query_terms: &[String],
     colorscheme: PreviewColorscheme,
     height: u16,
 ) -> Paragraph<'static> {
@@ -415,20 +598,31 @@ fn compute_paragraph_from_highlighted_lines(
                         " │ ",
                         Style::default().fg(colorscheme.gutter_fg).dim(),
                     )))
-                    .chain(l.iter().cloned().map(|sr| {
-                        convert_syn_region_to_span(
-                            &(sr.0, sr.1),
-                            if line_specifier.is_some()
-                                && i == line_specifier
-                                    .unwrap()
-                                    .saturating_sub(1)
-                            {
-                                Some(colorscheme.highlight_bg)
-                            } else {
-                                None
-                            },
-                        )
-                    })),
+                    .chain(highlight_search_matches(
+                        highlight_query_terms(
+                            l.iter()
+                                .cloned()
+                                .map(|sr| {
+                                    convert_syn_region_to_span(
+                                        &(sr.0, sr.1),
+                                        if line_specifier.is_some()
+                                            && i == line_specifier
+                                                .unwrap()
+                                                .saturating_sub(1)
+                                        {
+                                            Some(colorscheme.highlight_bg)
+                                        } else {
+                                            None
+                                        },
+                                    )
+                                })
+                                .collect(),
+                            query_terms,
+                            colorscheme.match_fg,
+                        ),
+                        i,
+                        search,
+                    )),
             )
         })
         .collect();
@@ -459,3 +653,56 @@ fn convert_syn_color_to_ratatui_color(
 ) -> Color {
     Color::Rgb(color.r, color.g, color.b)
 }
+
+/// The one-line header shown at the top of the preview pane.
+///
+/// File previews carry a header showing the file's full path, size and
+/// modification time while other entries show their display string.
+pub fn preview_header(entry: &Entry, preview: &Preview) -> String {
+    preview
+        .header
+        .clone()
+        .unwrap_or_else(|| entry.stdout_repr())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::entry::PreviewType;
+
+    #[test]
+    fn test_highlight_search_matches() {
+        let mut search = PreviewSearch::new();
+        search.query = String::from("ab");
+        search.update_matches(&["xabyab".to_string()]);
+        let spans = vec![
+            Span::raw("xa"),
+            Span::styled("by", Style::default().bold()),
+            Span::raw("ab"),
+        ];
+        let highlighted = highlight_search_matches(spans, 0, Some(&search));
+        assert_eq!(
+            highlighted
+                .iter()
+                .map(|s| s.content.as_ref())
+                .collect::<Vec<_>>(),
+            vec!["x", "a", "b", "y", "ab"]
+        );
+        // the first match is the current one
+        assert!(highlighted[1]
+            .style
+            .add_modifier
+            .contains(Modifier::REVERSED | Modifier::UNDERLINED));
+        assert!(!highlighted[3]
+            .style
+            .add_modifier
+            .contains(Modifier::REVERSED));
+        assert_eq!(highlighted[4].style.add_modifier, Modifier::REVERSED);
+    }
+
+    #[test]
+    fn test_preview_header_without_metadata() {
+        let entry = Entry::new("PATH".to_string(), PreviewType::EnvVar);
+        assert_eq!(preview_header(&entry, &Preview::default()), "PATH");
+    }
+}
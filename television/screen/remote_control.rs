@@ -1,5 +1,7 @@
 use crate::channels::entry::Entry;
+use crate::config::SendToChannelMode;
 use crate::screen::colors::{Colorscheme, GeneralColorscheme};
+use crate::screen::layout::Truncation;
 use crate::screen::logo::build_remote_logo_paragraph;
 use crate::screen::mode::mode_color;
 use crate::screen::results::build_results_list;
@@ -25,6 +27,7 @@ pub fn draw_remote_control(
     picker_state: &mut ListState,
     input_state: &mut Input,
     mode: &Mode,
+    send_to_channel_mode: SendToChannelMode,
     colorscheme: &Colorscheme,
 ) -> Result<()> {
     let layout = Layout::default()
@@ -46,7 +49,11 @@ pub fn draw_remote_control(
         picker_state,
         colorscheme,
     );
-    draw_rc_input(f, layout[1], input_state, colorscheme)?;
+    let title = match mode {
+        Mode::SendToChannel => format!("{mode} ({send_to_channel_mode})"),
+        _ => String::from("Remote Control"),
+    };
+    draw_rc_input(f, layout[1], &title, input_state, colorscheme)?;
     draw_rc_logo(
         f,
         layout[2],
@@ -73,6 +80,7 @@ fn draw_rc_channels(
                 .bg(colorscheme.general.background.unwrap_or_default()),
         )
         .padding(Padding::right(1));
+    let available_width = rc_block.inner(area).width;
 
     let channel_list = build_results_list(
         rc_block,
@@ -80,7 +88,13 @@ fn draw_rc_channels(
         None,
         ListDirection::TopToBottom,
         use_nerd_font_icons,
+        false,
+        Truncation::Right,
         &colorscheme.results,
+        available_width,
+        0,
+        false,
+        None,
     );
 
     f.render_stateful_widget(channel_list, area, picker_state);
@@ -89,11 +103,12 @@ fn draw_rc_channels(
 fn draw_rc_input(
     f: &mut Frame,
     area: Rect,
+    title: &str,
     input: &mut Input,
     colorscheme: &Colorscheme,
 ) -> Result<()> {
     let input_block = Block::default()
-        .title_top(Line::from("Remote Control").alignment(Alignment::Center))
+        .title_top(Line::from(title).alignment(Alignment::Center))
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
         .border_style(Style::default().fg(colorscheme.general.border_fg))
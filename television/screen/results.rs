@@ -1,42 +1,180 @@
-use crate::channels::entry::Entry;
+use crate::channels::entry::{Entry, EntryKind, PreviewType};
 use crate::screen::colors::{Colorscheme, ResultsColorscheme};
-use crate::screen::layout::InputPosition;
+use crate::screen::layout::{BorderKind, InputPosition, Truncation};
 use crate::utils::strings::{
-    make_matched_string_printable, next_char_boundary,
-    slice_at_char_boundaries,
+    make_matched_string_printable, next_char_boundary, shorten_path,
+    slice_at_char_boundaries, slice_suffix_up_to_width, slice_up_to_width,
+    ELLIPSIS,
 };
 use anyhow::Result;
 use ratatui::layout::{Alignment, Rect};
-use ratatui::prelude::{Color, Line, Span, Style};
+use ratatui::prelude::{Color, Line, Span, Style, Text};
 use ratatui::style::Stylize;
 use ratatui::widgets::{
-    Block, BorderType, Borders, List, ListDirection, ListState, Padding,
+    Block, Borders, List, ListDirection, ListItem, ListState, Padding,
+    Paragraph,
 };
 use ratatui::Frame;
 use rustc_hash::FxHashSet;
 use std::str::FromStr;
+use unicode_width::UnicodeWidthStr;
 
 const POINTER_SYMBOL: &str = "> ";
 const SELECTED_SYMBOL: &str = "● ";
 const DESELECTED_SYMBOL: &str = "  ";
 
+pub const SCANNING_MSG: &str = "Searching...";
+
+/// What each row of the results list is prefixed with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowPrefix<'a> {
+    /// Row numbers, the first entry being numbered with the given number.
+    Numbers(usize),
+    /// The given labels, one per row (see `Quicknav`).
+    Labels(&'a [&'a str]),
+}
+
+/// The message shown in place of the results when a query matches nothing.
+///
+/// While the channel is still running, more entries may match later on and
+/// the search is reported as ongoing rather than unsuccessful. Nothing is
+/// shown without a query since an empty channel is already obvious.
+pub fn empty_results_message(
+    has_query: bool,
+    result_count: u32,
+    running: bool,
+    no_results_message: &str,
+) -> Option<&str> {
+    if !has_query || result_count > 0 {
+        None
+    } else if running {
+        Some(SCANNING_MSG)
+    } else {
+        Some(no_results_message)
+    }
+}
+
+/// The number of results that fit in a list of `height` rows when
+/// `row_spacing` blank lines are inserted after each result, results taking
+/// an extra row for their details when `two_line` is set.
+pub fn results_visible_rows(
+    height: u16,
+    row_spacing: u16,
+    two_line: bool,
+) -> usize {
+    usize::from(height / row_spacing.saturating_add(1 + u16::from(two_line)))
+}
+
+/// The width of the number column of a results list numbered from `first`
+/// and showing `count` entries, not counting the separating space.
+pub fn line_number_width(first: usize, count: usize) -> usize {
+    let last = first + count.saturating_sub(1);
+    last.checked_ilog10().unwrap_or(0) as usize + 1
+}
+
+/// The notice shown when only the first `shown` of the `total` results are
+/// listed (see `UiConfig::max_shown_results`).
+pub fn capped_results_notice(shown: u32, total: u32) -> Option<String> {
+    (total > shown).then(|| {
+        format!(
+            "showing {} of {}",
+            group_thousands(shown),
+            group_thousands(total)
+        )
+    })
+}
+
+/// Format `n` with its digits grouped by thousands (e.g. `5,000`).
+fn group_thousands(n: u32) -> String {
+    let digits = n.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// Builds the list widget used to display results.
+///
+/// `available_width` is the inner width of `results_block` once rendered: lines that don't fit
+/// in it (pointer symbol included) get truncated with an ellipsis.
+/// `row_spacing` blank lines are inserted after each result.
+/// When `two_line` is set, the line number, value and content snippet of
+/// each result are shown dimmed on a line of their own below its name.
+/// When `shorten_paths` is set, the intermediate components of the names of
+/// path entries are shortened (see `shorten_path`).
+/// With `Truncation::Left`, the start of the names of single-line entries
+/// is cut off first so that their end (e.g. a file name) stays visible.
+/// When `row_prefix` is set, each row is prefixed with its number or its
+/// label (see `RowPrefix`).
+#[allow(clippy::too_many_arguments)]
 pub fn build_results_list<'a, 'b>(
     results_block: Block<'b>,
     entries: &'a [Entry],
     selected_entries: Option<&FxHashSet<Entry>>,
     list_direction: ListDirection,
     use_icons: bool,
+    shorten_paths: bool,
+    truncation: Truncation,
     colorscheme: &ResultsColorscheme,
+    available_width: u16,
+    row_spacing: u16,
+    two_line: bool,
+    row_prefix: Option<RowPrefix>,
 ) -> List<'a>
 where
     'b: 'a,
 {
-    List::new(entries.iter().map(|entry| {
-        let mut spans = Vec::new();
+    let max_line_width =
+        usize::from(available_width).saturating_sub(POINTER_SYMBOL.width());
+    let prefix_width = match row_prefix {
+        Some(RowPrefix::Numbers(first)) => {
+            line_number_width(first, entries.len())
+        }
+        Some(RowPrefix::Labels(labels)) => {
+            labels.iter().map(|label| label.width()).max().unwrap_or(0)
+        }
+        None => 0,
+    };
+    List::new(entries.iter().enumerate().map(move |(i, entry)| {
+        if let EntryKind::GroupHeader { collapsed } = entry.kind {
+            let symbol = if collapsed { "▸ " } else { "▾ " };
+            let header = vec![Span::styled(
+                format!("{symbol}{}", entry.name),
+                Style::default().fg(colorscheme.result_name_fg).bold(),
+            )];
+            let mut lines = vec![Line::from(truncate_spans_to_width(
+                header,
+                max_line_width,
+            ))];
+            lines.resize(1 + usize::from(row_spacing), Line::default());
+            return ListItem::new(Text::from(lines));
+        }
+        let mut prefix = Vec::new();
+        // optional row number or label
+        match row_prefix {
+            Some(RowPrefix::Numbers(first)) => prefix.push(Span::styled(
+                format!("{:>prefix_width$} ", first + i),
+                Style::default().fg(colorscheme.result_line_number_fg).dim(),
+            )),
+            Some(RowPrefix::Labels(labels)) => prefix.push(Span::styled(
+                format!(
+                    "{:<prefix_width$} ",
+                    labels.get(i).copied().unwrap_or_default()
+                ),
+                Style::default()
+                    .fg(colorscheme.match_foreground_color)
+                    .bold(),
+            )),
+            None => {}
+        }
         // optional selection symbol
         if let Some(selected_entries) = selected_entries {
             if !selected_entries.is_empty() {
-                spans.push(if selected_entries.contains(entry) {
+                prefix.push(if selected_entries.contains(entry) {
                     Span::styled(
                         SELECTED_SYMBOL,
                         Style::default().fg(colorscheme.result_selected_fg),
@@ -49,47 +187,36 @@ where
         // optional icon
         if let Some(icon) = entry.icon.as_ref() {
             if use_icons {
-                spans.push(Span::styled(
+                prefix.push(Span::styled(
                     icon.to_string(),
                     Style::default().fg(Color::from_str(icon.color).unwrap()),
                 ));
 
-                spans.push(Span::raw(" "));
+                prefix.push(Span::raw(" "));
             }
         }
-        // entry name
-        let (entry_name, name_match_ranges) = make_matched_string_printable(
-            &entry.name,
-            entry.name_match_ranges.as_deref(),
-        );
-        let mut last_match_end = 0;
-        for (start, end) in name_match_ranges
-            .iter()
-            .map(|(s, e)| (*s as usize, *e as usize))
-        {
-            // from the end of the last match to the start of the current one
-            spans.push(Span::styled(
-                slice_at_char_boundaries(&entry_name, last_match_end, start)
-                    .to_string(),
-                Style::default().fg(colorscheme.result_name_fg),
-            ));
-            // the current match
-            spans.push(Span::styled(
-                slice_at_char_boundaries(&entry_name, start, end).to_string(),
-                Style::default().fg(colorscheme.match_foreground_color),
-            ));
-            last_match_end = end;
+        // entry name, continuation lines being aligned with the first one
+        let mut lines = name_lines(entry, shorten_paths, colorscheme);
+        let name = prefix.len()..prefix.len() + lines[0].len();
+        let indent = " ".repeat(prefix.iter().map(Span::width).sum());
+        for (i, line) in lines.iter_mut().enumerate() {
+            if i == 0 {
+                line.splice(0..0, prefix.clone());
+            } else {
+                line.insert(0, Span::raw(indent.clone()));
+            }
         }
-        // we need to push a span for the remainder of the entry name
-        // but only if there's something left
-        let next_boundary = next_char_boundary(&entry_name, last_match_end);
-        if next_boundary < entry_name.len() {
-            let remainder = entry_name[next_boundary..].to_string();
+        let spans = lines.last_mut().unwrap();
+        if entry.kind == EntryKind::Directory
+            && !entry.name.ends_with(std::path::MAIN_SEPARATOR)
+        {
             spans.push(Span::styled(
-                remainder,
+                std::path::MAIN_SEPARATOR_STR,
                 Style::default().fg(colorscheme.result_name_fg),
             ));
         }
+        let mut details = Vec::new();
+        let spans = if two_line { &mut details } else { spans };
         // optional line number
         if let Some(line_number) = entry.line_number {
             spans.push(Span::styled(
@@ -99,38 +226,55 @@ where
         }
         // optional preview
         if let Some(preview) = &entry.value {
-            spans.push(Span::raw(": "));
+            if !spans.is_empty() || !two_line {
+                spans.push(Span::raw(": "));
+            }
 
             let (preview, preview_match_ranges) =
                 make_matched_string_printable(
                     preview,
                     entry.value_match_ranges.as_deref(),
                 );
-            let mut last_match_end = 0;
-            for (start, end) in preview_match_ranges
-                .iter()
-                .map(|(s, e)| (*s as usize, *e as usize))
-            {
-                spans.push(Span::styled(
-                    slice_at_char_boundaries(&preview, last_match_end, start)
-                        .to_string(),
-                    Style::default().fg(colorscheme.result_preview_fg),
-                ));
-                spans.push(Span::styled(
-                    slice_at_char_boundaries(&preview, start, end).to_string(),
-                    Style::default().fg(colorscheme.match_foreground_color),
-                ));
-                last_match_end = end;
-            }
-            let next_boundary = next_char_boundary(&preview, last_match_end);
-            if next_boundary < preview.len() {
-                spans.push(Span::styled(
-                    preview[next_boundary..].to_string(),
-                    Style::default().fg(colorscheme.result_preview_fg),
-                ));
+            spans.extend(matched_spans(
+                &preview,
+                &preview_match_ranges,
+                Style::default().fg(colorscheme.result_preview_fg),
+                Style::default().fg(colorscheme.match_foreground_color),
+            ));
+        }
+        // optional content snippet
+        if let Some(snippet) = &entry.snippet {
+            if !spans.is_empty() || !two_line {
+                spans.push(Span::raw(": "));
             }
+            let (snippet, _) = make_matched_string_printable(snippet, None);
+            spans.push(Span::styled(
+                snippet,
+                Style::default().fg(colorscheme.result_preview_fg),
+            ));
+        }
+        if two_line {
+            details.insert(0, Span::raw(indent));
+            lines.push(details.into_iter().map(Stylize::dim).collect());
         }
-        Line::from(spans)
+        if truncation == Truncation::Left && entry.display_height() == 1 {
+            let line = &mut lines[0];
+            let name_spans = line.drain(name.clone()).collect::<Vec<_>>();
+            let other_width = line.iter().map(Span::width).sum::<usize>();
+            let name_spans = truncate_spans_from_left(
+                name_spans,
+                max_line_width.saturating_sub(other_width),
+            );
+            line.splice(name.start..name.start, name_spans);
+        }
+        let mut lines = lines
+            .into_iter()
+            .map(|spans| {
+                Line::from(truncate_spans_to_width(spans, max_line_width))
+            })
+            .collect::<Vec<_>>();
+        lines.resize(lines.len() + usize::from(row_spacing), Line::default());
+        ListItem::new(Text::from(lines))
     }))
     .direction(list_direction)
     .highlight_style(
@@ -140,7 +284,197 @@ where
     .block(results_block)
 }
 
-#[allow(clippy::too_many_arguments)]
+/// The spans of each line of an entry's name, with its matches highlighted.
+///
+/// When `shorten_paths` is set, the intermediate components of single-line
+/// path entries are shortened (see `shorten_path`).
+fn name_lines(
+    entry: &Entry,
+    shorten_paths: bool,
+    colorscheme: &ResultsColorscheme,
+) -> Vec<Vec<Span<'static>>> {
+    let name_style = Style::default().fg(colorscheme.result_name_fg);
+    let match_style = Style::default().fg(colorscheme.match_foreground_color);
+    let match_ranges = entry.name_match_ranges.as_deref().unwrap_or_default();
+    if entry.display_height() == 1 {
+        let (entry_name, name_match_ranges) =
+            make_matched_string_printable(&entry.name, Some(match_ranges));
+        let is_path = entry.preview_type == PreviewType::Files
+            || entry.kind == EntryKind::Directory;
+        let (entry_name, name_match_ranges) = if shorten_paths && is_path {
+            shorten_path(&entry_name, &name_match_ranges)
+        } else {
+            (entry_name, name_match_ranges)
+        };
+        return vec![matched_spans(
+            &entry_name,
+            &name_match_ranges,
+            name_style,
+            match_style,
+        )];
+    }
+    // match ranges are char ranges in the whole name
+    let mut line_start = 0;
+    entry
+        .display_lines()
+        .map(|line| {
+            let line_end = line_start + line.chars().count();
+            let line_ranges = match_ranges
+                .iter()
+                .map(|&(start, end)| (start as usize, end as usize))
+                .filter(|&(start, end)| start < line_end && end > line_start)
+                .map(|(start, end)| {
+                    (
+                        u32::try_from(start.max(line_start) - line_start)
+                            .unwrap_or(u32::MAX),
+                        u32::try_from(end.min(line_end) - line_start)
+                            .unwrap_or(u32::MAX),
+                    )
+                })
+                .collect::<Vec<_>>();
+            // skip the line feed
+            line_start = line_end + 1;
+            let (line, line_ranges) =
+                make_matched_string_printable(line, Some(&line_ranges));
+            matched_spans(&line, &line_ranges, name_style, match_style)
+        })
+        .collect()
+}
+
+/// Split `text` into spans, the characters within `match_ranges` being
+/// styled with `match_style` and the others with `style`.
+fn matched_spans(
+    text: &str,
+    match_ranges: &[(u32, u32)],
+    style: Style,
+    match_style: Style,
+) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut last_match_end = 0;
+    for (start, end) in
+        match_ranges.iter().map(|(s, e)| (*s as usize, *e as usize))
+    {
+        // from the end of the last match to the start of the current one
+        spans.push(Span::styled(
+            slice_at_char_boundaries(text, last_match_end, start).to_string(),
+            style,
+        ));
+        // the current match
+        spans.push(Span::styled(
+            slice_at_char_boundaries(text, start, end).to_string(),
+            match_style,
+        ));
+        last_match_end = end;
+    }
+    // we need to push a span for the remainder of the text but only if
+    // there's something left
+    let next_boundary = next_char_boundary(text, last_match_end);
+    if next_boundary < text.len() {
+        spans.push(Span::styled(text[next_boundary..].to_string(), style));
+    }
+    spans
+}
+
+/// Truncate a line's spans so that their cumulative display width doesn't exceed
+/// `max_width`, replacing whatever was cut off with an ellipsis.
+///
+/// Spans are only ever cut on grapheme boundaries and the styles of the remaining
+/// spans (e.g. match highlighting) are preserved.
+fn truncate_spans_to_width(
+    spans: Vec<Span<'_>>,
+    max_width: usize,
+) -> Vec<Span<'_>> {
+    if spans.iter().map(Span::width).sum::<usize>() <= max_width {
+        return spans;
+    }
+    let mut remaining_width = max_width;
+    let mut truncated = Vec::with_capacity(spans.len());
+    for span in spans {
+        let span_width = span.width();
+        if span_width < remaining_width {
+            remaining_width -= span_width;
+            truncated.push(span);
+            continue;
+        }
+        if remaining_width > 0 {
+            truncated.push(Span::styled(
+                slice_up_to_width(&span.content, remaining_width - 1)
+                    .to_string(),
+                span.style,
+            ));
+            truncated.push(Span::styled(ELLIPSIS.to_string(), span.style));
+        }
+        break;
+    }
+    truncated
+}
+
+/// Truncate a line's spans so that their cumulative display width doesn't exceed
+/// `max_width`, replacing whatever was cut off at their start with an ellipsis.
+///
+/// As with `truncate_spans_to_width`, spans are only ever cut on grapheme
+/// boundaries and their styles are preserved.
+fn truncate_spans_from_left(
+    spans: Vec<Span<'_>>,
+    max_width: usize,
+) -> Vec<Span<'_>> {
+    if spans.iter().map(Span::width).sum::<usize>() <= max_width {
+        return spans;
+    }
+    let mut remaining_width = max_width;
+    let mut truncated = Vec::with_capacity(spans.len());
+    for span in spans.into_iter().rev() {
+        let span_width = span.width();
+        if span_width < remaining_width {
+            remaining_width -= span_width;
+            truncated.push(span);
+            continue;
+        }
+        if remaining_width > 0 {
+            truncated.push(Span::styled(
+                slice_suffix_up_to_width(&span.content, remaining_width - 1)
+                    .to_string(),
+                span.style,
+            ));
+            truncated.push(Span::styled(ELLIPSIS.to_string(), span.style));
+        }
+        break;
+    }
+    truncated.reverse();
+    truncated
+}
+
+/// Copies of the given entries with `prefix` stripped from their names,
+/// their match ranges being shifted accordingly.
+fn strip_prefix<'a>(
+    entries: impl Iterator<Item = &'a Entry> + 'a,
+    prefix: &'a str,
+) -> impl Iterator<Item = Entry> + 'a {
+    let prefix_chars = u32::try_from(prefix.chars().count()).unwrap_or(0);
+    entries.map(move |entry| {
+        let Some(name) = entry.name.strip_prefix(prefix) else {
+            return entry.clone();
+        };
+        let mut stripped = entry.clone();
+        stripped.name = name.to_string();
+        stripped.name_match_ranges =
+            entry.name_match_ranges.as_ref().map(|ranges| {
+                ranges
+                    .iter()
+                    .filter(|(_, end)| *end > prefix_chars)
+                    .map(|(start, end)| {
+                        (
+                            start.saturating_sub(prefix_chars),
+                            end - prefix_chars,
+                        )
+                    })
+                    .collect()
+            });
+        stripped
+    })
+}
+
+#[allow(clippy::too_many_arguments, clippy::fn_params_excessive_bools)]
 pub fn draw_results_list(
     f: &mut Frame,
     rect: Rect,
@@ -149,7 +483,17 @@ pub fn draw_results_list(
     relative_picker_state: &mut ListState,
     input_bar_position: InputPosition,
     use_nerd_font_icons: bool,
+    shorten_paths: bool,
+    truncation: Truncation,
     colorscheme: &Colorscheme,
+    border_kind: BorderKind,
+    padding: (u16, u16),
+    row_spacing: u16,
+    two_line: bool,
+    row_prefix: Option<RowPrefix>,
+    common_prefix: Option<&str>,
+    capped_notice: Option<&str>,
+    empty_message: Option<&str>,
     help_keybinding: &str,
     preview_keybinding: &str,
     preview_togglable: bool,
@@ -165,17 +509,44 @@ pub fn draw_results_list(
         ));
     }
 
-    let results_block = Block::default()
+    let mut results_block = Block::default()
         .title_top(Line::from(" Results ").alignment(Alignment::Center))
         .title_bottom(Line::from(toggle_hints).alignment(Alignment::Center))
         .borders(Borders::ALL)
-        .border_type(BorderType::Rounded)
-        .border_style(Style::default().fg(colorscheme.general.border_fg))
+        .border_set(border_kind.border_set())
+        .border_style(Style::default().fg(colorscheme.results.border_fg))
         .style(
             Style::default()
                 .bg(colorscheme.general.background.unwrap_or_default()),
         )
-        .padding(Padding::right(1));
+        .padding(Padding::new(padding.0, padding.1.saturating_add(1), 0, 0));
+    if let Some(notice) = capped_notice {
+        results_block = results_block.title_top(
+            Line::from(format!(" {notice} "))
+                .alignment(Alignment::Right)
+                .style(Style::default().fg(colorscheme.results.border_fg)),
+        );
+    }
+    let inner = results_block.inner(rect);
+    let available_width = inner.width;
+
+    // the prefix is shown once in the title instead of on every entry
+    let stripped;
+    let (entries, selected_entries) = match common_prefix {
+        Some(prefix) if !prefix.is_empty() => {
+            results_block = results_block.title_top(
+                Line::from(format!(" {prefix} "))
+                    .alignment(Alignment::Left)
+                    .style(Style::default().fg(colorscheme.results.border_fg)),
+            );
+            stripped = (
+                strip_prefix(entries.iter(), prefix).collect::<Vec<_>>(),
+                strip_prefix(selected_entries.iter(), prefix).collect(),
+            );
+            (stripped.0.as_slice(), &stripped.1)
+        }
+        _ => (entries, selected_entries),
+    };
 
     let results_list = build_results_list(
         results_block,
@@ -186,9 +557,245 @@ pub fn draw_results_list(
             InputPosition::Top => ListDirection::TopToBottom,
         },
         use_nerd_font_icons,
+        shorten_paths,
+        truncation,
         &colorscheme.results,
+        available_width,
+        row_spacing,
+        two_line,
+        row_prefix,
     );
 
     f.render_stateful_widget(results_list, rect, relative_picker_state);
+
+    if let Some(message) = empty_message.filter(|_| inner.height > 0) {
+        f.render_widget(
+            Paragraph::new(message)
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(colorscheme.results.border_fg))
+                .italic(),
+            Rect {
+                y: inner.y + inner.height / 2,
+                height: 1,
+                ..inner
+            },
+        );
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{buffer::Buffer, widgets::Widget};
+
+    use super::*;
+    use crate::channels::entry::PreviewType;
+    use crate::matcher::{config::Config, matched_item::MatchedItem, Matcher};
+
+    fn rendered_rows(
+        entries: &[Entry],
+        two_line: bool,
+        truncation: Truncation,
+    ) -> Vec<String> {
+        let colorscheme = ResultsColorscheme {
+            border_fg: Color::Reset,
+            result_name_fg: Color::Reset,
+            result_preview_fg: Color::Reset,
+            result_line_number_fg: Color::Reset,
+            result_selected_fg: Color::Reset,
+            result_selected_bg: Color::Reset,
+            match_foreground_color: Color::Reset,
+        };
+        let height = entries
+            .iter()
+            .map(|entry| entry.display_height() + usize::from(two_line))
+            .sum::<usize>();
+        let area = Rect::new(0, 0, 20, u16::try_from(height).unwrap());
+        let mut buffer = Buffer::empty(area);
+        build_results_list(
+            Block::default(),
+            entries,
+            None,
+            ListDirection::TopToBottom,
+            false,
+            false,
+            truncation,
+            &colorscheme,
+            area.width,
+            0,
+            two_line,
+            None,
+        )
+        .render(area, &mut buffer);
+        (0..area.height)
+            .map(|y| {
+                (0..area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_empty_results_message() {
+        let message = "Nothing here";
+        // still scanning
+        assert_eq!(
+            empty_results_message(true, 0, true, message),
+            Some(SCANNING_MSG)
+        );
+        // done scanning
+        assert_eq!(
+            empty_results_message(true, 0, false, message),
+            Some(message)
+        );
+        // some results or no query
+        assert_eq!(empty_results_message(true, 3, false, message), None);
+        assert_eq!(empty_results_message(false, 0, true, message), None);
+        assert_eq!(empty_results_message(false, 0, false, message), None);
+    }
+
+    #[test]
+    fn test_capped_results_notice() {
+        assert_eq!(
+            capped_results_notice(200, 5000).as_deref(),
+            Some("showing 200 of 5,000")
+        );
+        assert_eq!(
+            capped_results_notice(1000, 1_234_567).as_deref(),
+            Some("showing 1,000 of 1,234,567")
+        );
+        // every result is shown
+        assert_eq!(capped_results_notice(200, 200), None);
+        assert_eq!(capped_results_notice(200, 12), None);
+    }
+
+    #[test]
+    fn test_line_number_width() {
+        assert_eq!(line_number_width(1, 0), 1);
+        assert_eq!(line_number_width(1, 9), 1);
+        assert_eq!(line_number_width(1, 10), 2);
+        assert_eq!(line_number_width(95, 5), 2);
+        assert_eq!(line_number_width(95, 6), 3);
+        assert_eq!(line_number_width(1000, 20), 4);
+    }
+
+    #[test]
+    fn test_directories_render_with_trailing_slash() {
+        let entries = [
+            Entry::new("src/screen".to_string(), PreviewType::Basic)
+                .with_kind(EntryKind::Directory),
+            Entry::new("src/main.rs".to_string(), PreviewType::Basic),
+        ];
+        assert_eq!(
+            rendered_rows(&entries, false, Truncation::Right),
+            vec!["src/screen/", "src/main.rs"]
+        );
+        // the emitted path is left untouched
+        assert_eq!(entries[0].stdout_repr(), "src/screen");
+    }
+
+    #[test]
+    fn test_left_truncation_keeps_the_end_of_names() {
+        let entries = [
+            Entry::new(
+                "src/components/television/main.rs".to_string(),
+                PreviewType::Files,
+            ),
+            Entry::new(
+                "src/components/app.rs".to_string(),
+                PreviewType::Files,
+            )
+            .with_line_number(12),
+            Entry::new("src/main.rs".to_string(), PreviewType::Files),
+        ];
+        assert_eq!(
+            rendered_rows(&entries, false, Truncation::Left),
+            vec!["…elevision/main.rs", "…ponents/app.rs:12", "src/main.rs"]
+        );
+        assert_eq!(
+            rendered_rows(&entries, false, Truncation::Right),
+            vec!["src/components/te…", "src/components/ap…", "src/main.rs"]
+        );
+    }
+
+    #[test]
+    fn test_truncate_spans_from_left() {
+        let spans = vec![Span::raw("src/"), Span::raw("lib.rs")];
+        assert_eq!(
+            truncate_spans_from_left(spans.clone(), 7),
+            vec![Span::raw("…"), Span::raw(""), Span::raw("lib.rs")]
+        );
+        assert_eq!(
+            truncate_spans_from_left(spans.clone(), 8),
+            vec![Span::raw("…"), Span::raw("/"), Span::raw("lib.rs")]
+        );
+        assert_eq!(truncate_spans_from_left(spans.clone(), 10), spans);
+        assert!(truncate_spans_from_left(spans, 0).is_empty());
+    }
+
+    #[test]
+    fn test_multi_line_entries_span_several_rows() {
+        let entries = [
+            Entry::new(
+                "fix: a bug\n\nwith details\n".to_string(),
+                PreviewType::Basic,
+            )
+            .with_line_number(3),
+            Entry::new("single line".to_string(), PreviewType::Basic),
+        ];
+        assert_eq!(entries[0].display_height(), 3);
+        assert_eq!(
+            rendered_rows(&entries, false, Truncation::Right),
+            vec!["fix: a bug", "", "with details:3", "single line"]
+        );
+    }
+
+    #[test]
+    fn test_two_line_rows_show_details_below_the_name() {
+        let entries = [
+            Entry::new("main.rs".to_string(), PreviewType::Basic)
+                .with_line_number(3)
+                .with_value("main()".to_string()),
+            Entry::new("lib.rs".to_string(), PreviewType::Basic),
+        ];
+        assert_eq!(
+            rendered_rows(&entries, true, Truncation::Right),
+            vec!["main.rs", ":3: main()", "lib.rs", ""]
+        );
+        assert_eq!(
+            rendered_rows(&entries, false, Truncation::Right),
+            vec!["main.rs:3: main()", "lib.rs"]
+        );
+    }
+
+    #[test]
+    fn test_results_visible_rows() {
+        assert_eq!(results_visible_rows(10, 0, false), 10);
+        assert_eq!(results_visible_rows(10, 0, true), 5);
+        assert_eq!(results_visible_rows(10, 1, true), 3);
+    }
+
+    #[test]
+    fn test_directories_match_without_trailing_slash() {
+        let mut matcher = Matcher::new(Config::default());
+        matcher.injector().push_entry(
+            Entry::new("src/screen".to_string(), PreviewType::Basic)
+                .with_kind(EntryKind::Directory),
+        );
+        matcher.find("screen$");
+        matcher.tick();
+        while matcher.status.running {
+            matcher.tick();
+        }
+        let results: Vec<Entry> = matcher
+            .results(10, 0)
+            .into_iter()
+            .map(MatchedItem::into_entry)
+            .collect();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].kind, EntryKind::Directory);
+    }
+}
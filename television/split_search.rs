@@ -0,0 +1,110 @@
+use std::path::PathBuf;
+
+use crate::action::Action;
+use crate::channels::entry::Entry;
+use crate::channels::{text, ChannelConfig, OnAir, TelevisionChannel};
+use crate::input::convert_action_to_input_request;
+use crate::picker::Picker;
+use crate::utils::strings::EMPTY_STRING;
+
+/// The state of the right pane in split search mode.
+///
+/// The pane searches the contents of the entry selected in the left pane
+/// with its own channel, picker and input. Its channel is re-initialized
+/// every time the left pane's selection changes while its query is kept.
+pub struct SplitSearch {
+    pub channel: TelevisionChannel,
+    pub picker: Picker,
+    pub pattern: String,
+    /// Whether the right pane has the focus, i.e. receives the input and
+    /// navigation actions.
+    pub focused: bool,
+    /// The left pane's entry the channel was initialized from.
+    source: Option<Entry>,
+    /// The settings the channel is created with.
+    config: ChannelConfig,
+}
+
+impl SplitSearch {
+    pub fn new(config: ChannelConfig) -> Self {
+        Self {
+            channel: channel_for(None, &config),
+            picker: Picker::default(),
+            pattern: EMPTY_STRING.to_string(),
+            focused: false,
+            source: None,
+            config,
+        }
+    }
+
+    /// The left pane's entry the channel was initialized from.
+    pub fn source(&self) -> Option<&Entry> {
+        self.source.as_ref()
+    }
+
+    /// Re-initialize the channel from the left pane's selection if it
+    /// changed since the last call.
+    ///
+    /// # Returns
+    /// Whether the channel was re-initialized.
+    pub fn sync_with(&mut self, selection: Option<&Entry>) -> bool {
+        if self.source.as_ref() == selection {
+            return false;
+        }
+        self.channel.shutdown();
+        self.channel = channel_for(selection, &self.config);
+        self.channel.find(&self.pattern);
+        self.picker.reset_selection();
+        self.source = selection.cloned();
+        true
+    }
+
+    pub fn handle_input_action(&mut self, action: &Action) {
+        self.picker
+            .input
+            .handle(convert_action_to_input_request(action).unwrap());
+        let new_pattern = self.picker.input.value().to_string();
+        if new_pattern != self.pattern {
+            self.channel.find(&new_pattern);
+            self.pattern = new_pattern;
+            self.picker.reset_selection();
+        }
+    }
+
+    pub fn selected_entry(&self) -> Option<Entry> {
+        self.picker
+            .selected()
+            .and_then(|i| self.channel.get_result(i.try_into().unwrap()))
+    }
+
+    pub fn update_picker_state(&mut self, height: u16) {
+        if self.picker.selected().is_none() && self.channel.result_count() > 0
+        {
+            self.picker.select(Some(0));
+            self.picker.relative_select(Some(0));
+        }
+        self.picker.entries = self.channel.results(
+            height.into(),
+            u32::try_from(self.picker.offset()).unwrap(),
+        );
+        self.picker.total_items = self.channel.result_count();
+    }
+
+    pub fn shutdown(&self) {
+        self.channel.shutdown();
+    }
+}
+
+/// A text channel over the lines of the given entry's file, which is empty
+/// if the entry isn't a file.
+fn channel_for(
+    entry: Option<&Entry>,
+    config: &ChannelConfig,
+) -> TelevisionChannel {
+    let paths = entry
+        .map(|e| PathBuf::from(&e.name))
+        .filter(|path| path.is_file())
+        .into_iter()
+        .collect();
+    TelevisionChannel::Text(text::Channel::from_file_paths(paths, config))
+}
@@ -1,28 +1,55 @@
 use crate::action::Action;
 use crate::cable::load_cable_channels;
-use crate::channels::entry::{Entry, ENTRY_PLACEHOLDER};
+use crate::channels::cable::DEFAULT_DELIMITER;
+use crate::channels::entry::{Entry, PreviewCommand, ENTRY_PLACEHOLDER};
 use crate::channels::{
+    pending::PendingChannel,
     remote_control::{load_builtin_channels, RemoteControl},
     OnAir, TelevisionChannel, UnitChannel,
 };
-use crate::config::{Config, Theme};
-use crate::draw::{ChannelState, Ctx, TvState};
+use crate::config::{
+    save_keybinding, AlternativePreviewer, ClipboardSeparator, Config,
+    MissingEntryBehavior, SendToChannelMode, Theme,
+};
+use crate::confirmation::{Answer, Confirmation};
+use crate::debug_log::DebugLog;
+use crate::draw::{ChannelState, Ctx, SplitPaneState, TvState};
+use crate::event::Key;
+use crate::groups::{GroupRow, ResultGroups};
 use crate::input::convert_action_to_input_request;
+use crate::keybinding_editor::KeybindingEditor;
+use crate::matcher::config::MatchTarget;
 use crate::picker::Picker;
-use crate::preview::{Preview, PreviewState, Previewer};
+use crate::preview::previewers::meta;
+use crate::preview::{
+    centered_scroll_offset, Preview, PreviewContent, PreviewState, Previewer,
+};
+use crate::quicknav::{Jump, Quicknav};
 use crate::render::UiState;
 use crate::screen::colors::Colorscheme;
+use crate::screen::keybindings::shift_help_column_ratio;
 use crate::screen::layout::InputPosition;
+use crate::screen::results::results_visible_rows;
 use crate::screen::spinner::{Spinner, SpinnerState};
+use crate::split_search::SplitSearch;
 use crate::utils::clipboard::CLIPBOARD;
+use crate::utils::command::expand_command_template;
+use crate::utils::files::{parent_directory, relative_path};
+use crate::utils::hook::SelectionHook;
+use crate::utils::input::Input;
 use crate::utils::metadata::AppMetadata;
-use crate::utils::strings::EMPTY_STRING;
+use crate::utils::strings::{
+    common_path_prefix, longest_common_prefix, sanitize_query, EMPTY_STRING,
+};
 use anyhow::Result;
-use rustc_hash::{FxBuildHasher, FxHashSet};
+use rustc_hash::{FxBuildHasher, FxHashMap, FxHashSet};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc::UnboundedSender;
+use tracing::{error, warn};
 
 #[derive(PartialEq, Copy, Clone, Hash, Eq, Debug, Serialize, Deserialize)]
 pub enum Mode {
@@ -31,6 +58,17 @@ pub enum Mode {
     SendToChannel,
 }
 
+/// The entries selected in a channel taken off air while the selection
+/// persists across channels (see `AppConfig::persistent_selection`).
+#[derive(Debug, Clone, Default)]
+pub struct CarriedSelection {
+    pub entries: FxHashSet<Entry>,
+    /// Whether the entries are paths, which are checked for existence when
+    /// accepted (see `TelevisionChannel::is_file_backed`).
+    pub file_backed: bool,
+}
+
+#[allow(clippy::struct_excessive_bools)]
 pub struct Television {
     action_tx: UnboundedSender<Action>,
     pub config: Config,
@@ -38,6 +76,9 @@ pub struct Television {
     pub remote_control: TelevisionChannel,
     pub mode: Mode,
     pub current_pattern: String,
+    /// Queries pinned with `Action::PushFilter` that every result must
+    /// match in addition to the current pattern.
+    pub pinned_filters: Vec<String>,
     pub results_picker: Picker,
     pub rc_picker: Picker,
     pub previewer: Previewer,
@@ -48,8 +89,87 @@ pub struct Television {
     pub colorscheme: Colorscheme,
     pub ticks: u64,
     pub ui_state: UiState,
+    pub keybinding_editor: Option<KeybindingEditor>,
+    /// Whether the help overlay is shown on top of the results.
+    pub help_visible: bool,
+    /// The latest keys received and actions dispatched.
+    pub debug_log: DebugLog,
+    /// Whether the debug overlay listing the `debug_log` is shown.
+    pub debug_overlay_visible: bool,
+    /// Whether the preview is expanded to the whole terminal, the results
+    /// and the input being hidden.
+    pub preview_fullscreen: bool,
+    /// The previewer of the selected entry, the channel's own one being 0
+    /// and the following ones its alternative previewers (see
+    /// `Action::CyclePreviewer`).
+    pub previewer_index: usize,
+    /// The right pane of the split search mode, searching the contents of
+    /// the entry selected in the results.
+    pub split_search: Option<SplitSearch>,
+    /// Whether the results are listed from the best match to the worst one,
+    /// regardless of the layout (see `Picker::inverted`).
+    pub sort_descending: bool,
+    /// Whether sending entries to a channel replaces or appends to the
+    /// entries sent to it previously.
+    pub send_to_channel_mode: SendToChannelMode,
+    /// The prompt asking to confirm the command about to be run, if any.
+    pub confirmation: Option<Confirmation>,
+    /// An error shown to the user until the next key press.
+    pub error: Option<String>,
+    /// The line the preview of the initially selected entry is scrolled to
+    /// (see `--preview-line`), until that preview is shown.
+    pub initial_preview_line: Option<u16>,
+    /// A message confirming an action, shown to the user until the next key
+    /// press (which is handled as usual).
+    pub notice: Option<String>,
+    /// The letter labels shown next to the visible results, if any.
+    pub quicknav: Option<Quicknav>,
+    /// Whether loading the channel's entries in the background is paused.
+    pub scanning_paused: bool,
+    /// What the channel's entries are matched against.
+    pub match_target: MatchTarget,
+    /// Warnings to print once the application exits.
+    pub warnings: Vec<String>,
+    /// The channel being constructed in the background, which replaces
+    /// `channel` once ready (see `AppConfig::async_channel_init`).
+    pending_channel: Option<PendingChannel>,
+    /// The entry to re-select once the channel is done matching a new query
+    /// (see `UiConfig::keep_selection_on_query_change`).
+    pending_reselection: Option<Entry>,
+    /// The entry whose preview was requested when previews are generated on
+    /// demand (see `UiConfig::preview_on_demand`).
+    on_demand_preview: Option<Entry>,
+    /// The channel of the remote control whose first entries were asked to
+    /// be listed in the preview.
+    guide_preview_request: Option<String>,
+    /// The directory prefix shared by all the results along with the
+    /// pattern and counts it was computed for (see
+    /// `UiConfig::strip_common_prefix`).
+    common_prefix: Option<((String, u32, u32), String)>,
+    /// The results laid out under their groups' headers (see
+    /// `UiConfig::group_results`).
+    result_groups: ResultGroups,
+    /// The channel entries were last sent to in append mode, kept while
+    /// other channels are on air so that entries sent to it again are added
+    /// to it.
+    send_target: Option<TelevisionChannel>,
+    /// Whether the channel on air is the one entries were last sent to in
+    /// append mode.
+    on_air_send_target: bool,
+    /// When the channel's source was last reloaded, the channel being shown
+    /// as running for a little while afterwards.
+    reloaded_at: Option<Instant>,
+    /// Runs `AppConfig::on_change_command` when the selection changes.
+    on_change_hook: Option<SelectionHook>,
+    /// The entries selected in the channels previously on air, by the name
+    /// of their channel (see `AppConfig::persistent_selection`).
+    carried_selection: FxHashMap<String, CarriedSelection>,
 }
 
+/// How long the channel is shown as running after its source is reloaded,
+/// so that quick reloads are noticed too.
+const RELOAD_INDICATOR_DURATION: Duration = Duration::from_millis(500);
+
 impl Television {
     #[must_use]
     pub fn new(
@@ -58,7 +178,8 @@ impl Television {
         config: Config,
         input: Option<String>,
     ) -> Self {
-        let mut results_picker = Picker::new(input.clone());
+        let mut results_picker = Picker::new(input.clone())
+            .with_scroll_off(config.ui.scroll_off.into());
         if config.ui.input_bar_position == InputPosition::Bottom {
             results_picker = results_picker.inverted();
         }
@@ -75,9 +196,13 @@ impl Television {
                 .to_string_lossy()
                 .to_string(),
         );
-        let colorscheme = (&Theme::from_name(&config.ui.theme)).into();
+        let colorscheme =
+            (&Theme::from_name(config.ui.theme(&channel.config_name())))
+                .into();
 
-        channel.find(&input.unwrap_or(EMPTY_STRING.to_string()));
+        let input = input.unwrap_or(EMPTY_STRING.to_string());
+        channel.set_typed_query(&input);
+        channel.find(&input);
         let spinner = Spinner::default();
 
         let preview_state = PreviewState::new(
@@ -87,8 +212,15 @@ impl Television {
             None,
         );
 
+        let on_change_hook = config
+            .application
+            .on_change_command
+            .clone()
+            .map(SelectionHook::new);
+
         Self {
             action_tx,
+            send_to_channel_mode: config.application.send_to_channel_mode,
             config,
             channel,
             remote_control: TelevisionChannel::RemoteControl(
@@ -96,6 +228,7 @@ impl Television {
             ),
             mode: Mode::Channel,
             current_pattern: EMPTY_STRING.to_string(),
+            pinned_filters: Vec::new(),
             results_picker,
             rc_picker: Picker::default(),
             previewer,
@@ -106,6 +239,33 @@ impl Television {
             colorscheme,
             ticks: 0,
             ui_state: UiState::default(),
+            keybinding_editor: None,
+            help_visible: false,
+            debug_log: DebugLog::default(),
+            debug_overlay_visible: false,
+            preview_fullscreen: false,
+            previewer_index: 0,
+            split_search: None,
+            sort_descending: true,
+            confirmation: None,
+            error: None,
+            initial_preview_line: None,
+            notice: None,
+            quicknav: None,
+            scanning_paused: false,
+            match_target: MatchTarget::default(),
+            warnings: Vec::new(),
+            pending_channel: None,
+            pending_reselection: None,
+            on_demand_preview: None,
+            guide_preview_request: None,
+            common_prefix: None,
+            result_groups: ResultGroups::default(),
+            send_target: None,
+            on_air_send_target: false,
+            reloaded_at: None,
+            on_change_hook,
+            carried_selection: FxHashMap::default(),
         }
     }
 
@@ -125,19 +285,52 @@ impl Television {
 
     pub fn dump_context(&self) -> Ctx {
         let channel_state = ChannelState::new(
-            self.channel.name(),
+            self.pending_channel
+                .as_ref()
+                .map_or_else(|| self.channel.name(), |p| p.name.clone()),
+            self.pending_channel.as_ref().map_or_else(
+                || self.channel.config_name(),
+                |p| p.name.to_lowercase(),
+            ),
             self.channel.selected_entries().clone(),
             self.channel.total_count(),
-            self.channel.running(),
+            self.channel_running(),
+            self.pinned_filters.clone(),
+            self.config
+                .ui
+                .strip_common_prefix
+                .then(|| self.common_prefix.as_ref().map(|(_, p)| p.clone()))
+                .flatten(),
+            self.match_target,
         );
+        let split_pane = self.split_search.as_ref().map(|split| {
+            SplitPaneState::new(
+                split.picker.clone(),
+                split.channel.name(),
+                split.channel.config_name(),
+                split.channel.total_count(),
+                split.channel.running(),
+                split.focused,
+            )
+        });
         let tv_state = TvState::new(
             self.mode,
+            self.send_to_channel_mode,
             self.get_selected_entry(Some(Mode::Channel)),
-            self.results_picker.clone(),
+            self.results_picker.animated_view(),
             self.rc_picker.clone(),
             channel_state,
             self.spinner,
             self.preview_state.clone(),
+            self.keybinding_editor.clone(),
+            self.help_visible,
+            self.debug_overlay_visible.then(|| self.debug_log.clone()),
+            self.preview_fullscreen,
+            split_pane,
+            self.confirmation.clone(),
+            self.error.clone(),
+            self.notice.clone(),
+            self.quicknav.clone(),
         );
 
         Ctx::new(
@@ -160,14 +353,91 @@ impl Television {
         self.reset_picker_selection();
         self.reset_picker_input();
         self.current_pattern = EMPTY_STRING.to_string();
-        self.channel.shutdown();
-        self.channel = channel;
+        self.pinned_filters.clear();
+        self.result_groups = ResultGroups::default();
+        self.previewer_index = 0;
+        let mut previous = std::mem::replace(&mut self.channel, channel);
+        if std::mem::take(&mut self.scanning_paused) {
+            previous.resume();
+        }
+        self.match_target = MatchTarget::default();
+        if self.config.application.persistent_selection {
+            self.carry_selection(&previous);
+        }
+        self.take_off_air(previous);
+        if let Some(split) = self.split_search.as_mut() {
+            split.focused = false;
+        }
+        self.update_colorscheme();
+    }
+
+    /// Shut the channel taken off air down, keeping it to send entries to
+    /// in append mode if it is the channel they were last sent to.
+    fn take_off_air(&mut self, channel: TelevisionChannel) {
+        channel.shutdown();
+        if std::mem::take(&mut self.on_air_send_target) {
+            self.send_target = Some(channel);
+        }
+    }
+
+    /// Use the theme of the current channel, `UiConfig::themes` overriding
+    /// the global theme.
+    fn update_colorscheme(&mut self) {
+        self.colorscheme = (&Theme::from_name(
+            self.config.ui.theme(&self.channel.config_name()),
+        ))
+            .into();
+    }
+
+    /// Construct the channel in the background, showing it as loading until
+    /// it replaces the current one.
+    pub fn load_channel(&mut self, pending: PendingChannel) {
+        self.pending_channel = Some(pending);
+    }
+
+    /// Whether the channel is loading or still matching its entries.
+    fn channel_running(&self) -> bool {
+        self.pending_channel.is_some()
+            || self.channel.running()
+            || self.reloaded_at.is_some_and(|reloaded_at| {
+                reloaded_at.elapsed() < RELOAD_INDICATOR_DURATION
+            })
+    }
+
+    /// Swap in the pending channel if it is ready, keeping the query typed
+    /// while it was loading.
+    fn poll_pending_channel(&mut self) {
+        let Some(pending) = self.pending_channel.as_mut() else {
+            return;
+        };
+        let channel = match pending.try_take() {
+            Ok(Some(channel)) => channel,
+            Ok(None) => return,
+            Err(e) => {
+                error!("{}", e);
+                self.pending_channel = None;
+                return;
+            }
+        };
+        self.pending_channel = None;
+        let previous = std::mem::replace(&mut self.channel, channel);
+        self.take_off_air(previous);
+        self.previewer_index = 0;
+        self.update_colorscheme();
+        self.preview_state.enabled = self.channel.supports_preview();
+        let pattern = self.results_picker.input.value().to_string();
+        self.channel.set_typed_query(self.typed_pattern(&pattern));
+        self.channel.find(&self.filtered_pattern(&pattern));
+        self.reset_picker_selection();
+        self.preview_state.reset();
     }
 
     fn find(&mut self, pattern: &str) {
         match self.mode {
             Mode::Channel => {
-                self.channel.find(pattern);
+                self.channel.set_typed_query(self.typed_pattern(pattern));
+                self.channel.find(&self.filtered_pattern(pattern));
+                self.result_groups.set_start(0);
             }
             Mode::RemoteControl | Mode::SendToChannel => {
                 self.remote_control.find(pattern);
@@ -175,15 +445,103 @@ impl Television {
         }
     }
 
+    /// The pattern to match the channel against, i.e. the pinned filters
+    /// followed by the given pattern.
+    ///
+    /// Space separated terms must all match, which gives the pinned filters
+    /// their AND semantics.
+    fn filtered_pattern(&self, pattern: &str) -> String {
+        self.pinned_filters
+            .iter()
+            .map(String::as_str)
+            .chain(std::iter::once(pattern))
+            .map(|term| {
+                if self.config.application.sanitize_query {
+                    sanitize_query(term)
+                } else {
+                    term
+                }
+            })
+            .filter(|term| !term.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// The query typed by the user as matched against the channel, i.e.
+    /// without the pinned filters.
+    fn typed_pattern<'a>(&self, pattern: &'a str) -> &'a str {
+        if self.config.application.sanitize_query {
+            sanitize_query(pattern)
+        } else {
+            pattern
+        }
+    }
+
+    /// The split search pane if it has the focus.
+    fn focused_split(&self) -> Option<&SplitSearch> {
+        self.split_search.as_ref().filter(|split| split.focused)
+    }
+
+    /// The entry selected in the results, regardless of the split search
+    /// pane's focus.
+    fn results_selected_entry(&self) -> Option<Entry> {
+        if self.config.ui.group_results {
+            return match self
+                .result_groups
+                .row(self.results_picker.selected()?)?
+            {
+                GroupRow::Result(entry) => Some(entry.clone()),
+                GroupRow::Header { .. } => None,
+            };
+        }
+        self.results_picker
+            .selected()
+            .and_then(|i| self.channel_result(i.try_into().unwrap()))
+    }
+
+    /// The number of results listed, at most `UiConfig::max_shown_results`.
+    fn shown_result_count(&self) -> u32 {
+        let count = self.channel.result_count();
+        match self.config.ui.max_shown_results {
+            Some(max) if !self.config.ui.group_results => count.min(max),
+            _ => count,
+        }
+    }
+
+    /// The channel's result at `index` in the current sort direction.
+    fn channel_result(&self, index: u32) -> Option<Entry> {
+        if self.sort_descending {
+            return self.channel.get_result(index);
+        }
+        let last = self.channel.result_count().checked_sub(1)?;
+        self.channel.get_result(last.checked_sub(index)?)
+    }
+
+    /// The channel's results in the current sort direction.
+    fn channel_results(
+        &mut self,
+        num_entries: u32,
+        offset: u32,
+    ) -> Vec<Entry> {
+        if self.sort_descending {
+            return self.channel.results(num_entries, offset);
+        }
+        // fetching results is what updates the channel's result count
+        self.channel.results(0, 0);
+        let end = self.channel.result_count().saturating_sub(offset);
+        let start = end.saturating_sub(num_entries);
+        let mut results = self.channel.results(end - start, start);
+        results.reverse();
+        results
+    }
+
     #[must_use]
     pub fn get_selected_entry(&self, mode: Option<Mode>) -> Option<Entry> {
         match mode.unwrap_or(self.mode) {
-            Mode::Channel => {
-                if let Some(i) = self.results_picker.selected() {
-                    return self.channel.get_result(i.try_into().unwrap());
-                }
-                None
-            }
+            Mode::Channel => match self.focused_split() {
+                Some(split) => split.selected_entry(),
+                None => self.results_selected_entry(),
+            },
             Mode::RemoteControl | Mode::SendToChannel => {
                 if let Some(i) = self.rc_picker.selected() {
                     return self
@@ -196,58 +554,411 @@ impl Television {
     }
 
     #[must_use]
+    /// The selected entries, or the entry under the cursor if none are.
+    ///
+    /// The entries selected in the channels previously on air are included
+    /// when the selection persists across channels.
     pub fn get_selected_entries(
         &self,
         mode: Option<Mode>,
     ) -> Option<FxHashSet<Entry>> {
-        if self.channel.selected_entries().is_empty()
-            || matches!(mode, Some(Mode::RemoteControl))
-        {
+        if matches!(mode, Some(Mode::RemoteControl)) {
             return self.get_selected_entry(mode).map(|e| {
                 let mut set = HashSet::with_hasher(FxBuildHasher);
                 set.insert(e);
                 set
             });
         }
-        Some(self.channel.selected_entries().clone())
+        if self.channel.selected_entries().is_empty()
+            || self.focused_split().is_some()
+        {
+            self.get_selected_entry(mode).map(|e| {
+                let mut set = HashSet::with_hasher(FxBuildHasher);
+                set.insert(e);
+                set
+            })
+        } else {
+            Some(self.channel.selected_entries().clone())
+        }
     }
 
-    pub fn select_prev_entry(&mut self, step: u32) {
-        let (result_count, picker) = match self.mode {
-            Mode::Channel => {
-                (self.channel.result_count(), &mut self.results_picker)
+    /// The entries output when accepting the selection: the selected
+    /// entries along with the ones selected in the channels previously on
+    /// air when the selection persists across channels, the entry under the
+    /// cursor being output only if none are selected anywhere.
+    #[must_use]
+    pub fn get_accepted_entries(
+        &self,
+        mode: Option<Mode>,
+    ) -> Option<FxHashSet<Entry>> {
+        if self.carried_selection.is_empty()
+            || matches!(mode, Some(Mode::RemoteControl))
+        {
+            return self.get_selected_entries(mode);
+        }
+        let mut entries = self.channel.selected_entries().clone();
+        for selection in self.carried_selection.values() {
+            entries.extend(selection.entries.iter().cloned());
+        }
+        if entries.is_empty() {
+            return self.get_selected_entries(mode);
+        }
+        Some(entries)
+    }
+
+    /// The entries selected in the channels previously on air, by the name
+    /// of their channel.
+    pub fn carried_selection(&self) -> &FxHashMap<String, CarriedSelection> {
+        &self.carried_selection
+    }
+
+    /// Whether the entry is a path, i.e. comes from a file-backed channel:
+    /// the one on air, or the one it was selected in if it was carried over
+    /// from a previous channel.
+    fn is_path_entry(&self, entry: &Entry) -> bool {
+        if self.channel.selected_entries().contains(entry) {
+            return self.channel.is_file_backed();
+        }
+        self.carried_selection
+            .values()
+            .find(|selection| selection.entries.contains(entry))
+            .map_or_else(
+                || self.channel.is_file_backed(),
+                |selection| selection.file_backed,
+            )
+    }
+
+    /// Keep the selection of the channel taken off air, selecting the
+    /// entries carried for the channel now on air again so that they can
+    /// be seen and deselected there.
+    fn carry_selection(&mut self, previous: &TelevisionChannel) {
+        let selection = previous.selected_entries();
+        if selection.is_empty() {
+            self.carried_selection.remove(&previous.name());
+        } else {
+            self.carried_selection.insert(
+                previous.name(),
+                CarriedSelection {
+                    entries: selection.clone(),
+                    file_backed: previous.is_file_backed(),
+                },
+            );
+        }
+        if let Some(selection) =
+            self.carried_selection.remove(&self.channel.name())
+        {
+            for entry in &selection.entries {
+                self.channel.toggle_selection(entry);
             }
+        }
+    }
+
+    /// The 1-based rank among the results of the entry under the cursor in
+    /// the focused list.
+    ///
+    /// When results are grouped, the rank is the one of the result under
+    /// the cursor, regardless of the headers and of the window of results
+    /// laid out.
+    pub fn selected_rank(&self) -> Option<usize> {
+        if let Some(split) = self.focused_split() {
+            return split.picker.selected().map(|index| index + 1);
+        }
+        let index = self.results_picker.selected()?;
+        if self.config.ui.group_results {
+            return self
+                .result_groups
+                .result_index(index)
+                .map(|index| index as usize + 1);
+        }
+        Some(index + 1)
+    }
+
+    /// The number of entries that fit in the currently focused list.
+    fn results_rows(&self) -> usize {
+        // borders
+        let height = self.ui_state.layout.results.height.saturating_sub(2);
+        match self.mode {
+            Mode::Channel => results_visible_rows(
+                height,
+                self.config.ui.results_row_spacing,
+                self.config.ui.two_line_results,
+            ),
+            Mode::RemoteControl | Mode::SendToChannel => height.into(),
+        }
+    }
+
+    /// The result count and picker of the list that has the focus.
+    fn focused_list(&mut self) -> (u32, &mut Picker) {
+        let shown_result_count = self.shown_result_count();
+        match self.mode {
+            Mode::Channel => match self.split_search.as_mut() {
+                Some(split) if split.focused => {
+                    (split.channel.result_count(), &mut split.picker)
+                }
+                _ => (shown_result_count, &mut self.results_picker),
+            },
             Mode::RemoteControl | Mode::SendToChannel => {
                 (self.remote_control.total_count(), &mut self.rc_picker)
             }
-        };
+        }
+    }
+
+    /// Whether the results list (as opposed to the split search pane or the
+    /// remote control) has the focus.
+    fn results_focused(&self) -> bool {
+        self.mode == Mode::Channel && self.focused_split().is_none()
+    }
+
+    /// Move the selection in the results list, whose entries may span
+    /// several rows.
+    fn select_in_results(&mut self, step: u32, next: bool) {
+        if self.config.ui.group_results {
+            self.select_in_groups(step, next);
+            return;
+        }
+        let result_count = self.shown_result_count() as usize;
         if result_count == 0 {
             return;
         }
-        picker.select_prev(
-            step,
-            result_count as usize,
-            self.ui_state.layout.results.height.saturating_sub(2) as usize,
-        );
+        // borders
+        let rows = self.ui_state.layout.results.height.saturating_sub(2);
+        // each result is a single selectable unit, whatever its details
+        // and spacing rows
+        let spacing = usize::from(self.config.ui.results_row_spacing)
+            + usize::from(self.config.ui.two_line_results);
+        let mut heights = FxHashMap::default();
+        let mut picker = std::mem::take(&mut self.results_picker);
+        let from = picker.view_offset();
+        let item_height = |i: usize| {
+            *heights.entry(i).or_insert_with(|| {
+                u32::try_from(i)
+                    .ok()
+                    .and_then(|i| self.channel_result(i))
+                    .map_or(1, |entry| entry.display_height())
+                    + spacing
+            })
+        };
+        if next {
+            picker.select_next_with_heights(
+                step,
+                result_count,
+                rows.into(),
+                item_height,
+            );
+        } else {
+            picker.select_prev_with_heights(
+                step,
+                result_count,
+                rows.into(),
+                item_height,
+            );
+        }
+        if self.config.ui.scroll_animation {
+            picker.animate_scroll(
+                from,
+                SCROLL_ANIMATION_FRAMES,
+                SCROLL_ANIMATION_THRESHOLD,
+            );
+        }
+        self.results_picker = picker;
     }
 
-    pub fn select_next_entry(&mut self, step: u32) {
-        let (result_count, picker) = match self.mode {
-            Mode::Channel => {
-                (self.channel.result_count(), &mut self.results_picker)
+    /// Move the selection in the grouped results, skipping the headers that
+    /// can't be selected and moving on to the next window of results past
+    /// the ends of the current one.
+    fn select_in_groups(&mut self, step: u32, next: bool) {
+        // rows are listed from the first to the last one unless inverted
+        let forward = next != self.results_picker.is_inverted();
+        for _ in 0..step {
+            if !self.select_in_group_window(next, forward) {
+                self.move_group_window(forward);
             }
-            Mode::RemoteControl | Mode::SendToChannel => {
-                (self.remote_control.total_count(), &mut self.rc_picker)
+        }
+    }
+
+    /// Move the selection to the next selectable row of the window of
+    /// grouped results, returning whether there was one before its end.
+    fn select_in_group_window(&mut self, next: bool, forward: bool) -> bool {
+        let rows = self.result_groups.rows();
+        // borders
+        let height = self.ui_state.layout.results.height.saturating_sub(2);
+        let spacing = usize::from(self.config.ui.results_row_spacing);
+        let details = usize::from(self.config.ui.two_line_results);
+        let item_height = |i: usize| match rows.get(i) {
+            Some(GroupRow::Result(entry)) => {
+                entry.display_height() + details + spacing
             }
+            row => row.map_or(1, GroupRow::display_height) + spacing,
         };
+        if rows.is_empty() {
+            return false;
+        }
+        let before = self.results_picker.clone();
+        let mut selected = before.selected().unwrap_or(0);
+        loop {
+            if next {
+                self.results_picker.select_next_with_heights(
+                    1,
+                    rows.len(),
+                    height.into(),
+                    item_height,
+                );
+            } else {
+                self.results_picker.select_prev_with_heights(
+                    1,
+                    rows.len(),
+                    height.into(),
+                    item_height,
+                );
+            }
+            let Some(index) = self.results_picker.selected() else {
+                return false;
+            };
+            // the picker wraps around past the ends of the window
+            if (forward && index <= selected)
+                || (!forward && index >= selected)
+            {
+                self.results_picker = before;
+                return false;
+            }
+            if rows[index].selectable() {
+                return true;
+            }
+            selected = index;
+        }
+    }
+
+    /// Lay out the window of results following or preceding the current
+    /// one, wrapping around, and select its first or last selectable row.
+    fn move_group_window(&mut self, forward: bool) {
+        let len = self.group_window_len();
+        let count = self.channel.result_count();
+        let start = self.result_groups.start();
+        let start = match (forward, start.checked_sub(len)) {
+            (true, _) if start + len < count => start + len,
+            (true, _) => 0,
+            (false, Some(previous)) => previous,
+            (false, None) => count.saturating_sub(1) / len * len,
+        };
+        self.result_groups.set_start(start);
+        self.regroup_results();
+        let rows = self.result_groups.rows();
+        let index = if forward {
+            rows.iter().position(GroupRow::selectable)
+        } else {
+            rows.iter().rposition(GroupRow::selectable)
+        };
+        if let Some(index) = index {
+            let height = self.results_rows();
+            self.results_picker.select_index(index, height);
+        }
+    }
+
+    /// The number of results laid out under their groups' headers at a
+    /// time, i.e. as many as fit in the results list.
+    fn group_window_len(&self) -> u32 {
+        u32::try_from(self.results_rows())
+            .unwrap_or(u32::MAX)
+            .max(1)
+    }
+
+    /// Lay out the current window of results under their groups' headers,
+    /// going back to the first window if the results no longer reach it.
+    fn regroup_results(&mut self) {
+        let len = self.group_window_len();
+        let mut results =
+            self.channel_results(len, self.result_groups.start());
+        if results.is_empty() && self.result_groups.start() > 0 {
+            self.result_groups.set_start(0);
+            results = self.channel_results(len, 0);
+        }
+        self.result_groups.update(results);
+    }
+
+    pub fn select_prev_entry(&mut self, step: u32) {
+        self.pending_reselection = None;
+        if self.results_focused() {
+            self.select_in_results(step, false);
+            return;
+        }
+        let results_rows = self.results_rows();
+        let (result_count, picker) = self.focused_list();
+        if result_count == 0 {
+            return;
+        }
+        picker.select_prev(step, result_count as usize, results_rows);
+    }
+
+    pub fn select_next_entry(&mut self, step: u32) {
+        self.pending_reselection = None;
+        if self.results_focused() {
+            self.select_in_results(step, true);
+            return;
+        }
+        let results_rows = self.results_rows();
+        let (result_count, picker) = self.focused_list();
         if result_count == 0 {
             return;
         }
-        picker.select_next(
-            step,
+        picker.select_next(step, result_count as usize, results_rows);
+    }
+
+    /// Select the entry at `relative_index` among the visible results,
+    /// leaving the selection untouched if there is no such entry.
+    pub fn select_visible_entry(&mut self, relative_index: usize) {
+        self.pending_reselection = None;
+        let results_rows = self.results_rows();
+        let (result_count, picker) = self.focused_list();
+        if picker.select_visible(
+            relative_index,
             result_count as usize,
-            self.ui_state.layout.results.height.saturating_sub(2) as usize,
-        );
+            results_rows,
+        ) {
+            self.preview_state.reset();
+        }
+    }
+
+    /// Label the visible results with letters, or remove the labels if
+    /// they are already shown.
+    pub fn handle_toggle_quicknav(&mut self) {
+        self.quicknav = (self.quicknav.is_none() && self.results_focused())
+            .then(|| Quicknav::new(self.results_picker.entries.len()));
+    }
+
+    /// Type a key of the label of the result to jump to, selecting that
+    /// result once its whole label is typed.
+    pub fn handle_quicknav_key(&mut self, key: Key) {
+        let Some(quicknav) = self.quicknav.as_mut() else {
+            return;
+        };
+        match quicknav.feed(key) {
+            Jump::To(relative_index) => {
+                self.quicknav = None;
+                self.select_visible_entry(relative_index);
+            }
+            Jump::Pending => {}
+            Jump::Cancelled => self.quicknav = None,
+        }
+    }
+
+    /// Move the cursor back onto the entry that was selected before the
+    /// query changed, leaving it at the top if that entry no longer matches.
+    fn reselect_pending_entry(&mut self) {
+        let Some(entry) = self.pending_reselection.take() else {
+            return;
+        };
+        let index = self
+            .channel_results(
+                self.channel.result_count().min(RESELECTION_SEARCH_LIMIT),
+                0,
+            )
+            .iter()
+            .position(|e| *e == entry);
+        if let Some(index) = index {
+            self.results_picker.select_index(index, self.results_rows());
+        } else {
+            self.results_picker.reset_selection();
+        }
     }
 
     fn reset_picker_selection(&mut self) {
@@ -269,13 +980,24 @@ impl Television {
     }
 }
 
+/// How many of the top results are searched when trying to re-select the
+/// previously selected entry after a query change.
+const RESELECTION_SEARCH_LIMIT: u32 = 1000;
+
 const RENDER_FIRST_N_TICKS: u64 = 20;
+/// The number of ticks the results list takes to scroll to the selection
+/// with `UiConfig::scroll_animation`.
+const SCROLL_ANIMATION_FRAMES: u16 = 6;
+/// How many entries the results list must scroll by for the scrolling to be
+/// animated.
+const SCROLL_ANIMATION_THRESHOLD: usize = 3;
 const RENDER_EVERY_N_TICKS: u64 = 10;
 
 impl Television {
     fn should_render(&self, action: &Action) -> bool {
         self.ticks < RENDER_FIRST_N_TICKS
             || self.ticks % RENDER_EVERY_N_TICKS == 0
+            || self.results_picker.animating()
             || matches!(
                 action,
                 Action::AddInputChar(_)
@@ -288,22 +1010,68 @@ impl Television {
                     | Action::GoToInputEnd
                     | Action::ToggleSelectionDown
                     | Action::ToggleSelectionUp
+                    | Action::InvertSelection
                     | Action::ConfirmSelection
                     | Action::SelectNextEntry
                     | Action::SelectPrevEntry
                     | Action::SelectNextPage
                     | Action::SelectPrevPage
+                    | Action::SelectVisibleEntry(_)
+                    | Action::ToggleQuicknav
+                    | Action::QuicknavKey(_)
                     | Action::ScrollPreviewDown
                     | Action::ScrollPreviewUp
                     | Action::ScrollPreviewHalfPageDown
                     | Action::ScrollPreviewHalfPageUp
+                    | Action::ScrollPreviewTop
+                    | Action::ScrollPreviewBottom
+                    | Action::PreviewCursorDown
+                    | Action::ShowPreviewForCurrent
+                    | Action::LockPreviewScroll
+                    | Action::PreviewCursorUp
+                    | Action::TogglePreviewSearch
+                    | Action::NextPreviewMatch
+                    | Action::PrevPreviewMatch
                     | Action::ToggleRemoteControl
                     | Action::ToggleSendToChannel
+                    | Action::ToggleSendToChannelMode
                     | Action::ToggleHelp
+                    | Action::WidenHelpActions
+                    | Action::WidenHelpKeys
                     | Action::TogglePreview
+                    | Action::ToggleInputWrap
+                    | Action::ToggleDebugOverlay
+                    | Action::TogglePreviewFullscreen
+                    | Action::CyclePreviewer
                     | Action::CopyEntryToClipboard
+                    | Action::CopyAllSelected
+                    | Action::CopyRelativePath
+                    | Action::SetRelativePathBase
+                    | Action::RevealInParent
+                    | Action::UseSelectionAsQuery
+                    | Action::CompleteCommonPrefix
+                    | Action::SetQuery(_)
+                    | Action::PushFilter
+                    | Action::PopFilter
+                    | Action::ToggleSortDirection
+                    | Action::ToggleGroup
+                    | Action::ResetChannel
+                    | Action::ReloadSource
+                    | Action::TogglePauseScanning
+                    | Action::ToggleMatchTarget
+                    | Action::ToggleSplitSearch
+                    | Action::SwitchSplitFocus
+                    | Action::ToggleKeybindingEditor
+                    | Action::CaptureKey(_)
+                    | Action::ExecuteCommand
+                    | Action::AnswerConfirmation(_)
+                    | Action::DismissError
             )
-            || self.channel.running()
+            || self.channel_running()
+            || self
+                .split_search
+                .as_ref()
+                .is_some_and(|split| split.channel.running())
     }
 
     pub fn update_preview_state(
@@ -312,32 +1080,66 @@ impl Television {
     ) -> Result<()> {
         if self.config.ui.show_preview_panel && self.channel.supports_preview()
         {
-            // preview content
-            if let Some(preview) = self
-                .previewer
-                .preview(selected_entry, self.ui_state.layout.preview_window)
+            if self.config.ui.preview_on_demand
+                && self.on_demand_preview.as_ref() != Some(selected_entry)
             {
+                let placeholder = self.on_demand_placeholder(selected_entry);
+                if *self.preview_state.preview != placeholder {
+                    self.preview_state.update(Arc::new(placeholder), 0, None);
+                    self.action_tx.send(Action::Render)?;
+                }
+                return Ok(());
+            }
+            // preview content
+            let preview = match self.active_alternative_previewer() {
+                Some(alternative) => self
+                    .previewer
+                    .preview_with_command(
+                        selected_entry,
+                        &PreviewCommand::new(
+                            &alternative.command,
+                            DEFAULT_DELIMITER,
+                        ),
+                    )
+                    .map(|preview| {
+                        let title = format!(
+                            "{} [{}]",
+                            preview.title, alternative.name
+                        );
+                        if self.preview_state.preview.title == title {
+                            self.preview_state.preview.clone()
+                        } else {
+                            Arc::new(Preview {
+                                title,
+                                ..(*preview).clone()
+                            })
+                        }
+                    }),
+                None => self.previewer.preview(
+                    selected_entry,
+                    self.ui_state.layout.preview_window,
+                ),
+            };
+            if let Some(preview) = preview {
                 // only update if the preview content has changed
                 if self.preview_state.preview.title != preview.title {
+                    let target_line =
+                        self.initial_preview_line.take().or_else(|| {
+                            selected_entry
+                                .line_number
+                                .and_then(|l| l.try_into().ok())
+                        });
                     self.preview_state.update(
                         preview,
-                        // scroll to center the selected entry
-                        selected_entry
-                            .line_number
-                            .unwrap_or(0)
-                            .saturating_sub(
-                                (self
-                                    .ui_state
-                                    .layout
-                                    .preview_window
-                                    .map_or(0, |w| w.height)
-                                    / 2)
-                                .into(),
-                            )
-                            .try_into()?,
-                        selected_entry
-                            .line_number
-                            .and_then(|l| l.try_into().ok()),
+                        // scroll to center the target line
+                        centered_scroll_offset(
+                            target_line.unwrap_or(0),
+                            self.ui_state
+                                .layout
+                                .preview_window
+                                .map_or(0, |w| w.height),
+                        ),
+                        target_line,
                     );
                     self.action_tx.send(Action::Render)?;
                 }
@@ -348,7 +1150,147 @@ impl Television {
         Ok(())
     }
 
+    /// The alternative previewers of the current channel, by channel name.
+    fn alternative_previewers(&self) -> &[AlternativePreviewer] {
+        self.config
+            .previewers
+            .alternatives
+            .get(&self.channel.config_name())
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// The alternative previewer the selected entry is previewed with
+    /// instead of the channel's own previewer, if any.
+    fn active_alternative_previewer(&self) -> Option<AlternativePreviewer> {
+        self.previewer_index
+            .checked_sub(1)
+            .and_then(|i| self.alternative_previewers().get(i))
+            .cloned()
+    }
+
+    /// Move on to the next of the channel's previewers, going back to its
+    /// own one after the last alternative.
+    fn handle_cycle_previewer(&mut self) {
+        let count = self.alternative_previewers().len();
+        if count == 0 {
+            return;
+        }
+        self.previewer_index = (self.previewer_index + 1) % (count + 1);
+        self.preview_state.reset();
+    }
+
+    /// Preview the channel highlighted in the remote control by describing
+    /// it, its first entries being listed once requested (see
+    /// `Action::ShowPreviewForCurrent`).
+    fn update_guide_preview_state(&mut self) -> Result<()> {
+        if !self.config.ui.show_preview_panel {
+            self.preview_state.reset();
+            return Ok(());
+        }
+        let Some(entry) = self.get_selected_entry(Some(Mode::RemoteControl))
+        else {
+            return Ok(());
+        };
+        let num_entries = self
+            .remote_control
+            .guide_preview_entries(&entry.name)
+            .unwrap_or(self.config.ui.guide_preview_entries);
+        let preview = if self.guide_preview_request.as_ref()
+            == Some(&entry.name)
+        {
+            self.previewer
+                .guide_preview(
+                    &entry.name,
+                    num_entries,
+                    self.remote_control
+                        .lazy_zap(&entry.name, self.config.channel_config()),
+                )
+                .unwrap_or_else(|| meta::loading(&entry.name))
+        } else {
+            Arc::new(self.guide_placeholder(&entry.name, num_entries))
+        };
+        if self.preview_state.preview != preview {
+            // the loading placeholder has the same title as the preview
+            // replacing it
+            self.preview_state.reset();
+            self.preview_state.update(preview, 0, None);
+            self.action_tx.send(Action::Render)?;
+        }
+        Ok(())
+    }
+
+    /// The preview shown in place of the selected entry's preview until it
+    /// is requested.
+    fn on_demand_placeholder(&self, entry: &Entry) -> Preview {
+        let hint = match self
+            .config
+            .keybindings
+            .get(&Action::ShowPreviewForCurrent)
+        {
+            Some(binding) => format!("Press <{binding}> to preview"),
+            None => String::from("Preview on demand"),
+        };
+        Preview::new(
+            entry.name.clone(),
+            PreviewContent::PlainTextWrapped(hint),
+            entry.icon,
+            None,
+            1,
+        )
+    }
+
+    /// The preview describing a channel of the remote control until its
+    /// first entries are requested.
+    fn guide_placeholder(
+        &self,
+        channel_name: &str,
+        num_entries: u32,
+    ) -> Preview {
+        let mut lines = self.remote_control.describe(channel_name);
+        lines.push(String::new());
+        lines.push(
+            match self.config.keybindings.get(&Action::ShowPreviewForCurrent) {
+                Some(binding) => format!(
+                    "Press <{binding}> to list its first {num_entries} entries"
+                ),
+                None => String::from("Its entries are listed on demand"),
+            },
+        );
+        let total_lines = u16::try_from(lines.len()).unwrap_or(u16::MAX);
+        Preview::new(
+            channel_name.to_string(),
+            PreviewContent::PlainText(lines),
+            None,
+            None,
+            total_lines,
+        )
+    }
+
+    /// Request the preview of the selected entry when previews are generated
+    /// on demand.
+    ///
+    /// In the remote control, this lists the first entries of the
+    /// highlighted channel, sampling them again if they were listed before.
+    pub fn handle_show_preview_for_current(&mut self) {
+        if self.mode == Mode::RemoteControl {
+            if let Some(entry) =
+                self.get_selected_entry(Some(Mode::RemoteControl))
+            {
+                self.previewer.invalidate_guide_preview(&entry.name);
+                self.guide_preview_request = Some(entry.name);
+            }
+            return;
+        }
+        self.on_demand_preview = self.get_selected_entry(Some(Mode::Channel));
+        // make sure the placeholder gets replaced
+        self.preview_state.reset();
+    }
+
     pub fn update_results_picker_state(&mut self) {
+        if self.config.ui.group_results {
+            self.update_grouped_results_picker_state();
+            return;
+        }
         if self.results_picker.selected().is_none()
             && self.channel.result_count() > 0
         {
@@ -356,11 +1298,79 @@ impl Television {
             self.results_picker.relative_select(Some(0));
         }
 
-        self.results_picker.entries = self.channel.results(
+        self.results_picker.settle_animation();
+        self.results_picker.entries = self.channel_results(
             self.ui_state.layout.results.height.into(),
-            u32::try_from(self.results_picker.offset()).unwrap(),
+            u32::try_from(self.results_picker.view_offset()).unwrap(),
         );
+        // fetching results ticks the channel, so its running status is now
+        // up to date with the latest query
+        if self.pending_reselection.is_some() && !self.channel.running() {
+            self.reselect_pending_entry();
+            self.results_picker.settle_animation();
+            self.results_picker.entries = self.channel_results(
+                self.ui_state.layout.results.height.into(),
+                u32::try_from(self.results_picker.view_offset()).unwrap(),
+            );
+        }
         self.results_picker.total_items = self.channel.result_count();
+        // the results beyond the listed ones are left out
+        let shown = self.shown_result_count() as usize;
+        let listed = shown.saturating_sub(self.results_picker.view_offset());
+        self.results_picker.entries.truncate(listed);
+        if self.config.ui.strip_common_prefix && !self.channel.running() {
+            self.update_common_prefix();
+        }
+    }
+
+    /// Lay the results out under their groups' headers, keeping the
+    /// selection on a row that can be selected.
+    fn update_grouped_results_picker_state(&mut self) {
+        self.regroup_results();
+        let rows = self.result_groups.rows();
+        let selected = self.results_picker.selected().unwrap_or(0);
+        if !rows.get(selected).is_some_and(GroupRow::selectable) {
+            // the next selectable row, wrapping around
+            let index = (selected..rows.len())
+                .chain(0..selected)
+                .find(|&i| rows[i].selectable());
+            if let Some(index) = index {
+                let height = self.results_rows();
+                self.results_picker.select_index(index, height);
+            }
+        }
+        let rows = self.result_groups.rows();
+        let height = usize::from(self.ui_state.layout.results.height);
+        self.results_picker.entries = rows
+            .iter()
+            .skip(self.results_picker.offset())
+            .take(height)
+            .map(GroupRow::to_entry)
+            .collect();
+        self.results_picker.total_items =
+            u32::try_from(rows.len()).unwrap_or(u32::MAX);
+        if self.config.ui.strip_common_prefix && !self.channel.running() {
+            self.update_common_prefix();
+        }
+    }
+
+    /// Recompute the directory prefix shared by all the results if they
+    /// changed since it was last computed.
+    fn update_common_prefix(&mut self) {
+        let key = (
+            self.filtered_pattern(&self.current_pattern),
+            self.channel.result_count(),
+            self.channel.total_count(),
+        );
+        if self.common_prefix.as_ref().is_some_and(|(k, _)| *k == key) {
+            return;
+        }
+        let prefix = common_path_prefix(
+            (0..key.1)
+                .map_while(|i| self.channel.get_result(i))
+                .map(|entry| entry.name),
+        );
+        self.common_prefix = Some((key, prefix));
     }
 
     pub fn update_rc_picker_state(&mut self) {
@@ -380,6 +1390,25 @@ impl Television {
     }
 
     pub fn handle_input_action(&mut self, action: &Action) {
+        if self
+            .preview_state
+            .search
+            .as_ref()
+            .is_some_and(|search| search.editing)
+        {
+            let pane_height = self.preview_pane_height();
+            self.preview_state.handle_search_input(action, pane_height);
+            return;
+        }
+        if self.mode == Mode::Channel {
+            if let Some(split) =
+                self.split_search.as_mut().filter(|split| split.focused)
+            {
+                split.handle_input_action(action);
+                self.preview_state.reset();
+                return;
+            }
+        }
         let input = match self.mode {
             Mode::Channel => &mut self.results_picker.input,
             Mode::RemoteControl | Mode::SendToChannel => {
@@ -394,6 +1423,15 @@ impl Television {
             | Action::DeleteNextChar => {
                 let new_pattern = input.value().to_string();
                 if new_pattern != self.current_pattern {
+                    // keep the original entry if the previous query change
+                    // hasn't been resolved yet (e.g. when typing quickly)
+                    if self.mode == Mode::Channel
+                        && self.config.ui.keep_selection_on_query_change
+                        && self.pending_reselection.is_none()
+                    {
+                        self.pending_reselection =
+                            self.get_selected_entry(Some(Mode::Channel));
+                    }
                     self.current_pattern.clone_from(&new_pattern);
                     self.find(&new_pattern);
                     self.reset_picker_selection();
@@ -416,12 +1454,21 @@ impl Television {
                 self.init_remote_control();
                 self.remote_control.find(EMPTY_STRING);
                 self.reset_picker_selection();
+                // drop the guide's preview
+                self.preview_state.reset();
+                self.guide_preview_request = None;
                 self.mode = Mode::Channel;
             }
             Mode::SendToChannel => {}
         }
     }
 
+    fn handle_widen_help_column(&mut self, action: &Action) {
+        let ratio = &mut self.config.ui.help_column_ratio;
+        *ratio =
+            shift_help_column_ratio(*ratio, *action == Action::WidenHelpKeys);
+    }
+
     pub fn handle_toggle_send_to_channel(&mut self) {
         match self.mode {
             Mode::Channel | Mode::RemoteControl => {
@@ -440,7 +1487,9 @@ impl Television {
     }
 
     pub fn handle_toggle_selection(&mut self, action: &Action) {
-        if matches!(self.mode, Mode::Channel) {
+        // the split search pane's entries can't be added to the selection
+        if matches!(self.mode, Mode::Channel) && self.focused_split().is_none()
+        {
             if let Some(entry) = self.get_selected_entry(None) {
                 self.channel.toggle_selection(&entry);
                 if matches!(action, Action::ToggleSelectionDown) {
@@ -452,6 +1501,20 @@ impl Television {
         }
     }
 
+    /// Toggle the selection of every entry matching the current query.
+    ///
+    /// Selected entries that don't match the query are left untouched.
+    pub fn handle_invert_selection(&mut self) {
+        if self.mode != Mode::Channel || self.focused_split().is_some() {
+            return;
+        }
+        for index in 0..self.channel.result_count() {
+            if let Some(entry) = self.channel.get_result(index) {
+                self.channel.toggle_selection(&entry);
+            }
+        }
+    }
+
     pub fn handle_confirm_selection(&mut self) -> Result<()> {
         match self.mode {
             Mode::Channel => {
@@ -459,8 +1522,10 @@ impl Television {
             }
             Mode::RemoteControl => {
                 if let Some(entry) = self.get_selected_entry(None) {
-                    let new_channel =
-                        self.remote_control.zap(entry.name.as_str())?;
+                    let new_channel = self.remote_control.zap(
+                        entry.name.as_str(),
+                        self.config.channel_config(),
+                    )?;
                     // this resets the RC picker
                     self.reset_picker_selection();
                     self.reset_picker_input();
@@ -471,14 +1536,28 @@ impl Television {
             }
             Mode::SendToChannel => {
                 if let Some(entry) = self.get_selected_entry(None) {
-                    let new_channel = self
-                        .channel
-                        .transition_to(entry.name.as_str().try_into()?);
+                    let target = entry.name.as_str().try_into()?;
+                    let append =
+                        self.send_to_channel_mode == SendToChannelMode::Append;
+                    let new_channel = match self.send_target.take() {
+                        Some(mut channel)
+                            if append
+                                && UnitChannel::from(&channel) == target =>
+                        {
+                            channel.ingest(&mut self.channel)?;
+                            channel
+                        }
+                        _ => self.channel.transition_to(
+                            target,
+                            &self.config.channel_config(),
+                        ),
+                    };
                     self.reset_picker_selection();
                     self.reset_picker_input();
                     self.remote_control.find(EMPTY_STRING);
                     self.mode = Mode::Channel;
                     self.change_channel(new_channel);
+                    self.on_air_send_target = append;
                 }
             }
         }
@@ -499,7 +1578,475 @@ impl Television {
         }
     }
 
+    /// Copy every selected entry of the current channel to the clipboard,
+    /// without exiting, and confirm how many were copied.
+    pub fn handle_copy_all_selected(&mut self) {
+        if self.mode != Mode::Channel {
+            return;
+        }
+        let entries = self.channel.selected_entries();
+        self.notice = Some(match entries.len() {
+            0 => String::from("No selected entries to copy"),
+            count => {
+                tokio::spawn(CLIPBOARD.set(join_entries(
+                    entries,
+                    self.config.application.copy_all_separator,
+                )));
+                if count == 1 {
+                    String::from("Copied 1 entry to the clipboard")
+                } else {
+                    format!("Copied {count} entries to the clipboard")
+                }
+            }
+        });
+    }
+
+    /// Copy the paths of the selected entries relative to
+    /// `AppConfig::relative_path_base` (or the current directory) to the
+    /// clipboard, falling back to absolute paths for entries that aren't
+    /// under it.
+    pub fn handle_copy_relative_path(&mut self) {
+        if self.mode != Mode::Channel {
+            return;
+        }
+        let Some(entries) = self.get_selected_entries(None) else {
+            return;
+        };
+        let Ok(cwd) = std::env::current_dir() else {
+            return;
+        };
+        let base = self
+            .config
+            .application
+            .relative_path_base
+            .clone()
+            .unwrap_or_else(|| cwd.clone());
+        let copied_string = entries
+            .iter()
+            .map(|e| {
+                relative_path(Path::new(&e.name), &base, &cwd)
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        tokio::spawn(CLIPBOARD.set(copied_string));
+    }
+
+    /// Use the selected entry if it is a directory, or the directory it is
+    /// in otherwise, as the base directory of `Action::CopyRelativePath`.
+    pub fn handle_set_relative_path_base(&mut self) {
+        if self.mode != Mode::Channel {
+            return;
+        }
+        let Some(entry) = self.get_selected_entry(None) else {
+            return;
+        };
+        let Ok(cwd) = std::env::current_dir() else {
+            return;
+        };
+        let path = cwd.join(&entry.name);
+        let base = if path.is_dir() {
+            path
+        } else {
+            path.parent().map_or(cwd, Path::to_path_buf)
+        };
+        self.config.application.relative_path_base = Some(base);
+    }
+
+    /// The directory containing the entry selected in a file-backed
+    /// channel, an error being shown for entries without any.
+    pub fn selected_parent_directory(&mut self) -> Option<PathBuf> {
+        if self.mode != Mode::Channel || !self.channel.is_file_backed() {
+            return None;
+        }
+        let entry = self.get_selected_entry(None)?;
+        let cwd = std::env::current_dir().ok()?;
+        let parent = parent_directory(Path::new(&entry.name), &cwd);
+        if parent.is_none() {
+            self.error =
+                Some(format!("{} has no parent directory", entry.name));
+        }
+        parent
+    }
+
+    /// Collapse or expand the group of the selected row when results are
+    /// grouped, keeping the selection within that group.
+    pub fn handle_toggle_group(&mut self) {
+        if !self.config.ui.group_results || !self.results_focused() {
+            return;
+        }
+        let Some(group) = self
+            .results_picker
+            .selected()
+            .and_then(|i| self.result_groups.row(i))
+            .and_then(GroupRow::group)
+            .map(ToString::to_string)
+        else {
+            return;
+        };
+        self.result_groups.toggle(&group);
+        self.update_results_picker_state();
+        if let Some(header) = self.result_groups.header_index(&group) {
+            // a collapsed group's header is the only row left to select,
+            // otherwise select the group's first result
+            let collapsed = self
+                .result_groups
+                .row(header)
+                .is_some_and(GroupRow::selectable);
+            let index = if collapsed { header } else { header + 1 };
+            let height = self.results_rows();
+            self.results_picker.select_index(index, height);
+            self.update_results_picker_state();
+        }
+    }
+
+    /// Replace the current query with the selected entry's value (or its name if it
+    /// doesn't have one) and re-run the search.
+    pub fn handle_use_selection_as_query(&mut self) {
+        if self.mode != Mode::Channel {
+            return;
+        }
+        if let Some(entry) = self.get_selected_entry(None) {
+            let new_pattern = entry.value.unwrap_or(entry.name);
+            self.handle_set_query(new_pattern.trim());
+        }
+    }
+
+    /// Append the text following the match that all the results have in
+    /// common to the query, if any, and re-run the search.
+    pub fn handle_complete_common_prefix(&mut self) {
+        if self.mode != Mode::Channel {
+            return;
+        }
+        let count = self.channel.result_count();
+        let channel = &mut self.channel;
+        // results are fetched one page at a time until the completion is
+        // known to be ambiguous
+        let completion = longest_common_prefix(
+            (0..count)
+                .step_by(COMPLETION_PAGE_SIZE as usize)
+                .flat_map(|offset| {
+                    channel.results(
+                        COMPLETION_PAGE_SIZE.min(count - offset),
+                        offset,
+                    )
+                })
+                .map(|entry| text_after_match(&entry).to_string()),
+        );
+        if !completion.is_empty() {
+            let query =
+                format!("{}{completion}", self.results_picker.input.value());
+            self.handle_set_query(&query);
+        }
+    }
+
+    /// Replace the current query and re-run the search.
+    pub fn handle_set_query(&mut self, query: &str) {
+        if self.mode != Mode::Channel {
+            return;
+        }
+        self.results_picker.input = Input::new(query.to_string());
+        if query != self.current_pattern {
+            self.find(query);
+            self.current_pattern = query.to_string();
+            self.reset_picker_selection();
+            self.preview_state.reset();
+        }
+    }
+
+    /// Pin the current query as a filter and clear the input to narrow the
+    /// results down further.
+    pub fn handle_push_filter(&mut self) {
+        if self.mode != Mode::Channel || self.current_pattern.trim().is_empty()
+        {
+            return;
+        }
+        let filter = self.current_pattern.trim().to_string();
+        self.pinned_filters.push(filter);
+        self.results_picker.input = Input::new(EMPTY_STRING.to_string());
+        self.current_pattern = EMPTY_STRING.to_string();
+        self.find(EMPTY_STRING);
+        self.reset_picker_selection();
+        self.preview_state.reset();
+    }
+
+    /// Remove the last pinned filter.
+    pub fn handle_pop_filter(&mut self) {
+        if self.mode != Mode::Channel || self.pinned_filters.pop().is_none() {
+            return;
+        }
+        let pattern = self.current_pattern.clone();
+        self.find(&pattern);
+        self.reset_picker_selection();
+        self.preview_state.reset();
+    }
+
+    /// Reverse the order of the results, independently of the layout.
+    pub fn handle_toggle_sort_direction(&mut self) {
+        if self.mode != Mode::Channel {
+            return;
+        }
+        self.sort_descending = !self.sort_descending;
+        self.result_groups.set_start(0);
+        self.results_picker.reset_selection();
+        self.preview_state.reset();
+    }
+
+    /// Clear the query, the pinned filters and the selected entries, going
+    /// back to the channel's unfiltered results.
+    pub fn handle_reset_channel(&mut self) {
+        if self.mode != Mode::Channel {
+            return;
+        }
+        self.pinned_filters.clear();
+        self.carried_selection.clear();
+        self.current_pattern = EMPTY_STRING.to_string();
+        self.pending_reselection = None;
+        self.results_picker.reset_input();
+        self.results_picker.reset_selection();
+        self.channel.reset();
+        self.preview_state.reset();
+    }
+
+    /// Run the channel's source command again and match its new entries
+    /// against the current query.
+    ///
+    /// Unlike `handle_reset_channel`, the query, the pinned filters and the
+    /// selected entries are kept.
+    pub fn handle_reload_source(&mut self) {
+        if self.mode != Mode::Channel || !self.channel.reload_source() {
+            return;
+        }
+        self.find(&self.current_pattern.clone());
+        self.reloaded_at = Some(Instant::now());
+        self.results_picker.reset_selection();
+        self.preview_state.reset();
+    }
+
+    /// Pause loading the channel's entries in the background, or resume it.
+    pub fn handle_toggle_pause_scanning(&mut self) {
+        if self.mode != Mode::Channel {
+            return;
+        }
+        if self.scanning_paused {
+            self.channel.resume();
+            self.scanning_paused = false;
+        } else {
+            self.scanning_paused = self.channel.pause();
+        }
+    }
+
+    /// Switch between matching the entries' full text and only their
+    /// visible text, if the channel supports it.
+    pub fn handle_toggle_match_target(&mut self) {
+        if self.mode != Mode::Channel
+            || !self.channel.set_match_target(self.match_target.toggled())
+        {
+            return;
+        }
+        self.match_target = self.match_target.toggled();
+        self.results_picker.reset_selection();
+        self.preview_state.reset();
+    }
+
+    /// Open or close the split search pane.
+    pub fn handle_toggle_split_search(&mut self) {
+        if let Some(split) = self.split_search.take() {
+            split.shutdown();
+        } else if self.mode == Mode::Channel {
+            let mut split = SplitSearch::new(self.config.channel_config());
+            split.sync_with(self.results_selected_entry().as_ref());
+            self.split_search = Some(split);
+        }
+        self.preview_state.reset();
+    }
+
+    /// Move the focus between the results and the split search pane.
+    pub fn handle_switch_split_focus(&mut self) {
+        if self.mode != Mode::Channel {
+            return;
+        }
+        if let Some(split) = self.split_search.as_mut() {
+            split.focused = !split.focused;
+            self.preview_state.reset();
+        }
+    }
+
+    /// Re-initialize the split search pane's channel from the entry
+    /// selected in the results and refresh its picker.
+    pub fn update_split_search_state(&mut self) {
+        let selected_entry = self.results_selected_entry();
+        let height = self.ui_state.layout.results.height;
+        if let Some(split) = self.split_search.as_mut() {
+            split.sync_with(selected_entry.as_ref());
+            split.update_picker_state(height);
+        }
+    }
+
+    /// The configured `execute_command` with its placeholder replaced by the
+    /// selected entries, if any.
+    pub fn execute_command_line(&self) -> Option<String> {
+        let template = self.config.application.execute_command.as_ref()?;
+        let mut entries = self
+            .get_selected_entries(Some(Mode::Channel))?
+            .iter()
+            .map(Entry::stdout_repr)
+            .collect::<Vec<_>>();
+        entries.sort();
+        Some(expand_command_template(template, &entries))
+    }
+
+    /// Whether `execute_command` asks for confirmation before running in
+    /// the current channel (see `AppConfig::confirm_execute_command`).
+    pub fn execute_needs_confirmation(&self) -> bool {
+        let channel = self.channel.config_name();
+        self.config
+            .application
+            .confirm_execute_command
+            .iter()
+            .any(|name| name.to_lowercase() == channel)
+    }
+
+    /// Answer the pending confirmation prompt with the given key.
+    ///
+    /// # Returns
+    /// The command to run if the key confirmed it.
+    pub fn answer_confirmation(&mut self, key: Key) -> Option<String> {
+        match self.confirmation.as_ref()?.answer(key) {
+            Answer::Confirmed => self.confirmation.take().map(|c| c.command),
+            Answer::Cancelled => {
+                self.confirmation = None;
+                None
+            }
+            Answer::Pending => None,
+        }
+    }
+
+    /// Check that the files behind the accepted entries still exist.
+    ///
+    /// Depending on `AppConfig::on_missing_entry`, missing files either
+    /// refuse the selection and show an error or are accepted with a
+    /// warning.
+    ///
+    /// # Returns
+    /// Whether the entries can be accepted.
+    pub fn check_accepted_entries(
+        &mut self,
+        entries: &FxHashSet<Entry>,
+    ) -> bool {
+        let mut missing = entries
+            .iter()
+            .filter(|entry| self.is_path_entry(entry))
+            .filter(|entry| !Path::new(&entry.name).exists())
+            .map(|entry| entry.name.as_str())
+            .collect::<Vec<_>>();
+        if missing.is_empty() {
+            return true;
+        }
+        missing.sort_unstable();
+        let message = match missing.as_slice() {
+            [name] => format!("{name} no longer exists"),
+            names => format!("{} no longer exist", names.join(", ")),
+        };
+        match self.config.application.on_missing_entry {
+            MissingEntryBehavior::Refuse => {
+                self.error = Some(message);
+                false
+            }
+            MissingEntryBehavior::Warn => {
+                warn!("{message}");
+                self.warnings.push(message);
+                true
+            }
+        }
+    }
+
+    /// The number of content lines that fit in the preview pane.
+    fn preview_pane_height(&self) -> u16 {
+        self.ui_state
+            .layout
+            .preview_window
+            // borders + top padding (+ header)
+            .map_or(0, |w| {
+                w.height.saturating_sub(
+                    3 + u16::from(self.config.ui.show_preview_header),
+                )
+            })
+    }
+
+    /// Start typing a preview search, or close it if it is already open.
+    pub fn handle_toggle_preview_search(&mut self) {
+        if self.preview_state.search.is_some() {
+            self.preview_state.close_search();
+        } else {
+            self.preview_state.start_search();
+        }
+    }
+
+    pub fn handle_toggle_keybinding_editor(&mut self) {
+        self.keybinding_editor = match self.keybinding_editor {
+            Some(_) => None,
+            None => Some(KeybindingEditor::default()),
+        };
+    }
+
+    /// Handle actions while the keybinding editor is open.
+    ///
+    /// Navigation actions move through the editor's list of actions and
+    /// confirming starts capturing a new key for the selected action. Every
+    /// other action is ignored until the editor is closed.
+    pub fn handle_keybinding_editor_action(&mut self, action: &Action) {
+        let Some(editor) = self.keybinding_editor.as_mut() else {
+            return;
+        };
+        match action {
+            Action::ToggleKeybindingEditor => self.keybinding_editor = None,
+            Action::SelectNextEntry => editor.select_next(),
+            Action::SelectPrevEntry => editor.select_prev(),
+            Action::ConfirmSelection => editor.start_capture(),
+            Action::CaptureKey(key) => self.handle_capture_key(*key),
+            _ => {}
+        }
+    }
+
+    /// Rebind the action selected in the keybinding editor to `key` and
+    /// persist the new binding to the user's configuration file.
+    pub fn handle_capture_key(&mut self, key: Key) {
+        let Some(editor) = self.keybinding_editor.as_mut() else {
+            return;
+        };
+        if let Some(binding) =
+            editor.capture(key, &mut self.config.keybindings)
+        {
+            if let Err(e) = save_keybinding(
+                &self.config.application.config_dir,
+                editor.selected_action(),
+                &binding,
+            ) {
+                editor.message =
+                    Some(format!("Failed to save keybinding: {e}"));
+            }
+        }
+    }
+
     pub fn handle_action(&mut self, action: &Action) -> Result<()> {
+        if self.keybinding_editor.is_some() {
+            self.handle_keybinding_editor_action(action);
+            return Ok(());
+        }
+        // the help overlay leaves the underlying state untouched until it
+        // is dismissed
+        if self.help_visible {
+            match action {
+                Action::ToggleHelp => self.help_visible = false,
+                Action::WidenHelpActions | Action::WidenHelpKeys => {
+                    self.handle_widen_help_column(action);
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
         // handle actions
         match action {
             Action::AddInputChar(_)
@@ -523,25 +2070,21 @@ impl Television {
             Action::SelectNextPage => {
                 self.preview_state.reset();
                 self.select_next_entry(
-                    self.ui_state
-                        .layout
-                        .results
-                        .height
-                        .saturating_sub(2)
-                        .into(),
+                    u32::try_from(self.results_rows()).unwrap_or(u32::MAX),
                 );
             }
             Action::SelectPrevPage => {
                 self.preview_state.reset();
                 self.select_prev_entry(
-                    self.ui_state
-                        .layout
-                        .results
-                        .height
-                        .saturating_sub(2)
-                        .into(),
+                    u32::try_from(self.results_rows()).unwrap_or(u32::MAX),
                 );
             }
+            Action::SelectVisibleEntry(index) => {
+                self.select_visible_entry(*index);
+            }
+            Action::ToggleQuicknav => self.handle_toggle_quicknav(),
+            Action::QuicknavKey(key) => self.handle_quicknav_key(*key),
+            Action::Tick => self.results_picker.tick_animation(),
             Action::ScrollPreviewDown => self.preview_state.scroll_down(1),
             Action::ScrollPreviewUp => self.preview_state.scroll_up(1),
             Action::ScrollPreviewHalfPageDown => {
@@ -550,28 +2093,146 @@ impl Television {
             Action::ScrollPreviewHalfPageUp => {
                 self.preview_state.scroll_up(20);
             }
+            Action::ScrollPreviewTop => self.preview_state.scroll_to_top(),
+            Action::ScrollPreviewBottom => {
+                self.preview_state
+                    .scroll_to_bottom(self.preview_pane_height());
+            }
+            Action::LockPreviewScroll => {
+                self.preview_state.toggle_scroll_lock();
+            }
+            Action::ShowPreviewForCurrent => {
+                self.handle_show_preview_for_current();
+            }
+            Action::PreviewCursorDown => {
+                self.preview_state
+                    .cursor_down(1, self.preview_pane_height());
+            }
+            Action::PreviewCursorUp => {
+                self.preview_state.cursor_up(1, self.preview_pane_height());
+            }
+            Action::TogglePreviewSearch => {
+                self.handle_toggle_preview_search();
+            }
+            Action::NextPreviewMatch => {
+                self.preview_state
+                    .next_search_match(self.preview_pane_height());
+            }
+            Action::PrevPreviewMatch => {
+                self.preview_state
+                    .prev_search_match(self.preview_pane_height());
+            }
             Action::ToggleRemoteControl => {
                 self.handle_toggle_rc();
             }
             Action::ToggleSelectionDown | Action::ToggleSelectionUp => {
                 self.handle_toggle_selection(action);
             }
+            Action::InvertSelection => {
+                self.handle_invert_selection();
+            }
+            Action::ConfirmSelection
+                if self
+                    .preview_state
+                    .search
+                    .as_ref()
+                    .is_some_and(|search| search.editing) =>
+            {
+                self.preview_state
+                    .confirm_search(self.preview_pane_height());
+            }
             Action::ConfirmSelection => {
                 self.handle_confirm_selection()?;
             }
             Action::CopyEntryToClipboard => {
                 self.handle_copy_entry_to_clipboard();
             }
+            Action::CopyAllSelected => {
+                self.handle_copy_all_selected();
+            }
+            Action::CopyRelativePath => {
+                self.handle_copy_relative_path();
+            }
+            Action::SetRelativePathBase => {
+                self.handle_set_relative_path_base();
+            }
+            Action::CompleteCommonPrefix => {
+                self.handle_complete_common_prefix();
+            }
+            Action::UseSelectionAsQuery => {
+                self.handle_use_selection_as_query();
+            }
+            Action::SetQuery(query) => {
+                self.handle_set_query(query);
+            }
+            Action::PushFilter => {
+                self.handle_push_filter();
+            }
+            Action::PopFilter => {
+                self.handle_pop_filter();
+            }
+            Action::DismissError => {
+                self.error = None;
+            }
+            Action::ToggleSortDirection => {
+                self.handle_toggle_sort_direction();
+            }
+            Action::ToggleGroup => {
+                self.handle_toggle_group();
+            }
+            Action::ResetChannel => {
+                self.handle_reset_channel();
+            }
+            Action::ReloadSource => {
+                self.handle_reload_source();
+            }
+            Action::TogglePauseScanning => {
+                self.handle_toggle_pause_scanning();
+            }
+            Action::ToggleMatchTarget => {
+                self.handle_toggle_match_target();
+            }
+            Action::ToggleSplitSearch => {
+                self.handle_toggle_split_search();
+            }
+            Action::SwitchSplitFocus => {
+                self.handle_switch_split_focus();
+            }
             Action::ToggleSendToChannel => {
                 self.handle_toggle_send_to_channel();
             }
+            Action::ToggleSendToChannelMode => {
+                self.send_to_channel_mode =
+                    self.send_to_channel_mode.toggled();
+                // entries are no longer appended to the channel they were
+                // last sent to
+                if self.send_to_channel_mode == SendToChannelMode::Replace {
+                    self.send_target = None;
+                }
+            }
             Action::ToggleHelp => {
-                self.config.ui.show_help_bar = !self.config.ui.show_help_bar;
+                self.help_visible = !self.help_visible;
+            }
+            Action::WidenHelpActions | Action::WidenHelpKeys => {
+                self.handle_widen_help_column(action);
             }
             Action::TogglePreview => {
                 self.config.ui.show_preview_panel =
                     !self.config.ui.show_preview_panel;
             }
+            Action::ToggleInputWrap => {
+                self.config.ui.wrap_input = !self.config.ui.wrap_input;
+            }
+            Action::ToggleDebugOverlay => {
+                self.debug_overlay_visible = !self.debug_overlay_visible;
+            }
+            Action::TogglePreviewFullscreen => {
+                self.preview_fullscreen = !self.preview_fullscreen;
+            }
+            Action::CyclePreviewer => self.handle_cycle_previewer(),
+            Action::ToggleKeybindingEditor => {
+                self.handle_toggle_keybinding_editor();
+            }
             _ => {}
         }
         Ok(())
@@ -584,20 +2245,30 @@ impl Television {
     pub fn update(&mut self, action: &Action) -> Result<Option<Action>> {
         self.handle_action(action)?;
 
+        self.poll_pending_channel();
+
         self.update_results_picker_state();
 
         self.update_rc_picker_state();
 
-        let selected_entry = self
-            .get_selected_entry(Some(Mode::Channel))
-            .unwrap_or(ENTRY_PLACEHOLDER);
+        self.update_split_search_state();
 
-        self.update_preview_state(&selected_entry)?;
+        if self.mode == Mode::RemoteControl {
+            self.update_guide_preview_state()?;
+        } else {
+            let selected_entry = self.get_selected_entry(Some(Mode::Channel));
+            if let Some(hook) = self.on_change_hook.as_mut() {
+                hook.update(selected_entry.as_ref(), Instant::now());
+            }
+            let selected_entry = selected_entry.unwrap_or(ENTRY_PLACEHOLDER);
+
+            self.update_preview_state(&selected_entry)?;
+        }
 
         self.ticks += 1;
 
         Ok(if self.should_render(action) {
-            if self.channel.running() {
+            if self.channel_running() {
                 self.spinner.tick();
             }
 
@@ -607,3 +2278,1181 @@ impl Television {
         })
     }
 }
+
+/// The number of results fetched at once when completing the query.
+const COMPLETION_PAGE_SIZE: u32 = 256;
+
+/// The part of the entry's name following its last character matching the
+/// query, the whole name if nothing matched.
+fn text_after_match(entry: &Entry) -> &str {
+    let end = entry
+        .name_match_ranges
+        .as_ref()
+        .and_then(|ranges| ranges.last())
+        .map_or(0, |&(_, end)| end as usize);
+    entry
+        .name
+        .char_indices()
+        .nth(end)
+        .map_or("", |(i, _)| &entry.name[i..])
+}
+
+/// The names of the given entries in alphabetical order, joined by the
+/// separator.
+fn join_entries<'a>(
+    entries: impl IntoIterator<Item = &'a Entry>,
+    separator: ClipboardSeparator,
+) -> String {
+    let mut names = entries
+        .into_iter()
+        .map(|entry| entry.name.as_str())
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+    names.join(separator.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::channels::entry::PreviewType;
+    use crate::channels::ChannelConfig;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use super::*;
+
+    /// A files channel listing the files of the test target directory.
+    fn target_dir_channel() -> TelevisionChannel {
+        let target_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("target_dir");
+        TelevisionChannel::Files(crate::channels::files::Channel::new(
+            vec![target_dir],
+            &ChannelConfig::default(),
+        ))
+    }
+
+    fn setup_television() -> Television {
+        let (action_tx, _) = tokio::sync::mpsc::unbounded_channel();
+        Television::new(
+            action_tx,
+            target_dir_channel(),
+            Config::default(),
+            None,
+        )
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_channel_theme_overrides_global_theme() {
+        let mut config = Config::default();
+        config
+            .ui
+            .themes
+            .insert(String::from("env"), String::from("gruvbox-light"));
+        let (action_tx, _) = tokio::sync::mpsc::unbounded_channel();
+        let mut television = Television::new(
+            action_tx,
+            TelevisionChannel::Files(crate::channels::files::Channel::new(
+                Vec::new(),
+                &ChannelConfig::default(),
+            )),
+            config,
+            None,
+        );
+        let global: Colorscheme = (&Theme::from_name("default")).into();
+        let gruvbox: Colorscheme = (&Theme::from_name("gruvbox-light")).into();
+        assert_ne!(global, gruvbox);
+        assert_eq!(television.colorscheme, global);
+
+        television.change_channel(TelevisionChannel::Env(
+            crate::channels::env::Channel::new(&ChannelConfig::default()),
+        ));
+        assert_eq!(television.colorscheme, gruvbox);
+
+        television.change_channel(TelevisionChannel::Files(
+            crate::channels::files::Channel::new(
+                Vec::new(),
+                &ChannelConfig::default(),
+            ),
+        ));
+        assert_eq!(television.colorscheme, global);
+    }
+
+    /// Wait for the channel's result count to satisfy the given predicate.
+    async fn wait_for_results(
+        television: &mut Television,
+        predicate: impl Fn(u32) -> bool,
+    ) {
+        for _ in 0..100 {
+            television.update_results_picker_state();
+            if predicate(television.channel.result_count()) {
+                // make sure the picker's selection reflects the new results
+                television.update_results_picker_state();
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("channel results did not settle in time");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_pending_channel_shows_loading_then_populates() {
+        let placeholder = TelevisionChannel::Text(
+            crate::channels::text::Channel::from_file_paths(
+                Vec::new(),
+                &ChannelConfig::default(),
+            ),
+        );
+        let (action_tx, _) = tokio::sync::mpsc::unbounded_channel();
+        let mut television =
+            Television::new(action_tx, placeholder, Config::default(), None);
+        let (ready_tx, ready_rx) = std::sync::mpsc::channel::<()>();
+        television.load_channel(PendingChannel::spawn(
+            "slow".to_string(),
+            move || {
+                // a slow channel that's ready only once the test says so
+                ready_rx.recv().unwrap();
+                target_dir_channel()
+            },
+        ));
+
+        type_query(&mut television, "file");
+        television.poll_pending_channel();
+        let channel_state = television.dump_context().tv_state.channel_state;
+        assert_eq!(channel_state.current_channel_name, "slow");
+        assert!(channel_state.running);
+
+        ready_tx.send(()).unwrap();
+        for _ in 0..100 {
+            television.poll_pending_channel();
+            if television.pending_channel.is_none() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(television.pending_channel.is_none());
+        assert_eq!(television.channel.name(), "files");
+        // the query typed while loading applies to the new channel
+        wait_for_results(&mut television, |count| count > 0).await;
+        assert!(television
+            .results_picker
+            .entries
+            .iter()
+            .all(|entry| entry.name.contains("file")));
+    }
+
+    #[test]
+    fn test_common_prefix_completion() {
+        let completion = |matches: &[(&str, (u32, u32))]| {
+            longest_common_prefix(matches.iter().map(|(name, range)| {
+                let entry =
+                    Entry::new((*name).to_string(), PreviewType::Files)
+                        .with_name_match_ranges(&[*range]);
+                text_after_match(&entry).to_string()
+            }))
+        };
+
+        // `src/ma`
+        assert_eq!(
+            completion(&[
+                ("src/matcher/mod.rs", (0, 6)),
+                ("src/matcher/config.rs", (0, 6)),
+            ]),
+            "tcher/"
+        );
+        // `conf`, matching at different positions
+        assert_eq!(
+            completion(&[
+                ("src/config/ui.rs", (4, 8)),
+                ("config.toml", (0, 4)),
+            ]),
+            "ig"
+        );
+        assert_eq!(
+            completion(&[("src/config/ui.rs", (4, 8)), ("conf.d", (0, 4)),]),
+            ""
+        );
+        assert_eq!(completion(&[("src/main.rs", (4, 11))]), "");
+        assert_eq!(completion(&[]), "");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_complete_common_prefix() {
+        let mut television = setup_television();
+        wait_for_results(&mut television, |count| count == 2).await;
+
+        television.handle_set_query("target_dir/fi");
+        wait_for_results(&mut television, |count| count == 2).await;
+        television
+            .handle_action(&Action::CompleteCommonPrefix)
+            .unwrap();
+
+        assert_eq!(television.results_picker.input.value(), "target_dir/file");
+        assert_eq!(television.current_pattern, "target_dir/file");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_quicknav_selects_labelled_entry() {
+        let mut television = setup_television();
+        television.ui_state.layout.results =
+            ratatui::layout::Rect::new(0, 0, 40, 10);
+        wait_for_results(&mut television, |count| count == 2).await;
+
+        television.handle_action(&Action::ToggleQuicknav).unwrap();
+        assert_eq!(
+            television.quicknav.as_ref().unwrap().visible_labels(),
+            ["a", "s"]
+        );
+        let second = television.channel_results(2, 0)[1].clone();
+        television
+            .handle_action(&Action::QuicknavKey(Key::Char('s')))
+            .unwrap();
+
+        assert!(television.quicknav.is_none());
+        assert_eq!(television.results_picker.selected(), Some(1));
+        assert_eq!(television.get_selected_entry(None), Some(second));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_use_selection_as_query() {
+        let mut television = setup_television();
+        wait_for_results(&mut television, |count| count == 2).await;
+
+        let selected = television.get_selected_entry(None).unwrap();
+        television
+            .handle_action(&Action::UseSelectionAsQuery)
+            .unwrap();
+
+        assert_eq!(television.results_picker.input.value(), selected.name);
+        assert_eq!(television.current_pattern, selected.name);
+
+        // the channel should have been re-matched against the new query
+        wait_for_results(&mut television, |count| count == 1).await;
+        assert_eq!(
+            television.get_selected_entry(None).unwrap().name,
+            selected.name
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_pinned_filters_compose_with_query() {
+        let mut television = setup_television();
+        wait_for_results(&mut television, |count| count == 2).await;
+
+        type_query(&mut television, "file2");
+        television.handle_action(&Action::PushFilter).unwrap();
+        assert_eq!(television.pinned_filters, vec!["file2"]);
+        assert_eq!(television.results_picker.input.value(), "");
+        assert_eq!(television.current_pattern, "");
+        wait_for_results(&mut television, |count| count == 1).await;
+
+        // both entries match the query alone but only one matches the filter
+        type_query(&mut television, "txt");
+        wait_for_results(&mut television, |count| count == 1).await;
+        assert!(television
+            .get_selected_entry(None)
+            .unwrap()
+            .name
+            .ends_with("file2.txt"));
+
+        television.handle_action(&Action::PopFilter).unwrap();
+        assert!(television.pinned_filters.is_empty());
+        assert_eq!(television.current_pattern, "txt");
+        wait_for_results(&mut television, |count| count == 2).await;
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_toggle_help_overlay() {
+        let mut television = setup_television();
+        wait_for_results(&mut television, |count| count == 2).await;
+        assert!(!television.help_visible);
+
+        television.handle_action(&Action::ToggleHelp).unwrap();
+        assert!(television.help_visible);
+
+        // the results are left untouched while the overlay is shown
+        television.handle_action(&Action::SelectNextEntry).unwrap();
+        television
+            .handle_action(&Action::AddInputChar('x'))
+            .unwrap();
+        assert_eq!(television.results_picker.selected(), Some(0));
+        assert_eq!(television.current_pattern, "");
+
+        television.handle_action(&Action::ToggleHelp).unwrap();
+        assert!(!television.help_visible);
+        television.handle_action(&Action::SelectNextEntry).unwrap();
+        assert_eq!(television.results_picker.selected(), Some(1));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_pause_scanning_only_where_supported() {
+        let mut tv = setup_television();
+        tv.handle_toggle_pause_scanning();
+        assert!(tv.scanning_paused);
+        tv.handle_toggle_pause_scanning();
+        assert!(!tv.scanning_paused);
+
+        tv.change_channel(TelevisionChannel::Env(
+            crate::channels::env::Channel::new(&ChannelConfig::default()),
+        ));
+        tv.handle_toggle_pause_scanning();
+        assert!(!tv.scanning_paused);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_toggle_match_target_only_where_supported() {
+        let mut tv = setup_television();
+        tv.handle_toggle_match_target();
+        assert_eq!(tv.match_target, MatchTarget::Full);
+
+        tv.change_channel(TelevisionChannel::Cable(
+            crate::channels::cable::Channel::new(
+                "words",
+                "echo word",
+                None,
+                &ChannelConfig::default(),
+            ),
+        ));
+        tv.handle_toggle_match_target();
+        assert_eq!(tv.match_target, MatchTarget::Visible);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_toggle_preview_fullscreen() {
+        let mut television = setup_television();
+        television
+            .handle_action(&Action::AddInputChar('t'))
+            .unwrap();
+        wait_for_results(&mut television, |count| count == 2).await;
+        television.handle_action(&Action::SelectNextEntry).unwrap();
+        assert_eq!(television.results_picker.selected(), Some(1));
+        assert!(!television.preview_fullscreen);
+
+        television
+            .handle_action(&Action::TogglePreviewFullscreen)
+            .unwrap();
+        assert!(television.preview_fullscreen);
+        assert!(television.dump_context().tv_state.preview_fullscreen);
+
+        television
+            .handle_action(&Action::TogglePreviewFullscreen)
+            .unwrap();
+        assert!(!television.preview_fullscreen);
+        // the results are left untouched
+        assert_eq!(television.results_picker.selected(), Some(1));
+        assert_eq!(television.current_pattern, "t");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_max_shown_results_bounds_navigation() {
+        let mut television = setup_television();
+        television.config.ui.max_shown_results = Some(1);
+        television
+            .handle_action(&Action::AddInputChar('t'))
+            .unwrap();
+        wait_for_results(&mut television, |count| count == 2).await;
+        assert_eq!(television.results_picker.entries.len(), 1);
+        assert_eq!(television.results_picker.total_items, 2);
+
+        // the selection wraps around within the listed results
+        television.handle_action(&Action::SelectNextEntry).unwrap();
+        assert_eq!(television.results_picker.selected(), Some(0));
+    }
+
+    #[test]
+    fn test_join_entries() {
+        let entries = ["src/main.rs", "Cargo.toml", "README.md"]
+            .into_iter()
+            .map(|name| Entry::new(name.to_string(), PreviewType::Files))
+            .collect::<FxHashSet<_>>();
+
+        assert_eq!(
+            join_entries(&entries, ClipboardSeparator::Newline),
+            "Cargo.toml\nREADME.md\nsrc/main.rs"
+        );
+        assert_eq!(
+            join_entries(&entries, ClipboardSeparator::Nul),
+            "Cargo.toml\0README.md\0src/main.rs"
+        );
+        assert_eq!(
+            join_entries(&FxHashSet::default(), ClipboardSeparator::Nul),
+            ""
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_copy_all_selected_without_selection() {
+        let mut television = setup_television();
+
+        television.handle_action(&Action::CopyAllSelected).unwrap();
+        assert_eq!(
+            television.notice.as_deref(),
+            Some("No selected entries to copy")
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_cycle_previewer_wraps_around() {
+        let mut television = setup_television();
+        // without alternative previewers, the channel's own one is kept
+        television.handle_action(&Action::CyclePreviewer).unwrap();
+        assert_eq!(television.previewer_index, 0);
+
+        television.config.previewers.alternatives.insert(
+            String::from("files"),
+            ["hexdump", "metadata"]
+                .map(|name| AlternativePreviewer {
+                    name: name.to_string(),
+                    command: format!("{name} {{}}"),
+                })
+                .to_vec(),
+        );
+        let mut active = Vec::new();
+        for _ in 0..4 {
+            television.handle_action(&Action::CyclePreviewer).unwrap();
+            active.push((
+                television.previewer_index,
+                television
+                    .active_alternative_previewer()
+                    .map(|previewer| previewer.name),
+            ));
+        }
+        assert_eq!(
+            active,
+            vec![
+                (1, Some(String::from("hexdump"))),
+                (2, Some(String::from("metadata"))),
+                (0, None),
+                (1, Some(String::from("hexdump"))),
+            ]
+        );
+    }
+
+    /// Wait for the split search pane to list the lines of its entry.
+    async fn wait_for_split_results(television: &mut Television) {
+        for _ in 0..100 {
+            television.update_split_search_state();
+            let split = television.split_search.as_ref().unwrap();
+            if split.channel.result_count() > 0 && !split.channel.running() {
+                television.update_split_search_state();
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("split search results did not settle in time");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_split_search_follows_results_selection() {
+        let mut television = setup_television();
+        wait_for_results(&mut television, |count| count == 2).await;
+
+        television
+            .handle_action(&Action::ToggleSplitSearch)
+            .unwrap();
+        let first = television.get_selected_entry(None).unwrap();
+        wait_for_split_results(&mut television).await;
+        let split = television.split_search.as_ref().unwrap();
+        assert_eq!(split.source(), Some(&first));
+        // the pane lists the lines of the selected file
+        assert_eq!(split.picker.entries[0].name, first.name);
+
+        // typing in the focused pane only searches the pane's channel
+        television.handle_action(&Action::SwitchSplitFocus).unwrap();
+        type_query(&mut television, "number");
+        assert_eq!(television.current_pattern, "");
+        let split = television.split_search.as_ref().unwrap();
+        assert_eq!(split.pattern, "number");
+        assert_eq!(
+            television.get_selected_entry(None),
+            split.selected_entry()
+        );
+
+        // moving the results selection re-initializes the pane's channel
+        television.handle_action(&Action::SwitchSplitFocus).unwrap();
+        television.select_next_entry(1);
+        let second = television.get_selected_entry(None).unwrap();
+        assert_ne!(first, second);
+        wait_for_split_results(&mut television).await;
+        let split = television.split_search.as_ref().unwrap();
+        assert_eq!(split.source(), Some(&second));
+        assert_eq!(split.pattern, "number");
+        // the pane lists the lines of the selected file
+        assert_eq!(split.picker.entries[0].name, second.name);
+
+        television
+            .handle_action(&Action::ToggleSplitSearch)
+            .unwrap();
+        assert!(television.split_search.is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_invert_selection() {
+        let mut television = setup_television();
+        wait_for_results(&mut television, |count| count == 2).await;
+        let selected_names = |television: &Television| {
+            let mut names = television
+                .channel
+                .selected_entries()
+                .iter()
+                .map(|entry| {
+                    entry.name.rsplit('/').next().unwrap().to_string()
+                })
+                .collect::<Vec<_>>();
+            names.sort();
+            names
+        };
+
+        type_query(&mut television, "file2");
+        wait_for_results(&mut television, |count| count == 1).await;
+        television
+            .handle_action(&Action::ToggleSelectionDown)
+            .unwrap();
+        assert_eq!(selected_names(&television), ["file2.txt"]);
+        television.handle_action(&Action::DeletePrevWord).unwrap();
+        wait_for_results(&mut television, |count| count == 2).await;
+
+        television.handle_action(&Action::InvertSelection).unwrap();
+        assert_eq!(selected_names(&television), ["file1.txt"]);
+
+        // only the entries matching the query are toggled
+        type_query(&mut television, "file1");
+        wait_for_results(&mut television, |count| count == 1).await;
+        television.handle_action(&Action::InvertSelection).unwrap();
+        assert!(selected_names(&television).is_empty());
+
+        television.handle_action(&Action::DeletePrevWord).unwrap();
+        wait_for_results(&mut television, |count| count == 2).await;
+        television.handle_action(&Action::InvertSelection).unwrap();
+        assert_eq!(selected_names(&television), ["file1.txt", "file2.txt"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_accept_missing_file() {
+        let mut television = setup_television();
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let name = file.path().to_string_lossy().to_string();
+        let entries = FxHashSet::from_iter([Entry::new(
+            name.clone(),
+            PreviewType::Files,
+        )]);
+        assert!(television.check_accepted_entries(&entries));
+        assert_eq!(television.error, None);
+
+        file.close().unwrap();
+        assert!(!television.check_accepted_entries(&entries));
+        assert_eq!(
+            television.error.as_deref(),
+            Some(format!("{name} no longer exists").as_str())
+        );
+        assert!(television.warnings.is_empty());
+        television.handle_action(&Action::DismissError).unwrap();
+        assert_eq!(television.error, None);
+
+        television.config.application.on_missing_entry =
+            MissingEntryBehavior::Warn;
+        assert!(television.check_accepted_entries(&entries));
+        assert_eq!(television.error, None);
+        assert_eq!(television.warnings, [format!("{name} no longer exists")]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_reset_channel() {
+        let mut television = setup_television();
+        wait_for_results(&mut television, |count| count == 2).await;
+        television
+            .handle_action(&Action::ToggleSelectionDown)
+            .unwrap();
+        type_query(&mut television, "file1");
+        television.handle_action(&Action::PushFilter).unwrap();
+        type_query(&mut television, "txt");
+        wait_for_results(&mut television, |count| count == 1).await;
+        assert_eq!(television.channel.selected_entries().len(), 1);
+
+        television.handle_action(&Action::ResetChannel).unwrap();
+        assert!(television.channel.selected_entries().is_empty());
+        assert!(television.pinned_filters.is_empty());
+        assert_eq!(television.current_pattern, "");
+        assert_eq!(television.results_picker.input.value(), "");
+        wait_for_results(&mut television, |count| count == 2).await;
+        assert_eq!(television.results_picker.selected(), Some(0));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_toggle_sort_direction() {
+        let mut television = setup_television();
+        television.ui_state.layout.results.height = 10;
+        wait_for_results(&mut television, |count| count == 2).await;
+        let names = |television: &Television| {
+            television
+                .results_picker
+                .entries
+                .iter()
+                .map(|entry| entry.name.clone())
+                .collect::<Vec<_>>()
+        };
+        let descending = names(&television);
+        assert_eq!(descending.len(), 2);
+
+        television
+            .handle_action(&Action::ToggleSortDirection)
+            .unwrap();
+        assert!(!television.sort_descending);
+        television.update_results_picker_state();
+        let mut ascending = names(&television);
+        ascending.reverse();
+        assert_eq!(ascending, descending);
+        // the selection follows the displayed order
+        assert_eq!(
+            television.get_selected_entry(None).map(|entry| entry.name),
+            descending.last().cloned()
+        );
+
+        television
+            .handle_action(&Action::ToggleSortDirection)
+            .unwrap();
+        television.update_results_picker_state();
+        assert_eq!(names(&television), descending);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_navigation_skips_collapsed_group() {
+        let dir = tempfile::tempdir().unwrap();
+        for path in ["a/1.txt", "a/2.txt", "b/1.txt"] {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "").unwrap();
+        }
+        let channel =
+            TelevisionChannel::Files(crate::channels::files::Channel::new(
+                vec![dir.path().to_path_buf()],
+                &ChannelConfig {
+                    group_results: true,
+                    ..ChannelConfig::default()
+                },
+            ));
+        let (action_tx, _) = tokio::sync::mpsc::unbounded_channel();
+        let mut config = Config::default();
+        config.ui.group_results = true;
+        let mut television = Television::new(action_tx, channel, config, None);
+        television.ui_state.layout.results.height = 10;
+        wait_for_results(&mut television, |count| count == 3).await;
+        let selected_group = |television: &Television| {
+            television
+                .get_selected_entry(None)
+                .and_then(|entry| entry.group)
+        };
+
+        // the first header is skipped
+        assert_eq!(television.results_picker.total_items, 5);
+        assert_eq!(television.results_picker.selected(), Some(1));
+        let first_group = selected_group(&television).unwrap();
+
+        // collapsing the group leaves its header selected
+        television.handle_action(&Action::ToggleGroup).unwrap();
+        assert_eq!(television.results_picker.total_items, 3);
+        assert_eq!(television.results_picker.selected(), Some(0));
+        assert_eq!(television.get_selected_entry(None), None);
+
+        // moving down skips the group's members and the next header
+        television.select_next_entry(1);
+        assert_eq!(television.results_picker.selected(), Some(2));
+        let second_group = selected_group(&television).unwrap();
+        assert_ne!(second_group, first_group);
+        television.select_next_entry(1);
+        assert_eq!(television.results_picker.selected(), Some(0));
+        television.select_prev_entry(1);
+        assert_eq!(television.results_picker.selected(), Some(2));
+        television.select_prev_entry(1);
+        assert_eq!(television.results_picker.selected(), Some(0));
+
+        // expanding it again selects its first member
+        television.handle_action(&Action::ToggleGroup).unwrap();
+        assert_eq!(television.results_picker.total_items, 5);
+        assert_eq!(television.results_picker.selected(), Some(1));
+        assert_eq!(selected_group(&television), Some(first_group));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_navigation_moves_through_group_windows() {
+        let dir = tempfile::tempdir().unwrap();
+        for path in ["a/1.txt", "a/2.txt", "b/1.txt", "b/2.txt"] {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "").unwrap();
+        }
+        let channel =
+            TelevisionChannel::Files(crate::channels::files::Channel::new(
+                vec![dir.path().to_path_buf()],
+                &ChannelConfig {
+                    group_results: true,
+                    ..ChannelConfig::default()
+                },
+            ));
+        let (action_tx, _) = tokio::sync::mpsc::unbounded_channel();
+        let mut config = Config::default();
+        config.ui.group_results = true;
+        let mut television = Television::new(action_tx, channel, config, None);
+        // two results at a time
+        television.ui_state.layout.results.height = 4;
+        wait_for_results(&mut television, |count| count == 4).await;
+        let selected_name = |television: &Television| {
+            television.get_selected_entry(None).unwrap().name
+        };
+
+        // the rank of the selected result skips the headers and counts the
+        // results of the previous windows
+        let check_rank = |television: &Television| {
+            let rank = television.selected_rank().unwrap();
+            assert_eq!(
+                television.channel_result(u32::try_from(rank).unwrap() - 1),
+                television.get_selected_entry(None)
+            );
+            rank
+        };
+        let mut ranks = vec![check_rank(&television)];
+        let mut seen = vec![selected_name(&television)];
+        for _ in 0..3 {
+            television.select_next_entry(1);
+            seen.push(selected_name(&television));
+            ranks.push(check_rank(&television));
+        }
+        ranks.sort_unstable();
+        assert_eq!(ranks, [1, 2, 3, 4]);
+        assert_eq!(television.result_groups.start(), 2);
+        let mut names = seen.clone();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), 4);
+
+        // the selection wraps around to the first window
+        television.select_next_entry(1);
+        assert_eq!(television.result_groups.start(), 0);
+        assert_eq!(selected_name(&television), seen[0]);
+        television.select_prev_entry(1);
+        assert_eq!(television.result_groups.start(), 2);
+        assert_eq!(selected_name(&television), seen[3]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_guide_samples_entries_once_requested() {
+        // previews being rendered as they change, the receiver is kept
+        let (action_tx, _action_rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut television = Television::new(
+            action_tx,
+            target_dir_channel(),
+            Config::default(),
+            None,
+        );
+        television.handle_toggle_rc();
+        // the first update ticks the matcher, the second one selects
+        television.update_rc_picker_state();
+        television.update_rc_picker_state();
+        television.update_guide_preview_state().unwrap();
+        let placeholder = television.preview_state.preview.clone();
+        assert!(matches!(
+            &placeholder.content,
+            PreviewContent::PlainText(lines)
+                if lines.last().is_some_and(|line| line.contains("entries"))
+        ));
+
+        television.handle_show_preview_for_current();
+        for _ in 0..500 {
+            television.update_guide_preview_state().unwrap();
+            let preview = &television.preview_state.preview;
+            if *preview != placeholder
+                && preview.content != PreviewContent::Loading
+            {
+                assert_eq!(preview.title, placeholder.title);
+                // the channel is sampled again when requested again
+                television.handle_show_preview_for_current();
+                television.update_guide_preview_state().unwrap();
+                assert_eq!(
+                    television.preview_state.preview.content,
+                    PreviewContent::Loading
+                );
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("the channel's entries were not sampled in time");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_execute_command_line() {
+        let mut television = setup_television();
+        assert_eq!(television.execute_command_line(), None);
+
+        television.config.application.execute_command =
+            Some(String::from("echo {}"));
+        // entries are passed as they're output, line numbers included
+        television.channel.toggle_selection(
+            &Entry::new(String::from("b.txt"), PreviewType::Files)
+                .with_line_number(12),
+        );
+        television.channel.toggle_selection(&Entry::new(
+            String::from("a.txt"),
+            PreviewType::Files,
+        ));
+        assert_eq!(
+            television.execute_command_line().as_deref(),
+            Some("echo 'a.txt' 'b.txt:12'")
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_execute_command_confirmation() {
+        let mut television = setup_television();
+        assert!(!television.execute_needs_confirmation());
+        television.config.application.confirm_execute_command =
+            vec!["Files".to_string()];
+        assert!(television.execute_needs_confirmation());
+
+        // unrelated keys keep the prompt open and esc cancels it
+        television.confirmation =
+            Some(Confirmation::new("rm file1.txt".to_string()));
+        assert_eq!(television.answer_confirmation(Key::Char('x')), None);
+        assert!(television.confirmation.is_some());
+        assert_eq!(television.answer_confirmation(Key::Esc), None);
+        assert!(television.confirmation.is_none());
+
+        television.confirmation =
+            Some(Confirmation::new("rm file1.txt".to_string()));
+        assert_eq!(
+            television.answer_confirmation(Key::Char('y')),
+            Some("rm file1.txt".to_string())
+        );
+        assert!(television.confirmation.is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_start_in_send_to_channel_keeps_entries() {
+        let mut television = setup_television();
+        television.handle_toggle_send_to_channel();
+        assert_eq!(television.mode, Mode::SendToChannel);
+        // the seeded entries are loaded while the target channels are listed
+        wait_for_results(&mut television, |count| count == 2).await;
+        television.update_rc_picker_state();
+        assert_eq!(
+            television
+                .rc_picker
+                .entries
+                .iter()
+                .map(|entry| entry.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["files", "text"]
+        );
+    }
+
+    /// Select the entry named `name` in the current channel and send it to
+    /// the files channel.
+    async fn send_to_files(television: &mut Television, name: &str) {
+        wait_for_results(television, |count| count == 2).await;
+        let entry = television
+            .channel
+            .results(2, 0)
+            .into_iter()
+            .find(|entry| entry.name.ends_with(name))
+            .unwrap();
+        television.channel.toggle_selection(&entry);
+        television.handle_toggle_send_to_channel();
+        // the first update ticks the matcher, the second one selects
+        television.update_rc_picker_state();
+        television.update_rc_picker_state();
+        assert_eq!(
+            television.get_selected_entry(None).map(|entry| entry.name),
+            Some(String::from("files"))
+        );
+        television.handle_confirm_selection().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_send_to_channel_appends() {
+        let mut television = setup_television();
+        television.send_to_channel_mode = SendToChannelMode::Append;
+        send_to_files(&mut television, "file1.txt").await;
+        wait_for_results(&mut television, |count| count == 1).await;
+
+        // switch to another channel and send another entry from it
+        television.change_channel(target_dir_channel());
+        send_to_files(&mut television, "file2.txt").await;
+        wait_for_results(&mut television, |count| count == 2).await;
+        let mut names = television
+            .channel
+            .results(2, 0)
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect::<Vec<_>>();
+        names.sort();
+        assert!(names[0].ends_with("file1.txt"));
+        assert!(names[1].ends_with("file2.txt"));
+
+        // replacing starts over with only the entries being sent
+        television.send_to_channel_mode = SendToChannelMode::Replace;
+        television.change_channel(target_dir_channel());
+        send_to_files(&mut television, "file2.txt").await;
+        wait_for_results(&mut television, |count| count == 1).await;
+    }
+
+    /// Type the given text into the results picker's input.
+    fn type_query(television: &mut Television, query: &str) {
+        for c in query.chars() {
+            television.handle_action(&Action::AddInputChar(c)).unwrap();
+        }
+    }
+
+    /// Wait for the channel to settle and any pending re-selection to happen.
+    async fn wait_for_reselection(television: &mut Television) {
+        for _ in 0..100 {
+            television.update_results_picker_state();
+            if television.pending_reselection.is_none() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("pending selection was never resolved");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_keep_selection_on_query_change_still_present() {
+        let mut television = setup_television();
+        television.config.ui.keep_selection_on_query_change = true;
+        wait_for_results(&mut television, |count| count == 2).await;
+
+        television.select_next_entry(1);
+        let selected = television.get_selected_entry(None).unwrap();
+        assert_eq!(television.results_picker.selected(), Some(1));
+
+        // both entries still match
+        type_query(&mut television, "txt");
+        assert_eq!(television.results_picker.selected(), Some(0));
+        wait_for_reselection(&mut television).await;
+
+        assert_eq!(television.get_selected_entry(None).unwrap(), selected);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_keep_selection_on_query_change_now_absent() {
+        let mut television = setup_television();
+        television.config.ui.keep_selection_on_query_change = true;
+        wait_for_results(&mut television, |count| count == 2).await;
+
+        television.select_next_entry(1);
+        let selected = television.get_selected_entry(None).unwrap();
+        let other = if selected.name.ends_with("file1.txt") {
+            "file2"
+        } else {
+            "file1"
+        };
+
+        // the selected entry doesn't match anymore
+        type_query(&mut television, other);
+        wait_for_results(&mut television, |count| count == 1).await;
+        wait_for_reselection(&mut television).await;
+
+        assert_eq!(television.results_picker.selected(), Some(0));
+        assert!(television
+            .get_selected_entry(None)
+            .unwrap()
+            .name
+            .ends_with(&format!("{other}.txt")));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_preview_on_demand() {
+        let mut television = setup_television();
+        // keep the action channel open to receive render requests
+        let (action_tx, _action_rx) = tokio::sync::mpsc::unbounded_channel();
+        television.action_tx = action_tx;
+        television.config.ui.preview_on_demand = true;
+        wait_for_results(&mut television, |count| count == 2).await;
+
+        // changing the selection doesn't generate any preview
+        for _ in 0..2 {
+            television.select_next_entry(1);
+            let selected = television.get_selected_entry(None).unwrap();
+            television.update_preview_state(&selected).unwrap();
+            assert!(!television.previewer.is_requested(&selected));
+            assert_eq!(
+                *television.preview_state.preview,
+                television.on_demand_placeholder(&selected)
+            );
+        }
+
+        // until it is requested
+        television
+            .handle_action(&Action::ShowPreviewForCurrent)
+            .unwrap();
+        let selected = television.get_selected_entry(None).unwrap();
+        television.update_preview_state(&selected).unwrap();
+        assert!(television.previewer.is_requested(&selected));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_results_row_spacing_reduces_visible_rows() {
+        let mut television = setup_television();
+        television.ui_state.layout.results =
+            ratatui::layout::Rect::new(0, 0, 80, 12);
+        assert_eq!(television.results_rows(), 10);
+        television.config.ui.results_row_spacing = 1;
+        assert_eq!(television.results_rows(), 5);
+        television.config.ui.results_row_spacing = 2;
+        assert_eq!(television.results_rows(), 3);
+
+        // only one entry fits so navigation keeps it at the top of the list
+        television.config.ui.results_row_spacing = 9;
+        wait_for_results(&mut television, |count| count == 2).await;
+        television.select_next_entry(1);
+        assert_eq!(television.results_picker.selected(), Some(1));
+        assert_eq!(
+            television.results_picker.relative_state.selected(),
+            Some(0)
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_two_line_results_navigate_by_result() {
+        let mut television = setup_television();
+        television.ui_state.layout.results =
+            ratatui::layout::Rect::new(0, 0, 80, 5);
+        assert_eq!(television.results_rows(), 3);
+        television.config.ui.two_line_results = true;
+        assert_eq!(television.results_rows(), 1);
+
+        // a single two-line result fits in the three rows, so moving to the
+        // next one scrolls the list by a whole result
+        wait_for_results(&mut television, |count| count == 2).await;
+        television.select_next_entry(1);
+        assert_eq!(television.results_picker.selected(), Some(1));
+        assert_eq!(television.results_picker.offset(), 1);
+        assert_eq!(
+            television.results_picker.relative_state.selected(),
+            Some(0)
+        );
+        television.select_prev_entry(1);
+        assert_eq!(television.results_picker.selected(), Some(0));
+        assert_eq!(television.results_picker.offset(), 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_reload_source_reruns_the_command() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source");
+        std::fs::write(&source, "foo1\nbar\n").unwrap();
+        let channel =
+            TelevisionChannel::Cable(crate::channels::cable::Channel::new(
+                "test",
+                &format!("cat {}", source.display()),
+                None,
+                &ChannelConfig::default(),
+            ));
+        let (action_tx, _) = tokio::sync::mpsc::unbounded_channel();
+        let mut television =
+            Television::new(action_tx, channel, Config::default(), None);
+        wait_for_results(&mut television, |count| count == 2).await;
+        television.handle_set_query("foo");
+        wait_for_results(&mut television, |count| count == 1).await;
+
+        std::fs::write(&source, "foo1\nfoo2\nbar\n").unwrap();
+        television.handle_action(&Action::ReloadSource).unwrap();
+
+        // the new entries are matched against the same query
+        assert!(television.channel_running());
+        wait_for_results(&mut television, |count| count == 2).await;
+        assert_eq!(television.results_picker.input.value(), "foo");
+        assert_eq!(television.channel.total_count(), 3);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_selection_persists_across_channels() {
+        let mut config = Config::default();
+        config.application.persistent_selection = true;
+        let target_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("target_dir");
+        let channel =
+            TelevisionChannel::Files(crate::channels::files::Channel::new(
+                vec![target_dir],
+                &ChannelConfig::default(),
+            ));
+        let (action_tx, _) = tokio::sync::mpsc::unbounded_channel();
+        let mut television = Television::new(action_tx, channel, config, None);
+        wait_for_results(&mut television, |count| count == 2).await;
+        let file = television.get_selected_entry(None).unwrap();
+        television
+            .handle_action(&Action::ToggleSelectionDown)
+            .unwrap();
+
+        television.change_channel(TelevisionChannel::Cable(
+            crate::channels::cable::Channel::new(
+                "words",
+                "echo word",
+                None,
+                &ChannelConfig::default(),
+            ),
+        ));
+        wait_for_results(&mut television, |count| count == 1).await;
+        // the entry under the cursor isn't output along with the selection
+        assert_eq!(
+            television.get_accepted_entries(Some(Mode::Channel)),
+            Some(FxHashSet::from_iter([file.clone()]))
+        );
+        television
+            .handle_action(&Action::ToggleSelectionDown)
+            .unwrap();
+
+        let mut names = television
+            .get_accepted_entries(Some(Mode::Channel))
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect::<Vec<_>>();
+        names.sort();
+        let mut expected = vec![file.name.clone(), String::from("word")];
+        expected.sort();
+        assert_eq!(names, expected);
+        assert!(television
+            .carried_selection()
+            .get("files")
+            .is_some_and(|selection| selection.entries.contains(&file)));
+        // copying, executing or sending entries only acts on the channel's
+        // own selection
+        assert_eq!(
+            television
+                .get_selected_entries(Some(Mode::Channel))
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.name)
+                .collect::<Vec<_>>(),
+            vec![String::from("word")]
+        );
+
+        // the selection shows up again in its channel, where it can be
+        // deselected
+        television.change_channel(target_dir_channel());
+        assert!(television.channel.selected_entries().contains(&file));
+        assert!(television
+            .carried_selection()
+            .get("words")
+            .is_some_and(|selection| selection.entries.len() == 1));
+        // the entries carried from other channels aren't taken for paths
+        let accepted = television
+            .get_accepted_entries(Some(Mode::Channel))
+            .unwrap();
+        assert!(television.check_accepted_entries(&accepted));
+        assert_eq!(television.error, None);
+        television.channel.toggle_selection(&file);
+        assert_eq!(
+            television
+                .get_accepted_entries(Some(Mode::Channel))
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.name)
+                .collect::<Vec<_>>(),
+            vec![String::from("word")]
+        );
+
+        // resetting the channel starts over
+        television.handle_reset_channel();
+        assert!(television.carried_selection().is_empty());
+    }
+}
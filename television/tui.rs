@@ -56,7 +56,11 @@ where
 
             disable_raw_mode()?;
             let mut buffered_stderr = LineWriter::new(stderr());
-            execute!(buffered_stderr, cursor::Show)?;
+            execute!(
+                buffered_stderr,
+                cursor::Show,
+                cursor::SetCursorStyle::DefaultUserShape
+            )?;
             execute!(buffered_stderr, LeaveAlternateScreen)?;
         }
 
@@ -1,4 +1,5 @@
-use std::process::Command;
+use std::path::Path;
+use std::process::{Command, Stdio};
 
 #[cfg(not(windows))]
 pub fn shell_command() -> Command {
@@ -17,3 +18,86 @@ pub fn shell_command() -> Command {
 
     cmd
 }
+
+/// The program opening files and directories with the user's default
+/// application.
+#[cfg(target_os = "macos")]
+const OPENER: &str = "open";
+#[cfg(windows)]
+const OPENER: &str = "explorer";
+#[cfg(not(any(target_os = "macos", windows)))]
+const OPENER: &str = "xdg-open";
+
+/// Open `path` with the platform's default application (e.g. a directory
+/// in the file manager) without waiting for it to exit.
+///
+/// # Errors
+/// If the opener can't be started.
+pub fn open_with_default_app(path: &Path) -> std::io::Result<()> {
+    let mut child = Command::new(OPENER)
+        .arg(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    // reap the opener once it exits
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+/// Quote a string so that it is passed as a single argument to the shell.
+#[cfg(not(windows))]
+pub fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+/// Quote a string so that it is passed as a single argument to the shell.
+#[cfg(windows)]
+pub fn shell_quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('"', "\"\""))
+}
+
+/// Build a command line from a template by replacing every `{}` with the
+/// given entries, quoted and separated by spaces.
+///
+/// Example: `less {}` with entries `a.txt` and `my file.txt` gives
+/// `less 'a.txt' 'my file.txt'`.
+pub fn expand_command_template<S: AsRef<str>>(
+    template: &str,
+    entries: &[S],
+) -> String {
+    let args = entries
+        .iter()
+        .map(|e| shell_quote(e.as_ref()))
+        .collect::<Vec<_>>()
+        .join(" ");
+    template.replace("{}", &args)
+}
+
+#[cfg(all(test, not(windows)))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_command_template_multiple_entries() {
+        assert_eq!(
+            expand_command_template(
+                "less {}",
+                &["a.txt", "my file.txt", "it's.txt"]
+            ),
+            "less 'a.txt' 'my file.txt' 'it'\\''s.txt'"
+        );
+    }
+
+    #[test]
+    fn test_expand_command_template_repeated_placeholder() {
+        assert_eq!(
+            expand_command_template("echo {} && wc -l {}", &["a", "b"]),
+            "echo 'a' 'b' && wc -l 'a' 'b'"
+        );
+        assert_eq!(
+            expand_command_template::<&str>("echo done", &[]),
+            "echo done"
+        );
+    }
+}
@@ -106,6 +106,28 @@ pub fn get_file_size(path: &Path) -> Option<u64> {
     std::fs::metadata(path).ok().map(|m| m.len())
 }
 
+/// The path of `path` relative to `base`, both of them being resolved
+/// against `cwd` if they are relative.
+///
+/// Paths that aren't under `base` are returned as absolute paths.
+pub fn relative_path(path: &Path, base: &Path, cwd: &Path) -> PathBuf {
+    let path = cwd.join(path);
+    let base = cwd.join(base);
+    match path.strip_prefix(&base) {
+        Ok(relative) if relative.as_os_str().is_empty() => PathBuf::from("."),
+        Ok(relative) => relative.to_path_buf(),
+        Err(_) => path,
+    }
+}
+
+/// The directory containing `path`, which is resolved against `cwd` if it
+/// is relative.
+///
+/// The root of the file system has no parent and gives `None`.
+pub fn parent_directory(path: &Path, cwd: &Path) -> Option<PathBuf> {
+    cwd.join(path).parent().map(Path::to_path_buf)
+}
+
 #[derive(Debug)]
 pub enum FileType {
     Text,
@@ -145,6 +167,12 @@ where
     }
 }
 
+/// Whether the given bytes (typically the start of a file) look like binary
+/// content, i.e. contain a NUL byte.
+pub fn is_binary(bytes: &[u8]) -> bool {
+    bytes.contains(&0)
+}
+
 pub fn is_known_text_extension<P>(path: P) -> bool
 where
     P: AsRef<Path>,
@@ -519,3 +547,67 @@ pub fn get_known_image_file_extensions() -> &'static FxHashSet<&'static str> {
         .collect()
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_path() {
+        let cwd = Path::new("/home/user/project");
+        assert_eq!(
+            relative_path(Path::new("src/main.rs"), Path::new("src"), cwd),
+            PathBuf::from("main.rs")
+        );
+        assert_eq!(
+            relative_path(
+                Path::new("/home/user/project/src/lib.rs"),
+                Path::new("/home/user"),
+                cwd
+            ),
+            PathBuf::from("project/src/lib.rs")
+        );
+        assert_eq!(
+            relative_path(Path::new("src"), Path::new("src"), cwd),
+            PathBuf::from(".")
+        );
+    }
+
+    #[test]
+    fn test_parent_directory() {
+        let cwd = Path::new("/home/user/project");
+        assert_eq!(
+            parent_directory(Path::new("src/main.rs"), cwd),
+            Some(PathBuf::from("/home/user/project/src"))
+        );
+        assert_eq!(
+            parent_directory(Path::new("Cargo.toml"), cwd),
+            Some(PathBuf::from("/home/user/project"))
+        );
+        assert_eq!(
+            parent_directory(Path::new("/etc/hosts"), cwd),
+            Some(PathBuf::from("/etc"))
+        );
+        // directories are revealed in their own parent
+        assert_eq!(
+            parent_directory(Path::new("/etc/"), cwd),
+            Some(PathBuf::from("/"))
+        );
+        #[cfg(unix)]
+        assert_eq!(parent_directory(Path::new("/"), cwd), None);
+    }
+
+    #[test]
+    fn test_relative_path_not_under_base_is_absolute() {
+        let cwd = Path::new("/home/user/project");
+        assert_eq!(
+            relative_path(Path::new("src/main.rs"), Path::new("/etc"), cwd),
+            PathBuf::from("/home/user/project/src/main.rs")
+        );
+        // `srcs` shares a prefix with `src` but isn't under it
+        assert_eq!(
+            relative_path(Path::new("srcs/a.rs"), Path::new("src"), cwd),
+            PathBuf::from("/home/user/project/srcs/a.rs")
+        );
+    }
+}
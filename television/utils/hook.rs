@@ -0,0 +1,190 @@
+//! Run a command whenever the selected entry changes (see
+//! `AppConfig::on_change_command`).
+
+use std::process::{Child, Stdio};
+use std::time::{Duration, Instant};
+
+use tracing::debug;
+
+use crate::channels::entry::Entry;
+use crate::utils::command::{expand_command_template, shell_command};
+
+/// How long an entry has to stay selected before the hook runs for it, so
+/// that scrolling through the results doesn't run it for every entry.
+pub const ON_CHANGE_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// Reports a value once it stopped changing for a given delay.
+///
+/// The current time is always passed in by the caller, which keeps the
+/// debouncer independent from the system clock.
+#[derive(Debug, Clone)]
+pub struct Debouncer<T> {
+    delay: Duration,
+    /// The last value reported.
+    current: Option<T>,
+    /// The value waiting to be reported and when it was first seen.
+    pending: Option<(T, Instant)>,
+}
+
+impl<T: PartialEq> Debouncer<T> {
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            current: None,
+            pending: None,
+        }
+    }
+
+    /// Record the latest value, a change restarting the delay.
+    pub fn update(&mut self, value: T, now: Instant) {
+        if self
+            .pending
+            .as_ref()
+            .is_some_and(|(pending, _)| *pending == value)
+        {
+            return;
+        }
+        if self.current.as_ref() == Some(&value) {
+            // back to the value already reported
+            self.pending = None;
+        } else {
+            self.pending = Some((value, now));
+        }
+    }
+
+    /// The value to report, if it stayed the same for the whole delay.
+    pub fn poll(&mut self, now: Instant) -> Option<&T> {
+        if self.pending.as_ref().is_some_and(|(_, since)| {
+            now.saturating_duration_since(*since) >= self.delay
+        }) {
+            self.current = self.pending.take().map(|(value, _)| value);
+            return self.current.as_ref();
+        }
+        None
+    }
+}
+
+/// Runs a command for the selected entry each time it changes, the
+/// invocation for the previous entry being killed if it is still running.
+#[derive(Debug)]
+pub struct SelectionHook {
+    /// The command to run, `{}` being replaced with the entry.
+    template: String,
+    debouncer: Debouncer<String>,
+    child: Option<Child>,
+}
+
+impl SelectionHook {
+    pub fn new(template: String) -> Self {
+        Self {
+            template,
+            debouncer: Debouncer::new(ON_CHANGE_DEBOUNCE),
+            child: None,
+        }
+    }
+
+    /// Record the selected entry and run the command if it stayed selected
+    /// long enough.
+    pub fn update(&mut self, selected: Option<&Entry>, now: Instant) {
+        if let Some(entry) = selected {
+            self.debouncer.update(entry.stdout_repr(), now);
+        }
+        let Some(entry) = self.debouncer.poll(now) else {
+            return;
+        };
+        let command = expand_command_template(&self.template, &[entry]);
+        self.stop();
+        match shell_command()
+            .arg(&command)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            Ok(child) => self.child = Some(child),
+            Err(e) => debug!("Failed to run {:?}: {:?}", command, e),
+        }
+    }
+
+    /// Kill the last invocation if it is still running.
+    pub fn stop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            if matches!(child.try_wait(), Ok(None)) {
+                let _ = child.kill();
+            }
+            let _ = child.wait();
+        }
+    }
+}
+
+impl Drop for SelectionHook {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::entry::PreviewType;
+
+    #[test]
+    fn test_rapid_changes_are_reported_once() {
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let mut debouncer = Debouncer::new(Duration::from_millis(100));
+        let mut reported = Vec::new();
+
+        // scrolling through ten entries, 20ms apart
+        for i in 0..10 {
+            debouncer.update(i, at(i * 20));
+            reported.extend(debouncer.poll(at(i * 20)).copied());
+        }
+        assert!(reported.is_empty());
+        reported.extend(debouncer.poll(at(279)).copied());
+        assert!(reported.is_empty());
+        reported.extend(debouncer.poll(at(280)).copied());
+        assert_eq!(reported, [9]);
+
+        // staying on the same entry doesn't report it again
+        debouncer.update(9, at(300));
+        assert_eq!(debouncer.poll(at(500)), None);
+    }
+
+    #[test]
+    fn test_going_back_to_the_reported_value_cancels_the_change() {
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let mut debouncer = Debouncer::new(Duration::from_millis(100));
+        debouncer.update("a", at(0));
+        assert_eq!(debouncer.poll(at(100)), Some(&"a"));
+
+        debouncer.update("b", at(110));
+        debouncer.update("a", at(120));
+        assert_eq!(debouncer.poll(at(300)), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_hook_runs_once_per_settled_selection() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("log");
+        let mut hook =
+            SelectionHook::new(format!("echo {{}} >> {}", log.display()));
+        let start = Instant::now();
+        let at = |ms| start + ON_CHANGE_DEBOUNCE / 10 * ms;
+
+        for i in 0..5 {
+            let entry = Entry::new(format!("entry{i}"), PreviewType::Basic);
+            hook.update(Some(&entry), at(i));
+        }
+        hook.update(None, at(20));
+        hook.update(None, at(30));
+        // wait for the command to be done
+        if let Some(mut child) = hook.child.take() {
+            child.wait().unwrap();
+        }
+
+        assert_eq!(std::fs::read_to_string(&log).unwrap(), "entry4\n");
+    }
+}
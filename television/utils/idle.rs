@@ -0,0 +1,55 @@
+use std::time::{Duration, Instant};
+
+/// A timer that elapses after a period without user input.
+///
+/// The current time is always passed in by the caller, which keeps the
+/// timer independent from the system clock.
+#[derive(Debug, Clone, Copy)]
+pub struct IdleTimer {
+    timeout: Duration,
+    last_input: Instant,
+}
+
+impl IdleTimer {
+    pub fn new(timeout: Duration, now: Instant) -> Self {
+        Self {
+            timeout,
+            last_input: now,
+        }
+    }
+
+    /// Restart the timer, typically on a key event.
+    pub fn reset(&mut self, now: Instant) {
+        self.last_input = now;
+    }
+
+    /// Whether no input was received for at least the timer's timeout.
+    pub fn elapsed(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.last_input) >= self.timeout
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fires_once_timeout_elapsed() {
+        let start = Instant::now();
+        let timer = IdleTimer::new(Duration::from_millis(500), start);
+
+        assert!(!timer.elapsed(start));
+        assert!(!timer.elapsed(start + Duration::from_millis(499)));
+        assert!(timer.elapsed(start + Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn test_input_resets_timer() {
+        let start = Instant::now();
+        let mut timer = IdleTimer::new(Duration::from_millis(500), start);
+
+        timer.reset(start + Duration::from_millis(400));
+        assert!(!timer.elapsed(start + Duration::from_millis(600)));
+        assert!(timer.elapsed(start + Duration::from_millis(900)));
+    }
+}
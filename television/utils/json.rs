@@ -0,0 +1,81 @@
+/// A path to a field of a JSON object, e.g. `.name` or `.user.name`.
+///
+/// Path segments are separated by dots and may be object keys or array
+/// indices (e.g. `.tags.0`). The path `.` designates the whole object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonFieldPath {
+    /// The path as a JSON pointer (RFC 6901), e.g. `/user/name`.
+    pointer: String,
+}
+
+impl JsonFieldPath {
+    pub fn parse(raw: &str) -> Self {
+        let pointer = raw
+            .trim()
+            .trim_start_matches('.')
+            .split('.')
+            .filter(|segment| !segment.is_empty())
+            .fold(String::new(), |mut pointer, segment| {
+                pointer.push('/');
+                pointer
+                    .push_str(&segment.replace('~', "~0").replace('/', "~1"));
+                pointer
+            });
+        Self { pointer }
+    }
+
+    /// Extract this field from a line of JSON.
+    ///
+    /// String fields are returned as is while other values are returned as
+    /// JSON. Returns `None` if the line isn't valid JSON or doesn't contain
+    /// the field.
+    pub fn extract(&self, line: &str) -> Option<String> {
+        let value: serde_json::Value = serde_json::from_str(line).ok()?;
+        match value.pointer(&self.pointer)? {
+            serde_json::Value::String(s) => Some(s.clone()),
+            v => Some(v.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_nested_field() {
+        let lines = [
+            r#"{"name": "alice", "user": {"name": "Alice", "id": 1}}"#,
+            r#"{"name": "bob", "user": {"name": "Bob", "id": 2}}"#,
+            r#"{"name": "carol", "user": {"id": 3}}"#,
+            "not json at all",
+        ];
+        let field = JsonFieldPath::parse(".user.name");
+        let extracted: Vec<Option<String>> =
+            lines.iter().map(|l| field.extract(l)).collect();
+        assert_eq!(
+            extracted,
+            vec![
+                Some("Alice".to_string()),
+                Some("Bob".to_string()),
+                None,
+                None
+            ]
+        );
+
+        // non-string values are extracted as JSON
+        let field = JsonFieldPath::parse(".user");
+        assert_eq!(
+            field.extract(lines[0]),
+            Some(r#"{"id":1,"name":"Alice"}"#.to_string())
+        );
+        assert_eq!(
+            JsonFieldPath::parse(".tags.1").extract(r#"{"tags": ["a", "b"]}"#),
+            Some("b".to_string())
+        );
+        assert_eq!(
+            JsonFieldPath::parse(".").extract(r#""whole""#),
+            Some("whole".to_string())
+        );
+    }
+}
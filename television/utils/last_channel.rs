@@ -0,0 +1,46 @@
+use std::path::Path;
+
+use anyhow::Result;
+use tracing::debug;
+
+/// The name of the file, in the data directory, recording the name of the
+/// last channel used.
+const LAST_CHANNEL_FILE_NAME: &str = "last_channel";
+
+/// Record `channel` as the last channel used.
+pub fn save_last_channel(data_dir: &Path, channel: &str) -> Result<()> {
+    std::fs::create_dir_all(data_dir)?;
+    std::fs::write(data_dir.join(LAST_CHANNEL_FILE_NAME), channel)?;
+    Ok(())
+}
+
+/// The name of the last channel used, if one was recorded.
+pub fn load_last_channel(data_dir: &Path) -> Option<String> {
+    let path = data_dir.join(LAST_CHANNEL_FILE_NAME);
+    match std::fs::read_to_string(&path) {
+        Ok(content) => Some(content.trim().to_string())
+            .filter(|channel| !channel.is_empty()),
+        Err(e) => {
+            debug!("Unable to read last channel {:?}: {:?}", path, e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_last_channel_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_dir = dir.path().join("television");
+        assert_eq!(load_last_channel(&data_dir), None);
+
+        save_last_channel(&data_dir, "git-repos").unwrap();
+        assert_eq!(load_last_channel(&data_dir).as_deref(), Some("git-repos"));
+
+        save_last_channel(&data_dir, "env").unwrap();
+        assert_eq!(load_last_channel(&data_dir).as_deref(), Some("env"));
+    }
+}
@@ -3,10 +3,15 @@ pub mod clipboard;
 pub mod command;
 pub mod files;
 pub mod hashmaps;
+pub mod hook;
+pub mod idle;
 pub mod image;
 pub mod indices;
 pub mod input;
+pub mod json;
+pub mod last_channel;
 pub mod metadata;
+pub mod query_fifo;
 pub mod rocell;
 pub mod shell;
 pub mod stdin;
@@ -0,0 +1,128 @@
+//! Drive the query from an external program writing lines to a named pipe,
+//! e.g. an editor sending the word under the cursor.
+
+use std::path::PathBuf;
+
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, BufReader};
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::debug;
+
+use crate::action::Action;
+
+/// Send every line read from `reader` as a new query, until the reader is
+/// closed or the application stops listening.
+///
+/// Returns whether the application is still listening.
+pub async fn forward_queries<R>(
+    reader: R,
+    action_tx: &UnboundedSender<Action>,
+) -> bool
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut lines = reader.lines();
+    loop {
+        match lines.next_line().await {
+            Ok(Some(line)) => {
+                let query = line.trim_end_matches('\r').to_string();
+                if action_tx.send(Action::SetQuery(query)).is_err() {
+                    return false;
+                }
+            }
+            Ok(None) => return true,
+            Err(e) => {
+                debug!("Failed to read from the query pipe: {:?}", e);
+                return true;
+            }
+        }
+    }
+}
+
+/// Read queries from the named pipe at `path` for as long as the
+/// application runs.
+///
+/// The pipe is reopened each time its writer closes it, so that successive
+/// writers (e.g. `echo foo > pipe`) keep driving the query. Regular files
+/// are read once.
+pub async fn read_query_fifo(
+    path: PathBuf,
+    action_tx: UnboundedSender<Action>,
+) {
+    loop {
+        // opening a named pipe blocks until a writer opens it as well
+        let file = match tokio::fs::File::open(&path).await {
+            Ok(file) => file,
+            Err(e) => {
+                debug!("Failed to open the query pipe {:?}: {:?}", path, e);
+                return;
+            }
+        };
+        if !forward_queries(BufReader::new(file), &action_tx).await
+            || !is_fifo(&path)
+        {
+            return;
+        }
+    }
+}
+
+#[cfg(unix)]
+fn is_fifo(path: &std::path::Path) -> bool {
+    use std::os::unix::fs::FileTypeExt;
+
+    std::fs::metadata(path).is_ok_and(|md| md.file_type().is_fifo())
+}
+
+#[cfg(not(unix))]
+fn is_fifo(_path: &std::path::Path) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_forward_queries() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let reader: &[u8] = b"foo\nbar baz\r\n\nqux";
+
+        assert!(forward_queries(reader, &tx).await);
+        let mut actions = Vec::new();
+        while let Ok(action) = rx.try_recv() {
+            actions.push(action);
+        }
+        assert_eq!(
+            actions,
+            [
+                Action::SetQuery(String::from("foo")),
+                Action::SetQuery(String::from("bar baz")),
+                Action::SetQuery(String::new()),
+                Action::SetQuery(String::from("qux")),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_forward_queries_stops_once_the_app_is_gone() {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        drop(rx);
+        let reader: &[u8] = b"foo\nbar\n";
+
+        assert!(!forward_queries(reader, &tx).await);
+    }
+
+    #[tokio::test]
+    async fn test_regular_file_is_read_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("queries");
+        std::fs::write(&path, "foo\n").unwrap();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        read_query_fifo(path, tx).await;
+        assert_eq!(
+            rx.recv().await,
+            Some(Action::SetQuery(String::from("foo")))
+        );
+        assert_eq!(rx.recv().await, None);
+    }
+}
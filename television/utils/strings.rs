@@ -1,3 +1,6 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
 /// Returns the index of the next character boundary in the given string.
 ///
 /// If the given index is already a character boundary, it is returned as is.
@@ -512,6 +515,243 @@ pub fn make_matched_string_printable(
     (printable, match_indices)
 }
 
+/// Shorten the intermediate components of a path to their first character
+/// (e.g. `s/m/t/file.rs` for `src/main/thing/file.rs`), keeping the basename
+/// whole.
+///
+/// The leading dot of hidden components is kept along with the following
+/// character (e.g. `.c/tv/config.toml` for `.config/tv/config.toml`).
+/// `match_ranges` are character ranges in `path` and the returned ranges are
+/// the corresponding ranges in the shortened path, which only cover the
+/// characters that were kept.
+///
+/// # Examples
+/// ```
+/// use television::utils::strings::shorten_path;
+///
+/// let (shortened, ranges) = shorten_path("src/main/thing/file.rs", &[(4, 8)]);
+/// assert_eq!(shortened, "s/m/t/file.rs");
+/// assert_eq!(ranges, vec![(2, 3)]);
+/// ```
+///
+/// # Panics
+/// This will panic if the length of the shortened path doesn't fit into a
+/// `u32`.
+pub fn shorten_path(
+    path: &str,
+    match_ranges: &[(u32, u32)],
+) -> (String, Vec<(u32, u32)>) {
+    let components = path.split(std::path::MAIN_SEPARATOR).collect::<Vec<_>>();
+    let last = components.len() - 1;
+    // the indices of the characters of `path` to keep
+    let mut kept = Vec::new();
+    let mut index = 0;
+    for (i, component) in components.iter().enumerate() {
+        if i > 0 {
+            // separator
+            kept.push(index);
+            index += 1;
+        }
+        let len = component.chars().count();
+        let keep = if i == last {
+            len
+        } else {
+            (usize::from(component.starts_with('.')) + 1).min(len)
+        };
+        kept.extend(index..index + keep);
+        index += len;
+    }
+
+    let chars = path.chars().collect::<Vec<_>>();
+    let shortened = kept.iter().map(|i| chars[*i]).collect();
+    let mut ranges: Vec<(u32, u32)> = Vec::new();
+    for (new_index, i) in kept.iter().enumerate() {
+        let matched = match_ranges
+            .iter()
+            .any(|(start, end)| (*start as usize..*end as usize).contains(i));
+        if !matched {
+            continue;
+        }
+        let new_index = u32::try_from(new_index).unwrap();
+        match ranges.last_mut() {
+            Some(range) if range.1 == new_index => range.1 += 1,
+            _ => ranges.push((new_index, new_index + 1)),
+        }
+    }
+    (shortened, ranges)
+}
+
+/// The longest directory prefix shared by all the given paths, including its
+/// trailing separator, or an empty string if there is none.
+///
+/// Only the paths' parent directories are considered so that stripping the
+/// prefix never leaves a path empty. The iteration stops as soon as the
+/// paths are found to have nothing in common.
+///
+/// # Examples
+/// ```
+/// use television::utils::strings::common_path_prefix;
+///
+/// let paths = ["/home/user/src/main.rs", "/home/user/src/lib.rs"];
+/// assert_eq!(common_path_prefix(paths), "/home/user/src/");
+/// assert_eq!(common_path_prefix(["src/main.rs", "build.rs"]), "");
+/// ```
+pub fn common_path_prefix<I, S>(paths: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    const SEPARATOR: char = std::path::MAIN_SEPARATOR;
+    let mut prefix: Option<String> = None;
+    for path in paths {
+        let path = path.as_ref().trim_end_matches(SEPARATOR);
+        let parent = path.rfind(SEPARATOR).map_or("", |i| &path[..=i]);
+        let common = match &prefix {
+            Some(prefix) => &parent[..common_prefix_len(prefix, parent)],
+            None => parent,
+        };
+        // only keep whole components
+        let common = common.rfind(SEPARATOR).map_or("", |i| &common[..=i]);
+        if common.is_empty() {
+            return String::new();
+        }
+        prefix = Some(common.to_string());
+    }
+    prefix.unwrap_or_default()
+}
+
+/// Normalize a query pasted or typed in the input before matching: leading
+/// and trailing whitespace is trimmed and quotes wrapping the whole query
+/// are removed, the case of the query being kept as is.
+///
+/// A trailing escaped space (`\ `) is part of the query and is kept, and
+/// quotes are only removed when they enclose the whole query (e.g. the
+/// `'foo` exact match syntax is left untouched).
+///
+/// # Examples
+/// ```
+/// use television::utils::strings::sanitize_query;
+///
+/// assert_eq!(sanitize_query("  \"My Documents/\" "), "My Documents/");
+/// assert_eq!(sanitize_query("'src/main.rs'"), "src/main.rs");
+/// assert_eq!(sanitize_query("'exact fuzzy"), "'exact fuzzy");
+/// ```
+pub fn sanitize_query(query: &str) -> &str {
+    let query = query.trim_start();
+    let trimmed = query.trim_end();
+    let query = if trimmed.ends_with('\\') && trimmed.len() < query.len() {
+        &query[..next_char_boundary(query, trimmed.len() + 1)]
+    } else {
+        trimmed
+    };
+    for quote in ['"', '\''] {
+        let inner = query
+            .strip_prefix(quote)
+            .and_then(|query| query.strip_suffix(quote));
+        if let Some(inner) = inner.filter(|inner| !inner.contains(quote)) {
+            return inner.trim();
+        }
+    }
+    query
+}
+
+/// The given string without its ANSI escape sequences (e.g. colors), as
+/// displayed by a terminal.
+///
+/// Control sequences (`ESC [ ... m`) and operating system commands
+/// (`ESC ] ... BEL`, e.g. hyperlinks) are removed along with other two-byte
+/// escapes.
+///
+/// # Examples
+/// ```
+/// use television::utils::strings::strip_ansi_codes;
+///
+/// assert_eq!(strip_ansi_codes("\x1b[1;31mred\x1b[0m text"), "red text");
+/// assert_eq!(
+///     strip_ansi_codes("\x1b]8;;https://a.b\x07link\x1b]8;;\x07"),
+///     "link"
+/// );
+/// assert_eq!(strip_ansi_codes("plain"), "plain");
+/// ```
+pub fn strip_ansi_codes(s: &str) -> std::borrow::Cow<'_, str> {
+    if !s.contains('\x1b') {
+        return s.into();
+    }
+    let mut stripped = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            stripped.push(c);
+            continue;
+        }
+        match chars.next() {
+            // control sequence, up to its final byte
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // operating system command, up to BEL or ST (`ESC \`)
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    stripped.into()
+}
+
+/// The longest prefix shared by all the given strings, empty if there are
+/// none.
+///
+/// Strings are only consumed until the prefix becomes empty.
+///
+/// # Examples
+/// ```
+/// use television::utils::strings::longest_common_prefix;
+///
+/// assert_eq!(longest_common_prefix(["matcher/", "match.rs"]), "match");
+/// assert_eq!(longest_common_prefix(["main.rs", "lib.rs"]), "");
+/// assert_eq!(longest_common_prefix(Vec::<&str>::new()), "");
+/// ```
+pub fn longest_common_prefix<I, S>(strings: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut strings = strings.into_iter();
+    let Some(first) = strings.next() else {
+        return String::new();
+    };
+    let mut prefix = first.as_ref().to_string();
+    for s in strings {
+        prefix.truncate(common_prefix_len(&prefix, s.as_ref()));
+        if prefix.is_empty() {
+            break;
+        }
+    }
+    prefix
+}
+
+/// The length in bytes of the longest common prefix of two strings.
+fn common_prefix_len(a: &str, b: &str) -> usize {
+    a.chars()
+        .zip(b.chars())
+        .take_while(|(a, b)| a == b)
+        .map(|(c, _)| c.len_utf8())
+        .sum()
+}
+
 /// Shrink a string to a maximum length, adding an ellipsis in the middle.
 ///
 /// If the string is shorter than the maximum length, it is returned as is.
@@ -527,18 +767,118 @@ pub fn make_matched_string_printable(
 /// assert_eq!(shrink_with_ellipsis(s, 6), "H…!");
 /// ```
 pub fn shrink_with_ellipsis(s: &str, max_length: usize) -> String {
-    if s.len() <= max_length {
+    if s.width() <= max_length {
         return s.to_string();
     }
 
     let half_max_length = (max_length / 2).saturating_sub(2);
-    let first_half = slice_up_to_char_boundary(s, half_max_length);
-    let second_half =
-        slice_at_char_boundaries(s, s.len() - half_max_length, s.len());
-    format!("{first_half}…{second_half}")
+    let first_half =
+        take_graphemes_up_to_width(s.graphemes(true), half_max_length);
+    let mut second_half =
+        take_graphemes_up_to_width(s.graphemes(true).rev(), half_max_length);
+    second_half.reverse();
+    format!("{}{ELLIPSIS}{}", first_half.concat(), second_half.concat())
+}
+
+/// The ellipsis character used when truncating strings.
+pub const ELLIPSIS: char = '…';
+
+/// Truncate a string so that its display width doesn't exceed `max_width`, appending an
+/// ellipsis if anything was cut off.
+///
+/// Truncation happens on grapheme boundaries and takes the display width of wide characters
+/// (e.g. CJK or emoji) into account, meaning a grapheme is never split in half and the
+/// returned string is guaranteed to fit in `max_width` columns (ellipsis included).
+///
+/// # Examples
+/// ```
+/// use television::utils::strings::truncate_to_width;
+///
+/// assert_eq!(truncate_to_width("Hello, World!", 13), "Hello, World!");
+/// assert_eq!(truncate_to_width("Hello, World!", 6), "Hello…");
+/// assert_eq!(truncate_to_width("你好,世界!", 6), "你好,…");
+/// assert_eq!(truncate_to_width("👋🌍!", 3), "👋…");
+/// assert_eq!(truncate_to_width("Hello", 0), "");
+/// ```
+pub fn truncate_to_width(s: &str, max_width: usize) -> String {
+    if s.width() <= max_width {
+        return s.to_string();
+    }
+    if max_width == 0 {
+        return String::new();
+    }
+    let mut truncated = slice_up_to_width(s, max_width - 1).to_string();
+    truncated.push(ELLIPSIS);
+    truncated
+}
+
+/// Returns the longest prefix of the given string whose display width doesn't exceed
+/// `max_width`, without ever splitting a grapheme.
+///
+/// # Examples
+/// ```
+/// use television::utils::strings::slice_up_to_width;
+///
+/// assert_eq!(slice_up_to_width("Hello, World!", 5), "Hello");
+/// assert_eq!(slice_up_to_width("Hello", 10), "Hello");
+/// // wide characters take up two columns
+/// assert_eq!(slice_up_to_width("你好,世界!", 3), "你");
+/// assert_eq!(slice_up_to_width("👋🌍!", 4), "👋🌍");
+/// ```
+pub fn slice_up_to_width(s: &str, max_width: usize) -> &str {
+    let mut width = 0;
+    for (i, grapheme) in s.grapheme_indices(true) {
+        width += grapheme.width();
+        if width > max_width {
+            return &s[..i];
+        }
+    }
+    s
+}
+
+/// Returns the longest suffix of the given string whose display width doesn't exceed
+/// `max_width`, without ever splitting a grapheme.
+///
+/// # Examples
+/// ```
+/// use television::utils::strings::slice_suffix_up_to_width;
+///
+/// assert_eq!(slice_suffix_up_to_width("src/main.rs", 7), "main.rs");
+/// assert_eq!(slice_suffix_up_to_width("你好世界", 5), "世界");
+/// assert_eq!(slice_suffix_up_to_width("Hello", 0), "");
+/// ```
+pub fn slice_suffix_up_to_width(s: &str, max_width: usize) -> &str {
+    let mut width = 0;
+    for (i, grapheme) in s.grapheme_indices(true).rev() {
+        width += grapheme.width();
+        if width > max_width {
+            return &s[i + grapheme.len()..];
+        }
+    }
+    s
+}
+
+/// Take graphemes from the given iterator as long as their cumulative display width doesn't
+/// exceed `max_width`.
+fn take_graphemes_up_to_width<'a>(
+    graphemes: impl Iterator<Item = &'a str>,
+    max_width: usize,
+) -> Vec<&'a str> {
+    let mut width = 0;
+    graphemes
+        .take_while(|g| {
+            width += g.width();
+            width <= max_width
+        })
+        .collect()
 }
 
 #[cfg(test)]
+#[allow(
+    clippy::needless_borrow,
+    clippy::semicolon_if_nothing_returned,
+    clippy::unreadable_literal
+)]
 mod tests {
     use super::*;
 
@@ -799,4 +1139,126 @@ mod tests {
         test_preprocess_line("Hello, World!\u{FEFF}", "Hello, World!␀");
         test_preprocess_line(&"a".repeat(400), &"a".repeat(300));
     }
+
+    fn test_truncate_to_width(input: &str, max_width: usize, expected: &str) {
+        let actual = truncate_to_width(input, max_width);
+        assert_eq!(actual, expected, "input: {:?}", input);
+        assert!(actual.width() <= max_width);
+    }
+
+    #[test]
+    fn test_truncate_to_width_ascii() {
+        test_truncate_to_width("Hello, World!", 20, "Hello, World!");
+        test_truncate_to_width("Hello, World!", 13, "Hello, World!");
+        test_truncate_to_width("Hello, World!", 12, "Hello, Worl…");
+        test_truncate_to_width("Hello, World!", 1, "…");
+        test_truncate_to_width("Hello, World!", 0, "");
+    }
+
+    #[test]
+    fn test_truncate_to_width_cjk() {
+        // each CJK character is 2 columns wide
+        test_truncate_to_width("你好,世界!", 10, "你好,世界!");
+        test_truncate_to_width("你好,世界!", 9, "你好,世…");
+        test_truncate_to_width("你好,世界!", 8, "你好,世…");
+        test_truncate_to_width("你好,世界!", 2, "…");
+        test_truncate_to_width("日本語のテキスト", 7, "日本語…");
+    }
+
+    #[test]
+    fn test_truncate_to_width_emoji() {
+        test_truncate_to_width("👋🌍!", 5, "👋🌍!");
+        test_truncate_to_width("👋🌍!", 4, "👋…");
+        test_truncate_to_width("👋🌍!", 2, "…");
+        // multi-codepoint graphemes are never split
+        test_truncate_to_width("👨‍👩‍👧 family", 6, "👨‍👩‍👧 fa…");
+        test_truncate_to_width("🇫🇷🇯🇵", 3, "🇫🇷…");
+    }
+
+    #[test]
+    fn test_slice_up_to_width() {
+        assert_eq!(slice_up_to_width("Hello", 3), "Hel");
+        assert_eq!(slice_up_to_width("你好世界", 5), "你好");
+        assert_eq!(slice_up_to_width("👋🌍", 1), "");
+        assert_eq!(slice_up_to_width("", 3), "");
+    }
+
+    #[test]
+    fn test_shrink_with_ellipsis_wide_characters() {
+        assert_eq!(shrink_with_ellipsis("你好,世界!", 10), "你好,世界!");
+        assert_eq!(shrink_with_ellipsis("你好你好你好你好", 12), "你好…你好");
+        assert_eq!(shrink_with_ellipsis("👋🌍👋🌍👋🌍", 10), "👋…🌍");
+    }
+
+    #[test]
+    fn test_shorten_path() {
+        assert_eq!(
+            shorten_path("src/main/java/com/example/thing/File.java", &[]),
+            (String::from("s/m/j/c/e/t/File.java"), vec![])
+        );
+        assert_eq!(
+            shorten_path("/home/user/.config/television/config.toml", &[]),
+            (String::from("/h/u/.c/t/config.toml"), vec![])
+        );
+        assert_eq!(
+            shorten_path("file.rs", &[(0, 4)]),
+            (String::from("file.rs"), vec![(0, 4)])
+        );
+    }
+
+    #[test]
+    fn test_shorten_path_match_ranges() {
+        // "src/main/thing/file.rs" matched on "main" and "file"
+        let (shortened, ranges) =
+            shorten_path("src/main/thing/file.rs", &[(4, 8), (15, 19)]);
+        assert_eq!(shortened, "s/m/t/file.rs");
+        assert_eq!(ranges, vec![(2, 3), (6, 10)]);
+
+        // contiguous kept characters merge into a single range
+        let (_, ranges) = shorten_path("ab/cd/ef", &[(0, 8)]);
+        assert_eq!(ranges, vec![(0, 6)]);
+    }
+
+    #[test]
+    fn test_sanitize_query() {
+        assert_eq!(sanitize_query("  foo bar "), "foo bar");
+        // quotes around the whole query are stripped
+        assert_eq!(sanitize_query("\"Foo/Bar/\""), "Foo/Bar/");
+        assert_eq!(sanitize_query(" ' foo ' "), "foo");
+        assert_eq!(sanitize_query("\"\""), "");
+        // the quotes have to match
+        assert_eq!(sanitize_query("\"foo'"), "\"foo'");
+        // quotes that are part of the query syntax are kept
+        assert_eq!(sanitize_query("'foo"), "'foo");
+        assert_eq!(sanitize_query("'foo 'bar"), "'foo 'bar");
+        assert_eq!(sanitize_query("\"a\" \"b\""), "\"a\" \"b\"");
+        assert_eq!(sanitize_query("'"), "'");
+        // escaped trailing spaces are kept
+        assert_eq!(sanitize_query("foo\\   "), "foo\\ ");
+    }
+
+    #[test]
+    fn test_common_path_prefix() {
+        assert_eq!(
+            common_path_prefix([
+                "/home/user/project/src/main.rs",
+                "/home/user/project/src/screen/results.rs",
+                "/home/user/project/src/main_test.rs",
+            ]),
+            "/home/user/project/src/"
+        );
+        // only whole components are shared
+        assert_eq!(
+            common_path_prefix(["/home/user/proj/a", "/home/user/project/b"]),
+            "/home/user/"
+        );
+        // a single path shares its parent directory
+        assert_eq!(common_path_prefix(["src/main.rs"]), "src/");
+        // directories (with or without a trailing separator) are kept whole
+        assert_eq!(common_path_prefix(["src/", "src/main.rs"]), "");
+        assert_eq!(common_path_prefix(["a/src", "a/src/main.rs"]), "a/");
+        assert_eq!(common_path_prefix(["main.rs", "lib.rs"]), "");
+        assert_eq!(common_path_prefix(Vec::<String>::new()), "");
+        assert_eq!(common_path_prefix(["/été/a", "/été/b"]), "/été/");
+    }
 }
@@ -1,8 +1,11 @@
 use std::{collections::HashSet, path::PathBuf, time::Duration};
 
 use television::{
-    action::Action, app::App, channels::TelevisionChannel,
-    config::default_config_from_file,
+    action::Action,
+    app::App,
+    channels::{ChannelConfig, TelevisionChannel},
+    config::{default_config_from_file, RevealMode},
+    debug_log::DebugEvent,
 };
 use tokio::{task::JoinHandle, time::timeout};
 
@@ -26,9 +29,11 @@ fn setup_app() -> (
         .join("tests")
         .join("target_dir");
     std::env::set_current_dir(&target_dir).unwrap();
-    let channel = TelevisionChannel::Files(
-        television::channels::files::Channel::new(vec![target_dir]),
-    );
+    let channel =
+        TelevisionChannel::Files(television::channels::files::Channel::new(
+            vec![target_dir],
+            &ChannelConfig::default(),
+        ));
     let config = default_config_from_file().unwrap();
     let passthrough_keybindings = Vec::new();
     let input = None;
@@ -133,3 +138,181 @@ async fn test_app_basic_search_multiselect() {
         HashSet::from([&"file1.txt".to_string(), &"file2.txt".to_string()])
     );
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn test_app_outputs_rank_of_accepted_entry() {
+    let dir = tempfile::tempdir().unwrap();
+    for name in ["a.txt", "b.txt", "c.txt", "d.txt"] {
+        std::fs::write(dir.path().join(name), "").unwrap();
+    }
+    let channel =
+        TelevisionChannel::Files(television::channels::files::Channel::new(
+            vec![dir.path().to_path_buf()],
+            &ChannelConfig::default(),
+        ));
+    let config = default_config_from_file().unwrap();
+    let mut app = App::new(channel, config, &Vec::new(), None);
+    let tx = app.action_tx.clone();
+    let f = tokio::spawn(async move { app.run_headless().await.unwrap() });
+    std::thread::sleep(Duration::from_millis(200));
+
+    // a query matching every file gets the results listed
+    for c in "txt".chars() {
+        tx.send(Action::AddInputChar(c)).unwrap();
+    }
+    std::thread::sleep(Duration::from_millis(50));
+    // accept the 3rd result
+    for _ in 0..2 {
+        tx.send(Action::SelectNextEntry).unwrap();
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    tx.send(Action::ConfirmSelection).unwrap();
+
+    let output = timeout(DEFAULT_TIMEOUT, f)
+        .await
+        .expect("app did not finish within the default timeout")
+        .unwrap();
+    assert_eq!(output.selected_index, Some(3));
+    assert_eq!(output.selected_entries.unwrap().len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn test_app_prints_revealed_parent_as_accepted_entry() {
+    let dir = tempfile::tempdir().unwrap();
+    for name in ["a.txt", "b.txt"] {
+        std::fs::write(dir.path().join(name), "").unwrap();
+    }
+    let channel =
+        TelevisionChannel::Files(television::channels::files::Channel::new(
+            vec![dir.path().to_path_buf()],
+            &ChannelConfig::default(),
+        ));
+    let mut config = default_config_from_file().unwrap();
+    config.application.reveal_in_parent = RevealMode::Print;
+    let mut app = App::new(channel, config, &Vec::new(), None);
+    let tx = app.action_tx.clone();
+    let f = tokio::spawn(async move { app.run_headless().await.unwrap() });
+    std::thread::sleep(Duration::from_millis(200));
+
+    for c in "b.txt".chars() {
+        tx.send(Action::AddInputChar(c)).unwrap();
+    }
+    std::thread::sleep(Duration::from_millis(50));
+    tx.send(Action::RevealInParent).unwrap();
+
+    let output = timeout(DEFAULT_TIMEOUT, f)
+        .await
+        .expect("app did not finish within the default timeout")
+        .unwrap();
+    // the query and rank are output along with the directory
+    assert_eq!(output.passthrough, None);
+    assert_eq!(output.query.as_deref(), Some("b.txt"));
+    assert_eq!(output.selected_index, Some(1));
+    assert_eq!(
+        output
+            .selected_entries
+            .unwrap()
+            .into_iter()
+            .map(|entry| PathBuf::from(entry.name))
+            .collect::<Vec<_>>(),
+        vec![dir.path().to_path_buf()]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn test_app_loop_mode_resets_after_accept() {
+    let target_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("target_dir");
+    std::env::set_current_dir(&target_dir).unwrap();
+    let channel =
+        TelevisionChannel::Files(television::channels::files::Channel::new(
+            vec![target_dir],
+            &ChannelConfig::default(),
+        ));
+    let config = default_config_from_file().unwrap();
+    let mut app = App::new(channel, config, &Vec::new(), None);
+    let (selections_tx, mut selections_rx) =
+        tokio::sync::mpsc::unbounded_channel();
+    app.loop_on_accept(Box::new(move |output| {
+        selections_tx.send(output.selected_entries.unwrap_or_default())?;
+        Ok(())
+    }));
+    let tx = app.action_tx.clone();
+    let f = tokio::spawn(async move { app.run_headless().await.unwrap() });
+    std::thread::sleep(Duration::from_millis(200));
+
+    // every round starts from an empty query, otherwise the second one
+    // wouldn't match anything
+    for name in ["file1", "file2"] {
+        for c in name.chars() {
+            tx.send(Action::AddInputChar(c)).unwrap();
+        }
+        std::thread::sleep(Duration::from_millis(50));
+        tx.send(Action::ConfirmSelection).unwrap();
+
+        let selection = timeout(DEFAULT_TIMEOUT, selections_rx.recv())
+            .await
+            .expect("selection was not accepted within the default timeout")
+            .unwrap();
+        assert_eq!(
+            selection.iter().map(|e| &e.name).collect::<Vec<_>>(),
+            vec![&format!("{name}.txt")]
+        );
+        assert!(!f.is_finished());
+    }
+
+    // aborting exits without any selection
+    tx.send(Action::Quit).unwrap();
+    let output = timeout(DEFAULT_TIMEOUT, f)
+        .await
+        .expect("app did not finish within the default timeout")
+        .unwrap();
+    assert!(output.selected_entries.is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn test_app_records_dispatched_actions() {
+    let target_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("target_dir");
+    std::env::set_current_dir(&target_dir).unwrap();
+    let channel =
+        TelevisionChannel::Files(television::channels::files::Channel::new(
+            vec![target_dir],
+            &ChannelConfig::default(),
+        ));
+    let config = default_config_from_file().unwrap();
+    let mut app = App::new(channel, config, &Vec::new(), None);
+    let tx = app.action_tx.clone();
+    let f = tokio::spawn(async move {
+        app.run_headless().await.unwrap();
+        app
+    });
+    std::thread::sleep(Duration::from_millis(200));
+
+    tx.send(Action::AddInputChar('f')).unwrap();
+    tx.send(Action::SelectNextEntry).unwrap();
+    tx.send(Action::Quit).unwrap();
+
+    let app = timeout(DEFAULT_TIMEOUT, f)
+        .await
+        .expect("app did not finish within the default timeout")
+        .unwrap();
+    let actions = app
+        .debug_log()
+        .records()
+        .filter_map(|record| match &record.event {
+            DebugEvent::Action(action) => Some(action.clone()),
+            DebugEvent::Key(_) => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        actions,
+        [
+            Action::AddInputChar('f'),
+            Action::SelectNextEntry,
+            Action::Quit
+        ]
+    );
+}